@@ -0,0 +1,304 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey, StandardVisualKey};
+use symphonia::core::probe::{Hint, ProbeResult};
+use super::folder_icon;
+
+// 一个文件夹中最多读取的音频文件数量
+const MAX_TRACKS: usize = 200;
+// 封面缩放后的最大边长
+const COVER_SIZE: u32 = 256;
+
+// 专辑中的一首曲目
+#[derive(Debug, Clone)]
+pub struct AlbumTrack {
+    pub path: PathBuf,
+    pub number: Option<u32>,
+    pub title: String,
+    pub artist: Option<String>,
+    pub duration: Option<f64>, // 秒
+}
+
+// 从文件夹中音频文件的标签汇总出的专辑信息
+pub struct AlbumInfo {
+    pub folder: PathBuf,
+    pub album: Option<String>,
+    pub artist: Option<String>,
+    pub tracks: Vec<AlbumTrack>,
+    pub cover: Option<image::RgbaImage>,
+}
+
+impl AlbumInfo {
+    // 总时长（有曲目时长未知时只计已知部分）
+    pub fn total_duration(&self) -> f64 {
+        self.tracks.iter().filter_map(|t| t.duration).sum()
+    }
+}
+
+// 单个音频文件读到的标签
+#[derive(Default)]
+struct TrackTags {
+    album: Option<String>,
+    album_artist: Option<String>,
+    artist: Option<String>,
+    title: Option<String>,
+    number: Option<u32>,
+    cover: Option<(bool, Vec<u8>)>, // (是否为封面, 图片数据)
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_lowercase().as_str(), "mp3" | "flac" | "ogg" | "oga" | "m4a" | "aac" | "wav"))
+        .unwrap_or(false)
+}
+
+// 曲目号可能写作 "3/12"
+fn parse_track_number(value: &str) -> Option<u32> {
+    value.split('/').next()?.trim().parse().ok()
+}
+
+// 时长显示为 m:ss，超过一小时为 h:mm:ss
+pub fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, secs) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+fn collect_tags(revision: &MetadataRevision, tags: &mut TrackTags) {
+    for tag in revision.tags() {
+        let value = tag.value.to_string().trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match tag.std_key {
+            Some(StandardTagKey::Album) => tags.album = Some(value),
+            Some(StandardTagKey::AlbumArtist) => tags.album_artist = Some(value),
+            Some(StandardTagKey::Artist) => tags.artist = Some(value),
+            Some(StandardTagKey::TrackTitle) => tags.title = Some(value),
+            Some(StandardTagKey::TrackNumber) => tags.number = parse_track_number(&value),
+            _ => {}
+        }
+    }
+    // 优先使用标记为封面的图片，其次是第一张图片
+    for visual in revision.visuals() {
+        let is_front = visual.usage == Some(StandardVisualKey::FrontCover);
+        if tags.cover.as_ref().is_none_or(|(front, _)| is_front && !front) {
+            tags.cover = Some((is_front, visual.data.to_vec()));
+        }
+    }
+}
+
+// 打开音频文件并识别容器格式
+fn probe(path: &Path) -> Option<ProbeResult> {
+    let file = fs::File::open(path).ok()?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+    symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()
+}
+
+// 标签可能在容器之前（如 ID3v2），也可能在容器内部，两处都读取
+fn for_each_revision(probed: &mut ProbeResult, mut f: impl FnMut(&MetadataRevision)) {
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        f(revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        f(revision);
+    }
+}
+
+fn track_duration(probed: &ProbeResult) -> Option<f64> {
+    let params = &probed.format.default_track()?.codec_params;
+    let time = params.time_base?.calc_time(params.n_frames?);
+    Some(time.seconds as f64 + time.frac)
+}
+
+// 音频文件的全部标签和流信息，用于预览栏的元数据页
+pub fn read_tag_rows(path: &Path) -> Vec<(String, String)> {
+    let Some(mut probed) = probe(path) else {
+        return Vec::new();
+    };
+    let mut rows = Vec::new();
+    for_each_revision(&mut probed, |revision| {
+        for tag in revision.tags() {
+            let key = tag.std_key.map(|k| format!("{:?}", k)).unwrap_or_else(|| tag.key.clone());
+            rows.push((key, tag.value.to_string()));
+        }
+        if !revision.visuals().is_empty() {
+            rows.push(("内嵌图片".to_string(), format!("{} 张", revision.visuals().len())));
+        }
+    });
+    if let Some(duration) = track_duration(&probed) {
+        rows.push(("时长".to_string(), format_duration(duration)));
+    }
+    if let Some(params) = probed.format.default_track().map(|t| &t.codec_params) {
+        if let Some(rate) = params.sample_rate {
+            rows.push(("采样率".to_string(), format!("{} Hz", rate)));
+        }
+        if let Some(channels) = params.channels {
+            rows.push(("声道".to_string(), channels.count().to_string()));
+        }
+    }
+    rows
+}
+
+// 读取音频文件的标签和时长
+fn read_track(path: &Path, want_cover: bool) -> (AlbumTrack, TrackTags) {
+    let mut tags = TrackTags::default();
+    let mut duration = None;
+
+    if let Some(mut probed) = probe(path) {
+        for_each_revision(&mut probed, |revision| collect_tags(revision, &mut tags));
+        duration = track_duration(&probed);
+    }
+    if !want_cover {
+        tags.cover = None;
+    }
+
+    let track = AlbumTrack {
+        path: path.to_path_buf(),
+        number: tags.number,
+        title: tags.title.clone().unwrap_or_else(|| {
+            path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+        }),
+        artist: tags.artist.clone(),
+        duration,
+    };
+    (track, tags)
+}
+
+// 出现次数最多的值
+fn most_common<'a>(values: impl Iterator<Item = &'a String>) -> Option<String> {
+    let mut counts: HashMap<&String, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    counts.into_iter().max_by_key(|(_, count)| *count).map(|(value, _)| value.clone())
+}
+
+fn decode_cover(data: &[u8]) -> Option<image::RgbaImage> {
+    let image = image::load_from_memory(data).ok()?;
+    Some(image.thumbnail(COVER_SIZE, COVER_SIZE).to_rgba8())
+}
+
+// 单个音频文件的预览信息：标签、时长和内嵌封面
+pub struct TrackInfo {
+    pub track: AlbumTrack,
+    pub album: Option<String>,
+    pub cover: Option<image::RgbaImage>,
+}
+
+// 读取单个音频文件的预览信息，应在后台线程调用
+pub fn read_track_info(path: &Path) -> TrackInfo {
+    let (track, tags) = read_track(path, true);
+    let cover = tags.cover.and_then(|(_, data)| decode_cover(&data));
+    TrackInfo { track, album: tags.album, cover }
+}
+
+// 读取文件夹中的音频文件并汇总专辑信息；没有音频文件时返回 None。
+// 读取标签需要打开每个文件，应在后台线程调用
+pub fn read_album(folder: &Path) -> Option<AlbumInfo> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(folder)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_audio_file(path))
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    paths.sort();
+    paths.truncate(MAX_TRACKS);
+
+    let mut tracks = Vec::new();
+    let mut all_tags = Vec::new();
+    let mut cover_data = None;
+    for path in &paths {
+        let (track, mut tags) = read_track(path, cover_data.is_none());
+        if let Some((_, data)) = tags.cover.take() {
+            cover_data = Some(data);
+        }
+        tracks.push(track);
+        all_tags.push(tags);
+    }
+    // 按曲目号排序，没有曲目号的按文件名排在后面
+    tracks.sort_by_key(|t| (t.number.is_none(), t.number));
+
+    let album = most_common(all_tags.iter().filter_map(|t| t.album.as_ref()));
+    let artist = most_common(all_tags.iter().filter_map(|t| t.album_artist.as_ref()))
+        .or_else(|| most_common(all_tags.iter().filter_map(|t| t.artist.as_ref())));
+    // 没有内嵌封面时使用文件夹中的 folder.jpg / cover.jpg
+    let cover = cover_data
+        .and_then(|data| decode_cover(&data))
+        .or_else(|| {
+            let path = folder_icon::find_folder_icon(folder)?;
+            decode_cover(&fs::read(path).ok()?)
+        });
+
+    Some(AlbumInfo { folder: folder.to_path_buf(), album, artist, tracks, cover })
+}
+
+// 在预览栏中显示专辑：封面、专辑名/艺术家、曲目数和总时长、曲目列表
+pub fn show_album(ui: &mut egui::Ui, info: &AlbumInfo, cover: Option<&egui::TextureHandle>) {
+    if let Some(texture) = cover {
+        let size = texture.size_vec2();
+        let scale = (ui.available_width() - 20.0).min(COVER_SIZE as f32) / size.x.max(1.0);
+        ui.add(egui::Image::from_texture(egui::load::SizedTexture::new(texture.id(), size * scale.min(1.0))));
+    }
+
+    let album = info.album.clone().unwrap_or_else(|| {
+        info.folder.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    });
+    ui.heading(album);
+    if let Some(artist) = &info.artist {
+        ui.label(artist);
+    }
+    ui.label(egui::RichText::new(format!(
+        "{} 首曲目，共 {}",
+        info.tracks.len(),
+        format_duration(info.total_duration())
+    )).weak());
+    ui.separator();
+
+    egui::Grid::new("album_tracks").striped(true).num_columns(3).show(ui, |ui| {
+        for track in &info.tracks {
+            ui.label(track.number.map(|n| n.to_string()).unwrap_or_default());
+            // 悬停显示文件名，以及与专辑艺术家不同的曲目艺术家
+            let mut hover = track.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if let Some(artist) = track.artist.as_ref().filter(|a| Some(*a) != info.artist.as_ref()) {
+                hover = format!("{}\n{}", artist, hover);
+            }
+            ui.label(&track.title).on_hover_text(hover);
+            ui.label(track.duration.map(format_duration).unwrap_or_else(|| "--:--".to_string()));
+            ui.end_row();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_number_and_duration_format() {
+        assert_eq!(parse_track_number("3/12"), Some(3));
+        assert_eq!(parse_track_number(" 7 "), Some(7));
+        assert_eq!(parse_track_number("A1"), None);
+
+        assert_eq!(format_duration(65.4), "1:05");
+        assert_eq!(format_duration(3723.0), "1:02:03");
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+use super::context_menu::ContextAction;
+use super::file_list::{FileDropRequest, SelectionCommand};
+use super::tags::TagColor;
+
+// 组件发出的应用事件。菜单栏、工具栏、各个列表只把用户的操作放入事件队列，
+// 由主程序的 handle_event 统一修改状态，不再各自返回一串布尔标志
+pub enum AppEvent {
+    // 内容框进入目录并记录到导航历史
+    Navigate(PathBuf),
+    // 内容框进入上级目录
+    NavigateUp,
+    GoBack,
+    GoForward,
+    // 打开文件所在的文件夹并选中它
+    Reveal(PathBuf),
+    // 目录框进入目录，不影响内容框
+    NavigateDirectory(PathBuf),
+    // 只刷新内容框
+    RefreshContent,
+    // 刷新内容框和目录框
+    RefreshAll,
+    // 切换到盘符的工作区，恢复其保存的路径和导航历史
+    SwitchWorkspace(PathBuf),
+    CreateFolder,
+    // 打开暂存篮窗口
+    OpenBasket,
+    // 在内容框中显示起始页
+    OpenStartPage,
+    // 对文件的操作，与右键菜单共用同一套动作
+    FileAction(ContextAction),
+    // 内容框就地重命名的提交
+    RenameCommitted { path: PathBuf, new_name: String },
+    // 内容框或目录框中的拖放
+    Drop(FileDropRequest),
+    // 把文件拖到预览栏上，与正在预览的文件比较
+    Compare { left: PathBuf, right: PathBuf },
+    Selection(SelectionCommand),
+    // 菜单栏打开的工具和对话框
+    Tool(ToolCommand),
+    // 切换或清除文件的颜色标签
+    ToggleTag(PathBuf, TagColor),
+    ClearTags(PathBuf),
+}
+
+// 菜单栏中启动的工具
+pub enum ToolCommand {
+    GenerateChecksums(PathBuf),
+    // 按清单文件校验
+    VerifyChecksums(PathBuf),
+    FindDuplicates(PathBuf),
+    FindBrokenLinks(PathBuf),
+    AnalyzeDiskUsage(PathBuf),
+    FileReport(PathBuf),
+    OrganizePhotos(PathBuf),
+    UndoPhotoOrganize,
+    OpenSettings,
+    ShowAbout,
+}
+
+// 事件队列：组件在绘制时放入事件，主程序在布局之后统一处理
+#[derive(Default)]
+pub struct EventQueue {
+    events: Vec<AppEvent>,
+}
+
+impl EventQueue {
+    pub fn emit(&mut self, event: AppEvent) {
+        self.events.push(event);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    // 取出全部待处理事件
+    pub fn drain(&mut self) -> Vec<AppEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
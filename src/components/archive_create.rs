@@ -0,0 +1,385 @@
+use eframe::egui;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use chrono::{Datelike, Timelike};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+// 压缩级别：0 只存储，9 压缩率最高
+const MAX_LEVEL: u32 = 9;
+const DEFAULT_LEVEL: u32 = 6;
+
+// 创建的压缩包格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+        }
+    }
+}
+
+// 确认后开始压缩的参数
+pub struct CompressRequest {
+    pub sources: Vec<PathBuf>,
+    pub archive: PathBuf,
+    pub format: ArchiveFormat,
+    pub level: u32,
+}
+
+// 压缩包中的一项：磁盘路径和包内名称（以 / 分隔）
+struct ArchiveEntry {
+    path: PathBuf,
+    name: String,
+    metadata: fs::Metadata,
+}
+
+// 展开源路径，文件夹在其内容之前；不进入符号链接，压缩包自身不放入
+fn collect_entries(sources: &[PathBuf], archive: &Path) -> Vec<ArchiveEntry> {
+    let mut entries = Vec::new();
+    for source in sources {
+        let Some(name) = source.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let mut pending = vec![(source.clone(), name)];
+        while let Some((path, name)) = pending.pop() {
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if path == archive {
+                continue;
+            }
+            if metadata.is_dir() {
+                if let Ok(children) = fs::read_dir(&path) {
+                    let mut children: Vec<_> = children.flatten().collect();
+                    children.sort_by_key(|c| std::cmp::Reverse(c.file_name()));
+                    pending.extend(children.into_iter().map(|c| (c.path(), format!("{}/{}", name, c.file_name().to_string_lossy()))));
+                }
+            }
+            entries.push(ArchiveEntry { path, name, metadata });
+        }
+    }
+    entries
+}
+
+// 取消时返回的错误。不能用 Interrupted：io::copy 和 tar 遇到它会不断重试读取
+pub fn cancelled_error() -> io::Error {
+    io::Error::other("已取消")
+}
+
+// 读取时报告字节数，取消后返回 cancelled_error()；解压时也用来统计读取的压缩包字节数
+pub struct ProgressReader<'a, R> {
+    inner: R,
+    on_bytes: &'a mut dyn FnMut(u64),
+    cancelled: &'a dyn Fn() -> bool,
+}
+
+impl<'a, R> ProgressReader<'a, R> {
+    pub fn new(inner: R, on_bytes: &'a mut dyn FnMut(u64), cancelled: &'a dyn Fn() -> bool) -> Self {
+        Self { inner, on_bytes, cancelled }
+    }
+}
+
+impl<R: Seek> Seek for ProgressReader<'_, R> {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(position)
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if (self.cancelled)() {
+            return Err(cancelled_error());
+        }
+        let count = self.inner.read(buf)?;
+        (self.on_bytes)(count as u64);
+        Ok(count)
+    }
+}
+
+#[cfg(unix)]
+fn unix_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn unix_mode(metadata: &fs::Metadata) -> u32 {
+    if metadata.is_dir() { 0o755 } else { 0o644 }
+}
+
+// zip 只能记录 1980 年以后的本地时间
+fn zip_time(metadata: &fs::Metadata) -> Option<zip::DateTime> {
+    let time = chrono::DateTime::<chrono::Local>::from(metadata.modified().ok()?);
+    zip::DateTime::from_date_and_time(
+        u16::try_from(time.year()).ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    ).ok()
+}
+
+fn write_zip(
+    entries: &[ArchiveEntry],
+    file: File,
+    level: u32,
+    on_bytes: &mut dyn FnMut(u64),
+    on_file: &mut dyn FnMut(&Path),
+    cancelled: &dyn Fn() -> bool,
+) -> io::Result<()> {
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let base = if level == 0 {
+        SimpleFileOptions::default().compression_method(CompressionMethod::Stored)
+    } else {
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated).compression_level(Some(level as i64))
+    };
+    for entry in entries {
+        if cancelled() {
+            return Err(cancelled_error());
+        }
+        let mut options = base.unix_permissions(unix_mode(&entry.metadata));
+        if let Some(time) = zip_time(&entry.metadata) {
+            options = options.last_modified_time(time);
+        }
+        if entry.metadata.is_dir() {
+            zip.add_directory(format!("{}/", entry.name), options)?;
+        } else if entry.metadata.is_symlink() {
+            let target = fs::read_link(&entry.path)?;
+            zip.add_symlink(&entry.name, target.to_string_lossy(), options)?;
+            on_file(&entry.path);
+        } else if entry.metadata.is_file() {
+            zip.start_file(&entry.name, options.large_file(entry.metadata.len() >= u32::MAX as u64))?;
+            let mut reader = ProgressReader::new(File::open(&entry.path)?, &mut *on_bytes, cancelled);
+            io::copy(&mut reader, &mut zip)?;
+            on_file(&entry.path);
+        }
+    }
+    zip.finish()?.flush()
+}
+
+fn write_tar_gz(
+    entries: &[ArchiveEntry],
+    file: File,
+    level: u32,
+    on_bytes: &mut dyn FnMut(u64),
+    on_file: &mut dyn FnMut(&Path),
+    cancelled: &dyn Fn() -> bool,
+) -> io::Result<()> {
+    let encoder = GzEncoder::new(BufWriter::new(file), Compression::new(level));
+    let mut builder = tar::Builder::new(encoder);
+    for entry in entries {
+        if cancelled() {
+            return Err(cancelled_error());
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata(&entry.metadata);
+        if entry.metadata.is_dir() {
+            builder.append_data(&mut header, &entry.name, io::empty())?;
+        } else if entry.metadata.is_symlink() {
+            builder.append_link(&mut header, &entry.name, fs::read_link(&entry.path)?)?;
+            on_file(&entry.path);
+        } else if entry.metadata.is_file() {
+            let reader = ProgressReader::new(File::open(&entry.path)?, &mut *on_bytes, cancelled);
+            builder.append_data(&mut header, &entry.name, reader)?;
+            on_file(&entry.path);
+        }
+    }
+    builder.into_inner()?.finish()?.flush()
+}
+
+// 把 sources 写入新的压缩包；失败或取消时删除未完成的压缩包
+pub fn write_archive(
+    request: &CompressRequest,
+    on_bytes: &mut dyn FnMut(u64),
+    on_file: &mut dyn FnMut(&Path),
+    cancelled: &dyn Fn() -> bool,
+) -> io::Result<()> {
+    let file = File::options().write(true).create_new(true).open(&request.archive)?;
+    let entries = collect_entries(&request.sources, &request.archive);
+    let level = request.level.min(MAX_LEVEL);
+    let result = match request.format {
+        ArchiveFormat::Zip => write_zip(&entries, file, level, on_bytes, on_file, cancelled),
+        ArchiveFormat::TarGz => write_tar_gz(&entries, file, level, on_bytes, on_file, cancelled),
+    };
+    if result.is_err() {
+        let _ = fs::remove_file(&request.archive);
+    }
+    result
+}
+
+// 不与已有文件重名的压缩包路径，如 "照片.zip"、"照片 (2).zip"
+fn unique_archive_path(dir: &Path, name: &str, format: ArchiveFormat) -> PathBuf {
+    (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{}.{}", name, format.extension())),
+            n => dir.join(format!("{} ({}).{}", name, n, format.extension())),
+        })
+        .find(|path| fs::symlink_metadata(path).is_err())
+        .unwrap_or_else(|| dir.join(format!("{}.{}", name, format.extension())))
+}
+
+// 默认名称：单个项目使用其名称（文件去掉扩展名），多个项目使用所在文件夹的名称
+fn default_archive_name(sources: &[PathBuf]) -> String {
+    let name = match sources {
+        [single] if single.is_dir() => single.file_name(),
+        [single] => single.file_stem(),
+        [first, ..] => first.parent().and_then(|p| p.file_name()),
+        [] => None,
+    };
+    name.map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "压缩文件".to_string())
+}
+
+fn level_label(level: u32) -> &'static str {
+    match level {
+        0 => "仅存储",
+        1..=3 => "快速",
+        4..=6 => "标准",
+        _ => "最高压缩率",
+    }
+}
+
+// "压缩为…" 窗口：选择格式、名称和压缩级别，压缩包放在选中项所在的文件夹
+pub struct CompressDialog {
+    sources: Vec<PathBuf>,
+    name: String,
+    format: ArchiveFormat,
+    level: u32,
+}
+
+impl CompressDialog {
+    pub fn new() -> Self {
+        Self { sources: Vec::new(), name: String::new(), format: ArchiveFormat::Zip, level: DEFAULT_LEVEL }
+    }
+
+    pub fn open(&mut self, sources: Vec<PathBuf>) {
+        self.name = default_archive_name(&sources);
+        self.sources = sources;
+    }
+
+    pub fn is_showing(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    // 显示窗口；点击“压缩”后返回压缩参数
+    pub fn show_compress_dialog(&mut self, ctx: &egui::Context) -> Option<CompressRequest> {
+        let dir = self.sources.first().and_then(|p| p.parent()).map(Path::to_path_buf).unwrap_or_default();
+        let name = self.name.trim().replace('/', "_");
+        let archive = unique_archive_path(&dir, if name.is_empty() { "压缩文件" } else { &name }, self.format);
+
+        let mut open = true;
+        let mut close = false;
+        let mut request = None;
+        egui::Window::new("压缩为")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let source_label = match self.sources.as_slice() {
+                    [single] => single.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    sources => format!("{} 项", sources.len()),
+                };
+                ui.label(format!("压缩: {}", source_label));
+                ui.separator();
+                egui::Grid::new("compress_grid").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                    ui.label("名称");
+                    ui.text_edit_singleline(&mut self.name);
+                    ui.end_row();
+                    ui.label("格式");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.format, ArchiveFormat::Zip, ".zip");
+                        ui.radio_value(&mut self.format, ArchiveFormat::TarGz, ".tar.gz");
+                    });
+                    ui.end_row();
+                    ui.label("压缩级别");
+                    let label = level_label(self.level);
+                    ui.add(egui::Slider::new(&mut self.level, 0..=MAX_LEVEL).text(label));
+                    ui.end_row();
+                });
+                // 重名时自动编号，显示实际的文件名
+                let file_name = archive.file_name().unwrap_or_default().to_string_lossy().to_string();
+                ui.label(egui::RichText::new(format!("将创建 {}", file_name)).weak());
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("压缩").clicked() {
+                        request = Some(CompressRequest {
+                            sources: self.sources.clone(),
+                            archive: archive.clone(),
+                            format: self.format,
+                            level: self.level,
+                        });
+                    }
+                    if ui.button("取消").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !open || close || request.is_some() {
+            self.sources.clear();
+        }
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_write_archive() {
+        let root = TestDir::new("archive_create");
+        fs::create_dir_all(root.join("photos/sub")).unwrap();
+        fs::write(root.join("photos/a.txt"), "hello").unwrap();
+        fs::write(root.join("photos/sub/b.txt"), "world").unwrap();
+        fs::write(root.join("photos.zip"), "").unwrap();
+
+        let sources = vec![root.join("photos")];
+        assert_eq!(default_archive_name(&sources), "photos");
+        let archive = unique_archive_path(&root, "photos", ArchiveFormat::Zip);
+        assert_eq!(archive, root.join("photos (2).zip"));
+
+        let request = CompressRequest { sources, archive: archive.clone(), format: ArchiveFormat::Zip, level: DEFAULT_LEVEL };
+        let (mut bytes, mut files) = (0, 0);
+        write_archive(&request, &mut |n| bytes += n, &mut |_| files += 1, &|| false).unwrap();
+        assert_eq!((bytes, files), (10, 2));
+        let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        let mut content = String::new();
+        zip.by_name("photos/sub/b.txt").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "world");
+
+        // 取消时不留下未完成的压缩包
+        let tar = CompressRequest { archive: root.join("photos.tar.gz"), format: ArchiveFormat::TarGz, ..request };
+        assert!(write_archive(&tar, &mut |_| {}, &mut |_| {}, &|| true).is_err());
+        assert!(!tar.archive.exists());
+    }
+
+    #[test]
+    fn test_cancel_while_reading_file() {
+        let root = TestDir::new("archive_cancel");
+        fs::write(root.join("big.bin"), vec![7u8; 4 * 1024 * 1024]).unwrap();
+
+        // 读到文件中间时取消，应立即结束而不是不断重试
+        for (format, name) in [(ArchiveFormat::Zip, "big.zip"), (ArchiveFormat::TarGz, "big.tar.gz")] {
+            let request = CompressRequest { sources: vec![root.join("big.bin")], archive: root.join(name), format, level: 1 };
+            let read = std::cell::Cell::new(0u64);
+            let result = write_archive(&request, &mut |n| read.set(read.get() + n), &mut |_| {}, &|| read.get() >= 1024 * 1024);
+            assert!(result.is_err());
+            assert!(read.get() < 4 * 1024 * 1024);
+            assert!(!request.archive.exists());
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use eframe::egui;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use zip::ZipArchive;
+use super::archive_create::{cancelled_error, ProgressReader};
+use super::text_encoding;
+
+// 支持解压的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExtractFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    SevenZip,
+}
+
+// 格式和对应的扩展名，较长的扩展名在前
+const EXTENSIONS: [(&str, ExtractFormat); 8] = [
+    (".tar.gz", ExtractFormat::TarGz),
+    (".tgz", ExtractFormat::TarGz),
+    (".tar.xz", ExtractFormat::TarXz),
+    (".txz", ExtractFormat::TarXz),
+    (".tar", ExtractFormat::Tar),
+    (".zip", ExtractFormat::Zip),
+    (".jar", ExtractFormat::Zip),
+    (".7z", ExtractFormat::SevenZip),
+];
+
+// 按文件名判断格式，返回格式和去掉扩展名后的名称
+fn split_archive_name(path: &Path) -> Option<(ExtractFormat, String)> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let lower = name.to_lowercase();
+    EXTENSIONS.iter()
+        .find(|(extension, _)| lower.len() > extension.len() && lower.ends_with(extension))
+        .map(|(extension, format)| (*format, name[..name.len() - extension.len()].to_string()))
+}
+
+pub fn is_extractable(path: &Path) -> bool {
+    split_archive_name(path).is_some()
+}
+
+// "解压到…" 的默认位置：压缩包旁边与其同名的文件夹
+fn default_destination(archive: &Path) -> PathBuf {
+    let dir = archive.parent().unwrap_or(Path::new("."));
+    let name = split_archive_name(archive).map(|(_, name)| name).unwrap_or_else(|| "解压".to_string());
+    dir.join(name)
+}
+
+// 压缩包内的名称转为相对路径：去掉开头的 / 和盘符，含 .. 时返回 None
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            part if relative.as_os_str().is_empty() && part.len() == 2 && part.ends_with(':') => {}
+            part => relative.push(part),
+        }
+    }
+    Some(relative)
+}
+
+// 位于 relative 的符号链接指向的位置是否仍在解压目录中
+fn link_stays_inside(relative: &Path, link: &Path) -> bool {
+    let mut depth = relative.components().count() as isize - 1;
+    for component in link.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth -= 1;
+                if depth < 0 {
+                    return false;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: Option<u32>) {
+    use std::os::unix::fs::PermissionsExt;
+    // 不恢复 setuid 等特殊位
+    if let Some(mode) = mode.map(|m| m & 0o777).filter(|m| *m != 0) {
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(mode));
+    }
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: Option<u32>) {}
+
+#[cfg(unix)]
+fn make_symlink(link: &[u8], relative: &Path, target: &Path) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    let link = Path::new(std::ffi::OsStr::from_bytes(link));
+    if !link_stays_inside(relative, link) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "符号链接指向解压目录之外，已跳过"));
+    }
+    std::os::unix::fs::symlink(link, target)
+}
+
+#[cfg(not(unix))]
+fn make_symlink(_link: &[u8], _relative: &Path, _target: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "当前系统不支持符号链接"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    Dir,
+    File,
+    Symlink, // 内容为链接目标
+}
+
+// 把条目写入解压目录；单个条目的问题通过 on_error 报告，只有取消时返回错误
+struct Extractor<'a> {
+    dest: &'a Path,
+    on_file: &'a mut dyn FnMut(&Path),
+    on_error: &'a mut dyn FnMut(&Path, String),
+    cancelled: &'a dyn Fn() -> bool,
+}
+
+impl Extractor<'_> {
+    fn write_entry(&mut self, name: &str, kind: EntryKind, reader: &mut dyn Read, mode: Option<u32>) -> io::Result<()> {
+        let Some(relative) = safe_relative(name) else {
+            (self.on_error)(Path::new(name), "路径包含 ..，已跳过".to_string());
+            return Ok(());
+        };
+        if relative.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let target = self.dest.join(&relative);
+        let result = self.write_target(&relative, &target, kind, reader, mode);
+        match result {
+            Ok(()) => {
+                if kind != EntryKind::Dir {
+                    (self.on_file)(&target);
+                }
+                Ok(())
+            }
+            Err(e) if (self.cancelled)() => Err(e),
+            Err(e) => {
+                // 读完跳过的内容，固实压缩包的后续条目依赖前面的数据
+                io::copy(reader, &mut io::sink())?;
+                (self.on_error)(&target, e.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    fn write_target(&self, relative: &Path, target: &Path, kind: EntryKind, reader: &mut dyn Read, mode: Option<u32>) -> io::Result<()> {
+        // 不经过符号链接写入，链接可能指向解压目录之外
+        let mut prefix = self.dest.to_path_buf();
+        for component in relative.parent().into_iter().flat_map(Path::components) {
+            prefix.push(component);
+            if fs::symlink_metadata(&prefix).is_ok_and(|m| m.file_type().is_symlink()) {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "路径经过符号链接，已跳过"));
+            }
+        }
+        if kind == EntryKind::Dir {
+            return fs::create_dir_all(target);
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::symlink_metadata(target).is_ok() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "已存在同名项目，未覆盖"));
+        }
+        if kind == EntryKind::Symlink {
+            let mut link = Vec::new();
+            reader.read_to_end(&mut link)?;
+            return make_symlink(&link, relative, target);
+        }
+        let mut file = File::options().write(true).create_new(true).open(target)?;
+        if let Err(e) = io::copy(reader, &mut file) {
+            drop(file);
+            let _ = fs::remove_file(target);
+            return Err(e);
+        }
+        set_mode(target, mode);
+        Ok(())
+    }
+}
+
+// 没有 UTF-8 标记的 zip 条目名称按系统编码保存（中文 Windows 上为 GBK），统一检测后解码
+fn zip_names<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Vec<String> {
+    let entries: Vec<(Vec<u8>, String)> = (0..archive.len())
+        .map(|index| match archive.by_index_raw(index) {
+            Ok(entry) => (entry.name_raw().to_vec(), entry.name().to_string()),
+            Err(_) => (Vec::new(), String::new()),
+        })
+        .collect();
+    let legacy: Vec<u8> = entries.iter()
+        .filter(|(raw, _)| std::str::from_utf8(raw).is_err())
+        .flat_map(|(raw, _)| raw.iter().copied().chain([b'\n']))
+        .collect();
+    let encoding = text_encoding::detect(&legacy);
+    entries.into_iter()
+        .map(|(raw, cp437)| match std::str::from_utf8(&raw) {
+            Ok(name) => name.to_string(),
+            // 检测不出东亚编码时使用 zip 规定的 CP437
+            Err(_) if encoding == encoding_rs::WINDOWS_1252 => cp437,
+            Err(_) => encoding.decode(&raw).0.into_owned(),
+        })
+        .collect()
+}
+
+fn extract_zip<R: Read + Seek>(reader: R, extractor: &mut Extractor, cancelled: &dyn Fn() -> bool) -> io::Result<()> {
+    let mut archive = ZipArchive::new(reader).map_err(io::Error::from)?;
+    let names = zip_names(&mut archive);
+    for (index, name) in names.iter().enumerate() {
+        if cancelled() {
+            return Err(cancelled_error());
+        }
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                let e = io::Error::from(e);
+                if cancelled() {
+                    return Err(e);
+                }
+                (extractor.on_error)(Path::new(name), e.to_string());
+                continue;
+            }
+        };
+        let kind = if entry.is_dir() {
+            EntryKind::Dir
+        } else if entry.is_symlink() {
+            EntryKind::Symlink
+        } else {
+            EntryKind::File
+        };
+        let mode = entry.unix_mode();
+        extractor.write_entry(name, kind, &mut entry, mode)?;
+    }
+    Ok(())
+}
+
+fn extract_tar(reader: impl Read, extractor: &mut Extractor) -> io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        if (extractor.cancelled)() {
+            return Err(cancelled_error());
+        }
+        let mut entry = entry?;
+        let name = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+        let entry_type = entry.header().entry_type();
+        let mode = entry.header().mode().ok();
+        if entry_type.is_dir() {
+            extractor.write_entry(&name, EntryKind::Dir, &mut entry, mode)?;
+        } else if entry_type.is_symlink() {
+            let link = entry.link_name_bytes().map(|link| link.to_vec()).unwrap_or_default();
+            extractor.write_entry(&name, EntryKind::Symlink, &mut link.as_slice(), None)?;
+        } else if entry_type.is_file() || entry_type.is_contiguous() {
+            extractor.write_entry(&name, EntryKind::File, &mut entry, mode)?;
+        } else if !entry_type.is_pax_global_extensions() {
+            (extractor.on_error)(Path::new(&name), "不支持的条目类型，已跳过".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn seven_zip_error(error: sevenz_rust::Error) -> io::Error {
+    match error {
+        sevenz_rust::Error::Io(e, _) => e,
+        e => io::Error::other(e.to_string()),
+    }
+}
+
+fn extract_seven_zip<R: Read + Seek>(reader: R, len: u64, extractor: &mut Extractor) -> io::Result<()> {
+    let mut archive = sevenz_rust::SevenZReader::new(reader, len, sevenz_rust::Password::empty()).map_err(seven_zip_error)?;
+    let mut result = Ok(());
+    archive.for_each_entries(|entry, reader| {
+        if entry.is_anti_item() {
+            return Ok(true);
+        }
+        let kind = if entry.is_directory() { EntryKind::Dir } else { EntryKind::File };
+        match extractor.write_entry(entry.name(), kind, reader, None) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                result = Err(e);
+                Ok(false)
+            }
+        }
+    }).map_err(seven_zip_error)?;
+    result
+}
+
+// 把压缩包解压到 dest（不存在时创建）；已存在的文件不覆盖，包含 .. 或指向外部的条目跳过并报告
+pub fn extract_archive(
+    archive: &Path,
+    dest: &Path,
+    on_bytes: &mut dyn FnMut(u64),
+    on_file: &mut dyn FnMut(&Path),
+    on_error: &mut dyn FnMut(&Path, String),
+    cancelled: &dyn Fn() -> bool,
+) -> io::Result<()> {
+    let (format, _) = split_archive_name(archive).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "不是支持的压缩包格式"))?;
+    fs::create_dir_all(dest)?;
+    let file = File::open(archive)?;
+    let len = file.metadata()?.len();
+    let reader = BufReader::new(ProgressReader::new(file, on_bytes, cancelled));
+    let mut extractor = Extractor { dest, on_file, on_error, cancelled };
+    match format {
+        ExtractFormat::Zip => extract_zip(reader, &mut extractor, cancelled),
+        ExtractFormat::Tar => extract_tar(reader, &mut extractor),
+        ExtractFormat::TarGz => extract_tar(flate2::read::GzDecoder::new(reader), &mut extractor),
+        ExtractFormat::TarXz => extract_tar(xz2::read::XzDecoder::new(reader), &mut extractor),
+        ExtractFormat::SevenZip => extract_seven_zip(reader, len, &mut extractor),
+    }
+}
+
+// "解压到…" 窗口：输入目标文件夹，默认为压缩包旁边的同名文件夹
+pub struct ExtractDialog {
+    archive: Option<PathBuf>,
+    destination: String,
+}
+
+impl ExtractDialog {
+    pub fn new() -> Self {
+        Self { archive: None, destination: String::new() }
+    }
+
+    pub fn open(&mut self, archive: PathBuf) {
+        self.destination = default_destination(&archive).display().to_string();
+        self.archive = Some(archive);
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.archive.is_some()
+    }
+
+    // 显示窗口；点击“解压”后返回压缩包和目标文件夹
+    pub fn show_extract_dialog(&mut self, ctx: &egui::Context) -> Option<(PathBuf, PathBuf)> {
+        let archive = self.archive.clone()?;
+        let mut open = true;
+        let mut close = false;
+        let mut result = None;
+        egui::Window::new("解压到")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(460.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("解压: {}", archive.file_name().unwrap_or_default().to_string_lossy()));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("目标文件夹:");
+                    ui.add(egui::TextEdit::singleline(&mut self.destination).desired_width(320.0));
+                });
+                ui.label(egui::RichText::new("文件夹不存在时自动创建，已存在的文件不会被覆盖").weak());
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let destination = self.destination.trim();
+                    if ui.add_enabled(!destination.is_empty(), egui::Button::new("解压")).clicked() {
+                        result = Some((archive.clone(), PathBuf::from(destination)));
+                    }
+                    if ui.button("取消").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !open || close || result.is_some() {
+            self.archive = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+    use std::io::Write;
+
+    #[test]
+    fn test_extract_archive() {
+        assert_eq!(split_archive_name(Path::new("/a/Backup.Tar.XZ")), Some((ExtractFormat::TarXz, "Backup".to_string())));
+        assert_eq!(default_destination(Path::new("/a/photos.zip")), PathBuf::from("/a/photos"));
+        assert_eq!(safe_relative("/abs/./x.txt"), Some(PathBuf::from("abs/x.txt")));
+        assert_eq!(safe_relative("C:\\docs\\a.txt"), Some(PathBuf::from("docs/a.txt")));
+        assert_eq!(safe_relative("a/../../etc/passwd"), None);
+        assert!(link_stays_inside(Path::new("a/b/link"), Path::new("../../c")));
+        assert!(!link_stays_inside(Path::new("a/link"), Path::new("../../c")));
+        assert!(!link_stays_inside(Path::new("link"), Path::new("/etc")));
+
+        let root = TestDir::new("archive_extract");
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, data) in [("docs/readme.txt", &b"hello"[..]), ("../escape.txt", &b"bad"[..])] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            // append_data 会拒绝 ..，直接写入名称字段
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_cksum();
+            builder.append(&header, data).unwrap();
+        }
+        let archive = root.join("test.tar");
+        fs::write(&archive, builder.into_inner().unwrap()).unwrap();
+
+        let dest = root.join("out");
+        let (mut files, mut errors) = (Vec::new(), Vec::new());
+        extract_archive(&archive, &dest, &mut |_| {}, &mut |path| files.push(path.to_path_buf()), &mut |path, _| errors.push(path.to_path_buf()), &|| false).unwrap();
+        assert_eq!(files, vec![dest.join("docs/readme.txt")]);
+        assert_eq!(errors, vec![PathBuf::from("../escape.txt")]);
+        assert_eq!(fs::read_to_string(dest.join("docs/readme.txt")).unwrap(), "hello");
+        assert!(!root.join("escape.txt").exists());
+
+        // 再次解压时不覆盖已有文件
+        errors.clear();
+        extract_archive(&archive, &dest, &mut |_| {}, &mut |_| {}, &mut |path, _| errors.push(path.to_path_buf()), &|| false).unwrap();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_cancel_inside_entry() {
+        let root = TestDir::new("archive_extract_cancel");
+        let data = vec![7u8; 4 * 1024 * 1024];
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, "big.bin", data.as_slice()).unwrap();
+        fs::write(root.join("big.tar"), builder.into_inner().unwrap()).unwrap();
+        let mut zip = zip::ZipWriter::new(File::create(root.join("big.zip")).unwrap());
+        zip.start_file("big.bin", zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored)).unwrap();
+        zip.write_all(&data).unwrap();
+        zip.finish().unwrap();
+
+        // 条目读到一半时取消：立即结束，并删除写了一半的文件
+        for name in ["big.tar", "big.zip"] {
+            let dest = root.join(format!("{}.out", name));
+            let read = std::cell::Cell::new(0u64);
+            let result = extract_archive(&root.join(name), &dest, &mut |n| read.set(read.get() + n), &mut |_| {}, &mut |_, _| {}, &|| read.get() >= 1024 * 1024);
+            assert!(result.is_err());
+            assert!(read.get() < data.len() as u64);
+            assert!(!dest.join("big.bin").exists());
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// 列出的条目数上限，超大压缩包只显示前面的部分
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZip,
+    Rar,
+}
+
+impl ArchiveFormat {
+    // 按文件名判断格式（.tar.gz 需要看两级扩展名）
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") || name.ends_with(".jar") {
+            Some(Self::Zip)
+        } else if name.ends_with(".7z") {
+            Some(Self::SevenZip)
+        } else if name.ends_with(".rar") {
+            Some(Self::Rar)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Zip => "ZIP",
+            Self::Tar => "TAR",
+            Self::TarGz => "TAR.GZ",
+            Self::SevenZip => "7Z",
+            Self::Rar => "RAR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub size: u64,
+    pub compressed_size: Option<u64>, // tar.gz 整体压缩，单个条目没有压缩后大小
+    pub is_dir: bool,
+}
+
+pub struct ArchiveListing {
+    pub format: &'static str,
+    pub entries: Vec<ArchiveEntry>,
+    pub truncated: bool,
+}
+
+pub fn is_archive_file(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some()
+}
+
+fn list_zip(path: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    for index in 0..archive.len().min(MAX_ENTRIES + 1) {
+        let entry = archive.by_index_raw(index).map_err(|e| e.to_string())?;
+        entries.push(ArchiveEntry {
+            name: entry.name().to_string(),
+            size: entry.size(),
+            compressed_size: Some(entry.compressed_size()),
+            is_dir: entry.is_dir(),
+        });
+    }
+    Ok(entries)
+}
+
+// 逐个读取 tar 头，跳过内容
+fn list_tar(reader: impl Read) -> Result<Vec<ArchiveEntry>, String> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    for entry in archive.entries().map_err(|e| e.to_string())?.take(MAX_ENTRIES + 1) {
+        let entry = entry.map_err(|e| e.to_string())?;
+        entries.push(ArchiveEntry {
+            name: entry.path().map(|p| p.display().to_string()).unwrap_or_default(),
+            size: entry.header().size().unwrap_or(0),
+            compressed_size: None,
+            is_dir: entry.header().entry_type().is_dir(),
+        });
+    }
+    Ok(entries)
+}
+
+fn list_seven_zip(path: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let archive = sevenz_rust::Archive::read(&mut file, len, &[]).map_err(|e| e.to_string())?;
+    Ok(archive.files.iter().take(MAX_ENTRIES + 1).map(|entry| ArchiveEntry {
+        name: entry.name().to_string(),
+        size: entry.size(),
+        // 固实压缩时只有每组的第一个文件记录压缩后大小
+        compressed_size: Some(entry.compressed_size).filter(|&size| size > 0),
+        is_dir: entry.is_directory(),
+    }).collect())
+}
+
+// 解析 `7z l -slt` 的输出：每个条目是一组 "键 = 值" 行，条目之间以空行分隔
+fn parse_7z_listing(output: &str) -> Vec<ArchiveEntry> {
+    // 条目列表从 "----------" 之后开始，前面是压缩包本身的信息
+    let body = output.split_once("\n----------").map(|(_, body)| body).unwrap_or(output);
+    body.split("\n\n")
+        .filter_map(|block| {
+            let field = |key: &str| block.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix(" = "));
+            Some(ArchiveEntry {
+                name: field("Path")?.to_string(),
+                size: field("Size").and_then(|s| s.parse().ok()).unwrap_or(0),
+                compressed_size: field("Packed Size").and_then(|s| s.parse().ok()),
+                is_dir: field("Folder") == Some("+") || field("Attributes").is_some_and(|a| a.starts_with('D')),
+            })
+        })
+        .take(MAX_ENTRIES + 1)
+        .collect()
+}
+
+// RAR 没有纯 Rust 的读取库，通过已安装的 7-Zip 列出
+fn list_rar(path: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    for program in ["7z", "7zz"] {
+        let output = Command::new(program)
+            .args(["l", "-slt"])
+            .arg(path)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        if let Ok(output) = output {
+            if output.status.success() {
+                return Ok(parse_7z_listing(&String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n")));
+            }
+        }
+    }
+    Err("读取 RAR 需要安装 7-Zip（7z 命令）".to_string())
+}
+
+// 列出压缩包中的条目；应在后台线程调用
+pub fn list_archive(path: &Path) -> Result<ArchiveListing, String> {
+    let format = ArchiveFormat::from_path(path).ok_or_else(|| "不是支持的压缩包格式".to_string())?;
+    let mut entries = match format {
+        ArchiveFormat::Zip => list_zip(path),
+        ArchiveFormat::Tar => list_tar(BufReader::new(fs::File::open(path).map_err(|e| e.to_string())?)),
+        ArchiveFormat::TarGz => {
+            let file = fs::File::open(path).map_err(|e| e.to_string())?;
+            list_tar(flate2::read::GzDecoder::new(BufReader::new(file)))
+        }
+        ArchiveFormat::SevenZip => list_seven_zip(path),
+        ArchiveFormat::Rar => list_rar(path),
+    }?;
+    let truncated = entries.len() > MAX_ENTRIES;
+    entries.truncate(MAX_ENTRIES);
+    Ok(ArchiveListing { format: format.label(), entries, truncated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_listing() {
+        assert_eq!(ArchiveFormat::from_path(Path::new("backup.TAR.GZ")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("photos.zip")), Some(ArchiveFormat::Zip));
+        assert!(!is_archive_file(Path::new("notes.gz")));
+
+        let output = "\
+7-Zip 23.01\n\nListing archive: a.rar\n\n--\nPath = a.rar\nType = Rar5\n\n----------\n\
+Path = docs\nFolder = +\nSize = 0\nPacked Size = 0\n\n\
+Path = docs/readme.txt\nFolder = -\nSize = 1200\nPacked Size = 512\n\n";
+        assert_eq!(parse_7z_listing(output), vec![
+            ArchiveEntry { name: "docs".to_string(), size: 0, compressed_size: Some(0), is_dir: true },
+            ArchiveEntry { name: "docs/readme.txt".to_string(), size: 1200, compressed_size: Some(512), is_dir: false },
+        ]);
+
+        // 在内存中构造 tar 包
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_cksum();
+        builder.append_data(&mut header, "hello.txt", &b"hello"[..]).unwrap();
+        let data = builder.into_inner().unwrap();
+        let entries = list_tar(&data[..]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].name.as_str(), entries[0].size, entries[0].is_dir), ("hello.txt", 5, false));
+    }
+}
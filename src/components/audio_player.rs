@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+// 可用于试听的外部播放器，都支持从指定秒数开始播放
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Backend {
+    Ffplay,
+    Mpv,
+}
+
+impl Backend {
+    fn program(self) -> &'static str {
+        match self {
+            Backend::Ffplay => "ffplay",
+            Backend::Mpv => "mpv",
+        }
+    }
+
+    fn version_arg(self) -> &'static str {
+        match self {
+            Backend::Ffplay => "-version",
+            Backend::Mpv => "--version",
+        }
+    }
+
+    fn command(self, path: &Path, start: f64) -> Command {
+        let start = format!("{:.3}", start.max(0.0));
+        let mut command = Command::new(self.program());
+        match self {
+            Backend::Ffplay => command.args(["-nodisp", "-autoexit", "-loglevel", "quiet", "-ss", &start]),
+            Backend::Mpv => command.args(["--no-video", "--really-quiet", &format!("--start={}", start)]),
+        };
+        command.arg(path).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+        command
+    }
+}
+
+// 查找可用的播放器（只检查一次），优先使用随 ffmpeg 安装的 ffplay
+fn backend() -> Option<Backend> {
+    static BACKEND: OnceLock<Option<Backend>> = OnceLock::new();
+    *BACKEND.get_or_init(|| {
+        [Backend::Ffplay, Backend::Mpv].into_iter().find(|backend| {
+            Command::new(backend.program())
+                .arg(backend.version_arg())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success())
+        })
+    })
+}
+
+pub fn player_available() -> bool {
+    backend().is_some()
+}
+
+// 用外部播放器试听音频文件。暂停时结束播放进程并记住位置，继续或拖动进度时从该位置重新启动
+pub struct AudioPlayer {
+    path: PathBuf,
+    child: Option<Child>,
+    started: Option<Instant>,
+    offset: f64, // 本次播放开始时的位置（秒）
+}
+
+impl AudioPlayer {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            child: None,
+            started: None,
+            offset: 0.0,
+        }
+    }
+
+    // 是否正在播放；播放进程自行结束（播完）时回到开头
+    pub fn is_playing(&mut self) -> bool {
+        let finished = match &mut self.child {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => return false,
+        };
+        if finished {
+            self.child = None;
+            self.started = None;
+            self.offset = 0.0;
+        }
+        !finished
+    }
+
+    pub fn position(&self) -> f64 {
+        self.offset + self.started.map(|s| s.elapsed().as_secs_f64()).unwrap_or(0.0)
+    }
+
+    pub fn play(&mut self) -> Result<(), String> {
+        self.stop_process();
+        let backend = backend().ok_or_else(|| "未找到 ffplay 或 mpv，无法播放".to_string())?;
+        let child = backend.command(&self.path, self.offset).spawn().map_err(|e| format!("无法启动播放器: {}", e))?;
+        self.child = Some(child);
+        self.started = Some(Instant::now());
+        Ok(())
+    }
+
+    pub fn pause(&mut self) {
+        self.offset = self.position();
+        self.stop_process();
+    }
+
+    // 跳到指定位置，正在播放时从新位置继续
+    pub fn seek(&mut self, seconds: f64) -> Result<(), String> {
+        let playing = self.is_playing();
+        self.stop_process();
+        self.offset = seconds.max(0.0);
+        if playing {
+            self.play()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn stop_process(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.started = None;
+    }
+}
+
+impl Drop for AudioPlayer {
+    fn drop(&mut self) {
+        self.stop_process();
+    }
+}
@@ -0,0 +1,120 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use super::app_event::{AppEvent, EventQueue};
+use super::file_list::{DraggedFiles, FileDropRequest};
+
+// 暂存篮：从不同文件夹收集文件，最后一次性复制或移动到同一个目标。只在本次运行中保留
+pub struct FileBasket {
+    show_panel: bool,
+    items: Vec<PathBuf>,
+    target: String, // 目标文件夹，打开时默认为当前目录
+}
+
+impl FileBasket {
+    pub fn new() -> Self {
+        Self {
+            show_panel: false,
+            items: Vec::new(),
+            target: String::new(),
+        }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show_panel
+    }
+
+    pub fn open(&mut self, current_path: &Path) {
+        if !self.show_panel {
+            self.target = current_path.display().to_string();
+        }
+        self.show_panel = true;
+    }
+
+    // 加入文件，已在篮中的忽略
+    pub fn add(&mut self, paths: Vec<PathBuf>, current_path: &Path) {
+        for path in paths {
+            if !self.items.contains(&path) {
+                self.items.push(path);
+            }
+        }
+        self.open(current_path);
+    }
+
+    // 复制或移动全部文件；复制后保留以便再复制到其它位置。移动在确认并执行后，
+    // 已移走的文件由 show_basket_panel 移出暂存篮，取消或移动失败的文件仍然保留
+    fn transfer(&self, copy: bool, events: &mut EventQueue) {
+        let target_dir = PathBuf::from(self.target.trim());
+        events.emit(AppEvent::Drop(FileDropRequest { paths: self.items.clone(), target_dir, copy }));
+    }
+
+    // 显示暂存篮窗口；可以把文件拖到窗口中加入
+    pub fn show_basket_panel(&mut self, ctx: &egui::Context, current_path: &Path, events: &mut EventQueue) {
+        // 已被删除或移走的文件不再保留
+        self.items.retain(|path| path.exists());
+        let mut open = true;
+
+        let response = egui::Window::new(format!("暂存篮 ({})", self.items.len()))
+            .resizable(true)
+            .default_pos(ctx.screen_rect().right_top() + egui::vec2(-340.0, 80.0))
+            .default_size(egui::vec2(320.0, 360.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.items.is_empty() {
+                    ui.label("在右键菜单中选择“加入暂存篮”，或把文件拖到这里");
+                }
+                let mut remove = None;
+                egui::ScrollArea::vertical().max_height(220.0).auto_shrink([false, true]).show(ui, |ui| {
+                    for (index, path) in self.items.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✕").on_hover_text("移出暂存篮").clicked() {
+                                remove = Some(index);
+                            }
+                            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                            let icon = if path.is_dir() { "📁" } else { "📄" };
+                            if ui.link(format!("{} {}", icon, name)).on_hover_text(path.display().to_string()).clicked() {
+                                events.emit(AppEvent::Reveal(path.clone()));
+                            }
+                        });
+                    }
+                });
+                if let Some(index) = remove {
+                    self.items.remove(index);
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("目标:");
+                    ui.add(egui::TextEdit::singleline(&mut self.target).desired_width(200.0));
+                    if ui.small_button("当前文件夹").clicked() {
+                        self.target = current_path.display().to_string();
+                    }
+                });
+                let target_ok = Path::new(self.target.trim()).is_dir();
+                if !target_ok && !self.target.trim().is_empty() {
+                    ui.colored_label(egui::Color32::RED, "目标文件夹不存在");
+                }
+                ui.horizontal(|ui| {
+                    let enabled = target_ok && !self.items.is_empty();
+                    if ui.add_enabled(enabled, egui::Button::new("全部复制到目标")).clicked() {
+                        self.transfer(true, events);
+                    }
+                    if ui.add_enabled(enabled, egui::Button::new("全部移动到目标")).clicked() {
+                        self.transfer(false, events);
+                    }
+                    if ui.add_enabled(!self.items.is_empty(), egui::Button::new("清空")).clicked() {
+                        self.items.clear();
+                    }
+                });
+            });
+
+        // 从列表拖入的文件加入暂存篮
+        if let Some(response) = response {
+            if let Some(payload) = response.response.dnd_release_payload::<DraggedFiles>() {
+                self.add(payload.0.clone(), current_path);
+            }
+        }
+        if !open {
+            self.show_panel = false;
+        }
+    }
+}
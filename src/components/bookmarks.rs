@@ -0,0 +1,137 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use super::app_event::{AppEvent, EventQueue};
+
+pub const SLOT_COUNT: usize = 9;
+
+// Ctrl+1..9 对应的文件夹书签，保存在配置目录的 bookmark_slots.json 中
+pub struct BookmarkSlots {
+    file_path: Option<PathBuf>,
+    slots: Vec<Option<PathBuf>>,
+}
+
+// 数字键对应的书签位置（0..9）
+fn slot_of_key(key: egui::Key) -> Option<usize> {
+    use egui::Key::*;
+    [Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9].iter().position(|k| *k == key)
+}
+
+impl BookmarkSlots {
+    // 从配置文件加载，文件不存在或损坏时所有位置为空
+    pub fn load() -> Self {
+        let file_path = dirs::config_dir().map(|dir| dir.join("file-explorer").join("bookmark_slots.json"));
+        let mut slots: Vec<Option<PathBuf>> = file_path
+            .as_ref()
+            .and_then(|path| crate::utils::load_json(path))
+            .unwrap_or_default();
+        slots.resize(SLOT_COUNT, None);
+        Self { file_path, slots }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if let Err(e) = crate::utils::save_json(path, &self.slots) {
+            eprintln!("保存书签失败: {}", e);
+        }
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&PathBuf> {
+        self.slots.get(slot)?.as_ref()
+    }
+
+    // 设置书签；同一文件夹只占一个位置
+    pub fn set(&mut self, slot: usize, path: &Path) {
+        if slot >= SLOT_COUNT {
+            return;
+        }
+        for existing in self.slots.iter_mut() {
+            if existing.as_deref() == Some(path) {
+                *existing = None;
+            }
+        }
+        self.slots[slot] = Some(path.to_path_buf());
+        self.save();
+    }
+
+    pub fn clear(&mut self, slot: usize) {
+        if let Some(existing) = self.slots.get_mut(slot) {
+            *existing = None;
+            self.save();
+        }
+    }
+
+    // 处理快捷键：Ctrl+数字跳转到书签，Ctrl+Shift+数字把 folder 设为该位置的书签。
+    // 按物理键判断，Shift+数字在部分键盘布局上输入的是符号
+    pub fn handle_shortcuts(&mut self, ctx: &egui::Context, folder: &Path, events: &mut EventQueue) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let pressed: Vec<(usize, bool)> = ctx.input(|i| {
+            i.events.iter().filter_map(|event| match event {
+                egui::Event::Key { key, physical_key, pressed: true, repeat: false, modifiers } if modifiers.command && !modifiers.alt => {
+                    slot_of_key(physical_key.unwrap_or(*key)).map(|slot| (slot, modifiers.shift))
+                }
+                _ => None,
+            }).collect()
+        });
+        for (slot, assign) in pressed {
+            if assign {
+                self.set(slot, folder);
+            } else if let Some(path) = self.get(slot) {
+                events.emit(AppEvent::Navigate(path.clone()));
+            }
+        }
+    }
+
+    // 目录栏顶部的书签列表，显示编号；右键可清除
+    pub fn show(&mut self, ui: &mut egui::Ui, current_path: &Path, events: &mut EventQueue) {
+        egui::CollapsingHeader::new("书签").id_salt("bookmark_slots").default_open(true).show(ui, |ui| {
+            let mut cleared = None;
+            let mut any = false;
+            for (slot, path) in self.slots.iter().enumerate() {
+                let Some(path) = path else {
+                    continue;
+                };
+                any = true;
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string());
+                let text = egui::RichText::new(format!("{}  📁 {}", slot + 1, name));
+                let response = ui.selectable_label(path == current_path, text)
+                    .on_hover_text(format!("{}\nCtrl+{} 跳转", path.display(), slot + 1));
+                if response.clicked() {
+                    events.emit(AppEvent::Navigate(path.clone()));
+                }
+                response.context_menu(|ui| {
+                    if ui.button("清除书签").clicked() {
+                        cleared = Some(slot);
+                        ui.close_menu();
+                    }
+                });
+            }
+            if let Some(slot) = cleared {
+                self.clear(slot);
+            }
+            if !any {
+                ui.label(egui::RichText::new("按 Ctrl+Shift+1..9 把当前文件夹设为书签").weak());
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmark_slots() {
+        let mut bookmarks = BookmarkSlots { file_path: None, slots: vec![None; SLOT_COUNT] };
+        bookmarks.set(0, Path::new("/tmp/a"));
+        bookmarks.set(4, Path::new("/tmp/a"));
+        assert_eq!(bookmarks.get(0), None);
+        assert_eq!(bookmarks.get(4), Some(&PathBuf::from("/tmp/a")));
+        bookmarks.set(SLOT_COUNT, Path::new("/tmp/b"));
+        assert_eq!(slot_of_key(egui::Key::Num9), Some(8));
+        assert_eq!(slot_of_key(egui::Key::Num0), None);
+    }
+}
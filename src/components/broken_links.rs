@@ -0,0 +1,256 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver};
+
+// 目标不存在的符号链接
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+    pub path: PathBuf,
+    pub target: PathBuf, // 链接中记录的目标（可能是相对路径）
+}
+
+// 递归查找断开的符号链接，不进入链接指向的文件夹；取消时返回 None
+pub fn find_broken_links(root: &Path, cancelled: &AtomicBool) -> Option<Vec<BrokenLink>> {
+    let mut found = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_symlink() && fs::metadata(&path).is_err() {
+                let target = fs::read_link(&path).unwrap_or_default();
+                found.push(BrokenLink { path, target });
+            }
+        }
+    }
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(found)
+}
+
+// 把链接改为指向新目标：在同一文件夹中创建临时链接，再改名替换旧链接，失败时旧链接保持不变
+#[cfg(unix)]
+pub fn retarget_link(link: &Path, new_target: &Path) -> Result<(), String> {
+    // 相对路径按链接所在目录解析后检查目标是否存在
+    let resolved = link.parent().map(|dir| dir.join(new_target)).unwrap_or_else(|| new_target.to_path_buf());
+    if !resolved.exists() {
+        return Err(format!("新目标不存在: {}", resolved.display()));
+    }
+    let name = link.file_name().ok_or_else(|| "无效的链接路径".to_string())?;
+    let temp = link.with_file_name(format!(".{}.retarget-{}", name.to_string_lossy(), std::process::id()));
+    std::os::unix::fs::symlink(new_target, &temp).map_err(|e| format!("创建链接失败: {}", e))?;
+    fs::rename(&temp, link).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("替换旧链接失败: {}", e)
+    })
+}
+
+#[cfg(not(unix))]
+pub fn retarget_link(_link: &Path, _new_target: &Path) -> Result<(), String> {
+    Err("当前平台不支持修改符号链接".to_string())
+}
+
+// 断开的符号链接查找对话框
+pub struct BrokenLinkFinder {
+    receiver: Option<Receiver<Vec<BrokenLink>>>,
+    cancelled: Arc<AtomicBool>,
+    root: PathBuf,
+    links: Option<Vec<BrokenLink>>,
+    retarget: Option<(usize, String)>, // 正在修改目标的条目下标和输入的新目标
+    message: Option<String>,
+}
+
+impl BrokenLinkFinder {
+    pub fn new() -> Self {
+        Self {
+            receiver: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            root: PathBuf::new(),
+            links: None,
+            retarget: None,
+            message: None,
+        }
+    }
+
+    // 开始在后台扫描文件夹
+    pub fn start(&mut self, root: PathBuf) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.cancelled = Arc::new(AtomicBool::new(false));
+        self.root = root.clone();
+        self.links = None;
+        self.retarget = None;
+        self.message = None;
+
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let cancel_flag = self.cancelled.clone();
+        std::thread::spawn(move || {
+            if let Some(links) = find_broken_links(&root, &cancel_flag) {
+                let _ = sender.send(links);
+            }
+        });
+        self.receiver = Some(receiver);
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.receiver.is_some() || self.links.is_some()
+    }
+
+    // 显示对话框，删除或修改了链接时返回 true 以便刷新列表
+    pub fn show_broken_links_dialog(&mut self, ctx: &egui::Context) -> bool {
+        if let Some(receiver) = &self.receiver {
+            if let Ok(links) = receiver.try_recv() {
+                self.links = Some(links);
+                self.receiver = None;
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+
+        let mut open = true;
+        let mut changed = false;
+        let mut delete_indices: Vec<usize> = Vec::new();
+        let mut retarget_commit: Option<(usize, String)> = None;
+        let mut cancel_retarget = false;
+
+        egui::Window::new("断开的符号链接")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_size(egui::Vec2::new(600.0, 360.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("文件夹: {}", self.root.display()));
+                if let Some(message) = &self.message {
+                    ui.colored_label(ui.visuals().warn_fg_color, message);
+                }
+                ui.separator();
+
+                let Some(links) = &self.links else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("正在扫描...");
+                    });
+                    return;
+                };
+                if links.is_empty() {
+                    ui.label("没有找到断开的符号链接。");
+                    return;
+                }
+
+                ui.label(format!("找到 {} 个断开的符号链接", links.len()));
+                egui::ScrollArea::vertical().max_height(ui.available_height() - 40.0).show(ui, |ui| {
+                    for (index, link) in links.iter().enumerate() {
+                        let relative = link.path.strip_prefix(&self.root).unwrap_or(&link.path);
+                        ui.horizontal(|ui| {
+                            ui.label(relative.to_string_lossy());
+                            ui.label(egui::RichText::new(format!("→ {}", link.target.display())).color(ui.visuals().error_fg_color));
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("删除").clicked() {
+                                    delete_indices.push(index);
+                                }
+                                if ui.small_button("重新指向...").clicked() {
+                                    self.retarget = Some((index, link.target.to_string_lossy().to_string()));
+                                }
+                            });
+                        });
+                        if let Some((editing, text)) = self.retarget.as_mut() {
+                            if *editing == index {
+                                ui.horizontal(|ui| {
+                                    ui.label("新目标:");
+                                    ui.add(egui::TextEdit::singleline(text).desired_width(320.0));
+                                    if ui.button("确定").clicked() {
+                                        retarget_commit = Some((index, text.clone()));
+                                    }
+                                    if ui.button("取消").clicked() {
+                                        cancel_retarget = true;
+                                    }
+                                });
+                            }
+                        }
+                    }
+                });
+
+                ui.separator();
+                if ui.button("全部删除").clicked() {
+                    delete_indices = (0..links.len()).collect();
+                }
+            });
+
+        if cancel_retarget {
+            self.retarget = None;
+        }
+        if let Some(links) = self.links.as_mut() {
+            if let Some((index, text)) = retarget_commit {
+                match retarget_link(&links[index].path, Path::new(text.trim())) {
+                    Ok(()) => {
+                        links.remove(index);
+                        self.retarget = None;
+                        self.message = None;
+                        changed = true;
+                    }
+                    Err(e) => self.message = Some(e),
+                }
+            }
+
+            // 从后往前删除，保持下标有效
+            for index in delete_indices.into_iter().rev() {
+                match fs::remove_file(&links[index].path) {
+                    Ok(()) => {
+                        links.remove(index);
+                        changed = true;
+                    }
+                    Err(e) => self.message = Some(format!("删除 {} 失败: {}", links[index].path.display(), e)),
+                }
+            }
+            if changed {
+                self.retarget = None;
+            }
+        }
+
+        if !open {
+            self.cancelled.store(true, Ordering::Relaxed);
+            self.receiver = None;
+            self.links = None;
+        }
+        changed
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_find_and_retarget_broken_links() {
+        let root = TestDir::new("links");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("real.txt"), "ok").unwrap();
+        std::os::unix::fs::symlink("real.txt", root.join("good")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", root.join("sub").join("bad")).unwrap();
+
+        let cancelled = AtomicBool::new(false);
+        let found = find_broken_links(&root, &cancelled).unwrap();
+        assert_eq!(found, vec![BrokenLink { path: root.join("sub").join("bad"), target: PathBuf::from("missing.txt") }]);
+
+        assert!(retarget_link(&found[0].path, Path::new("nothing_here")).is_err());
+        retarget_link(&found[0].path, Path::new("../real.txt")).unwrap();
+        assert_eq!(fs::read_to_string(root.join("sub").join("bad")).unwrap(), "ok");
+        assert!(find_broken_links(&root, &cancelled).unwrap().is_empty());
+        // 替换后不留下临时链接
+        assert_eq!(fs::read_dir(root.join("sub")).unwrap().count(), 1);
+    }
+}
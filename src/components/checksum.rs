@@ -0,0 +1,352 @@
+use eframe::egui;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver, Sender};
+use sha2::{Digest, Sha256};
+
+// 默认的校验清单文件名
+pub const MANIFEST_NAME: &str = "SHA256SUMS";
+
+// 后台校验线程发送的事件
+enum ChecksumEvent {
+    Progress { done: usize, total: usize, current: String },
+    Finished(Result<ChecksumOutcome, String>),
+}
+
+// 校验任务的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChecksumOutcome {
+    Generated { manifest: PathBuf, file_count: usize },
+    Verified { passed: usize, failures: Vec<VerifyFailure> },
+}
+
+// 校验未通过的条目
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyFailure {
+    Mismatch(String), // 内容与清单不一致
+    Missing(String),  // 文件不存在或无法读取
+}
+
+// 后台执行生成/校验任务，通过通道报告进度；取消或释放时停止
+pub struct ChecksumWorker {
+    receiver: Receiver<ChecksumEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ChecksumWorker {
+    // 为文件夹生成 SHA256SUMS 清单（递归，使用相对路径）
+    pub fn start_generate(dir: PathBuf) -> Self {
+        Self::spawn(move |sender, cancelled| generate_manifest(&dir, sender, cancelled))
+    }
+
+    // 按清单校验其所在文件夹
+    pub fn start_verify(manifest: PathBuf) -> Self {
+        Self::spawn(move |sender, cancelled| verify_manifest(&manifest, sender, cancelled))
+    }
+
+    fn spawn<F>(task: F) -> Self
+    where
+        F: FnOnce(&Sender<ChecksumEvent>, &AtomicBool) -> Result<ChecksumOutcome, String> + Send + 'static,
+    {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let cancel_flag = cancelled.clone();
+        std::thread::spawn(move || {
+            let result = task(&sender, &cancel_flag);
+            let _ = sender.send(ChecksumEvent::Finished(result));
+        });
+
+        Self { receiver, cancelled }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ChecksumWorker {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// 计算单个文件的 SHA256（十六进制小写）
+pub fn sha256_file(path: &Path, cancelled: &AtomicBool) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 256 * 1024];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "已取消"));
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// 递归列出文件夹中的文件（相对路径，'/' 分隔，排序），不跟随符号链接
+pub fn collect_files(root: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    let relative = relative.components()
+                        .map(|c| c.as_os_str().to_string_lossy().to_string())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    files.push(relative);
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn generate_manifest(dir: &Path, sender: &Sender<ChecksumEvent>, cancelled: &AtomicBool) -> Result<ChecksumOutcome, String> {
+    let manifest = dir.join(MANIFEST_NAME);
+    // 清单自身不参与计算
+    let files: Vec<String> = collect_files(dir).into_iter().filter(|f| f != MANIFEST_NAME).collect();
+    let total = files.len();
+
+    let mut lines = String::new();
+    for (index, relative) in files.iter().enumerate() {
+        let _ = sender.send(ChecksumEvent::Progress { done: index, total, current: relative.clone() });
+        let hash = sha256_file(&dir.join(relative), cancelled).map_err(|e| {
+            if cancelled.load(Ordering::Relaxed) {
+                "操作已取消".to_string()
+            } else {
+                format!("读取 {} 失败: {}", relative, e)
+            }
+        })?;
+        // 与 sha256sum 兼容的格式：哈希 + 两个空格 + 路径
+        lines.push_str(&format!("{}  {}\n", hash, relative));
+    }
+
+    fs::write(&manifest, lines).map_err(|e| format!("写入清单失败: {}", e))?;
+    Ok(ChecksumOutcome::Generated { manifest, file_count: total })
+}
+
+// 解析清单的一行，返回（哈希, 相对路径）；兼容 sha256sum 的二进制模式标记 '*'
+pub fn parse_manifest_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    if line.trim().is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (hash, rest) = line.split_once(' ')?;
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*')).unwrap_or(rest);
+    Some((hash.to_ascii_lowercase(), path.to_string()))
+}
+
+fn verify_manifest(manifest: &Path, sender: &Sender<ChecksumEvent>, cancelled: &AtomicBool) -> Result<ChecksumOutcome, String> {
+    let text = fs::read_to_string(manifest).map_err(|e| format!("读取清单失败: {}", e))?;
+    let root = manifest.parent().unwrap_or(Path::new("."));
+    let entries: Vec<(String, String)> = text.lines().filter_map(parse_manifest_line).collect();
+    if entries.is_empty() {
+        return Err("清单中没有有效的条目".to_string());
+    }
+    let total = entries.len();
+
+    let mut passed = 0;
+    let mut failures = Vec::new();
+    for (index, (expected, relative)) in entries.into_iter().enumerate() {
+        let _ = sender.send(ChecksumEvent::Progress { done: index, total, current: relative.clone() });
+        match sha256_file(&root.join(&relative), cancelled) {
+            Ok(actual) if actual == expected => passed += 1,
+            Ok(_) => failures.push(VerifyFailure::Mismatch(relative)),
+            Err(_) if cancelled.load(Ordering::Relaxed) => return Err("操作已取消".to_string()),
+            Err(_) => failures.push(VerifyFailure::Missing(relative)),
+        }
+    }
+    Ok(ChecksumOutcome::Verified { passed, failures })
+}
+
+// 校验任务的进度/结果窗口
+pub struct ChecksumDialog {
+    worker: Option<ChecksumWorker>,
+    title: String,
+    progress: (usize, usize), // 已完成, 总数
+    current: String,
+    result: Option<Result<ChecksumOutcome, String>>,
+}
+
+impl ChecksumDialog {
+    pub fn new() -> Self {
+        Self {
+            worker: None,
+            title: String::new(),
+            progress: (0, 0),
+            current: String::new(),
+            result: None,
+        }
+    }
+
+    // 开始为文件夹生成校验清单
+    pub fn start_generate(&mut self, dir: PathBuf) {
+        self.reset("生成校验清单");
+        self.worker = Some(ChecksumWorker::start_generate(dir));
+    }
+
+    // 开始按清单校验文件夹
+    pub fn start_verify(&mut self, manifest: PathBuf) {
+        self.reset("校验文件夹");
+        self.worker = Some(ChecksumWorker::start_verify(manifest));
+    }
+
+    fn reset(&mut self, title: &str) {
+        self.title = title.to_string();
+        self.progress = (0, 0);
+        self.current.clear();
+        self.result = None;
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.worker.is_some() || self.result.is_some()
+    }
+
+    // 显示进度窗口，任务完成后显示结果；返回 true 表示刚生成了新清单（需要刷新列表）
+    pub fn show_checksum_dialog(&mut self, ctx: &egui::Context) -> bool {
+        let mut generated = false;
+        if let Some(worker) = &self.worker {
+            for event in worker.receiver.try_iter() {
+                match event {
+                    ChecksumEvent::Progress { done, total, current } => {
+                        self.progress = (done, total);
+                        self.current = current;
+                    }
+                    ChecksumEvent::Finished(result) => {
+                        generated = matches!(result, Ok(ChecksumOutcome::Generated { .. }));
+                        self.result = Some(result);
+                    }
+                }
+            }
+            if self.result.is_some() {
+                self.worker = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        let mut close = false;
+        egui::Window::new(self.title.as_str())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                match &self.result {
+                    None => {
+                        let (done, total) = self.progress;
+                        let fraction = if total == 0 { 0.0 } else { done as f32 / total as f32 };
+                        ui.add(egui::ProgressBar::new(fraction).text(format!("{} / {}", done, total)));
+                        ui.label(egui::RichText::new(&self.current).weak());
+                        ui.separator();
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("取消").clicked() {
+                                if let Some(worker) = &self.worker {
+                                    worker.cancel();
+                                }
+                            }
+                        });
+                    }
+                    Some(result) => {
+                        match result {
+                            Ok(ChecksumOutcome::Generated { manifest, file_count }) => {
+                                ui.label(format!("已为 {} 个文件生成清单：", file_count));
+                                ui.label(manifest.to_string_lossy());
+                            }
+                            Ok(ChecksumOutcome::Verified { passed, failures }) => {
+                                if failures.is_empty() {
+                                    ui.label(format!("校验通过，共 {} 个文件。", passed));
+                                } else {
+                                    ui.label(format!("{} 个文件通过，{} 个文件未通过：", passed, failures.len()));
+                                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                        for failure in failures {
+                                            match failure {
+                                                VerifyFailure::Mismatch(path) => ui.label(format!("内容不一致: {}", path)),
+                                                VerifyFailure::Missing(path) => ui.label(format!("缺失或无法读取: {}", path)),
+                                            };
+                                        }
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                ui.label(e);
+                            }
+                        }
+                        ui.separator();
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("确定").clicked() {
+                                close = true;
+                            }
+                        });
+                    }
+                }
+            });
+
+        if close {
+            self.result = None;
+        }
+        generated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_manifest_generate_and_verify() {
+        let root = TestDir::new("checksum");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("a.txt"), "hello").unwrap();
+        fs::write(root.join("sub").join("b.txt"), "world").unwrap();
+
+        let (sender, _receiver) = crossbeam_channel::unbounded();
+        let cancelled = AtomicBool::new(false);
+        let outcome = generate_manifest(&root, &sender, &cancelled).unwrap();
+        assert_eq!(outcome, ChecksumOutcome::Generated { manifest: root.join(MANIFEST_NAME), file_count: 2 });
+
+        let text = fs::read_to_string(root.join(MANIFEST_NAME)).unwrap();
+        assert_eq!(
+            text.lines().next().unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  a.txt"
+        );
+        assert!(text.contains("  sub/b.txt"));
+
+        fs::write(root.join("a.txt"), "changed").unwrap();
+        fs::remove_file(root.join("sub").join("b.txt")).unwrap();
+        let outcome = verify_manifest(&root.join(MANIFEST_NAME), &sender, &cancelled).unwrap();
+        assert_eq!(
+            outcome,
+            ChecksumOutcome::Verified {
+                passed: 0,
+                failures: vec![VerifyFailure::Mismatch("a.txt".to_string()), VerifyFailure::Missing("sub/b.txt".to_string())],
+            }
+        );
+    }
+}
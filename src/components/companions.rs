@@ -0,0 +1,244 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::confirmation::PendingOperation;
+
+// 视频及其字幕
+const VIDEO_EXTENSIONS: &[&str] = &["mkv", "mp4", "m4v", "avi", "mov", "webm", "wmv", "flv", "ts", "mpg", "mpeg"];
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt", "sub", "idx", "sup"];
+// 照片（含 RAW）及其附属文件
+const PHOTO_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "tif", "tiff", "heic", "webp", "cr2", "cr3", "nef", "arw", "dng", "raf", "orf", "rw2"];
+const SIDECAR_EXTENSIONS: &[&str] = &["xmp", "pp3", "aae"];
+
+fn extension(name: &str) -> Option<String> {
+    Path::new(name).extension().map(|e| e.to_string_lossy().to_lowercase())
+}
+
+// companion 是否为 primary 的配套文件，是则返回 companion 名称中对应 primary 的前缀长度。
+// 支持 movie.mkv + movie.srt / movie.en.srt，以及 photo.jpg + photo.xmp / photo.jpg.xmp
+fn companion_base(primary: &str, companion: &str) -> Option<usize> {
+    let (primary_ext, companion_ext) = (extension(primary)?, extension(companion)?);
+    let allowed = if VIDEO_EXTENSIONS.contains(&primary_ext.as_str()) {
+        SUBTITLE_EXTENSIONS
+    } else if PHOTO_EXTENSIONS.contains(&primary_ext.as_str()) {
+        SIDECAR_EXTENSIONS
+    } else {
+        return None;
+    };
+    if !allowed.contains(&companion_ext.as_str()) {
+        return None;
+    }
+    // 完整文件名作前缀优先，其次是去掉扩展名的部分
+    let stem = &primary[..primary.len() - primary_ext.len() - 1];
+    [primary, stem]
+        .into_iter()
+        .find(|base| companion.len() > base.len() + 1 && companion.starts_with(base) && companion.as_bytes()[base.len()] == b'.')
+        .map(str::len)
+}
+
+pub fn is_companion_of(primary: &str, companion: &str) -> bool {
+    companion_base(primary, companion).is_some()
+}
+
+// 文件夹中各配套文件对应的主文件（按名称）；同时匹配多个主文件时归到前缀最长的那个
+pub fn group<'a>(names: &[&'a str]) -> HashMap<&'a str, &'a str> {
+    let mut groups: HashMap<&str, (&str, usize)> = HashMap::new();
+    for primary in names {
+        for companion in names {
+            let Some(base) = companion_base(primary, companion) else {
+                continue;
+            };
+            let entry = groups.entry(companion).or_insert((primary, base));
+            if base > entry.1 {
+                *entry = (primary, base);
+            }
+        }
+    }
+    groups.into_iter().map(|(companion, (primary, _))| (companion, primary)).collect()
+}
+
+// 同一文件夹中 path 的配套文件
+pub fn find_companions(path: &Path) -> Vec<PathBuf> {
+    let (Some(name), Some(parent)) = (path.file_name().and_then(|n| n.to_str()), path.parent()) else {
+        return Vec::new();
+    };
+    if path.is_dir() {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    let mut companions: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_str().is_some_and(|companion| is_companion_of(name, companion)))
+        .map(|entry| entry.path())
+        .collect();
+    companions.sort();
+    companions
+}
+
+// 多个项目的配套文件，不包括已在 paths 中的
+pub fn find_all_companions(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut result = Vec::new();
+    for path in paths {
+        for companion in find_companions(path) {
+            if !paths.contains(&companion) && !result.contains(&companion) {
+                result.push(companion);
+            }
+        }
+    }
+    result
+}
+
+// 主文件改名后配套文件的新名称：movie.en.srt 随 movie.mkv → film.mkv 改为 film.en.srt
+pub fn renamed_companion(old_name: &str, new_name: &str, companion: &str) -> Option<String> {
+    let base = companion_base(old_name, companion)?;
+    let suffix = &companion[base..];
+    let new_base = if base == old_name.len() {
+        new_name
+    } else {
+        Path::new(new_name).file_stem().and_then(|s| s.to_str()).unwrap_or(new_name)
+    };
+    Some(format!("{}{}", new_base, suffix))
+}
+
+// 主文件改名后各配套文件的新名称，名称不变的跳过
+pub fn plan_renames(path: &Path, new_name: &str, companions: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    let Some(old_name) = path.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    companions
+        .iter()
+        .filter_map(|companion| {
+            let name = companion.file_name()?.to_str()?;
+            let renamed = renamed_companion(old_name, new_name, name)?;
+            (renamed != name).then(|| (companion.clone(), renamed))
+        })
+        .collect()
+}
+
+// 等待用户选择是否一并处理配套文件
+enum CompanionPrompt {
+    Operation { operation: PendingOperation, companions: Vec<PathBuf> },
+    Rename { renames: Vec<(PathBuf, String)> },
+}
+
+// 用户的选择
+pub enum CompanionChoice {
+    Operation(PendingOperation, Vec<PathBuf>), // 要一并处理的配套文件，为空表示只处理所选项目
+    Rename(Vec<(PathBuf, String)>),
+}
+
+// 删除、移动、复制或重命名带有配套文件的项目时，询问是否一并处理
+pub struct CompanionDialog {
+    prompt: Option<CompanionPrompt>,
+}
+
+impl CompanionDialog {
+    pub fn new() -> Self {
+        Self { prompt: None }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    pub fn open_operation(&mut self, operation: PendingOperation, companions: Vec<PathBuf>) {
+        self.prompt = Some(CompanionPrompt::Operation { operation, companions });
+    }
+
+    pub fn open_rename(&mut self, renames: Vec<(PathBuf, String)>) {
+        self.prompt = Some(CompanionPrompt::Rename { renames });
+    }
+
+    fn show_names(ui: &mut egui::Ui, names: impl Iterator<Item = String>) {
+        egui::ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+            for name in names {
+                ui.label(name);
+            }
+        });
+    }
+
+    pub fn show_companion_dialog(&mut self, ctx: &egui::Context) -> Option<CompanionChoice> {
+        let prompt = self.prompt.as_ref()?;
+        let file_name = |path: &PathBuf| path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let mut open = true;
+        let mut choice = None; // Some(true) 一并处理，Some(false) 不处理配套文件
+        let mut cancelled = false;
+        egui::Window::new("配套文件")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .open(&mut open)
+            .show(ctx, |ui| match prompt {
+                CompanionPrompt::Operation { companions, .. } => {
+                    ui.label(format!("所选项目有 {} 个配套文件（字幕、附属文件等）:", companions.len()));
+                    Self::show_names(ui, companions.iter().map(file_name));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("一并处理").clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button("只处理所选项目").clicked() {
+                            choice = Some(false);
+                        }
+                        if ui.button("取消").clicked() {
+                            cancelled = true;
+                        }
+                    });
+                }
+                CompanionPrompt::Rename { renames } => {
+                    ui.label("是否同时重命名配套文件？");
+                    Self::show_names(ui, renames.iter().map(|(path, name)| format!("{} → {}", file_name(path), name)));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("同时重命名").clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button("不重命名").clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                }
+            });
+
+        if !open || cancelled {
+            // 关闭重命名询问即不重命名配套文件；关闭操作询问则取消整个操作
+            self.prompt = None;
+            return None;
+        }
+        let include = choice?;
+        match self.prompt.take()? {
+            CompanionPrompt::Operation { operation, companions } => {
+                Some(CompanionChoice::Operation(operation, if include { companions } else { Vec::new() }))
+            }
+            CompanionPrompt::Rename { renames } => include.then_some(CompanionChoice::Rename(renames)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_companions() {
+        assert!(is_companion_of("movie.mkv", "movie.srt"));
+        assert!(is_companion_of("movie.mkv", "movie.en.srt"));
+        assert!(is_companion_of("photo.JPG", "photo.xmp"));
+        assert!(is_companion_of("photo.jpg", "photo.jpg.xmp"));
+        assert!(!is_companion_of("movie.mkv", "movie.xmp"));
+        assert!(!is_companion_of("movie.mkv", "movies.srt"));
+        assert!(!is_companion_of("notes.txt", "notes.srt"));
+
+        let groups = group(&["a.mkv", "a.srt", "a.en.srt", "b.jpg", "b.jpg.xmp", "c.srt"]);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups.get("a.en.srt"), Some(&"a.mkv"));
+        assert_eq!(groups.get("c.srt"), None);
+
+        assert_eq!(renamed_companion("movie.mkv", "film.mkv", "movie.en.srt").as_deref(), Some("film.en.srt"));
+        assert_eq!(renamed_companion("photo.jpg", "pic.jpg", "photo.jpg.xmp").as_deref(), Some("pic.jpg.xmp"));
+        assert_eq!(renamed_companion("photo.jpg", "pic.jpg", "photo.xmp").as_deref(), Some("pic.xmp"));
+    }
+}
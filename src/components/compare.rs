@@ -0,0 +1,493 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use crossbeam_channel::{self, Receiver};
+use super::{checksum, image_formats, text_encoding};
+
+// 按文本比较的文件大小上限
+const MAX_TEXT_SIZE: u64 = 2 * 1024 * 1024;
+// 逐行比较的规模上限（两边不同部分行数的乘积），超过时整段视为替换
+const MAX_DIFF_CELLS: usize = 4_000_000;
+// 比较时图片缩放后的最大边长
+const MAX_IMAGE_SIZE: u32 = 1024;
+// 差异高亮时，任一颜色通道相差超过此值的像素视为不同（忽略有损压缩的细微噪点）
+const DIFF_THRESHOLD: u8 = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String), // 只在 A 中
+    Added(String),   // 只在 B 中
+}
+
+// 逐行比较两段文本：去掉相同的开头和结尾后，对中间部分求最长公共子序列
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut result: Vec<DiffLine> = a[..prefix].iter().map(|l| DiffLine::Same(l.to_string())).collect();
+    if mid_a.len() * mid_b.len() > MAX_DIFF_CELLS {
+        result.extend(mid_a.iter().map(|l| DiffLine::Removed(l.to_string())));
+        result.extend(mid_b.iter().map(|l| DiffLine::Added(l.to_string())));
+    } else {
+        // lcs[i][j]：mid_a[i..] 与 mid_b[j..] 的最长公共子序列长度
+        let (n, m) = (mid_a.len(), mid_b.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if mid_a[i] == mid_b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && mid_a[i] == mid_b[j] {
+                result.push(DiffLine::Same(mid_a[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                result.push(DiffLine::Added(mid_b[j].to_string()));
+                j += 1;
+            } else {
+                result.push(DiffLine::Removed(mid_a[i].to_string()));
+                i += 1;
+            }
+        }
+    }
+    result.extend(a[a.len() - suffix..].iter().map(|l| DiffLine::Same(l.to_string())));
+    result
+}
+
+// 两张图片的差异图：不同的像素标为红色，相同的像素显示为变暗的 A 图灰度
+struct ImageDiff {
+    image: egui::ColorImage,
+    changed: usize,
+    total: usize,
+}
+
+fn to_color_image(image: &image::RgbaImage) -> egui::ColorImage {
+    egui::ColorImage::from_rgba_unmultiplied([image.width() as usize, image.height() as usize], image.as_raw())
+}
+
+// 尺寸不同时先把 B 缩放到 A 的尺寸再逐像素比较
+fn image_diff(a: &image::RgbaImage, b: &image::RgbaImage) -> ImageDiff {
+    let resized;
+    let b = if a.dimensions() == b.dimensions() {
+        b
+    } else {
+        resized = image::imageops::resize(b, a.width(), a.height(), image::imageops::FilterType::Triangle);
+        &resized
+    };
+    let mut changed = 0;
+    let mut diff = image::RgbaImage::new(a.width(), a.height());
+    for ((pa, pb), out) in a.pixels().zip(b.pixels()).zip(diff.pixels_mut()) {
+        let delta = pa.0.iter().zip(pb.0.iter()).map(|(x, y)| x.abs_diff(*y)).max().unwrap_or(0);
+        *out = if delta > DIFF_THRESHOLD {
+            changed += 1;
+            image::Rgba([255, 40, 40, 255])
+        } else {
+            let gray = ((pa[0] as u32 * 30 + pa[1] as u32 * 59 + pa[2] as u32 * 11) / 100 * 35 / 100) as u8;
+            image::Rgba([gray, gray, gray, 255])
+        };
+    }
+    ImageDiff {
+        image: to_color_image(&diff),
+        changed,
+        total: (a.width() * a.height()) as usize,
+    }
+}
+
+// 后台读取的比较内容
+enum CompareContent {
+    Images(egui::ColorImage, egui::ColorImage, ImageDiff),
+    Text(Vec<DiffLine>),
+    Binary { identical: bool }, // 既不是图片也不是文本时只比较内容是否相同
+    Error(String),
+}
+
+fn load_image(path: &Path) -> Result<image::RgbaImage, String> {
+    let image = image_formats::open_image(path).map_err(|e| format!("无法打开 {}: {}", path.display(), e))?;
+    Ok(image.thumbnail(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE).to_rgba8())
+}
+
+// 较小且不像二进制文件（含 NUL 字节）的文件按文本比较，GBK 等编码也可以
+pub fn is_text_file(path: &Path) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_TEXT_SIZE => {
+            metadata.len() == 0 || text_encoding::detect_file(path).is_some()
+        }
+        _ => false,
+    }
+}
+
+fn read_text(path: &Path) -> Option<String> {
+    if !is_text_file(path) {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    Some(text_encoding::decode(&bytes).0)
+}
+
+fn load_content(left: &Path, right: &Path) -> CompareContent {
+    if image_formats::is_image_file(left) && image_formats::is_image_file(right) {
+        return match (load_image(left), load_image(right)) {
+            (Ok(a), Ok(b)) => {
+                let diff = image_diff(&a, &b);
+                CompareContent::Images(to_color_image(&a), to_color_image(&b), diff)
+            }
+            (Err(e), _) | (_, Err(e)) => CompareContent::Error(e),
+        };
+    }
+    if let (Some(a), Some(b)) = (read_text(left), read_text(right)) {
+        return CompareContent::Text(diff_lines(&a, &b));
+    }
+    let cancelled = AtomicBool::new(false);
+    match (checksum::sha256_file(left, &cancelled), checksum::sha256_file(right, &cancelled)) {
+        (Ok(a), Ok(b)) => CompareContent::Binary { identical: a == b },
+        (Err(e), _) | (_, Err(e)) => CompareContent::Error(format!("读取文件失败: {}", e)),
+    }
+}
+
+enum Loaded {
+    Images {
+        a: egui::TextureHandle,
+        b: egui::TextureHandle,
+        diff: egui::TextureHandle,
+        changed: usize,
+        total: usize,
+    },
+    Text(Vec<DiffLine>),
+    Binary { identical: bool },
+    Error(String),
+}
+
+// 图片的比较方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImageMode {
+    Split,      // A/B 分割滑块
+    Difference, // 高亮不同的像素
+}
+
+// 文本的比较方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextMode {
+    Unified,    // 合并显示，行首标记 -/+
+    SideBySide, // 左右并排，A 在左、B 在右
+}
+
+// 并排显示的一行：左右两侧分别是 (行号, 在差异结果中的序号)，None 表示该侧没有对应的行
+type SideRow = (Option<(usize, usize)>, Option<(usize, usize)>);
+
+// 相同的行左右对齐；连续的删除和新增逐行配对，多出的一侧留空
+fn side_by_side_rows(lines: &[DiffLine]) -> Vec<SideRow> {
+    let mut rows = Vec::new();
+    let (mut a_number, mut b_number) = (0, 0);
+    let mut i = 0;
+    while i < lines.len() {
+        if matches!(lines[i], DiffLine::Same(_)) {
+            rows.push((Some((a_number, i)), Some((b_number, i))));
+            a_number += 1;
+            b_number += 1;
+            i += 1;
+            continue;
+        }
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        while i < lines.len() && !matches!(lines[i], DiffLine::Same(_)) {
+            if matches!(lines[i], DiffLine::Removed(_)) {
+                removed.push((a_number, i));
+                a_number += 1;
+            } else {
+                added.push((b_number, i));
+                b_number += 1;
+            }
+            i += 1;
+        }
+        for row in 0..removed.len().max(added.len()) {
+            rows.push((removed.get(row).copied(), added.get(row).copied()));
+        }
+    }
+    rows
+}
+
+fn removed_color() -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(220, 60, 60, 50)
+}
+
+fn added_color() -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(40, 170, 60, 50)
+}
+
+// 预览栏中的快速比较：图片用 A/B 分割滑块或差异高亮，文本逐行比较
+pub struct CompareView {
+    left: PathBuf,
+    right: PathBuf,
+    receiver: Option<Receiver<CompareContent>>,
+    loaded: Option<Loaded>,
+    split: f32,          // 图片分割线位置，左侧为 A
+    image_mode: ImageMode,
+    text_mode: TextMode,
+    only_changes: bool,  // 文本比较只显示不同的行
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// 差异图和不同像素所占的比例
+fn show_difference(ui: &mut egui::Ui, diff: &egui::TextureHandle, changed: usize, total: usize) {
+    if changed == 0 {
+        ui.label("两张图片没有明显差异");
+    } else {
+        let percent = changed as f64 * 100.0 / total.max(1) as f64;
+        ui.colored_label(egui::Color32::from_rgb(200, 60, 60), format!("不同的像素: {} ({:.2}%)", changed, percent));
+    }
+    let size = diff.size_vec2();
+    let scale = ((ui.available_width() - 20.0) / size.x).min(1.0);
+    ui.add(egui::Image::from_texture(egui::load::SizedTexture::new(diff.id(), size * scale)));
+}
+
+impl CompareView {
+    pub fn new(left: PathBuf, right: PathBuf, ctx: &egui::Context) -> Self {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (a, b, ctx) = (left.clone(), right.clone(), ctx.clone());
+        std::thread::spawn(move || {
+            let _ = sender.send(load_content(&a, &b));
+            ctx.request_repaint();
+        });
+        Self {
+            left,
+            right,
+            receiver: Some(receiver),
+            loaded: None,
+            split: 0.5,
+            image_mode: ImageMode::Split,
+            text_mode: TextMode::Unified,
+            only_changes: false,
+        }
+    }
+
+    // 参与比较的文件
+    pub fn involves(&self, path: &Path) -> bool {
+        self.left == path || self.right == path
+    }
+
+    // 绘制比较结果，点击退出时返回 false
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        if let Some(content) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.receiver = None;
+            let ctx = ui.ctx();
+            self.loaded = Some(match content {
+                CompareContent::Images(a, b, diff) => Loaded::Images {
+                    a: ctx.load_texture("compare_a", a, egui::TextureOptions::LINEAR),
+                    b: ctx.load_texture("compare_b", b, egui::TextureOptions::LINEAR),
+                    diff: ctx.load_texture("compare_diff", diff.image, egui::TextureOptions::NEAREST),
+                    changed: diff.changed,
+                    total: diff.total,
+                },
+                CompareContent::Text(lines) => Loaded::Text(lines),
+                CompareContent::Binary { identical } => Loaded::Binary { identical },
+                CompareContent::Error(msg) => Loaded::Error(msg),
+            });
+        }
+
+        let mut keep_open = true;
+        ui.horizontal(|ui| {
+            ui.strong("比较");
+            if ui.button("✕ 退出比较").clicked() {
+                keep_open = false;
+            }
+        });
+        ui.label(format!("A: {}", file_name(&self.left))).on_hover_text(self.left.display().to_string());
+        ui.label(format!("B: {}", file_name(&self.right))).on_hover_text(self.right.display().to_string());
+        ui.separator();
+
+        // 取出结果以便绘制时修改分割线等状态
+        let loaded = self.loaded.take();
+        match &loaded {
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取文件...");
+                });
+            }
+            Some(Loaded::Images { a, b, diff, changed, total }) => {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.image_mode, ImageMode::Split, "分割滑块");
+                    ui.selectable_value(&mut self.image_mode, ImageMode::Difference, "差异高亮");
+                });
+                match self.image_mode {
+                    ImageMode::Split => self.show_images(ui, a, b),
+                    ImageMode::Difference => show_difference(ui, diff, *changed, *total),
+                }
+            }
+            Some(Loaded::Text(lines)) => self.show_text(ui, lines),
+            Some(Loaded::Binary { identical }) => {
+                ui.label(if *identical { "两个文件内容相同" } else { "两个文件内容不同" });
+            }
+            Some(Loaded::Error(msg)) => {
+                ui.colored_label(egui::Color32::RED, msg);
+            }
+        }
+        self.loaded = loaded;
+        keep_open
+    }
+
+    // 两张图片叠在同一区域，分割线左侧显示 A、右侧显示 B；在图片上拖动或用滑块移动分割线
+    fn show_images(&mut self, ui: &mut egui::Ui, a: &egui::TextureHandle, b: &egui::TextureHandle) {
+        if a.size() != b.size() {
+            ui.label(egui::RichText::new(format!(
+                "尺寸不同：A {} x {}，B {} x {}（已缩放到相同区域）",
+                a.size()[0], a.size()[1], b.size()[0], b.size()[1]
+            )).weak());
+        }
+        let size = a.size_vec2();
+        let scale = ((ui.available_width() - 20.0) / size.x).min(1.0);
+        let (rect, response) = ui.allocate_exact_size(size * scale, egui::Sense::click_and_drag());
+        if let Some(pointer) = response.interact_pointer_pos() {
+            self.split = ((pointer.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+        }
+        let split_x = rect.left() + rect.width() * self.split;
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        let left_clip = egui::Rect::from_min_max(rect.min, egui::pos2(split_x, rect.bottom()));
+        let right_clip = egui::Rect::from_min_max(egui::pos2(split_x, rect.top()), rect.max);
+        ui.painter().with_clip_rect(left_clip).image(a.id(), rect, uv, egui::Color32::WHITE);
+        ui.painter().with_clip_rect(right_clip).image(b.id(), rect, uv, egui::Color32::WHITE);
+        ui.painter().vline(split_x, rect.y_range(), egui::Stroke::new(2.0, ui.visuals().selection.stroke.color));
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("A");
+            ui.add(egui::Slider::new(&mut self.split, 0.0..=1.0).show_value(false));
+            ui.label("B");
+        });
+    }
+
+    fn show_text(&mut self, ui: &mut egui::Ui, lines: &[DiffLine]) {
+        let removed = lines.iter().filter(|l| matches!(l, DiffLine::Removed(_))).count();
+        let added = lines.iter().filter(|l| matches!(l, DiffLine::Added(_))).count();
+        ui.horizontal(|ui| {
+            if removed == 0 && added == 0 {
+                ui.label("两个文件内容相同");
+            } else {
+                ui.colored_label(egui::Color32::from_rgb(200, 60, 60), format!("-{} 行", removed));
+                ui.colored_label(egui::Color32::from_rgb(40, 150, 60), format!("+{} 行", added));
+            }
+            ui.checkbox(&mut self.only_changes, "只显示不同的行");
+        });
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.text_mode, TextMode::Unified, "合并");
+            ui.selectable_value(&mut self.text_mode, TextMode::SideBySide, "并排");
+        });
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        // 预览栏本身可以滚动，限制高度为可见区域，只绘制可见的行
+        let max_height = ui.clip_rect().height().max(200.0);
+        match self.text_mode {
+            TextMode::Unified => {
+                let visible: Vec<&DiffLine> = lines.iter().filter(|l| !self.only_changes || !matches!(l, DiffLine::Same(_))).collect();
+                egui::ScrollArea::both().id_salt("compare_text").auto_shrink([false, true]).max_height(max_height).show_rows(ui, row_height, visible.len(), |ui, range| {
+                    for line in &visible[range] {
+                        let (marker, text, background) = match line {
+                            DiffLine::Same(text) => (" ", text, egui::Color32::TRANSPARENT),
+                            DiffLine::Removed(text) => ("-", text, removed_color()),
+                            DiffLine::Added(text) => ("+", text, added_color()),
+                        };
+                        ui.label(egui::RichText::new(format!("{} {}", marker, text)).monospace().background_color(background));
+                    }
+                });
+            }
+            TextMode::SideBySide => {
+                let rows: Vec<SideRow> = side_by_side_rows(lines).into_iter()
+                    .filter(|(a, b)| !self.only_changes || !matches!((a, b), (Some((_, x)), Some((_, y))) if x == y))
+                    .collect();
+                egui::ScrollArea::vertical().id_salt("compare_side_by_side").auto_shrink([false, true]).max_height(max_height).show_rows(ui, row_height, rows.len(), |ui, range| {
+                    ui.spacing_mut().item_spacing.y = 0.0;
+                    for (left, right) in &rows[range] {
+                        self.show_side_row(ui, lines, *left, *right, row_height);
+                    }
+                });
+            }
+        }
+    }
+
+    // 并排显示的一行：左右各占一半，过长的行被截断；没有对应行的一侧显示为灰色
+    fn show_side_row(&self, ui: &mut egui::Ui, lines: &[DiffLine], left: Option<(usize, usize)>, right: Option<(usize, usize)>, row_height: f32) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), row_height), egui::Sense::hover());
+        let half = rect.width() / 2.0;
+        let font = egui::TextStyle::Monospace.resolve(ui.style());
+        let halves = [
+            (left, egui::Rect::from_min_size(rect.min, egui::vec2(half - 1.0, row_height))),
+            (right, egui::Rect::from_min_size(rect.min + egui::vec2(half + 1.0, 0.0), egui::vec2(half - 1.0, row_height))),
+        ];
+        for (side, side_rect) in halves {
+            let painter = ui.painter().with_clip_rect(side_rect);
+            let Some((number, index)) = side else {
+                painter.rect_filled(side_rect, 0.0, ui.visuals().faint_bg_color);
+                continue;
+            };
+            let (text, background) = match &lines[index] {
+                DiffLine::Same(text) => (text, egui::Color32::TRANSPARENT),
+                DiffLine::Removed(text) => (text, removed_color()),
+                DiffLine::Added(text) => (text, added_color()),
+            };
+            painter.rect_filled(side_rect, 0.0, background);
+            let number_rect = painter.text(side_rect.left_center() + egui::vec2(2.0, 0.0), egui::Align2::LEFT_CENTER, format!("{:>4}", number + 1), font.clone(), ui.visuals().weak_text_color());
+            painter.text(egui::pos2(number_rect.right() + 6.0, side_rect.center().y), egui::Align2::LEFT_CENTER, text, font.clone(), ui.visuals().text_color());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nc\nx\nd");
+        assert_eq!(diff, vec![
+            DiffLine::Same("a".to_string()),
+            DiffLine::Removed("b".to_string()),
+            DiffLine::Same("c".to_string()),
+            DiffLine::Added("x".to_string()),
+            DiffLine::Same("d".to_string()),
+        ]);
+        assert!(diff_lines("same\n", "same\n").iter().all(|l| matches!(l, DiffLine::Same(_))));
+        assert_eq!(diff_lines("", "new"), vec![DiffLine::Added("new".to_string())]);
+    }
+
+    #[test]
+    fn test_side_by_side_rows() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nc\nx\ny\nd");
+        assert_eq!(side_by_side_rows(&diff), vec![
+            (Some((0, 0)), Some((0, 0))),
+            (Some((1, 1)), None),
+            (Some((2, 2)), Some((1, 2))),
+            (None, Some((2, 3))),
+            (None, Some((3, 4))),
+            (Some((3, 5)), Some((4, 5))),
+        ]);
+        // 修改的行左右配对
+        let diff = diff_lines("a\nold\nz", "a\nnew\nz");
+        assert_eq!(side_by_side_rows(&diff)[1].0.map(|(n, _)| n), Some(1));
+        assert_eq!(side_by_side_rows(&diff)[1].1.map(|(n, _)| n), Some(1));
+    }
+
+    #[test]
+    fn test_image_diff() {
+        let a = image::RgbaImage::from_pixel(4, 4, image::Rgba([100, 100, 100, 255]));
+        let mut b = a.clone();
+        b.put_pixel(1, 2, image::Rgba([200, 100, 100, 255]));
+        b.put_pixel(3, 3, image::Rgba([110, 100, 100, 255])); // 小于阈值，视为相同
+        let diff = image_diff(&a, &b);
+        assert_eq!((diff.changed, diff.total), (1, 16));
+        assert_eq!(diff.image.size, [4, 4]);
+
+        let larger = image::RgbaImage::from_pixel(8, 8, image::Rgba([100, 100, 100, 255]));
+        assert_eq!(image_diff(&a, &larger).changed, 0);
+    }
+}
@@ -0,0 +1,382 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::components::file_list::FileDropRequest;
+use crate::components::file_operations::{ClipboardData, OperationType};
+
+// 哪些操作需要先确认，保存在设置中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationSettings {
+    pub trash_delete: bool,          // 删除到回收站
+    pub permanent_delete: bool,      // 永久删除
+    pub overwrite: bool,             // 覆盖目标位置的同名项目
+    pub large_move: bool,            // 一次移动较多项目
+    pub large_move_threshold: usize, // 超过该数量视为较多
+    pub system_paths: bool,          // 涉及系统目录
+    pub companion_files: bool,       // 项目带有配套文件（字幕、照片附属文件）时询问是否一并处理
+    pub protected_paths: Vec<ProtectedPath>,
+}
+
+impl Default for ConfirmationSettings {
+    fn default() -> Self {
+        Self {
+            trash_delete: false,
+            permanent_delete: true,
+            overwrite: true,
+            large_move: true,
+            large_move_threshold: 20,
+            system_paths: true,
+            companion_files: true,
+            protected_paths: Vec::new(),
+        }
+    }
+}
+
+// 受保护路径上的破坏性操作如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProtectionMode {
+    Block,         // 直接阻止
+    TypeToConfirm, // 输入文件夹名称后才能继续
+}
+
+impl ProtectionMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProtectionMode::Block => "阻止操作",
+            ProtectionMode::TypeToConfirm => "输入名称确认",
+        }
+    }
+}
+
+// 受保护的路径及其子项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtectedPath {
+    pub path: String, // 绝对路径，或相对于主目录的路径（如 "重要项目/"、"~/文档"）
+    pub mode: ProtectionMode,
+}
+
+impl ProtectedPath {
+    fn resolve(&self) -> Option<PathBuf> {
+        let text = self.path.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let relative = text.strip_prefix("~/").or_else(|| (text == "~").then_some(""));
+        match relative {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None if Path::new(text).is_absolute() => Some(PathBuf::from(text)),
+            None => dirs::home_dir().map(|home| home.join(text)),
+        }
+    }
+
+    // 确认时需要输入的名称：文件夹名，根目录为 "/"
+    fn confirm_name(root: &Path) -> String {
+        root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| root.display().to_string())
+    }
+}
+
+// 操作涉及受保护路径时的处理结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Protection {
+    Blocked(String),       // 阻止的原因
+    TypeToConfirm(String), // 需要输入的名称
+}
+
+// 等待确认或执行的文件操作
+pub enum PendingOperation {
+    Delete { paths: Vec<PathBuf>, permanent: bool },
+    Paste { target_dir: PathBuf, companions: Vec<PathBuf> }, // 粘贴剪贴板内容（和一并粘贴的配套文件）到该文件夹
+    Drop(FileDropRequest),
+}
+
+// 系统目录（用户主目录下的路径除外，如 /var/home/用户名）
+#[cfg(not(target_os = "windows"))]
+const SYSTEM_DIRS: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/opt", "/proc", "/run", "/sbin", "/srv", "/sys", "/usr", "/var",
+];
+
+#[cfg(target_os = "windows")]
+const SYSTEM_DIRS: &[&str] = &["C:\\Windows", "C:\\Program Files", "C:\\Program Files (x86)", "C:\\ProgramData"];
+
+pub fn is_system_path(path: &Path) -> bool {
+    if dirs::home_dir().is_some_and(|home| path.starts_with(home)) {
+        return false;
+    }
+    path.parent().is_none() || SYSTEM_DIRS.iter().any(|dir| path.starts_with(dir))
+}
+
+// 操作涉及的路径
+struct AffectedPaths<'a> {
+    sources: Vec<PathBuf>,
+    target_dir: Option<&'a Path>,
+    is_move: bool,
+    overwritten: Vec<PathBuf>, // 目标位置中将被覆盖的同名项目
+}
+
+fn affected_paths<'a>(operation: &'a PendingOperation, clipboard: Option<&'a ClipboardData>) -> AffectedPaths<'a> {
+    let (sources, target_dir, is_move, can_overwrite) = match operation {
+        PendingOperation::Delete { paths, .. } => (paths.clone(), None, false, false),
+        PendingOperation::Paste { target_dir, companions } => match clipboard {
+            Some(clipboard) => {
+                // 复制粘贴时同名项目另取名称，只有剪切粘贴会覆盖
+                let is_move = matches!(clipboard.operation, OperationType::Cut);
+                let sources = clipboard.source_paths.iter().chain(companions).cloned().collect();
+                (sources, Some(target_dir.as_path()), is_move, is_move)
+            }
+            None => (Vec::new(), None, false, false),
+        },
+        // 拖放复制时同名项目另取名称，拖放移动时跳过同名项目，都不会覆盖
+        PendingOperation::Drop(drop) => (drop.paths.clone(), Some(drop.target_dir.as_path()), !drop.copy, false),
+    };
+    let overwritten = match target_dir {
+        Some(target_dir) if can_overwrite => sources
+            .iter()
+            .filter(|source| source.parent() != Some(target_dir))
+            .filter_map(|source| source.file_name().map(|name| target_dir.join(name)))
+            .filter(|target| target.exists())
+            .collect(),
+        _ => Vec::new(),
+    };
+    AffectedPaths { sources, target_dir, is_move, overwritten }
+}
+
+// 检查操作是否会删除、移走或覆盖受保护的路径；有多条匹配时“阻止”优先
+pub fn check_protection(settings: &ConfirmationSettings, operation: &PendingOperation, clipboard: Option<&ClipboardData>) -> Option<Protection> {
+    let affected = affected_paths(operation, clipboard);
+    let removed: &[PathBuf] = if matches!(operation, PendingOperation::Delete { .. }) || affected.is_move { &affected.sources } else { &[] };
+    let mut result = None;
+    for protected in &settings.protected_paths {
+        let Some(root) = protected.resolve() else {
+            continue;
+        };
+        let Some(path) = removed.iter().chain(&affected.overwritten).find(|p| p.starts_with(&root)) else {
+            continue;
+        };
+        match protected.mode {
+            ProtectionMode::Block => {
+                return Some(Protection::Blocked(format!("{} 位于受保护的路径 {} 中，操作已被阻止。", path.display(), root.display())));
+            }
+            ProtectionMode::TypeToConfirm => {
+                result.get_or_insert(Protection::TypeToConfirm(ProtectedPath::confirm_name(&root)));
+            }
+        }
+    }
+    result
+}
+
+// 按设置列出操作需要确认的原因，为空表示可以直接执行
+pub fn confirmation_reasons(settings: &ConfirmationSettings, operation: &PendingOperation, clipboard: Option<&ClipboardData>) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if let PendingOperation::Delete { paths, permanent } = operation {
+        let names = describe_paths(paths);
+        if *permanent && settings.permanent_delete {
+            reasons.push(format!("确定要永久删除 {} 吗？此操作无法撤销。", names));
+        } else if !*permanent && settings.trash_delete {
+            reasons.push(format!("确定要将 {} 移到回收站吗？", names));
+        }
+    }
+
+    let AffectedPaths { sources, target_dir, is_move, overwritten } = affected_paths(operation, clipboard);
+    if let Some(target_dir) = target_dir {
+        if settings.overwrite && !overwritten.is_empty() {
+            reasons.push(format!("目标位置已有 {} 个同名项目，将被覆盖。", overwritten.len()));
+        }
+        if settings.large_move && is_move && sources.len() > settings.large_move_threshold {
+            reasons.push(format!("将移动 {} 个项目到 {}。", sources.len(), target_dir.display()));
+        }
+    }
+
+    if settings.system_paths {
+        let system_path = sources.iter().map(PathBuf::as_path).chain(target_dir).find(|p| is_system_path(p));
+        if let Some(path) = system_path {
+            reasons.push(format!("操作涉及系统目录: {}", path.display()));
+        }
+    }
+    reasons
+}
+
+// 单个项目显示名称，多个项目显示数量
+fn describe_paths(paths: &[PathBuf]) -> String {
+    match paths {
+        [path] => format!("\"{}\"", path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string())),
+        _ => format!("这 {} 个项目", paths.len()),
+    }
+}
+
+// 等待用户确认的操作
+struct PendingConfirmation {
+    operation: PendingOperation,
+    reasons: Vec<String>,
+    required_name: Option<String>, // 受保护路径需要输入的名称
+    typed_name: String,
+}
+
+// 统一的操作确认对话框
+pub struct ConfirmDialog {
+    pending: Option<PendingConfirmation>,
+    blocked_message: Option<String>, // 被受保护路径阻止的操作
+}
+
+impl ConfirmDialog {
+    pub fn new() -> Self {
+        Self { pending: None, blocked_message: None }
+    }
+
+    // 检查操作：无需确认时原样返回以便立即执行，否则保留到用户确认
+    pub fn request(&mut self, operation: PendingOperation, settings: &ConfirmationSettings, clipboard: Option<&ClipboardData>) -> Option<PendingOperation> {
+        let mut reasons = confirmation_reasons(settings, &operation, clipboard);
+        let required_name = match check_protection(settings, &operation, clipboard) {
+            Some(Protection::Blocked(message)) => {
+                self.blocked_message = Some(message);
+                return None;
+            }
+            Some(Protection::TypeToConfirm(name)) => {
+                reasons.push(format!("操作涉及受保护的文件夹，请输入 \"{}\" 以确认。", name));
+                Some(name)
+            }
+            None => None,
+        };
+        if reasons.is_empty() {
+            return Some(operation);
+        }
+        self.pending = Some(PendingConfirmation { operation, reasons, required_name, typed_name: String::new() });
+        None
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.pending.is_some() || self.blocked_message.is_some()
+    }
+
+    // 显示确认对话框，用户确认后返回要执行的操作
+    pub fn show_confirm_dialog(&mut self, ctx: &egui::Context) -> Option<PendingOperation> {
+        if let Some(message) = &self.blocked_message {
+            let mut open = true;
+            let mut closed = false;
+            egui::Window::new("操作被阻止")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    ui.label(message);
+                    ui.separator();
+                    closed = ui.button("确定").clicked();
+                });
+            if closed || !open {
+                self.blocked_message = None;
+            }
+            return None;
+        }
+
+        let Some(pending) = &mut self.pending else {
+            return None;
+        };
+        let PendingConfirmation { operation, reasons, required_name, typed_name } = pending;
+        let title = match operation {
+            PendingOperation::Delete { .. } => "确认删除",
+            PendingOperation::Paste { .. } | PendingOperation::Drop(_) => "确认操作",
+        };
+
+        let mut open = true;
+        let mut confirmed = None;
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                for reason in reasons.iter() {
+                    ui.label(reason);
+                }
+                if required_name.is_some() {
+                    ui.text_edit_singleline(typed_name);
+                }
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let name_matches = required_name.as_ref().is_none_or(|name| typed_name.trim() == name);
+                    if ui.add_enabled(name_matches, egui::Button::new("确定")).clicked() {
+                        confirmed = Some(true);
+                    }
+                    if ui.button("取消").clicked() {
+                        confirmed = Some(false);
+                    }
+                });
+            });
+
+        if !open {
+            confirmed = Some(false);
+        }
+        match confirmed {
+            Some(true) => self.pending.take().map(|pending| pending.operation),
+            Some(false) => {
+                self.pending = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+    use std::fs;
+
+    #[test]
+    fn test_confirmation_reasons() {
+        let root = TestDir::new("confirm");
+        let target = root.join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(target.join("a.txt"), "old").unwrap();
+
+        let mut settings = ConfirmationSettings { system_paths: false, large_move_threshold: 1, ..Default::default() };
+        let delete = PendingOperation::Delete { paths: vec![root.join("a.txt")], permanent: true };
+        assert_eq!(confirmation_reasons(&settings, &delete, None).len(), 1);
+        let trash = PendingOperation::Delete { paths: vec![root.join("a.txt")], permanent: false };
+        assert!(confirmation_reasons(&settings, &trash, None).is_empty());
+
+        // 移动到已有同名文件的文件夹需要确认覆盖，复制时另取名称，不需要确认
+        let clipboard = ClipboardData { operation: OperationType::Copy, source_paths: vec![root.join("a.txt")] };
+        let paste = PendingOperation::Paste { target_dir: target.clone(), companions: Vec::new() };
+        assert!(confirmation_reasons(&settings, &paste, Some(&clipboard)).is_empty());
+        let clipboard = ClipboardData { operation: OperationType::Cut, ..clipboard };
+        assert_eq!(confirmation_reasons(&settings, &paste, Some(&clipboard)).len(), 1);
+        settings.overwrite = false;
+        assert!(confirmation_reasons(&settings, &paste, Some(&clipboard)).is_empty());
+
+        // 移动超过阈值的项目数
+        let drop = PendingOperation::Drop(FileDropRequest { paths: vec![root.join("a.txt"), root.join("b.txt")], target_dir: target.clone(), copy: false });
+        assert_eq!(confirmation_reasons(&settings, &drop, None).len(), 1);
+
+        settings.system_paths = true;
+        let system = PendingOperation::Drop(FileDropRequest { paths: vec![root.join("a.txt")], target_dir: PathBuf::from("/usr/share"), copy: true });
+        assert!(!is_system_path(&root));
+        assert!(is_system_path(Path::new("/etc/fstab")));
+        assert_eq!(confirmation_reasons(&settings, &system, None).len(), 1);
+    }
+
+    #[test]
+    fn test_protected_paths() {
+        let protect = |path: &str, mode| ProtectedPath { path: path.to_string(), mode };
+        let settings = ConfirmationSettings {
+            protected_paths: vec![protect("/srv/data", ProtectionMode::TypeToConfirm), protect("/etc", ProtectionMode::Block)],
+            ..Default::default()
+        };
+        let delete = |path: &str| PendingOperation::Delete { paths: vec![PathBuf::from(path)], permanent: true };
+
+        assert_eq!(check_protection(&settings, &delete("/srv/data/a.txt"), None), Some(Protection::TypeToConfirm("data".to_string())));
+        assert!(matches!(check_protection(&settings, &delete("/etc/fstab"), None), Some(Protection::Blocked(_))));
+        assert_eq!(check_protection(&settings, &delete("/srv/other"), None), None);
+
+        // 复制出受保护的文件夹不受影响，移动则需要确认
+        let clipboard = ClipboardData { operation: OperationType::Copy, source_paths: vec![PathBuf::from("/srv/data/a.txt")] };
+        let paste = PendingOperation::Paste { target_dir: PathBuf::from("/nonexistent_target"), companions: Vec::new() };
+        assert_eq!(check_protection(&settings, &paste, Some(&clipboard)), None);
+        let clipboard = ClipboardData { operation: OperationType::Cut, ..clipboard };
+        assert!(check_protection(&settings, &paste, Some(&clipboard)).is_some());
+    }
+}
@@ -0,0 +1,136 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use super::archive_extract;
+use super::project_types::ProjectType;
+
+// 条目右键菜单中选择的操作，打开以外的操作由主程序执行
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextAction {
+    Open(PathBuf),
+    OpenWith(PathBuf),
+    Cut(Vec<PathBuf>),
+    Copy(Vec<PathBuf>),
+    CopyToMultiple(Vec<PathBuf>), // 一次复制到多个文件夹
+    Paste(PathBuf), // 粘贴到的文件夹
+    Rename(PathBuf),
+    CreateLink(PathBuf), // 创建符号链接或硬链接
+    Delete(Vec<PathBuf>),            // 移到回收站
+    DeletePermanently(Vec<PathBuf>), // 永久删除（Shift+Delete）
+    Properties(PathBuf),
+    VerifyChecksum(PathBuf), // 校验文件的 MD5/SHA 值
+    CopyPath(Vec<PathBuf>),
+    Compare(PathBuf, PathBuf), // 在预览栏中比较选中的两个文件
+    AddToBasket(Vec<PathBuf>), // 加入暂存篮
+    Compress(Vec<PathBuf>),    // 压缩为 zip 或 tar.gz
+    Extract { archive: PathBuf, dest: PathBuf }, // 解压到指定文件夹
+    ExtractTo(PathBuf),        // 选择解压位置
+    RunInTerminal { dir: PathBuf, command: String }, // 在项目文件夹中打开终端执行命令
+    OpenInEditor(PathBuf),
+}
+
+// 右键菜单内容：target 为右键点击的条目，paths 为全部选中项（包含 target），
+// project_types 为 target 文件夹识别出的项目类型
+pub fn show_item_menu(ui: &mut egui::Ui, target: &Path, is_dir: bool, paths: &[PathBuf], can_paste: bool, project_types: &[ProjectType]) -> Option<ContextAction> {
+    let mut action = None;
+    let single = paths.len() <= 1;
+
+    if ui.button("打开").clicked() {
+        action = Some(ContextAction::Open(target.to_path_buf()));
+    }
+    if ui.add_enabled(!is_dir, egui::Button::new("打开方式...")).clicked() {
+        action = Some(ContextAction::OpenWith(target.to_path_buf()));
+    }
+    ui.separator();
+
+    // 项目文件夹的专用操作
+    if is_dir && !project_types.is_empty() {
+        for kind in project_types {
+            ui.menu_button(kind.label(), |ui| {
+                for (label, command) in kind.commands(target) {
+                    if ui.button(label).clicked() {
+                        action = Some(ContextAction::RunInTerminal { dir: target.to_path_buf(), command });
+                    }
+                }
+            });
+        }
+        if ui.button("在编辑器中打开").clicked() {
+            action = Some(ContextAction::OpenInEditor(target.to_path_buf()));
+        }
+        ui.separator();
+    }
+
+    if ui.button("剪切").clicked() {
+        action = Some(ContextAction::Cut(paths.to_vec()));
+    }
+    if ui.button("复制").clicked() {
+        action = Some(ContextAction::Copy(paths.to_vec()));
+    }
+    if ui.button("复制到多个位置...").clicked() {
+        action = Some(ContextAction::CopyToMultiple(paths.to_vec()));
+    }
+    if ui.button("加入暂存篮").clicked() {
+        action = Some(ContextAction::AddToBasket(paths.to_vec()));
+    }
+    if ui.button("压缩为...").clicked() {
+        action = Some(ContextAction::Compress(paths.to_vec()));
+    }
+    if single && !is_dir && archive_extract::is_extractable(target) {
+        if let Some(dir) = target.parent() {
+            if ui.button("解压到当前文件夹").clicked() {
+                action = Some(ContextAction::Extract { archive: target.to_path_buf(), dest: dir.to_path_buf() });
+            }
+        }
+        if ui.button("解压到...").clicked() {
+            action = Some(ContextAction::ExtractTo(target.to_path_buf()));
+        }
+    }
+    // 在文件夹上粘贴时粘贴到该文件夹中，否则粘贴到条目所在的文件夹
+    let paste_dir = if is_dir { Some(target) } else { target.parent() };
+    if let Some(dir) = paste_dir {
+        let label = if is_dir { "粘贴到此文件夹" } else { "粘贴" };
+        if ui.add_enabled(can_paste, egui::Button::new(label)).clicked() {
+            action = Some(ContextAction::Paste(dir.to_path_buf()));
+        }
+    }
+    ui.separator();
+
+    if ui.add_enabled(single, egui::Button::new("重命名")).clicked() {
+        action = Some(ContextAction::Rename(target.to_path_buf()));
+    }
+    if ui.add_enabled(single, egui::Button::new("创建链接...")).clicked() {
+        action = Some(ContextAction::CreateLink(target.to_path_buf()));
+    }
+    let delete_label = if single { "移到回收站".to_string() } else { format!("{} 项移到回收站", paths.len()) };
+    if ui.button(delete_label).clicked() {
+        action = Some(ContextAction::Delete(paths.to_vec()));
+    }
+    if ui.button("永久删除").clicked() {
+        action = Some(ContextAction::DeletePermanently(paths.to_vec()));
+    }
+    ui.separator();
+
+    if ui.button(if single { "复制路径" } else { "复制全部路径" }).clicked() {
+        action = Some(ContextAction::CopyPath(paths.to_vec()));
+    }
+    // 恰好选中两个文件时可以比较
+    let pair = match paths {
+        [a, b] if a.is_file() && b.is_file() => Some((a, b)),
+        _ => None,
+    };
+    if ui.add_enabled(pair.is_some(), egui::Button::new("比较选中的两个文件")).clicked() {
+        if let Some((a, b)) = pair {
+            action = Some(ContextAction::Compare(a.clone(), b.clone()));
+        }
+    }
+    if ui.add_enabled(single && !is_dir, egui::Button::new("校验...")).clicked() {
+        action = Some(ContextAction::VerifyChecksum(target.to_path_buf()));
+    }
+    if ui.button("属性").clicked() {
+        action = Some(ContextAction::Properties(target.to_path_buf()));
+    }
+
+    if action.is_some() {
+        ui.close_menu();
+    }
+    action
+}
@@ -0,0 +1,221 @@
+use eframe::egui;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+// 链接的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkKind {
+    Symbolic,
+    Hard, // 只能用于文件，且必须在同一分区
+}
+
+// 从 from_dir 到 target 的相对路径，两者都是绝对路径，如 /a/b 到 /a/c/d → ../c/d
+fn relative_path(from_dir: &Path, target: &Path) -> PathBuf {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to: Vec<Component> = target.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &to[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+// 规范化上级目录，符号链接本身不解析
+fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的路径"))?;
+    Ok(fs::canonicalize(parent)?.join(name))
+}
+
+// 符号链接中记录的目标路径
+fn link_target(source: &Path, link_dir: &Path, relative: bool) -> io::Result<PathBuf> {
+    let source = absolute_path(source)?;
+    if relative {
+        Ok(relative_path(&fs::canonicalize(link_dir)?, &source))
+    } else {
+        Ok(source)
+    }
+}
+
+#[cfg(unix)]
+fn make_link(kind: LinkKind, source: &Path, target: &Path, link: &Path) -> io::Result<()> {
+    match kind {
+        LinkKind::Symbolic => std::os::unix::fs::symlink(target, link),
+        LinkKind::Hard => fs::hard_link(source, link),
+    }
+}
+
+#[cfg(not(unix))]
+fn make_link(kind: LinkKind, source: &Path, _target: &Path, link: &Path) -> io::Result<()> {
+    match kind {
+        LinkKind::Symbolic => Err(io::Error::new(io::ErrorKind::Unsupported, "当前系统不支持创建符号链接")),
+        LinkKind::Hard => fs::hard_link(source, link),
+    }
+}
+
+// 默认的链接名称，如 "report.pdf 的链接"，已存在时追加序号
+fn default_link_name(dir: &Path, source: &Path) -> String {
+    let name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let base = format!("{} 的链接", name);
+    (1..)
+        .map(|n| if n == 1 { base.clone() } else { format!("{} ({})", base, n) })
+        .find(|candidate| fs::symlink_metadata(dir.join(candidate)).is_err())
+        .unwrap_or(base)
+}
+
+// "创建链接" 窗口：在当前或指定的文件夹中为选中项创建符号链接或硬链接
+pub struct CreateLinkDialog {
+    source: Option<PathBuf>,
+    location: String,
+    current_dir: PathBuf,
+    name: String,
+    kind: LinkKind,
+    relative: bool,
+    error: Option<String>,
+}
+
+impl CreateLinkDialog {
+    pub fn new() -> Self {
+        Self {
+            source: None,
+            location: String::new(),
+            current_dir: PathBuf::new(),
+            name: String::new(),
+            kind: LinkKind::Symbolic,
+            relative: true,
+            error: None,
+        }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.source.is_some()
+    }
+
+    pub fn open(&mut self, source: PathBuf, current_dir: &Path) {
+        self.current_dir = current_dir.to_path_buf();
+        self.location = current_dir.display().to_string();
+        self.name = default_link_name(current_dir, &source);
+        self.kind = LinkKind::Symbolic;
+        self.error = None;
+        self.source = Some(source);
+    }
+
+    fn create(&self, source: &Path) -> Result<(), String> {
+        let dir = PathBuf::from(self.location.trim());
+        if !dir.is_dir() {
+            return Err(format!("不是文件夹: {}", dir.display()));
+        }
+        let name = self.name.trim();
+        if name.is_empty() || name.contains('/') {
+            return Err("链接名称无效".to_string());
+        }
+        let link = dir.join(name);
+        if fs::symlink_metadata(&link).is_ok() {
+            return Err(format!("{} 已存在", name));
+        }
+        let target = link_target(source, &dir, self.relative).map_err(|e| format!("无法确定链接目标: {}", e))?;
+        make_link(self.kind, source, &target, &link).map_err(|e| match e.kind() {
+            io::ErrorKind::PermissionDenied => "没有在该文件夹中创建链接的权限".to_string(),
+            _ if self.kind == LinkKind::Hard => format!("创建硬链接失败（硬链接必须在同一分区）: {}", e),
+            _ => format!("创建链接失败: {}", e),
+        })
+    }
+
+    // 显示窗口；返回 true 表示已创建链接（需要刷新列表）
+    pub fn show_create_link_dialog(&mut self, ctx: &egui::Context) -> bool {
+        let Some(source) = self.source.clone() else {
+            return false;
+        };
+        let is_dir = source.is_dir();
+        if is_dir {
+            self.kind = LinkKind::Symbolic;
+        }
+
+        let mut open = true;
+        let mut close = false;
+        let mut created = false;
+        egui::Window::new("创建链接")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(460.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("链接到: {}", source.display()));
+                ui.separator();
+                egui::Grid::new("create_link_grid").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                    ui.label("位置");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut self.location).desired_width(280.0));
+                        if ui.small_button("当前文件夹").clicked() {
+                            self.location = self.current_dir.display().to_string();
+                        }
+                    });
+                    ui.end_row();
+                    ui.label("名称");
+                    ui.add(egui::TextEdit::singleline(&mut self.name).desired_width(280.0));
+                    ui.end_row();
+                    ui.label("类型");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.kind, LinkKind::Symbolic, "符号链接");
+                        ui.add_enabled_ui(!is_dir, |ui| {
+                            ui.radio_value(&mut self.kind, LinkKind::Hard, "硬链接")
+                                .on_disabled_hover_text("文件夹不能创建硬链接");
+                        });
+                    });
+                    ui.end_row();
+                    if self.kind == LinkKind::Symbolic {
+                        ui.label("目标路径");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut self.relative, true, "相对路径");
+                            ui.radio_value(&mut self.relative, false, "绝对路径");
+                        });
+                        ui.end_row();
+                    }
+                });
+                // 预览链接中记录的目标，相对路径在移动整个目录树后仍然有效
+                if self.kind == LinkKind::Symbolic {
+                    if let Ok(target) = link_target(&source, Path::new(self.location.trim()), self.relative) {
+                        ui.label(egui::RichText::new(format!("→ {}", target.display())).weak());
+                    }
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("创建").clicked() {
+                        match self.create(&source) {
+                            Ok(()) => created = true,
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+                    if ui.button("取消").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !open || close || created {
+            self.source = None;
+        }
+        created
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path(Path::new("/a/b"), Path::new("/a/c/d")), PathBuf::from("../c/d"));
+        assert_eq!(relative_path(Path::new("/a"), Path::new("/a/x.txt")), PathBuf::from("x.txt"));
+        assert_eq!(relative_path(Path::new("/home/u/x"), Path::new("/etc/hosts")), PathBuf::from("../../../etc/hosts"));
+    }
+}
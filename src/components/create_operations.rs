@@ -1,11 +1,18 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io;
 use eframe::egui;
 
+// 记住的最近新建的名称数量
+const MAX_RECENT_NAMES: usize = 20;
+// 新建对话框中最多显示的建议数量
+const MAX_SUGGESTIONS: usize = 5;
+
 // 新建操作管理器
 pub struct CreateOperations {
     last_error: Option<String>,
+    recent_names: Vec<String>, // 最近新建的名称，最新的在末尾
 }
 
 #[derive(Debug)]
@@ -20,11 +27,12 @@ impl CreateOperations {
     pub fn new() -> Self {
         Self {
             last_error: None,
+            recent_names: Vec::new(),
         }
     }
 
     // 新建文件夹
-    pub fn create_folder(&self, parent_path: &Path, folder_name: &str) -> CreateOperationResult {
+    pub fn create_folder(&mut self, parent_path: &Path, folder_name: &str) -> CreateOperationResult {
         if folder_name.is_empty() {
             return CreateOperationResult::Error("文件夹名称不能为空".to_string());
         }
@@ -42,11 +50,39 @@ impl CreateOperations {
         }
 
         match fs::create_dir(&new_folder_path) {
-            Ok(_) => CreateOperationResult::Success,
+            Ok(_) => {
+                self.remember_name(folder_name);
+                CreateOperationResult::Success
+            }
             Err(e) => CreateOperationResult::Error(format!("创建文件夹失败: {}", e)),
         }
     }
 
+    fn remember_name(&mut self, name: &str) {
+        self.recent_names.retain(|n| n != name);
+        self.recent_names.push(name.to_string());
+        if self.recent_names.len() > MAX_RECENT_NAMES {
+            self.recent_names.remove(0);
+        }
+    }
+
+    // 新建文件夹对话框中的名称建议：先延续最近新建的名称的编号，
+    // 再延续目标文件夹中已有的编号序列（如 批次01..批次07 建议 批次08）
+    pub fn suggest_folder_names(&self, parent_path: &Path) -> Vec<String> {
+        let existing: Vec<String> = fs::read_dir(parent_path)
+            .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+            .unwrap_or_default();
+
+        let mut suggestions: Vec<String> = self.recent_names.iter().rev()
+            .filter_map(|name| continue_sequence(name, &existing))
+            .chain(sequence_continuations(&existing))
+            .collect();
+        let mut seen = std::collections::HashSet::new();
+        suggestions.retain(|name| !parent_path.join(name).exists() && seen.insert(name.clone()));
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
     // 生成唯一文件夹名
     pub fn generate_unique_folder_name(&self, parent_path: &Path, base_name: &str) -> String {
         let mut counter = 1;
@@ -164,6 +200,46 @@ impl CreateOperations {
     }
 }
 
+// 把名称拆成 (前缀, 编号, 编号位数, 后缀)，编号取最后一段数字，如 批次07 -> ("批次", 7, 2, "")
+fn split_counter(name: &str) -> Option<(&str, u64, usize, &str)> {
+    let end = name.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = name[..end].rfind(|c: char| !c.is_ascii_digit()).map(|i| i + name[i..].chars().next().map_or(1, char::len_utf8)).unwrap_or(0);
+    let number = name[start..end].parse().ok()?;
+    Some((&name[..start], number, end - start, &name[end..]))
+}
+
+// 同一序列中编号最大的下一个名称，保持编号位数（补零）
+fn next_name(prefix: &str, max: u64, width: usize, suffix: &str) -> String {
+    format!("{}{:0width$}{}", prefix, max + 1, suffix, width = width)
+}
+
+// 延续 name 所在的编号序列，已有名称中同一序列的更大编号也考虑在内
+fn continue_sequence(name: &str, existing: &[String]) -> Option<String> {
+    let (prefix, number, width, suffix) = split_counter(name)?;
+    let max = existing.iter()
+        .filter_map(|n| split_counter(n))
+        .filter(|(p, _, _, s)| *p == prefix && *s == suffix)
+        .map(|(_, n, _, _)| n)
+        .fold(number, u64::max);
+    Some(next_name(prefix, max, width, suffix))
+}
+
+// 已有名称中至少有两项的编号序列的下一个名称，项数多的序列在前
+fn sequence_continuations(existing: &[String]) -> Vec<String> {
+    let mut groups: HashMap<(&str, &str), (usize, u64, usize)> = HashMap::new();
+    for (prefix, number, width, suffix) in existing.iter().filter_map(|n| split_counter(n)) {
+        let group = groups.entry((prefix, suffix)).or_insert((0, 0, width));
+        group.0 += 1;
+        group.1 = group.1.max(number);
+        group.2 = group.2.max(width);
+    }
+    let mut groups: Vec<_> = groups.into_iter().filter(|(_, (count, _, _))| *count >= 2).collect();
+    groups.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(&b.0)));
+    groups.into_iter()
+        .map(|((prefix, suffix), (_, max, width))| next_name(prefix, max, width, suffix))
+        .collect()
+}
+
 // 辅助函数：生成默认文件夹名称
 pub fn generate_default_folder_name(parent_path: &Path) -> String {
     let base_name = "新建文件夹";
@@ -194,4 +270,28 @@ pub fn generate_default_folder_name(parent_path: &Path) -> String {
                 .as_secs());
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_folder_name_suggestions() {
+        assert_eq!(split_counter("批次07"), Some(("批次", 7, 2, "")));
+        assert_eq!(split_counter("IMG_0012 备份"), Some(("IMG_", 12, 4, " 备份")));
+        assert_eq!(split_counter("照片"), None);
+
+        let root = TestDir::new("suggest");
+        for i in 1..=7 {
+            fs::create_dir_all(root.join(format!("批次{:02}", i))).unwrap();
+        }
+        let mut operations = CreateOperations::new();
+        assert_eq!(operations.suggest_folder_names(&root), vec!["批次08".to_string()]);
+
+        // 最近新建的名称的序列排在前面
+        assert!(matches!(operations.create_folder(&root, "第1章"), CreateOperationResult::Success));
+        assert_eq!(operations.suggest_folder_names(&root), vec!["第2章".to_string(), "批次08".to_string()]);
+    }
 }
\ No newline at end of file
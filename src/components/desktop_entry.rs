@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use super::project_types;
+
+// 查找应用图标时依次尝试的尺寸（hicolor 主题）
+const ICON_SIZES: &[&str] = &["256x256", "128x128", "96x96", "64x64", "48x48", "32x32"];
+
+// Exec 字段中代表要打开的文件或 URL 的字段代码
+const FILE_CODES: &[&str] = &["%f", "%F", "%u", "%U"];
+
+// .desktop 启动器中 [Desktop Entry] 段的主要字段
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesktopEntry {
+    pub name: String,
+    pub comment: Option<String>,
+    pub exec: Option<String>,
+    pub icon: Option<String>,
+    pub url: Option<String>, // Type=Link 的链接
+    pub working_dir: Option<String>,
+    pub terminal: bool,
+}
+
+pub fn is_desktop_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("desktop"))
+}
+
+// 当前语言的候选后缀，如 zh_CN.UTF-8 依次为 zh_CN、zh
+fn locale_keys() -> Vec<String> {
+    let lang = ["LC_ALL", "LC_MESSAGES", "LANG"].iter().find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()));
+    let Some(lang) = lang else {
+        return Vec::new();
+    };
+    let lang = lang.split(['.', '@']).next().unwrap_or_default().to_string();
+    let mut keys = vec![lang.clone()];
+    if let Some((language, _)) = lang.split_once('_') {
+        keys.push(language.to_string());
+    }
+    keys
+}
+
+fn parse_with_locale(text: &str, locales: &[String]) -> Option<DesktopEntry> {
+    let mut fields = HashMap::new();
+    let mut in_entry = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            fields.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    // 优先使用当前语言的名称和说明
+    let localized = |key: &str| {
+        locales.iter()
+            .find_map(|locale| fields.get(&format!("{}[{}]", key, locale)))
+            .or_else(|| fields.get(key))
+            .filter(|value| !value.is_empty())
+            .cloned()
+    };
+    Some(DesktopEntry {
+        name: localized("Name")?,
+        comment: localized("Comment").or_else(|| localized("GenericName")),
+        exec: fields.get("Exec").cloned(),
+        icon: fields.get("Icon").cloned(),
+        url: fields.get("URL").cloned(),
+        working_dir: fields.get("Path").cloned(),
+        terminal: fields.get("Terminal").is_some_and(|value| value == "true"),
+    })
+}
+
+// 解析 .desktop 文件；没有 [Desktop Entry] 段或 Name 字段时返回 None
+pub fn parse(text: &str) -> Option<DesktopEntry> {
+    parse_with_locale(text, &locale_keys())
+}
+
+pub fn read(path: &Path) -> Result<DesktopEntry, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse(&text).ok_or_else(|| "不是有效的 .desktop 启动器".to_string())
+}
+
+// 把 Icon 字段解析为图片文件：绝对路径直接使用，名称在 hicolor 主题和 pixmaps 中查找 PNG 图标
+pub fn find_icon(icon: &str) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let mut roots: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+    let data_dirs = std::env::var("XDG_DATA_DIRS").unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+    roots.extend(std::env::split_paths(&data_dirs));
+    let file_name = format!("{}.png", icon);
+    let file_name = file_name.as_str();
+    roots.iter()
+        .flat_map(|root| ICON_SIZES.iter().map(move |size| root.join("icons").join("hicolor").join(size).join("apps").join(file_name)))
+        .chain(std::iter::once(PathBuf::from("/usr/share/pixmaps").join(file_name)))
+        .find(|candidate| candidate.is_file())
+}
+
+// 按规范拆分 Exec 字段：支持双引号和反斜杠转义，字段代码原样保留
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let (mut current, mut quoted, mut started) = (String::new(), false, false);
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            '\\' if quoted => current.extend(chars.next()),
+            ' ' | '\t' if !quoted => {
+                if started {
+                    arguments.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        arguments.push(current);
+    }
+    arguments
+}
+
+// 把 Exec 字段展开为参数：%f %F %u %U 替换为 file（为 None 时去掉），其它字段代码去掉，%% 还原为 %
+pub fn exec_arguments(exec: &str, file: Option<&Path>) -> Vec<String> {
+    split_exec(exec).into_iter()
+        .filter_map(|argument| {
+            if FILE_CODES.contains(&argument.as_str()) {
+                return file.map(|file| file.to_string_lossy().to_string());
+            }
+            let mut chars = argument.chars();
+            let field_code = chars.next() == Some('%') && chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.next().is_none();
+            (!field_code).then(|| argument.replace("%%", "%"))
+        })
+        .collect()
+}
+
+// Exec 字段中是否有 %f %F %u %U；没有时由调用方把文件追加在参数最后
+pub fn takes_files(exec: &str) -> bool {
+    split_exec(exec).iter().any(|argument| FILE_CODES.contains(&argument.as_str()))
+}
+
+// 启动应用（Type=Link 时打开链接）；Terminal=true 的应用在终端中运行
+pub fn launch(entry: &DesktopEntry, path: &Path) -> Result<(), String> {
+    if let Some(url) = &entry.url {
+        return Command::new("xdg-open").arg(url).stdin(Stdio::null()).spawn().map(|_| ()).map_err(|e| format!("无法打开链接: {}", e));
+    }
+    let exec = entry.exec.as_deref().ok_or("启动器中没有 Exec 命令")?;
+    let arguments = exec_arguments(exec, None);
+    let (program, args) = arguments.split_first().ok_or("启动器中没有 Exec 命令")?;
+    let dir = entry.working_dir.as_ref().map(PathBuf::from)
+        .or_else(|| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("/"));
+    if entry.terminal {
+        let command: Vec<String> = arguments.iter().map(|argument| project_types::shell_quote(argument)).collect();
+        return project_types::run_in_terminal(&dir, &command.join(" "));
+    }
+    Command::new(program)
+        .args(args)
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("启动 {} 失败: {}", program, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_desktop_entry() {
+        let text = "[Desktop Entry]\nType=Application\nName=Text Editor\nName[zh_CN]=文本编辑器\nComment=Edit text files\nExec=gedit --new-window %U\nIcon=org.gnome.gedit\nTerminal=false\n\n[Desktop Action new]\nName=New Window\nExec=gedit\n";
+        let entry = parse_with_locale(text, &["zh_CN".to_string(), "zh".to_string()]).unwrap();
+        assert_eq!(entry.name, "文本编辑器");
+        assert_eq!(entry.comment.as_deref(), Some("Edit text files"));
+        assert_eq!(entry.exec.as_deref(), Some("gedit --new-window %U"));
+        assert!(!entry.terminal);
+        assert_eq!(parse_with_locale(text, &[]).unwrap().name, "Text Editor");
+        assert!(parse_with_locale("[Other]\nName=x\n", &[]).is_none());
+
+        assert_eq!(exec_arguments("gedit --new-window %U", None), vec!["gedit", "--new-window"]);
+        assert_eq!(exec_arguments("\"/opt/My App/run\" --rate 100%% %f", None), vec!["/opt/My App/run", "--rate", "100%"]);
+        assert_eq!(exec_arguments("sh -c \"echo \\\"hi\\\"\"", None), vec!["sh", "-c", "echo \"hi\""]);
+        assert_eq!(exec_arguments("viewer %i %U", Some(Path::new("/tmp/a b.png"))), vec!["viewer", "/tmp/a b.png"]);
+        assert!(takes_files("viewer %u") && !takes_files("viewer 100%%"));
+    }
+}
@@ -0,0 +1,219 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::utils;
+use super::app_event::{AppEvent, EventQueue};
+use super::context_menu::ContextAction;
+use super::folder_size::FolderSizeWorker;
+use super::locale_format;
+
+// 条形的颜色：文件夹和文件
+const FOLDER_COLOR: egui::Color32 = egui::Color32::from_rgb(66, 133, 244);
+const FILE_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 120, 120);
+
+// 分析结果中的一项：文件夹的大小在后台统计，完成前为 None
+struct UsageRow {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    size: Option<u64>,
+    deleting: bool, // 已请求删除，操作结束后由 settle_deletions 移除或恢复
+}
+
+// 列出文件夹的直接子项，文件的大小立即可知
+fn list_children(dir: &Path) -> Vec<UsageRow> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries.flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let is_dir = metadata.is_dir();
+            Some(UsageRow {
+                path: entry.path(),
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir,
+                size: (!is_dir).then_some(metadata.len()),
+                deleting: false,
+            })
+        })
+        .collect()
+}
+
+// 从大到小排列，尚未统计完的文件夹放在最后
+fn sort_rows(rows: &mut [UsageRow]) {
+    rows.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+}
+
+// 磁盘使用分析：按大小列出文件夹中各子项的占用，可进入子文件夹、在内容框中打开或删除
+pub struct DiskUsageAnalyzer {
+    dir: Option<PathBuf>,
+    rows: Vec<UsageRow>,
+    worker: Option<FolderSizeWorker>,
+    history: Vec<(PathBuf, Vec<UsageRow>)>, // 进入子文件夹前的结果，返回时不必重新扫描
+}
+
+impl DiskUsageAnalyzer {
+    pub fn new() -> Self {
+        Self { dir: None, rows: Vec::new(), worker: None, history: Vec::new() }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    pub fn start(&mut self, dir: PathBuf) {
+        self.history.clear();
+        self.scan(dir);
+    }
+
+    fn scan(&mut self, dir: PathBuf) {
+        self.rows = list_children(&dir);
+        sort_rows(&mut self.rows);
+        self.resume(dir);
+    }
+
+    // 为尚未统计的文件夹启动后台统计
+    fn resume(&mut self, dir: PathBuf) {
+        let pending: Vec<PathBuf> = self.rows.iter().filter(|row| row.size.is_none()).map(|row| row.path.clone()).collect();
+        self.worker = (!pending.is_empty()).then(|| FolderSizeWorker::start(pending));
+        self.dir = Some(dir);
+    }
+
+    fn enter(&mut self, dir: PathBuf) {
+        if let Some(current) = self.dir.take() {
+            self.history.push((current, std::mem::take(&mut self.rows)));
+        }
+        self.scan(dir);
+    }
+
+    fn go_up(&mut self) {
+        if let Some((dir, rows)) = self.history.pop() {
+            self.rows = rows;
+            self.resume(dir);
+        } else if let Some(parent) = self.dir.as_ref().and_then(|d| d.parent()).map(Path::to_path_buf) {
+            self.scan(parent);
+        }
+    }
+
+    fn poll(&mut self, ctx: &egui::Context) {
+        let Some(worker) = self.worker.as_mut() else {
+            return;
+        };
+        let results = worker.poll();
+        let running = worker.is_running();
+        if !results.is_empty() {
+            for (path, size) in results {
+                if let Some(row) = self.rows.iter_mut().find(|row| row.path == path) {
+                    row.size = Some(size);
+                }
+            }
+            sort_rows(&mut self.rows);
+        }
+        if running {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        } else {
+            self.worker = None;
+        }
+    }
+
+    // 没有等待确认或执行中的操作时调用：已删除的条目从列表中移除，取消或删除失败的条目恢复删除按钮
+    pub fn settle_deletions(&mut self) {
+        if self.rows.iter().any(|row| row.deleting) {
+            self.rows.retain(|row| !row.deleting || fs::symlink_metadata(&row.path).is_ok());
+            for row in &mut self.rows {
+                row.deleting = false;
+            }
+        }
+    }
+
+    pub fn show_disk_usage_dialog(&mut self, ctx: &egui::Context, events: &mut EventQueue) {
+        self.poll(ctx);
+        let Some(dir) = self.dir.clone() else {
+            return;
+        };
+
+        let format = locale_format::current();
+        let total: u64 = self.rows.iter().filter_map(|row| row.size).sum();
+        let pending = self.rows.iter().filter(|row| row.size.is_none()).count();
+        let mut open = true;
+        let mut enter = None;
+        let mut go_up = false;
+        egui::Window::new("磁盘使用分析")
+            .collapsible(false)
+            .resizable(true)
+            .default_width(560.0)
+            .default_height(460.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(dir.parent().is_some(), egui::Button::new("⬆ 上一级")).clicked() {
+                        go_up = true;
+                    }
+                    ui.label(egui::RichText::new(dir.display().to_string()).strong());
+                });
+                ui.horizontal(|ui| {
+                    ui.label(format!("共 {}，{} 项", utils::get_file_size_str(total), format.count(self.rows.len() as u64)));
+                    if pending > 0 {
+                        ui.spinner();
+                        ui.label(format!("正在统计 {} 个文件夹...", format.count(pending as u64)));
+                    }
+                });
+                ui.separator();
+
+                let largest = self.rows.iter().filter_map(|row| row.size).max().unwrap_or(0).max(1);
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    egui::Grid::new("disk_usage_grid").num_columns(4).striped(true).spacing([8.0, 4.0]).show(ui, |ui| {
+                        for row in self.rows.iter_mut() {
+                            let icon = if row.is_dir { "📁" } else { "📄" };
+                            let label = ui.add(egui::Label::new(format!("{} {}", icon, row.name)).truncate().sense(egui::Sense::click()));
+                            if row.is_dir && label.on_hover_text("双击进入").double_clicked() {
+                                enter = Some(row.path.clone());
+                            }
+
+                            // 条形长度相对最大的一项，便于比较
+                            let (rect, _) = ui.allocate_exact_size(egui::vec2(160.0, 12.0), egui::Sense::hover());
+                            ui.painter().rect_filled(rect, 2.0, ui.visuals().faint_bg_color);
+                            if let Some(size) = row.size {
+                                let width = rect.width() * size as f32 / largest as f32;
+                                let bar = egui::Rect::from_min_size(rect.min, egui::vec2(width, rect.height()));
+                                ui.painter().rect_filled(bar, 2.0, if row.is_dir { FOLDER_COLOR } else { FILE_COLOR });
+                            }
+
+                            match row.size {
+                                Some(size) => ui.label(format!("{}  {:.1}%", utils::get_file_size_str(size), size as f32 * 100.0 / total.max(1) as f32)),
+                                None => ui.spinner(),
+                            };
+
+                            ui.horizontal(|ui| {
+                                if row.is_dir && ui.small_button("进入").clicked() {
+                                    enter = Some(row.path.clone());
+                                }
+                                let open_event = if row.is_dir { AppEvent::Navigate(row.path.clone()) } else { AppEvent::Reveal(row.path.clone()) };
+                                if ui.small_button("打开").on_hover_text("在内容框中打开").clicked() {
+                                    events.emit(open_event);
+                                }
+                                if ui.add_enabled(!row.deleting, egui::Button::new("删除").small()).on_hover_text("移到回收站（需确认）").clicked() {
+                                    row.deleting = true;
+                                    events.emit(AppEvent::FileAction(ContextAction::Delete(vec![row.path.clone()])));
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if let Some(path) = enter {
+            self.enter(path);
+        } else if go_up {
+            self.go_up();
+        }
+        if !open {
+            self.dir = None;
+            self.worker = None;
+            self.rows.clear();
+            self.history.clear();
+        }
+    }
+}
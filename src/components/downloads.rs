@@ -0,0 +1,154 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::file_list::FileDropRequest;
+
+// 浏览器下载文件时在扩展属性中记录的来源地址
+#[cfg(target_os = "linux")]
+const ORIGIN_ATTR: &str = "user.xdg.origin.url";
+
+// 下载文件夹，进入时使用单独的分组方式
+pub fn is_downloads_folder(path: &Path) -> bool {
+    dirs::download_dir().is_some_and(|dir| dir == path)
+}
+
+// 从地址中取出网站名，如 https://www.example.com/a.zip -> example.com
+fn origin_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map(|(_, host)| host).unwrap_or(host);
+    let host = host.split(':').next()?.trim_start_matches("www.");
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+// 文件的下载来源（网站名）；没有记录来源时返回 None
+pub fn download_source(path: &Path) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let value = xattr::get(path, ORIGIN_ATTR).ok()??;
+        origin_host(&String::from_utf8_lossy(&value))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+// 把一个分组的全部文件移动到其它文件夹
+pub struct GroupMoveDialog {
+    show_dialog: bool,
+    group: String,
+    paths: Vec<PathBuf>,
+    source_dir: PathBuf,
+    target: String,
+    error: Option<String>,
+}
+
+impl GroupMoveDialog {
+    pub fn new() -> Self {
+        Self {
+            show_dialog: false,
+            group: String::new(),
+            paths: Vec::new(),
+            source_dir: PathBuf::new(),
+            target: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show_dialog
+    }
+
+    // 打开对话框：group 为组标题，paths 为组内的全部条目，source_dir 为它们所在的文件夹
+    pub fn open(&mut self, group: String, paths: Vec<PathBuf>, source_dir: PathBuf) {
+        self.target = source_dir.join(&group).display().to_string();
+        self.group = group;
+        self.paths = paths;
+        self.source_dir = source_dir;
+        self.error = None;
+        self.show_dialog = true;
+    }
+
+    // 常用的目标文件夹：以组名命名的子文件夹和用户目录下的文档、图片等文件夹
+    fn suggestions(&self) -> Vec<(String, PathBuf)> {
+        let mut suggestions = vec![(format!("子文件夹 {}", self.group), self.source_dir.join(&self.group))];
+        let user_dirs = [
+            ("文档", dirs::document_dir()),
+            ("图片", dirs::picture_dir()),
+            ("视频", dirs::video_dir()),
+            ("音乐", dirs::audio_dir()),
+            ("桌面", dirs::desktop_dir()),
+        ];
+        suggestions.extend(user_dirs.into_iter().filter_map(|(label, dir)| Some((label.to_string(), dir?))));
+        suggestions
+    }
+
+    // 显示对话框；点击移动后返回移动请求（不存在的目标文件夹先创建）
+    pub fn show_group_move_dialog(&mut self, ctx: &egui::Context) -> Option<FileDropRequest> {
+        let mut open = true;
+        let mut request = None;
+
+        egui::Window::new("移动分组")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("把分组“{}”中的 {} 项移动到:", self.group, self.paths.len()));
+                ui.add(egui::TextEdit::singleline(&mut self.target).hint_text("输入文件夹路径").desired_width(360.0));
+                ui.horizontal_wrapped(|ui| {
+                    for (label, dir) in self.suggestions() {
+                        if ui.small_button(label).on_hover_text(dir.display().to_string()).clicked() {
+                            self.target = dir.display().to_string();
+                        }
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("移动").clicked() {
+                        let target = PathBuf::from(self.target.trim());
+                        if self.target.trim().is_empty() {
+                            self.error = Some("请输入目标文件夹".to_string());
+                        } else if target == self.source_dir {
+                            self.error = Some("目标就是当前文件夹".to_string());
+                        } else if let Err(e) = fs::create_dir_all(&target) {
+                            self.error = Some(format!("无法创建目标文件夹: {}", e));
+                        } else {
+                            request = Some(FileDropRequest {
+                                paths: std::mem::take(&mut self.paths),
+                                target_dir: target,
+                                copy: false,
+                            });
+                            self.show_dialog = false;
+                        }
+                    }
+                    if ui.button("取消").clicked() {
+                        self.show_dialog = false;
+                    }
+                });
+            });
+
+        if !open {
+            self.show_dialog = false;
+        }
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_host() {
+        assert_eq!(origin_host("https://www.example.com/files/a.zip?x=1").as_deref(), Some("example.com"));
+        assert_eq!(origin_host("http://user@Mirror.Host.org:8080/pkg").as_deref(), Some("mirror.host.org"));
+        assert_eq!(origin_host("https://"), None);
+    }
+}
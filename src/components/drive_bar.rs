@@ -2,6 +2,75 @@ use eframe::egui;
 use std::path::PathBuf;
 use std::fs;
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crossbeam_channel::{self, Receiver};
+use serde::{Deserialize, Serialize};
+use sysinfo::Disks;
+
+// 磁盘可用空间的刷新间隔
+const DISK_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+// 每个工作区保存的导航历史条数上限
+const MAX_SAVED_HISTORY: usize = 50;
+// 挂载表，挂载或卸载时内容会变化
+const MOUNTINFO: &str = "/proc/self/mountinfo";
+// 检查挂载表的间隔
+const MOUNT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// 这些目录下的挂载点（U 盘、移动硬盘、手动挂载的分区）显示为盘符
+const MOUNT_ROOTS: [&str; 3] = ["/media", "/run/media", "/mnt"];
+
+// mountinfo 中的路径把空格等字符转义为 \040 这样的八进制形式
+fn unescape_mount_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).and_then(|d| std::str::from_utf8(d).ok()).and_then(|d| u8::from_str_radix(d, 8).ok());
+        match octal {
+            Some(byte) if bytes[i] == b'\\' => {
+                result.push(byte);
+                i += 4;
+            }
+            _ => {
+                result.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&result).to_string()
+}
+
+// 挂载表中位于 MOUNT_ROOTS 之下的挂载点（第 5 列）
+fn parse_mount_points(mountinfo: &str) -> Vec<PathBuf> {
+    let mut mounts: Vec<PathBuf> = mountinfo
+        .lines()
+        .filter_map(|line| line.split(' ').nth(4))
+        .map(|path| PathBuf::from(unescape_mount_path(path)))
+        .filter(|path| MOUNT_ROOTS.iter().any(|root| path.starts_with(root) && path != Path::new(root)))
+        .collect();
+    mounts.sort();
+    mounts.dedup();
+    mounts
+}
+
+// 后台定期读取挂载表，内容变化时发送新的挂载表并请求重绘
+fn spawn_mount_watcher(ctx: egui::Context, mut last: String) -> Receiver<String> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(MOUNT_POLL_INTERVAL);
+        let Ok(current) = fs::read_to_string(MOUNTINFO) else {
+            continue;
+        };
+        if current != last {
+            if sender.send(current.clone()).is_err() {
+                return;
+            }
+            ctx.request_repaint();
+            last = current;
+        }
+    });
+    receiver
+}
 
 #[derive(Debug, Clone)]
 pub struct Drive {
@@ -10,16 +79,69 @@ pub struct Drive {
     pub is_mounted: bool,
 }
 
+// 一个盘符的工作区：内容框和目录框的路径以及导航历史
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceState {
+    pub current_path: PathBuf,
+    pub directory_current_path: PathBuf,
+    pub nav_history: Vec<PathBuf>,
+    pub history_pos: usize,
+}
+
+impl WorkspaceState {
+    fn at(path: &Path) -> Self {
+        Self {
+            current_path: path.to_path_buf(),
+            directory_current_path: path.to_path_buf(),
+            nav_history: vec![path.to_path_buf()],
+            history_pos: 0,
+        }
+    }
+
+    // 恢复前检查保存的目录是否还在：不在时退回盘符根目录，并从历史中去掉
+    fn validated(mut self, drive_root: &Path) -> Self {
+        if !self.current_path.is_dir() {
+            self.current_path = drive_root.to_path_buf();
+        }
+        if !self.directory_current_path.is_dir() {
+            self.directory_current_path = self.current_path.clone();
+        }
+        let before = self.nav_history.len();
+        self.nav_history.retain(|p| p.is_dir());
+        if self.nav_history.len() != before || self.nav_history.get(self.history_pos) != Some(&self.current_path) {
+            self.nav_history.retain(|p| p != &self.current_path);
+            self.nav_history.push(self.current_path.clone());
+            self.history_pos = self.nav_history.len() - 1;
+        }
+        self
+    }
+}
+
 pub struct DriveBar {
     drives: Vec<Drive>,
-    saved_paths: HashMap<PathBuf, PathBuf>,  // 盘符路径 -> 保存的工作路径
+    workspaces: HashMap<String, WorkspaceState>, // 盘符路径 -> 保存的工作区
+    file_path: Option<PathBuf>,                  // 工作区保存在配置目录的 workspaces.json 中
+    disks: Disks,                                // 已挂载的磁盘（用于查询可用空间）
+    disks_refreshed: Instant,
+    mountinfo: Option<String>,                   // 最近读取的挂载表，不可读时按目录扫描
+    mount_receiver: Option<Receiver<String>>,
 }
 
 impl DriveBar {
-    pub fn new(current_path: &PathBuf) -> Self {
+    pub fn new() -> Self {
+        let file_path = dirs::config_dir().map(|dir| dir.join("file-explorer").join("workspaces.json"));
+        let workspaces = file_path
+            .as_ref()
+            .and_then(|path| crate::utils::load_json(path))
+            .unwrap_or_default();
         let mut drive_bar = Self {
             drives: Vec::new(),
-            saved_paths: HashMap::new(),
+            workspaces,
+            file_path,
+            disks: Disks::new_with_refreshed_list(),
+            disks_refreshed: Instant::now(),
+            mountinfo: fs::read_to_string(MOUNTINFO).ok(),
+            mount_receiver: None,
         };
         drive_bar.refresh_drives();
         drive_bar
@@ -34,8 +156,18 @@ impl DriveBar {
             is_mounted: true,
         });
 
-        self.scan_mount_points("/media");
-        self.scan_mount_points("/mnt");
+        match &self.mountinfo {
+            Some(mountinfo) => {
+                for path in parse_mount_points(mountinfo) {
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    self.drives.push(Drive { path, name, is_mounted: true });
+                }
+            }
+            None => {
+                self.scan_mount_points("/media");
+                self.scan_mount_points("/mnt");
+            }
+        }
 
         let common_mounts = ["/home", "/var", "/opt", "/usr"];
         for mount in &common_mounts {
@@ -72,33 +204,44 @@ impl DriveBar {
         }
     }
 
-    fn find_drive_root(&self, path: &PathBuf) -> PathBuf {
-        println!("盘符栏: 查找路径 {} 的盘符根目录", path.display());
-        for drive in &self.drives {
-            if path.starts_with(&drive.path) {
-                println!("盘符栏: 找到匹配盘符 {}", drive.path.display());
-                return drive.path.clone();
-            }
-        }
-        println!("盘符栏: 没有找到匹配盘符，使用根目录");
-        PathBuf::from("/")
+    // 路径所属的盘符（取最长的匹配），没有匹配时使用根目录
+    fn find_drive_root(&self, path: &Path) -> PathBuf {
+        self.drives.iter()
+            .filter(|d| path.starts_with(&d.path))
+            .max_by_key(|d| d.path.as_os_str().len())
+            .map(|d| d.path.clone())
+            .unwrap_or_else(|| PathBuf::from("/"))
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf) -> bool {
-        // 调试：显示当前保存的路径状态
-        if self.saved_paths.len() > 0 {
-            println!("盘符栏: 当前保存的工作区路径:");
-            for (drive_root, saved_path) in &self.saved_paths {
-                println!("  {} -> {}", drive_root.display(), saved_path.display());
-            }
+    // 挂载表变化时重新生成盘符，去掉已卸载的、加入新挂载的
+    fn poll_mounts(&mut self, ctx: &egui::Context) {
+        let Some(mountinfo) = &self.mountinfo else {
+            return;
+        };
+        let receiver = self.mount_receiver.get_or_insert_with(|| spawn_mount_watcher(ctx.clone(), mountinfo.clone()));
+        if let Some(latest) = receiver.try_iter().last() {
+            self.mountinfo = Some(latest);
+            self.refresh_drives();
+            self.disks.refresh_list();
+            self.disks_refreshed = Instant::now();
         }
-        let mut workspace_switched = false;
+    }
+
+    pub fn drives(&self) -> &[Drive] {
+        &self.drives
+    }
+
+    // 显示盘符按钮，返回点击的盘符；切换工作区由主程序完成
+    pub fn show(&mut self, ui: &mut egui::Ui, current_path: &Path) -> Option<PathBuf> {
+        self.poll_mounts(ui.ctx());
+        let current_drive = self.find_drive_root(current_path);
+        let mut clicked = None;
 
         ui.horizontal(|ui| {
             ui.label("盘符:");
 
             for drive in &self.drives {
-                let is_current = current_path.starts_with(&drive.path);
+                let is_current = drive.path == current_drive;
 
                 let button_text = if is_current {
                     format!("✓ {}", drive.name)
@@ -115,47 +258,104 @@ impl DriveBar {
                             egui::Color32::TRANSPARENT
                         })
                 ).clicked() {
-                    println!("盘符栏: 点击了盘符 {}", drive.path.display());
-                    println!("盘符栏: 切换前的当前路径 {}", current_path.display());
-
-                    // 简单直接：保存当前绝对路径到对应的盘符
-                    // 找到当前路径属于哪个盘符
-                    let mut current_drive = PathBuf::from("/");
-                    for d in &self.drives {
-                        if current_path.starts_with(&d.path) && d.path.as_os_str().len() > current_drive.as_os_str().len() {
-                            current_drive = d.path.clone();
-                        }
-                    }
-
-                    println!("盘符栏: 保存路径 {} 到盘符 {}", current_path.display(), current_drive.display());
-                    self.saved_paths.insert(current_drive.clone(), current_path.clone());
-
-                    // 切换到新盘符，恢复保存的路径
-                    if let Some(saved_path) = self.saved_paths.get(&drive.path) {
-                        println!("盘符栏: 恢复保存的路径 {}", saved_path.display());
-                        *current_path = saved_path.clone();
-                    } else {
-                        println!("盘符栏: 没有保存的路径，使用盘符根目录 {}", drive.path.display());
-                        *current_path = drive.path.clone();
-                    }
-
-                    println!("盘符栏: 切换后的路径 {}", current_path.display());
-                    workspace_switched = true;
+                    clicked = Some(drive.path.clone());
                 }
             }
         });
 
-        workspace_switched
+        clicked
+    }
+
+    // 路径所在磁盘的可用空间（取挂载点最长的匹配），定期刷新
+    pub fn free_space(&mut self, path: &Path) -> Option<u64> {
+        if self.disks_refreshed.elapsed() >= DISK_REFRESH_INTERVAL {
+            self.disks.refresh();
+            self.disks_refreshed = Instant::now();
+        }
+        self.disks.list().iter()
+            .filter(|disk| path.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())
+            .map(|disk| disk.available_space())
     }
 
+    // 记录当前路径所属盘符的工作区，有变化时写入配置文件
     pub fn save_workspace_state(
         &mut self,
-        current_path: &PathBuf,
-        _directory_current_path: &PathBuf,
-        _nav_history: &[PathBuf],
-        _history_pos: usize,
+        current_path: &Path,
+        directory_current_path: &Path,
+        nav_history: &[PathBuf],
+        history_pos: usize,
     ) {
-        let drive_root = self.find_drive_root(current_path);
-        self.saved_paths.insert(drive_root, current_path.clone());
+        // 只保存最近的历史，丢弃的条数从位置中减去
+        let skip = nav_history.len().saturating_sub(MAX_SAVED_HISTORY);
+        let state = WorkspaceState {
+            current_path: current_path.to_path_buf(),
+            directory_current_path: directory_current_path.to_path_buf(),
+            nav_history: nav_history[skip..].to_vec(),
+            history_pos: history_pos.saturating_sub(skip),
+        };
+        let key = self.find_drive_root(current_path).to_string_lossy().to_string();
+        if self.workspaces.get(&key) == Some(&state) {
+            return;
+        }
+        self.workspaces.insert(key, state);
+        self.save();
+    }
+
+    // 切换到盘符时恢复的工作区；没有保存过时从盘符根目录开始
+    pub fn workspace_state(&self, drive_root: &Path) -> WorkspaceState {
+        match self.workspaces.get(drive_root.to_string_lossy().as_ref()) {
+            Some(state) => state.clone().validated(drive_root),
+            None => WorkspaceState::at(drive_root),
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if let Err(e) = crate::utils::save_json(path, &self.workspaces) {
+            eprintln!("保存工作区失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_parse_mount_points() {
+        let mountinfo = "\
+22 1 8:2 / / rw,relatime - ext4 /dev/sda2 rw
+40 22 8:17 / /media/li/My\\040Disk rw,nosuid - vfat /dev/sdb1 rw
+41 22 8:33 / /mnt/backup rw - ext4 /dev/sdc1 rw
+42 22 0:5 / /mnt rw - tmpfs tmpfs rw
+43 22 0:6 / /proc rw - proc proc rw";
+        assert_eq!(parse_mount_points(mountinfo), vec![PathBuf::from("/media/li/My Disk"), PathBuf::from("/mnt/backup")]);
+    }
+
+    #[test]
+    fn test_workspace_state_validation() {
+        let root = TestDir::new("workspace");
+        let (a, b) = (root.join("a"), root.join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+
+        let state = WorkspaceState {
+            current_path: b.clone(),
+            directory_current_path: a.clone(),
+            nav_history: vec![root.clone(), a.clone(), b.clone()],
+            history_pos: 2,
+        };
+        assert_eq!(state.clone().validated(&root), state);
+
+        // 删除的目录从历史中去掉，当前目录不在时退回盘符根目录
+        fs::remove_dir_all(&b).unwrap();
+        let restored = state.validated(&root);
+        assert_eq!(restored.current_path, *root);
+        assert_eq!(restored.nav_history, vec![a.clone(), root.clone()]);
+        assert_eq!(restored.history_pos, 1);
     }
 }
\ No newline at end of file
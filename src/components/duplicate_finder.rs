@@ -0,0 +1,383 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver, Sender};
+use super::checksum::{collect_files, sha256_file};
+use super::image_formats;
+use crate::utils;
+
+// 感知哈希的汉明距离不超过该值时视为相似图片
+const SIMILAR_THRESHOLD: u32 = 6;
+// 预览缩略图的最大边长
+const PREVIEW_SIZE: u32 = 128;
+
+// 重复组的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicateKind {
+    Exact,   // 内容完全相同
+    Similar, // 视觉上相同的图片（尺寸或压缩不同）
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateItem {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    pub items: Vec<DuplicateItem>,
+}
+
+// 后台线程生成的预览图（RGBA）
+struct PreviewImage {
+    path: PathBuf,
+    size: [usize; 2],
+    rgba: Vec<u8>,
+    dimensions: (u32, u32), // 原图尺寸
+}
+
+enum ScanEvent {
+    Progress { done: usize, total: usize, current: String },
+    Finished { groups: Vec<DuplicateGroup>, previews: Vec<PreviewImage> },
+}
+
+fn is_hashable_image(path: &Path) -> bool {
+    image_formats::is_image_file(path)
+}
+
+// 差值哈希（dHash）：缩放为 9x8 灰度图，比较相邻像素亮度
+pub fn difference_hash(image: &image::DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, image::imageops::FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+// 并查集，用于把两两相似的图片合并成组
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+fn scan(root: &Path, sender: &Sender<ScanEvent>, cancelled: &AtomicBool) -> Option<(Vec<DuplicateGroup>, Vec<PreviewImage>)> {
+    let files: Vec<(PathBuf, u64)> = collect_files(root)
+        .into_iter()
+        .map(|relative| root.join(relative))
+        .filter_map(|path| fs::metadata(&path).ok().map(|m| (path, m.len())))
+        .collect();
+
+    // 大小相同的非空文件才可能内容相同，只对这些文件计算 SHA256
+    let mut by_size: HashMap<u64, Vec<&PathBuf>> = HashMap::new();
+    for (path, size) in &files {
+        if *size > 0 {
+            by_size.entry(*size).or_default().push(path);
+        }
+    }
+    let candidates: Vec<(&PathBuf, u64)> = by_size.iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.iter().map(move |p| (*p, *size)))
+        .collect();
+    let images: Vec<&(PathBuf, u64)> = files.iter().filter(|(p, _)| is_hashable_image(p)).collect();
+    let total = candidates.len() + images.len();
+    let mut done = 0;
+
+    let mut by_hash: HashMap<String, Vec<DuplicateItem>> = HashMap::new();
+    for (path, size) in candidates {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let _ = sender.send(ScanEvent::Progress { done, total, current: path.to_string_lossy().to_string() });
+        done += 1;
+        if let Ok(hash) = sha256_file(path, cancelled) {
+            by_hash.entry(hash).or_default().push(DuplicateItem { path: path.clone(), size });
+        }
+    }
+    let mut groups: Vec<DuplicateGroup> = by_hash.into_values()
+        .filter(|items| items.len() > 1)
+        .map(|mut items| {
+            items.sort_by(|a, b| a.path.cmp(&b.path));
+            DuplicateGroup { kind: DuplicateKind::Exact, items }
+        })
+        .collect();
+
+    // 解码图片计算感知哈希，同时保留预览图
+    let mut hashed: Vec<(DuplicateItem, u64)> = Vec::new();
+    let mut decoded: HashMap<PathBuf, (image::DynamicImage, (u32, u32))> = HashMap::new();
+    for (path, size) in images {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let _ = sender.send(ScanEvent::Progress { done, total, current: path.to_string_lossy().to_string() });
+        done += 1;
+        let Ok(image) = image_formats::open_image(path) else {
+            continue;
+        };
+        hashed.push((DuplicateItem { path: path.clone(), size: *size }, difference_hash(&image)));
+        decoded.insert(path.clone(), (image.thumbnail(PREVIEW_SIZE, PREVIEW_SIZE), (image.width(), image.height())));
+    }
+
+    let mut parent: Vec<usize> = (0..hashed.len()).collect();
+    for i in 0..hashed.len() {
+        for j in (i + 1)..hashed.len() {
+            if (hashed[i].1 ^ hashed[j].1).count_ones() <= SIMILAR_THRESHOLD {
+                let (a, b) = (find_root(&mut parent, i), find_root(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+    let mut similar: HashMap<usize, Vec<DuplicateItem>> = HashMap::new();
+    for (i, (item, _)) in hashed.iter().enumerate() {
+        let root = find_root(&mut parent, i);
+        similar.entry(root).or_default().push(item.clone());
+    }
+    for mut items in similar.into_values().filter(|items| items.len() > 1) {
+        // 全部成员已在同一个完全相同组中时不再重复列出
+        let paths: HashSet<&PathBuf> = items.iter().map(|i| &i.path).collect();
+        let covered = groups.iter().any(|g| paths.iter().all(|p| g.items.iter().any(|i| &i.path == *p)));
+        if !covered {
+            items.sort_by_key(|i| std::cmp::Reverse(i.size)); // 体积最大（通常质量最好）的排在前面
+            groups.push(DuplicateGroup { kind: DuplicateKind::Similar, items });
+        }
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.items.iter().map(|i| i.size).sum::<u64>()));
+
+    // 只保留出现在结果中的图片预览
+    let shown: HashSet<&PathBuf> = groups.iter().flat_map(|g| g.items.iter().map(|i| &i.path)).collect();
+    let previews = decoded.into_iter()
+        .filter(|(path, _)| shown.contains(path))
+        .map(|(path, (image, dimensions))| {
+            let rgba = image.to_rgba8();
+            PreviewImage { path, size: [rgba.width() as usize, rgba.height() as usize], rgba: rgba.into_raw(), dimensions }
+        })
+        .collect();
+    Some((groups, previews))
+}
+
+// 查找重复文件的对话框：后台扫描，按组显示并排预览，勾选后删除
+pub struct DuplicateFinder {
+    receiver: Option<Receiver<ScanEvent>>,
+    cancelled: Arc<AtomicBool>,
+    root: PathBuf,
+    progress: (usize, usize),
+    current: String,
+    groups: Option<Vec<DuplicateGroup>>,
+    previews: HashMap<PathBuf, PreviewImage>,
+    textures: HashMap<PathBuf, egui::TextureHandle>,
+    marked: HashSet<PathBuf>, // 勾选待删除的文件
+    confirm_delete: bool,
+}
+
+impl DuplicateFinder {
+    pub fn new() -> Self {
+        Self {
+            receiver: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            root: PathBuf::new(),
+            progress: (0, 0),
+            current: String::new(),
+            groups: None,
+            previews: HashMap::new(),
+            textures: HashMap::new(),
+            marked: HashSet::new(),
+            confirm_delete: false,
+        }
+    }
+
+    // 开始扫描文件夹（递归）
+    pub fn start(&mut self, root: PathBuf) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.cancelled = Arc::new(AtomicBool::new(false));
+        self.root = root.clone();
+        self.progress = (0, 0);
+        self.current.clear();
+        self.groups = None;
+        self.previews.clear();
+        self.textures.clear();
+        self.marked.clear();
+        self.confirm_delete = false;
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let cancel_flag = self.cancelled.clone();
+        std::thread::spawn(move || {
+            if let Some((groups, previews)) = scan(&root, &sender, &cancel_flag) {
+                let _ = sender.send(ScanEvent::Finished { groups, previews });
+            }
+        });
+        self.receiver = Some(receiver);
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.receiver.is_some() || self.groups.is_some()
+    }
+
+    fn close(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.receiver = None;
+        self.groups = None;
+        self.previews.clear();
+        self.textures.clear();
+    }
+
+    // 显示对话框；用户确认删除时返回要删除的文件，由主程序执行删除
+    pub fn show_duplicate_dialog(&mut self, ctx: &egui::Context) -> Option<Vec<PathBuf>> {
+        if let Some(receiver) = &self.receiver {
+            for event in receiver.try_iter() {
+                match event {
+                    ScanEvent::Progress { done, total, current } => {
+                        self.progress = (done, total);
+                        self.current = current;
+                    }
+                    ScanEvent::Finished { groups, previews } => {
+                        self.groups = Some(groups);
+                        self.previews = previews.into_iter().map(|p| (p.path.clone(), p)).collect();
+                    }
+                }
+            }
+            if self.groups.is_some() {
+                self.receiver = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        let mut open = true;
+        let mut to_delete = None;
+        egui::Window::new("查找重复文件")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_size(egui::Vec2::new(720.0, 480.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("文件夹: {}", self.root.display()));
+                ui.separator();
+
+                let Some(groups) = &self.groups else {
+                    let (done, total) = self.progress;
+                    let fraction = if total == 0 { 0.0 } else { done as f32 / total as f32 };
+                    ui.add(egui::ProgressBar::new(fraction).text(format!("{} / {}", done, total)));
+                    ui.label(egui::RichText::new(&self.current).weak());
+                    return;
+                };
+
+                if groups.is_empty() {
+                    ui.label("没有找到重复文件。");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("找到 {} 组重复文件", groups.len()));
+                    if ui.button("每组保留第一个，勾选其余").clicked() {
+                        self.marked = groups.iter().flat_map(|g| g.items.iter().skip(1).map(|i| i.path.clone())).collect();
+                    }
+                    if ui.button("清除勾选").clicked() {
+                        self.marked.clear();
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(ui.available_height() - 40.0).show(ui, |ui| {
+                    for (index, group) in groups.iter().enumerate() {
+                        let kind = match group.kind {
+                            DuplicateKind::Exact => "内容相同",
+                            DuplicateKind::Similar => "相似图片",
+                        };
+                        ui.label(egui::RichText::new(format!("{} · {} 个文件", kind, group.items.len())).strong());
+                        ui.push_id(index, |ui| {
+                            egui::ScrollArea::horizontal().show(ui, |ui| {
+                                ui.horizontal_top(|ui| {
+                                    for item in &group.items {
+                                        Self::show_item(ui, item, &self.root, &self.previews, &mut self.textures, &mut self.marked);
+                                    }
+                                });
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if self.confirm_delete {
+                        ui.label(format!("确定要把选中的 {} 个文件移到回收站吗？", self.marked.len()));
+                        if ui.button("确认删除").clicked() {
+                            to_delete = Some(self.marked.iter().cloned().collect::<Vec<_>>());
+                            self.confirm_delete = false;
+                        }
+                        if ui.button("取消").clicked() {
+                            self.confirm_delete = false;
+                        }
+                    } else if ui.add_enabled(!self.marked.is_empty(), egui::Button::new(format!("删除选中 ({})", self.marked.len()))).clicked() {
+                        self.confirm_delete = true;
+                    }
+                });
+            });
+
+        // 从结果中移除已删除的文件，少于两个成员的组不再显示
+        if let (Some(paths), Some(groups)) = (&to_delete, &mut self.groups) {
+            for group in groups.iter_mut() {
+                group.items.retain(|i| !paths.contains(&i.path));
+            }
+            groups.retain(|g| g.items.len() > 1);
+            self.marked.clear();
+        }
+
+        if !open {
+            self.close();
+        }
+        to_delete
+    }
+
+    // 显示单个文件：预览图、名称、大小和删除勾选框
+    fn show_item(
+        ui: &mut egui::Ui,
+        item: &DuplicateItem,
+        root: &Path,
+        previews: &HashMap<PathBuf, PreviewImage>,
+        textures: &mut HashMap<PathBuf, egui::TextureHandle>,
+        marked: &mut HashSet<PathBuf>,
+    ) {
+        ui.vertical(|ui| {
+            ui.set_width(PREVIEW_SIZE as f32 + 8.0);
+            if let Some(preview) = previews.get(&item.path) {
+                let texture = textures.entry(item.path.clone()).or_insert_with(|| {
+                    let image = egui::ColorImage::from_rgba_unmultiplied(preview.size, &preview.rgba);
+                    ui.ctx().load_texture(item.path.to_string_lossy(), image, egui::TextureOptions::LINEAR)
+                });
+                ui.add(egui::Image::new(&*texture).max_size(egui::vec2(PREVIEW_SIZE as f32, PREVIEW_SIZE as f32)));
+                ui.label(egui::RichText::new(format!("{} × {}", preview.dimensions.0, preview.dimensions.1)).small().weak());
+            }
+            let relative = item.path.strip_prefix(root).unwrap_or(&item.path);
+            ui.label(relative.to_string_lossy()).on_hover_text(item.path.to_string_lossy());
+            ui.label(utils::get_file_size_str(item.size));
+            let mut checked = marked.contains(&item.path);
+            if ui.checkbox(&mut checked, "删除").changed() {
+                if checked {
+                    marked.insert(item.path.clone());
+                } else {
+                    marked.remove(&item.path);
+                }
+            }
+        });
+    }
+}
+
+impl Drop for DuplicateFinder {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,262 @@
+use eframe::egui;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use crossbeam_channel::{self, Receiver};
+
+// pkexec 的退出码：126 表示用户取消或未通过认证，127 表示无法进行认证
+const PKEXEC_DISMISSED: i32 = 126;
+const PKEXEC_FAILED: i32 = 127;
+
+// 通过 pkexec 以管理员身份运行系统命令，由 polkit 弹出认证窗口。
+// 会一直等到命令结束，需要在后台线程中调用
+pub fn run_as_root<S: AsRef<OsStr>>(program: &str, args: &[S]) -> Result<(), String> {
+    let output = Command::new("pkexec")
+        .arg(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|_| "需要安装 pkexec（polkit）".to_string())?;
+    match output.status.code() {
+        Some(0) => Ok(()),
+        Some(PKEXEC_DISMISSED) => Err("已取消管理员认证".to_string()),
+        Some(PKEXEC_FAILED) => Err("无法进行管理员认证".to_string()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            Err(if stderr.is_empty() { format!("{} 执行失败", program) } else { stderr })
+        }
+    }
+}
+
+// 以管理员权限运行的系统命令使用绝对路径，不依赖调用者的 PATH
+pub const CHOWN: &str = "/usr/bin/chown";
+const CP: &str = "/usr/bin/cp";
+const MV: &str = "/usr/bin/mv";
+const RM: &str = "/usr/bin/rm";
+const SH: &str = "/bin/sh";
+
+// 因权限不足失败、可以用管理员权限重新执行的文件操作。
+// 复制和移动记录第一次执行时选定的目标路径，重试时写到同一位置
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElevatedTask {
+    Copy(Vec<(PathBuf, PathBuf)>), // (源, 目标路径)
+    Move(Vec<(PathBuf, PathBuf)>),
+    Delete(Vec<PathBuf>),
+    Rename { from: PathBuf, to: PathBuf },
+}
+
+impl ElevatedTask {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ElevatedTask::Copy(pairs) | ElevatedTask::Move(pairs) => pairs.is_empty(),
+            ElevatedTask::Delete(paths) => paths.is_empty(),
+            ElevatedTask::Rename { .. } => false,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            ElevatedTask::Copy(_) => "复制",
+            ElevatedTask::Move(_) => "移动",
+            ElevatedTask::Delete(_) => "删除",
+            ElevatedTask::Rename { .. } => "重命名",
+        }
+    }
+
+    // 对应的系统命令，不覆盖已有的文件。第一次执行时已处理完（不再存在）的项目不再传入，全部处理完时返回 None
+    fn command(&self) -> Option<(&'static str, Vec<OsString>)> {
+        let exists = |path: &Path| fs::symlink_metadata(path).is_ok();
+        // cp -n / mv -n 跳过已有的目标时仍返回 0，先检查目标，已存在时报错而不是静默跳过
+        const TARGET_EXISTS: &str = "[ -e \"$2\" ] || [ -L \"$2\" ]";
+        let (program, options, guard, pairs) = match self {
+            // 复制到已选定的目标，合并第一次执行时已复制的文件夹；目标为已有的文件时报错
+            ElevatedTask::Copy(pairs) => (CP, "-a -n -T", format!("[ ! -d \"$2\" ] && {{ {}; }}", TARGET_EXISTS), pairs.clone()),
+            ElevatedTask::Move(pairs) => (MV, "-n -T", TARGET_EXISTS.to_string(), pairs.clone()),
+            ElevatedTask::Rename { from, to } => (MV, "-n -T", TARGET_EXISTS.to_string(), vec![(from.clone(), to.clone())]),
+            ElevatedTask::Delete(paths) => {
+                let paths: Vec<OsString> = paths.iter().filter(|p| exists(p)).map(|p| p.clone().into_os_string()).collect();
+                if paths.is_empty() {
+                    return None;
+                }
+                return Some((RM, ["-rf", "--"].into_iter().map(OsString::from).chain(paths).collect()));
+            }
+        };
+        let pairs: Vec<(PathBuf, PathBuf)> = pairs.into_iter().filter(|(source, _)| exists(source)).collect();
+        if pairs.is_empty() {
+            return None;
+        }
+        // 通过 sh 依次执行，多组时只需认证一次；路径作为参数传入，不拼接到脚本中
+        let script = format!(
+            "while [ $# -ge 2 ]; do if {}; then echo \"目标已存在: $2\" >&2; exit 1; fi; {} {} -- \"$1\" \"$2\" || exit 1; shift 2; done",
+            guard, program, options
+        );
+        let mut args: Vec<OsString> = vec!["-c".into(), script.into(), "sh".into()];
+        args.extend(pairs.into_iter().flat_map(|(source, target)| [source.into_os_string(), target.into_os_string()]));
+        Some((SH, args))
+    }
+
+    fn describe(&self) -> String {
+        let name = |path: &Path| path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().to_string();
+        let items = |paths: Vec<&PathBuf>| match paths.as_slice() {
+            [path] => name(path),
+            _ => format!("{} 个项目", paths.len()),
+        };
+        let destination = |pairs: &[(PathBuf, PathBuf)]| {
+            pairs.first().and_then(|(_, target)| target.parent()).map(|dir| dir.display().to_string()).unwrap_or_default()
+        };
+        match self {
+            ElevatedTask::Copy(pairs) => format!("将 {} 复制到 {}", items(pairs.iter().map(|(source, _)| source).collect()), destination(pairs)),
+            ElevatedTask::Move(pairs) => format!("将 {} 移动到 {}", items(pairs.iter().map(|(source, _)| source).collect()), destination(pairs)),
+            ElevatedTask::Delete(paths) => format!("永久删除 {}", items(paths.iter().collect())),
+            ElevatedTask::Rename { from, to } => format!("将 {} 重命名为 {}", name(from), name(to)),
+        }
+    }
+}
+
+// 权限不足时询问是否以管理员权限重试，在后台通过 pkexec 执行，不必以 root 身份重新启动程序
+pub struct ElevatedRetryDialog {
+    task: Option<ElevatedTask>,
+    message: String, // 第一次执行时的错误
+    running: Option<Receiver<Result<(), String>>>,
+    error: Option<String>,
+}
+
+impl ElevatedRetryDialog {
+    pub fn new() -> Self {
+        Self { task: None, message: String::new(), running: None, error: None }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.task.is_some()
+    }
+
+    pub fn offer(&mut self, task: ElevatedTask, message: String) {
+        // 正在重试时不替换
+        if self.running.is_some() {
+            return;
+        }
+        self.task = Some(task);
+        self.message = message;
+        self.error = None;
+    }
+
+    fn start(&mut self, ctx: &egui::Context) {
+        let Some((program, args)) = self.task.as_ref().and_then(ElevatedTask::command) else {
+            self.error = Some("项目已不存在，无需重试".to_string());
+            return;
+        };
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(run_as_root(program, &args));
+            ctx.request_repaint();
+        });
+        self.running = Some(receiver);
+        self.error = None;
+    }
+
+    // 重试成功时返回执行的操作，调用方据此刷新列表
+    pub fn show_elevated_retry_dialog(&mut self, ctx: &egui::Context) -> Option<ElevatedTask> {
+        let task = self.task.clone()?;
+        if let Some(result) = self.running.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.running = None;
+            match result {
+                Ok(()) => {
+                    self.task = None;
+                    return Some(task);
+                }
+                Err(e) => self.error = Some(e),
+            }
+        }
+
+        let mut open = true;
+        let mut close = false;
+        let mut retry = false;
+        egui::Window::new("权限不足")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(440.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("{}失败：没有足够的权限。", task.label()));
+                ui.label(egui::RichText::new(task.describe()).strong());
+                egui::ScrollArea::vertical().max_height(100.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(&self.message).weak().small());
+                });
+                ui.label("可以输入管理员密码后重试（只对这一次操作有效）。");
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if self.running.is_some() {
+                        ui.spinner();
+                        ui.label("正在等待管理员认证...");
+                    } else {
+                        if ui.button("以管理员权限重试").clicked() {
+                            retry = true;
+                        }
+                        if ui.button("取消").clicked() {
+                            close = true;
+                        }
+                    }
+                });
+            });
+
+        if retry {
+            self.start(ctx);
+        }
+        // 已开始的重试继续在后台执行，关闭窗口只是不再显示结果
+        if !open || close {
+            self.task = None;
+            self.running = None;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_elevated_command() {
+        let dir = TestDir::new("elevated");
+        let (a, b, gone) = (dir.join("a"), dir.join("b"), dir.join("gone"));
+        fs::write(&a, "").unwrap();
+        fs::write(&b, "").unwrap();
+
+        // 复制到第一次执行时选定的目标路径，已不存在的源跳过
+        let copy = ElevatedTask::Copy(vec![(a.clone(), PathBuf::from("/opt/a_1")), (gone.clone(), PathBuf::from("/opt/gone"))]);
+        let (program, args) = copy.command().unwrap();
+        assert_eq!(program, SH);
+        assert!(args[1].to_string_lossy().contains("/usr/bin/cp -a -n -T -- \"$1\" \"$2\""));
+        assert_eq!(args[3..], [a.clone().into(), OsString::from("/opt/a_1")]);
+
+        // 多组时通过 sh 一次执行，路径作为参数
+        let moved = ElevatedTask::Move(vec![(a.clone(), PathBuf::from("/opt/a")), (b.clone(), PathBuf::from("/opt/b"))]);
+        let (program, args) = moved.command().unwrap();
+        assert_eq!(program, SH);
+        assert!(args[1].to_string_lossy().contains("/usr/bin/mv -n -T -- \"$1\" \"$2\""));
+        assert_eq!(args[3..], [a.clone().into(), OsString::from("/opt/a"), b.clone().into(), "/opt/b".into()]);
+
+        // 目标已存在时脚本报错，而不是像 mv -n 那样跳过后返回成功
+        let run = |task: ElevatedTask| {
+            let (program, args) = task.command().unwrap();
+            std::process::Command::new(program).args(args).stderr(Stdio::null()).status().unwrap().success()
+        };
+        assert!(!run(ElevatedTask::Rename { from: a.clone(), to: b.clone() }));
+        assert!(a.exists() && b.exists());
+        assert!(!run(ElevatedTask::Copy(vec![(a.clone(), b.clone())])));
+        fs::create_dir_all(dir.join("d")).unwrap();
+        assert!(run(ElevatedTask::Copy(vec![(dir.join("d"), dir.join("e"))])));
+        assert!(run(ElevatedTask::Rename { from: a.clone(), to: dir.join("c") }));
+        assert!(!a.exists() && dir.join("c").exists());
+        // 已全部处理完时不再执行
+        assert_eq!(ElevatedTask::Delete(vec![gone]).command(), None);
+    }
+}
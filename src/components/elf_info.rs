@@ -0,0 +1,288 @@
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use goblin::container::Ctx;
+use goblin::elf::{dynamic, header, program_header, Dynamic, Elf, ProgramHeader, SectionHeader};
+
+// 单个表或段超过此大小时视为文件损坏
+const MAX_TABLE_SIZE: u64 = 64 * 1024 * 1024;
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+
+// 可执行文件或共享库的信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfInfo {
+    pub kind: &'static str,         // 可执行文件、共享库等
+    pub architecture: String,
+    pub bits: u8,
+    pub little_endian: bool,
+    pub interpreter: Option<String>, // 动态链接器
+    pub soname: Option<String>,
+    pub libraries: Vec<(String, Option<PathBuf>)>, // 直接依赖的库及找到的位置（None 表示未找到）
+    pub stripped: bool,             // 没有符号表
+    pub debug_info: bool,           // 含有 DWARF 调试信息
+}
+
+// 读取文件开头的魔数判断是否为 ELF 文件
+pub fn is_elf_file(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    path.is_file() && File::open(path).and_then(|mut file| file.read_exact(&mut magic)).is_ok() && &magic == ELF_MAGIC
+}
+
+fn architecture_name(machine: u16) -> String {
+    match machine {
+        header::EM_X86_64 => "x86-64".to_string(),
+        header::EM_386 => "x86".to_string(),
+        header::EM_AARCH64 => "AArch64 (ARM64)".to_string(),
+        header::EM_ARM => "ARM".to_string(),
+        header::EM_RISCV => "RISC-V".to_string(),
+        header::EM_PPC64 => "PowerPC64".to_string(),
+        header::EM_MIPS => "MIPS".to_string(),
+        header::EM_S390 => "s390".to_string(),
+        other => header::machine_to_str(other).to_string(),
+    }
+}
+
+// 从文件头、程序头、节头和动态段中解析出的内容
+struct ParsedElf {
+    header: header::Header,
+    is_64: bool,
+    little_endian: bool,
+    interpreter: Option<String>,
+    soname: Option<String>,
+    libraries: Vec<String>,
+    rpaths: Vec<String>,
+    runpaths: Vec<String>,
+    section_names: Vec<String>,
+}
+
+fn kind_name(elf: &ParsedElf) -> &'static str {
+    match elf.header.e_type {
+        header::ET_REL => "可重定位目标文件",
+        header::ET_EXEC => "可执行文件",
+        // 位置无关的可执行文件也是 ET_DYN，有解释器的视为可执行文件
+        header::ET_DYN if elf.interpreter.is_some() => "可执行文件（PIE）",
+        header::ET_DYN => "共享库",
+        header::ET_CORE => "核心转储",
+        _ => "ELF 文件",
+    }
+}
+
+// 候选库文件与程序的位数和架构相同
+fn library_matches(path: &Path, bits: u8, machine: u16) -> bool {
+    let mut ident = [0u8; 20];
+    if File::open(path).and_then(|mut file| file.read_exact(&mut ident)).is_err() || &ident[..4] != ELF_MAGIC {
+        return false;
+    }
+    let class = if bits == 64 { 2 } else { 1 };
+    let library_machine = if ident[5] == 2 { u16::from_be_bytes([ident[18], ident[19]]) } else { u16::from_le_bytes([ident[18], ident[19]]) };
+    ident[4] == class && library_machine == machine
+}
+
+// /etc/ld.so.conf 及其 include 的文件中列出的库文件夹
+fn ld_so_conf_dirs(conf: &Path, depth: usize) -> Vec<PathBuf> {
+    let Ok(text) = fs::read_to_string(conf) else {
+        return Vec::new();
+    };
+    let mut dirs = Vec::new();
+    for line in text.lines().map(|line| line.split('#').next().unwrap_or_default().trim()) {
+        if let Some(pattern) = line.strip_prefix("include ") {
+            let pattern = Path::new(pattern.trim());
+            let (Some(parent), Some(file_pattern)) = (pattern.parent(), pattern.file_name().and_then(|n| n.to_str())) else {
+                continue;
+            };
+            let Ok(entries) = fs::read_dir(parent) else {
+                continue;
+            };
+            let mut includes: Vec<PathBuf> = entries.flatten()
+                .filter(|entry| crate::utils::wildcard_match(file_pattern, &entry.file_name().to_string_lossy()))
+                .map(|entry| entry.path())
+                .collect();
+            includes.sort();
+            if depth < 4 {
+                dirs.extend(includes.iter().flat_map(|include| ld_so_conf_dirs(include, depth + 1)));
+            }
+        } else if line.starts_with('/') {
+            dirs.push(PathBuf::from(line));
+        }
+    }
+    dirs
+}
+
+// 按动态链接器的顺序查找依赖库：RPATH、LD_LIBRARY_PATH、RUNPATH、ld.so.conf、默认文件夹
+fn library_search_dirs(elf: &ParsedElf, origin: &Path) -> Vec<PathBuf> {
+    let expand = |paths: &[String]| -> Vec<PathBuf> {
+        paths.iter()
+            .flat_map(|list| list.split(':'))
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| PathBuf::from(dir.replace("${ORIGIN}", &origin.to_string_lossy()).replace("$ORIGIN", &origin.to_string_lossy())))
+            .collect()
+    };
+    let mut dirs = Vec::new();
+    if elf.runpaths.is_empty() {
+        dirs.extend(expand(&elf.rpaths));
+    }
+    if let Ok(paths) = std::env::var("LD_LIBRARY_PATH") {
+        dirs.extend(expand(&[paths]));
+    }
+    dirs.extend(expand(&elf.runpaths));
+    dirs.extend(ld_so_conf_dirs(Path::new("/etc/ld.so.conf"), 0));
+    let multiarch = match elf.header.e_machine {
+        header::EM_X86_64 => "x86_64-linux-gnu",
+        header::EM_386 => "i386-linux-gnu",
+        header::EM_AARCH64 => "aarch64-linux-gnu",
+        header::EM_ARM => "arm-linux-gnueabihf",
+        header::EM_RISCV => "riscv64-linux-gnu",
+        _ => "",
+    };
+    for root in ["/lib", "/usr/lib"] {
+        if !multiarch.is_empty() {
+            dirs.push(Path::new(root).join(multiarch));
+        }
+        if elf.is_64 {
+            dirs.push(PathBuf::from(format!("{}64", root)));
+        } else {
+            dirs.push(PathBuf::from(format!("{}32", root)));
+        }
+        dirs.push(PathBuf::from(root));
+    }
+    dirs
+}
+
+fn resolve_library(name: &str, dirs: &[PathBuf], bits: u8, machine: u16) -> Option<PathBuf> {
+    if name.contains('/') {
+        return Some(PathBuf::from(name)).filter(|path| path.is_file());
+    }
+    dirs.iter().map(|dir| dir.join(name)).find(|path| library_matches(path, bits, machine))
+}
+
+// 读取文件中指定位置的一段
+fn read_range(file: &mut File, offset: u64, len: u64) -> Result<Vec<u8>, String> {
+    if len > MAX_TABLE_SIZE {
+        return Err("ELF 文件已损坏".to_string());
+    }
+    let mut bytes = vec![0u8; len as usize];
+    file.seek(SeekFrom::Start(offset)).and_then(|_| file.read_exact(&mut bytes)).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+// 字符串表中从 offset 开始、以 0 结尾的字符串
+fn string_at(table: &[u8], offset: usize) -> Option<String> {
+    let bytes = table.get(offset..)?;
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+// 只读取需要的部分，不把整个文件读入内存
+fn parse_elf(path: &Path) -> Result<ParsedElf, String> {
+    let malformed = |e: goblin::error::Error| format!("无法解析 ELF 文件: {}", e);
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let mut header_bytes = Vec::with_capacity(header::header64::SIZEOF_EHDR);
+    file.by_ref().take(header::header64::SIZEOF_EHDR as u64).read_to_end(&mut header_bytes).map_err(|e| e.to_string())?;
+    let header = Elf::parse_header(&header_bytes).map_err(malformed)?;
+    let ctx = Ctx::new(header.container().map_err(malformed)?, header.endianness().map_err(malformed)?);
+
+    let mut program_headers = if header.e_phoff == 0 {
+        Vec::new()
+    } else {
+        let bytes = read_range(&mut file, header.e_phoff, header.e_phnum as u64 * ProgramHeader::size(ctx) as u64)?;
+        ProgramHeader::parse(&bytes, 0, header.e_phnum as usize, ctx).map_err(malformed)?
+    };
+    let section_headers = if header.e_shoff == 0 {
+        Vec::new()
+    } else {
+        let bytes = read_range(&mut file, header.e_shoff, header.e_shnum as u64 * SectionHeader::size(ctx) as u64)?;
+        SectionHeader::parse(&bytes, 0, header.e_shnum as usize, ctx).map_err(malformed)?
+    };
+    let section_names = match section_headers.get(header.e_shstrndx as usize) {
+        Some(strtab) => {
+            let table = read_range(&mut file, strtab.sh_offset, strtab.sh_size)?;
+            section_headers.iter().filter_map(|section| string_at(&table, section.sh_name)).collect()
+        }
+        None => Vec::new(),
+    };
+
+    let mut interpreter = None;
+    if let Some(interp) = program_headers.iter().find(|ph| ph.p_type == program_header::PT_INTERP) {
+        let bytes = read_range(&mut file, interp.p_offset, interp.p_filesz)?;
+        interpreter = string_at(&bytes, 0);
+    }
+
+    let mut parsed = ParsedElf {
+        header,
+        is_64: ctx.is_big(),
+        little_endian: ctx.is_little_endian(),
+        interpreter,
+        soname: None,
+        libraries: Vec::new(),
+        rpaths: Vec::new(),
+        runpaths: Vec::new(),
+        section_names,
+    };
+    let Some(dynamic_header) = program_headers.iter_mut().find(|ph| ph.p_type == program_header::PT_DYNAMIC) else {
+        return Ok(parsed);
+    };
+    // 只读入动态段，解析时把它的位置视为 0；字符串表地址仍按 PT_LOAD 段换算成文件偏移
+    let dynamic_bytes = read_range(&mut file, dynamic_header.p_offset, dynamic_header.p_filesz)?;
+    dynamic_header.p_offset = 0;
+    let Some(dynamic) = Dynamic::parse(&dynamic_bytes, &program_headers, ctx).map_err(malformed)? else {
+        return Ok(parsed);
+    };
+    if dynamic.info.strtab == 0 {
+        return Ok(parsed);
+    }
+    let strtab = read_range(&mut file, dynamic.info.strtab as u64, dynamic.info.strsz as u64)?;
+    for entry in &dynamic.dyns {
+        let Some(value) = string_at(&strtab, entry.d_val as usize) else {
+            continue;
+        };
+        match entry.d_tag {
+            dynamic::DT_NEEDED => parsed.libraries.push(value),
+            dynamic::DT_SONAME => parsed.soname = Some(value),
+            dynamic::DT_RPATH => parsed.rpaths.push(value),
+            dynamic::DT_RUNPATH => parsed.runpaths.push(value),
+            _ => {}
+        }
+    }
+    Ok(parsed)
+}
+
+// 解析 ELF 文件，并像 ldd 一样查找直接依赖的库（不递归）；应在后台线程调用
+pub fn read_elf_info(path: &Path) -> Result<ElfInfo, String> {
+    let elf = parse_elf(path)?;
+    let bits = if elf.is_64 { 64 } else { 32 };
+    let origin = path.parent().unwrap_or(Path::new("/"));
+    let dirs = library_search_dirs(&elf, origin);
+    let libraries = elf.libraries.iter()
+        .map(|name| (name.clone(), resolve_library(name, &dirs, bits, elf.header.e_machine)))
+        .collect();
+    Ok(ElfInfo {
+        kind: kind_name(&elf),
+        architecture: architecture_name(elf.header.e_machine),
+        bits,
+        little_endian: elf.little_endian,
+        stripped: !elf.section_names.iter().any(|name| name == ".symtab"),
+        debug_info: elf.section_names.iter().any(|name| name.starts_with(".debug_info") || name.starts_with(".zdebug_info")),
+        interpreter: elf.interpreter,
+        soname: elf.soname,
+        libraries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_elf_info() {
+        // 测试程序本身就是当前平台的 ELF 可执行文件
+        let exe = std::env::current_exe().unwrap();
+        assert!(is_elf_file(&exe));
+        assert!(!is_elf_file(Path::new("Cargo.toml")));
+        let info = read_elf_info(&exe).unwrap();
+        assert_eq!(info.bits as usize, usize::BITS as usize);
+        assert!(info.kind.starts_with("可执行文件"));
+        let libc = info.libraries.iter().find(|(name, _)| name.starts_with("libc.so")).and_then(|(_, path)| path.clone()).unwrap();
+        let libc_info = read_elf_info(&libc).unwrap();
+        assert!(libc_info.soname.is_some_and(|soname| soname.starts_with("libc.so")));
+    }
+}
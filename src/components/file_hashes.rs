@@ -0,0 +1,195 @@
+use eframe::egui;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::digest::DynDigest;
+use sha2::{Digest, Sha256};
+
+// 支持的校验算法。MD5 只用于核对下载文件，不用于安全用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 3] = [HashAlgorithm::Md5, HashAlgorithm::Sha1, HashAlgorithm::Sha256];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Sha256 => "SHA-256",
+        }
+    }
+
+    // 按长度识别十六进制校验值的算法：MD5 32 位、SHA-1 40 位、SHA-256 64 位
+    pub fn from_hex(hash: &str) -> Option<Self> {
+        if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        match hash.len() {
+            32 => Some(HashAlgorithm::Md5),
+            40 => Some(HashAlgorithm::Sha1),
+            64 => Some(HashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+
+    fn hasher(self) -> Box<dyn DynDigest> {
+        match self {
+            HashAlgorithm::Md5 => Box::new(Md5::new()),
+            HashAlgorithm::Sha1 => Box::new(Sha1::new()),
+            HashAlgorithm::Sha256 => Box::new(Sha256::new()),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 文件的校验值（十六进制小写），按请求的算法顺序排列
+pub type FileHashes = Vec<(HashAlgorithm, String)>;
+
+// 读取一遍文件同时计算 algorithms 中的校验值；progress 记录已读取的字节数
+pub fn hash_file(path: &Path, algorithms: &[HashAlgorithm], progress: &AtomicU64, cancelled: &AtomicBool) -> io::Result<FileHashes> {
+    let mut file = File::open(path)?;
+    let mut hashers: Vec<Box<dyn DynDigest>> = algorithms.iter().map(|algorithm| algorithm.hasher()).collect();
+    let mut buffer = vec![0u8; 256 * 1024];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "已取消"));
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for hasher in &mut hashers {
+            hasher.update(&buffer[..read]);
+        }
+        progress.fetch_add(read as u64, Ordering::Relaxed);
+    }
+    Ok(algorithms.iter().zip(hashers).map(|(algorithm, hasher)| (*algorithm, hex(&hasher.finalize()))).collect())
+}
+
+// 正在后台计算的文件
+struct HashTask {
+    receiver: Receiver<Result<FileHashes, String>>,
+    progress: Arc<AtomicU64>,
+    cancelled: Arc<AtomicBool>,
+    total: u64,
+}
+
+impl Drop for HashTask {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// 信息页中的校验值：点击按钮后在后台线程计算，可复制到剪贴板。切换文件时取消未完成的计算
+pub struct FileHashPanel {
+    path: Option<PathBuf>,
+    size: Option<u64>, // 切换文件时读取一次，不是普通文件时为 None
+    task: Option<HashTask>,
+    result: Option<Result<FileHashes, String>>,
+}
+
+impl FileHashPanel {
+    pub fn new() -> Self {
+        Self { path: None, size: None, task: None, result: None }
+    }
+
+    fn start(&mut self, path: &Path, total: u64, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let progress = Arc::new(AtomicU64::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (path, thread_progress, thread_cancelled, ctx) = (path.to_path_buf(), progress.clone(), cancelled.clone(), ctx.clone());
+        std::thread::spawn(move || {
+            let result = hash_file(&path, &HashAlgorithm::ALL, &thread_progress, &thread_cancelled).map_err(|e| e.to_string());
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.task = Some(HashTask { receiver, progress, cancelled, total });
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, path: &Path) {
+        if self.path.as_deref() != Some(path) {
+            self.path = Some(path.to_path_buf());
+            self.size = path.metadata().ok().filter(|m| m.is_file()).map(|m| m.len());
+            self.task = None;
+            self.result = None;
+        }
+        let Some(size) = self.size else {
+            return;
+        };
+        if let Some(result) = self.task.as_ref().and_then(|task| task.receiver.try_recv().ok()) {
+            self.result = Some(result);
+            self.task = None;
+        }
+
+        ui.group(|ui| {
+            ui.heading("校验值");
+            match (&self.task, &self.result) {
+                (Some(task), _) => {
+                    let done = task.progress.load(Ordering::Relaxed);
+                    let percent = (done * 100).checked_div(task.total).unwrap_or(100);
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!("正在计算... {}%", percent));
+                    });
+                    ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                (None, Some(Ok(hashes))) => {
+                    egui::Grid::new("preview_hash_grid").num_columns(3).show(ui, |ui| {
+                        for (algorithm, value) in hashes {
+                            ui.label(algorithm.name());
+                            ui.add(egui::Label::new(egui::RichText::new(value).monospace().small()).wrap());
+                            if ui.small_button("📋").on_hover_text("复制").clicked() {
+                                ui.ctx().copy_text(value.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                }
+                (None, Some(Err(e))) => {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("无法计算: {}", e));
+                }
+                (None, None) => {
+                    if ui.button(format!("计算校验值（{}）", crate::utils::get_file_size_str(size))).clicked() {
+                        self.start(path, size, ui.ctx());
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_hash_file() {
+        let dir = TestDir::new("hash");
+        let path = dir.join("fox.txt");
+        std::fs::write(&path, "The quick brown fox jumps over the lazy dog").unwrap();
+        let (progress, cancelled) = (AtomicU64::new(0), AtomicBool::new(false));
+        let hashes = hash_file(&path, &HashAlgorithm::ALL, &progress, &cancelled).unwrap();
+        assert_eq!(hashes[0], (HashAlgorithm::Md5, "9e107d9d372bb6826bd81d3542a419d6".to_string()));
+        assert_eq!(hashes[1], (HashAlgorithm::Sha1, "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12".to_string()));
+        assert_eq!(progress.load(Ordering::Relaxed), 43);
+        // 只计算需要的算法
+        let hashes = hash_file(&path, &[HashAlgorithm::Sha256], &progress, &cancelled).unwrap();
+        assert_eq!(hashes, vec![(HashAlgorithm::Sha256, "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592".to_string())]);
+        assert_eq!(HashAlgorithm::from_hex("d41d8cd98f00b204e9800998ecf8427e"), Some(HashAlgorithm::Md5));
+        assert_eq!(HashAlgorithm::from_hex("xyz"), None);
+    }
+}
@@ -1,9 +1,29 @@
 use eframe::egui;
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::SystemTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use serde::{Deserialize, Serialize};
 use crate::utils;
-use super::mouse_strategy::MouseDoubleClickStrategy;
+use super::mouse_strategy::{ClickOpenMode, MouseDoubleClickStrategy};
 use super::thumbnail_view::ThumbnailView;
+use super::folder_view_settings::FolderViewSettings;
+use super::folder_size::FolderSizeWorker;
+use super::settings::ColorRule;
+use super::tags::{self, TagColor, TagStore};
+use super::status_bar::ListSummary;
+use super::folder_icon::FolderIconCache;
+use super::context_menu::{self, ContextAction};
+use super::file_metadata::{self, MetadataRows, MetadataWorker};
+use super::prefetch::DirectoryPrefetcher;
+use super::downloads;
+use super::image_formats;
+use super::companions;
+use super::project_types::{self, ProjectType, ProjectTypeWorker};
+use super::text_elide;
+use super::locale_format;
 
 #[derive(Clone)]
 struct FileItem {
@@ -11,13 +31,29 @@ struct FileItem {
     name: String,
     size: u64,
     modified: String,
+    modified_time: Option<SystemTime>,
     is_dir: bool,
+    folder_size: Option<u64>, // 后台计算得到的文件夹大小
+    is_executable: bool,      // 是否带有可执行权限（用于颜色规则）
+    tags: Vec<TagColor>,      // 文件标签
+    link_target: Option<PathBuf>, // 符号链接指向的路径
+    is_broken_link: bool,     // 符号链接的目标不存在
+    project_types: Vec<ProjectType>, // 文件夹识别出的项目类型（Cargo、npm、Git 等）
+}
+
+// 两个列表共用的目录预读器
+fn prefetcher() -> MutexGuard<'static, DirectoryPrefetcher<FileItem>> {
+    static PREFETCHER: OnceLock<Mutex<DirectoryPrefetcher<FileItem>>> = OnceLock::new();
+    PREFETCHER.get_or_init(|| Mutex::new(DirectoryPrefetcher::new(FileList::read_listing)))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
 }
 
 pub struct FileList {
-    files: Vec<FileItem>,
-    sort_by: SortBy,
-    sort_ascending: bool,
+    files: Vec<FileItem>,     // 当前显示的条目（经过筛选）
+    all_files: Vec<FileItem>, // 目录中读取到的全部条目
+    loaded_path: PathBuf,     // 当前加载的目录
+    options: ListOptions,     // 本窗格的隐藏文件、筛选、排序和分组选项
     col_name_ratio: f32,
     col_modified_ratio: f32,
     col_type_ratio: f32,
@@ -25,560 +61,2390 @@ pub struct FileList {
     mouse_strategy: MouseDoubleClickStrategy,
     icon_manager: super::icon_manager::IconManager,
     thumbnail_view: ThumbnailView, // 缩略图视图模块
+    jump_bar_enabled: bool,        // 是否显示右侧跳转条
+    two_line_names: bool,          // 图标视图中文件名最多显示两行
+    recent_strip_enabled: bool,    // 是否在列表上方显示最近修改的文件
+    pending_jump: Option<usize>,   // 待滚动到的条目索引
+    collapsed_groups: HashSet<String>, // 已折叠的分组标签
+    sticky_header_enabled: bool,   // 滚动时固定显示当前分组标题
+    selection: HashSet<PathBuf>,   // 多选的条目（Ctrl+单击或框选）
+    rubber_band_origin: Option<egui::Vec2>, // 框选起点（相对于列表内容左上角，随滚动保持不变）
+    rubber_band_base: HashSet<PathBuf>,     // 框选开始前已选中的条目（按住Ctrl框选时保留）
+    inline_rename: Option<InlineRename>,    // 正在就地重命名的条目
+    rename_commit: Option<(PathBuf, String)>, // 待主程序执行的重命名（原路径, 新名称）
+    slow_click_rename: Option<(PathBuf, f64)>, // 慢速双击：再次单击已选中项名称的时间
+    spring_load: Option<(PathBuf, f64)>,    // 拖放时悬停的文件夹及开始悬停的时间
+    drop_request: Option<FileDropRequest>,  // 待主程序执行的拖放操作
+    folder_size_enabled: bool,              // 是否在后台计算文件夹大小
+    folder_size_worker: Option<FolderSizeWorker>,
+    project_type_worker: Option<ProjectTypeWorker>, // 后台识别文件夹的项目类型
+    color_rules: Vec<ColorRule>,            // 详细信息视图的行颜色规则（为空表示不着色）
+    companion_counts: HashMap<PathBuf, usize>, // 归组后各主文件隐藏的配套文件数
+    tag_revision: Option<u64>,              // 已同步的标签库版本，重新读取目录后为 None
+    keyboard_cursor: Option<PathBuf>,       // 键盘焦点所在的条目（Ctrl+方向键只移动焦点）
+    pending_reveal: Option<usize>,          // 键盘移动后需要滚动到可见的条目索引
+    nav_layout: NavLayout,                  // 上一帧的视图布局，用于计算上下移动和翻页的步长
+    icon_zoom: f32,                         // 图标视图的缩放比例
+    detail_row_width: Option<f32>,          // 详细信息视图各行的宽度，列头按此对齐
+    metadata_cache: HashMap<PathBuf, (Option<SystemTime>, MetadataRows)>, // 按 @字段=值 筛选时读取的元数据及读取时的修改时间，刷新后仍保留
+    metadata_worker: Option<MetadataWorker>,
+    download_sources: HashMap<PathBuf, Option<String>>, // 按下载来源分组时读取的来源网站
+    group_move_request: Option<(String, Vec<PathBuf>)>, // 组标题上点击“移动到”的分组，由主程序取走
+    view_image_request: Option<PathBuf>,    // 在图片上按 Enter，由主程序打开全窗口查看器
+    view_states: HashMap<PathBuf, DirectoryViewState>, // 各目录离开时的滚动位置和选中项
+    restore_view_state: bool,               // 下次刷新时恢复目录的滚动位置和选中项（历史导航）
+    pending_scroll_offset: Option<f32>,     // 恢复的滚动位置，下一帧应用
+    restored_selection: Option<PathBuf>,    // 恢复的主选中项，由主程序取走
+    folder_icons: FolderIconCache,          // 文件夹自定义图标（.directory / folder.jpg）
+    context_action: Option<ContextAction>,  // 待主程序执行的右键菜单操作
+    clipboard_available: bool,              // 剪贴板中有可粘贴的内容（右键菜单的粘贴项）
+}
+
+// 离开目录时记录的滚动位置和选中项，返回该目录时恢复
+struct DirectoryViewState {
+    scroll_offset: f32,
+    selection: HashSet<PathBuf>,
+    cursor: Option<PathBuf>,
+}
+
+// 最多记住的目录数
+const MAX_VIEW_STATES: usize = 256;
+// 元数据缓存的文件数上限，超过后清空重新读取
+const METADATA_CACHE_LIMIT: usize = 20_000;
+
+// 视图布局信息：每行条目数、行高（含间距）、可见高度和滚动位置
+#[derive(Clone, Copy)]
+struct NavLayout {
+    columns: usize,
+    row_height: f32,
+    viewport_height: f32,
+    scroll_offset: f32,
+}
+
+impl Default for NavLayout {
+    fn default() -> Self {
+        Self {
+            columns: 1,
+            row_height: 24.0,
+            viewport_height: 0.0,
+            scroll_offset: 0.0,
+        }
+    }
+}
+
+// 内部拖放携带的数据：被拖动的文件路径
+pub struct DraggedFiles(pub Vec<PathBuf>);
+
+impl DraggedFiles {
+    // 目标目录不能是被拖动的文件夹本身或其子目录
+    fn accepts(&self, target_dir: &Path) -> bool {
+        !self.0.iter().any(|p| target_dir.starts_with(p))
+    }
 }
 
+// 编辑菜单中的选择命令
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionCommand {
+    SelectAll,
+    InvertSelection,
+    DeselectAll,
+}
+
+// 拖放完成后需要执行的文件操作
+pub struct FileDropRequest {
+    pub paths: Vec<PathBuf>,
+    pub target_dir: PathBuf,
+    pub copy: bool, // 按住Ctrl释放时复制，否则移动
+}
+
+// 图标视图缩放比例的范围
+const ICON_ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+
+// 拖放悬停在文件夹上多久后自动打开（秒）
+const SPRING_LOAD_DELAY: f64 = 1.0;
+
+// 详细信息视图中就地重命名的编辑状态
+struct InlineRename {
+    path: PathBuf,
+    text: String,
+    focus_pending: bool, // 首帧需要获取焦点并选中文件名主干
+}
+
+// 拖动到列表上下边缘此距离内时开始自动滚动
+const AUTO_PAN_EDGE: f32 = 24.0;
+// 自动滚动的最大速度（每帧像素）
+const AUTO_PAN_MAX_SPEED: f32 = 20.0;
+
+// 跳转条宽度（字母模式 / 日期模式）
+const JUMP_BAR_LETTER_WIDTH: f32 = 18.0;
+const JUMP_BAR_DATE_WIDTH: f32 = 34.0;
+
+// “最近修改”栏显示的文件数
+const RECENT_STRIP_COUNT: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ViewMode {
     Details,        // 详细信息（列表视图）
     LargeIcons,     // 大图标
     SmallIcons,     // 小图标
     ThumbnailIcons, // 缩略图模式（大图标增强）
+    Tiles,          // 平铺（中等图标 + 类型和大小两行信息）
+}
+
+// 详细信息视图的分组方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    None,     // 不分组
+    Type,     // 按类型
+    Modified, // 按修改日期（今天/本周/更早）
+    Letter,   // 按首字母
+    Source,   // 按下载来源网站
+}
+
+// 详细信息视图中的一项：组标题或文件行
+enum DetailEntry {
+    Header { label: String, count: usize, collapsed: bool },
+    Row(usize),
 }
 
-#[derive(Debug, Clone, Copy)]
-enum SortBy {
-    Name,
-    Size,
-    Modified,
-}
+// 排序时文件夹相对文件的位置
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FolderPlacement {
+    #[default]
+    First, // 文件夹在前
+    Last,  // 文件夹在后
+    Mixed, // 与文件混合排序
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SortBy {
+    Name,
+    Size,
+    Modified,
+    Type,
+}
+
+// 列表的显示选项。每个窗格（内容框、目录框）各有一份，刷新时传入，互不影响
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOptions {
+    pub show_hidden: bool,              // 是否包含隐藏文件（预读时使用相同设置）
+    pub filter_text: String,            // 筛选框内容（子串或通配符）
+    pub tag_filter: Option<TagColor>,   // 只显示带有该标签的条目
+    pub sort_by: SortBy,
+    pub sort_ascending: bool,
+    pub folder_placement: FolderPlacement, // 排序时文件夹的位置
+    pub group_by: GroupBy,              // 详细信息视图的分组方式
+    pub group_companions: bool,         // 把字幕等配套文件归到主文件下，不单独显示
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            show_hidden: false,
+            filter_text: String::new(),
+            tag_filter: None,
+            sort_by: SortBy::Name,
+            sort_ascending: true,
+            folder_placement: FolderPlacement::First,
+            group_by: GroupBy::None,
+            group_companions: false,
+        }
+    }
+}
+
+impl FileList {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            all_files: Vec::new(),
+            loaded_path: PathBuf::new(),
+            options: ListOptions::default(),
+            col_name_ratio: 0.5,
+            col_modified_ratio: 0.2,
+            col_type_ratio: 0.15,
+            col_size_ratio: 0.15,
+            mouse_strategy: MouseDoubleClickStrategy::new(),
+            icon_manager: super::icon_manager::IconManager::new(),
+            thumbnail_view: ThumbnailView::new(),
+            jump_bar_enabled: false,
+            two_line_names: false,
+            recent_strip_enabled: false,
+            pending_jump: None,
+            collapsed_groups: HashSet::new(),
+            sticky_header_enabled: true,
+            selection: HashSet::new(),
+            rubber_band_origin: None,
+            rubber_band_base: HashSet::new(),
+            inline_rename: None,
+            rename_commit: None,
+            slow_click_rename: None,
+            spring_load: None,
+            drop_request: None,
+            folder_size_enabled: false,
+            folder_size_worker: None,
+            project_type_worker: None,
+            color_rules: Vec::new(),
+            companion_counts: HashMap::new(),
+            tag_revision: None,
+            keyboard_cursor: None,
+            pending_reveal: None,
+            nav_layout: NavLayout::default(),
+            icon_zoom: 1.0,
+            detail_row_width: None,
+            metadata_cache: HashMap::new(),
+            metadata_worker: None,
+            download_sources: HashMap::new(),
+            group_move_request: None,
+            view_image_request: None,
+            view_states: HashMap::new(),
+            restore_view_state: false,
+            pending_scroll_offset: None,
+            restored_selection: None,
+            folder_icons: FolderIconCache::new(),
+            context_action: None,
+            clipboard_available: false,
+        }
+    }
+
+    // 设置滚动时是否固定显示当前分组（含路径）标题
+    pub fn set_sticky_header_enabled(&mut self, enabled: bool) {
+        self.sticky_header_enabled = enabled;
+    }
+
+    pub fn options(&self) -> &ListOptions {
+        &self.options
+    }
+
+    // 修改显示选项并重新排序、筛选，不重新读取目录；隐藏文件的改变在下次刷新时生效
+    pub fn set_options(&mut self, options: &ListOptions) {
+        if self.options == *options {
+            return;
+        }
+        self.apply_options(options);
+        self.sort_files();
+    }
+
+    fn apply_options(&mut self, options: &ListOptions) {
+        if self.options.group_by != options.group_by {
+            self.collapsed_groups.clear();
+        }
+        self.options = options.clone();
+    }
+
+    // 设置单击或双击打开条目
+    pub fn set_click_open_mode(&mut self, mode: ClickOpenMode) {
+        self.mouse_strategy.set_open_mode(mode);
+    }
+
+    // 图标视图中过长的文件名折成两行，否则单行在中间省略
+    pub fn set_two_line_names(&mut self, enabled: bool) {
+        self.two_line_names = enabled;
+    }
+
+    // 开启或关闭文件夹自定义图标，开启时重新查找当前列表中的文件夹
+    pub fn set_custom_folder_icons(&mut self, enabled: bool) {
+        if self.folder_icons.is_enabled() == enabled {
+            return;
+        }
+        self.folder_icons.set_enabled(enabled);
+        for file in self.all_files.iter().filter(|f| f.is_dir) {
+            self.folder_icons.update(&file.path);
+        }
+    }
+
+    // 设置缩略图使用的预览组件（目录框不经过 show，需要单独设置）
+    pub fn set_preview_ref(&mut self, preview: &super::preview::Preview) {
+        self.thumbnail_view.set_preview_ref(preview);
+    }
+
+    // 设置详细信息视图的行颜色规则
+    pub fn set_color_rules(&mut self, rules: Vec<ColorRule>) {
+        self.color_rules = rules;
+    }
+
+    // 根据颜色规则计算行文字样式：颜色取第一条设置了颜色的匹配规则，任一匹配规则要求加粗即加粗
+    fn row_text_style(&self, file: &FileItem, default_color: egui::Color32) -> (egui::Color32, bool) {
+        let mut color = None;
+        let mut bold = false;
+        for rule in &self.color_rules {
+            if !rule.matches(&file.path, file.is_dir, file.is_executable, file.modified_time) {
+                continue;
+            }
+            if rule.color_enabled && color.is_none() {
+                let [r, g, b] = rule.color;
+                color = Some(egui::Color32::from_rgb(r, g, b));
+            }
+            bold |= rule.bold;
+        }
+        (color.unwrap_or(default_color), bold)
+    }
+
+    #[cfg(unix)]
+    fn has_exec_permission(metadata: &fs::Metadata) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    fn has_exec_permission(_metadata: &fs::Metadata) -> bool {
+        false
+    }
+
+    // 设置是否在后台计算文件夹大小
+    pub fn set_folder_size_enabled(&mut self, enabled: bool) {
+        if self.folder_size_enabled == enabled {
+            return;
+        }
+        self.folder_size_enabled = enabled;
+        if enabled {
+            self.start_folder_size_worker();
+        } else {
+            self.folder_size_worker = None;
+            for file in self.all_files.iter_mut().chain(self.files.iter_mut()) {
+                file.folder_size = None;
+            }
+        }
+    }
+
+    // 为当前目录中的文件夹启动大小计算（替换并取消之前的计算）
+    fn start_folder_size_worker(&mut self) {
+        let folders: Vec<PathBuf> = self.all_files.iter().filter(|f| f.is_dir).map(|f| f.path.clone()).collect();
+        self.folder_size_worker = Some(FolderSizeWorker::start(folders));
+    }
+
+    // 接收后台读取的元数据，逐步更新按 @字段=值 筛选的结果
+    fn poll_metadata(&mut self, ctx: &egui::Context) {
+        let Some(worker) = self.metadata_worker.as_mut() else {
+            return;
+        };
+        let results = worker.poll();
+        let finished = !worker.is_running();
+        if self.metadata_cache.len() > METADATA_CACHE_LIMIT {
+            self.metadata_cache.clear();
+        }
+        let changed = !results.is_empty();
+        for (path, modified, rows) in results {
+            self.metadata_cache.insert(path, (modified, rows));
+        }
+        if finished {
+            self.metadata_worker = None;
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        if changed {
+            self.apply_filter();
+        }
+    }
+
+    // 接收后台识别的项目类型，逐步显示徽标
+    fn poll_project_types(&mut self, ctx: &egui::Context) {
+        let Some(worker) = self.project_type_worker.as_mut() else {
+            return;
+        };
+        let results = worker.poll();
+        let finished = !worker.is_running();
+        for (path, types) in results {
+            for file in self.all_files.iter_mut().chain(self.files.iter_mut()) {
+                if file.path == path {
+                    file.project_types = types.clone();
+                }
+            }
+        }
+        if finished {
+            self.project_type_worker = None;
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+
+    // 接收后台计算结果，逐步填入大小列
+    fn poll_folder_sizes(&mut self, ctx: &egui::Context) {
+        let Some(worker) = self.folder_size_worker.as_mut() else {
+            return;
+        };
+        let results = worker.poll();
+        let finished = !worker.is_running();
+        for (path, size) in results {
+            for file in self.all_files.iter_mut().chain(self.files.iter_mut()) {
+                if file.path == path {
+                    file.folder_size = Some(size);
+                }
+            }
+        }
+
+        if finished {
+            self.folder_size_worker = None;
+            // 全部完成后再按大小重新排序，避免计算过程中列表不断跳动
+            if self.options.sort_by == SortBy::Size {
+                self.sort_files();
+            }
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+
+    // 从标签库同步条目的标签并重新筛选；标签库未变化时不做任何事
+    pub fn sync_tags(&mut self, store: &TagStore) {
+        if self.tag_revision == Some(store.revision()) {
+            return;
+        }
+        self.tag_revision = Some(store.revision());
+        for file in self.all_files.iter_mut() {
+            file.tags = store.tags(&file.path).to_vec();
+        }
+        self.apply_filter();
+    }
+
+    // 符号链接悬停时显示目标路径
+    fn with_link_tooltip(response: egui::Response, file: &FileItem) -> egui::Response {
+        match &file.link_target {
+            Some(target) if file.is_broken_link => response.on_hover_text(format!("断开的链接 → {}（目标不存在）", target.display())),
+            Some(target) => response.on_hover_text(format!("链接到 {}", target.display())),
+            None => response,
+        }
+    }
+
+    // 在图标左下角绘制链接箭头角标（左侧垂直居中于 pos），断开的链接使用红色
+    fn draw_link_overlay(painter: &egui::Painter, pos: egui::Pos2, size: f32, broken: bool) {
+        let rect = egui::Rect::from_min_size(egui::pos2(pos.x, pos.y - size * 0.5), egui::vec2(size, size));
+        let (fill, arrow) = if broken {
+            (egui::Color32::from_rgb(220, 60, 60), egui::Color32::WHITE)
+        } else {
+            (egui::Color32::WHITE, egui::Color32::from_rgb(40, 100, 200))
+        };
+        painter.rect_filled(rect, 2.0, fill);
+        painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::from_gray(120)));
+        // 右上方向的箭头
+        let inset = size * 0.25;
+        let tail = egui::pos2(rect.left() + inset, rect.bottom() - inset);
+        let head = egui::pos2(rect.right() - inset, rect.top() + inset);
+        let stroke = egui::Stroke::new((size * 0.12).max(1.0), arrow);
+        painter.line_segment([tail, head], stroke);
+        painter.line_segment([head, egui::pos2(head.x - size * 0.35, head.y)], stroke);
+        painter.line_segment([head, egui::pos2(head.x, head.y + size * 0.35)], stroke);
+    }
+
+    // 设置是否显示右侧跳转条
+    pub fn set_jump_bar_enabled(&mut self, enabled: bool) {
+        self.jump_bar_enabled = enabled;
+    }
+
+    // 设置是否在列表上方显示最近修改的文件
+    pub fn set_recent_strip_enabled(&mut self, enabled: bool) {
+        self.recent_strip_enabled = enabled;
+    }
+
+    // 隐藏主文件也在列表中的配套文件，记录每个主文件隐藏的数量
+    fn hide_companions(&mut self) {
+        self.companion_counts.clear();
+        if !self.options.group_companions {
+            return;
+        }
+        let names: Vec<&str> = self.files.iter().filter(|f| !f.is_dir).map(|f| f.name.as_str()).collect();
+        let groups = companions::group(&names);
+        if groups.is_empty() {
+            return;
+        }
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for primary in groups.values() {
+            *counts.entry(primary.to_string()).or_default() += 1;
+        }
+        let hidden: HashSet<String> = groups.keys().map(|name| name.to_string()).collect();
+        self.files.retain(|f| f.is_dir || !hidden.contains(&f.name));
+        for file in &self.files {
+            if let Some(count) = counts.get(&file.name) {
+                self.companion_counts.insert(file.path.clone(), *count);
+            }
+        }
+    }
+
+    // 在名称后绘制“+N”，表示归组隐藏的配套文件数
+    fn draw_companion_badge(&self, painter: &egui::Painter, pos: egui::Pos2, file: &FileItem, color: egui::Color32) -> f32 {
+        let Some(count) = self.companion_counts.get(&file.path) else {
+            return 0.0;
+        };
+        let rect = painter.text(pos, egui::Align2::LEFT_CENTER, format!("+{}", count), egui::FontId::proportional(11.0), color);
+        rect.width() + 4.0
+    }
+
+    pub fn set_clipboard_available(&mut self, available: bool) {
+        self.clipboard_available = available;
+    }
+
+    // 开始就地重命名（仅详细信息视图显示输入框）
+    pub fn begin_inline_rename(&mut self, path: &Path) {
+        if let Some(file) = self.files.iter().find(|f| f.path == path) {
+            self.inline_rename = Some(InlineRename {
+                path: file.path.clone(),
+                text: file.name.clone(),
+                focus_pending: true,
+            });
+            self.slow_click_rename = None;
+        }
+    }
+
+    // 取出已确认的就地重命名请求，由主程序调用 FileOperations::rename_file 执行
+    pub fn take_rename_commit(&mut self) -> Option<(PathBuf, String)> {
+        self.rename_commit.take()
+    }
+
+    // 取出拖放请求，由主程序调用 FileOperations::drop_files 执行
+    pub fn take_drop_request(&mut self) -> Option<FileDropRequest> {
+        self.drop_request.take()
+    }
+
+    // 取出要整组移动的分组（组标题和组内条目），由主程序打开移动对话框
+    pub fn take_group_move_request(&mut self) -> Option<(String, Vec<PathBuf>)> {
+        self.group_move_request.take()
+    }
+
+    pub fn take_view_image_request(&mut self) -> Option<PathBuf> {
+        self.view_image_request.take()
+    }
+
+    // 当前列表中的图片，按显示顺序
+    pub fn image_paths(&self) -> Vec<PathBuf> {
+        self.files.iter()
+            .filter(|f| !f.is_dir && image_formats::is_image_file(&f.path))
+            .map(|f| f.path.clone())
+            .collect()
+    }
+
+    // 取出右键菜单选择的操作，由主程序执行
+    pub fn take_context_action(&mut self) -> Option<ContextAction> {
+        self.context_action.take()
+    }
+
+    // 按窗格的显示选项重新读取目录
+    pub fn refresh(&mut self, path: PathBuf, options: &ListOptions) {
+        // 进入其它目录时清空筛选；返回上级目录或通过历史导航时恢复上次的滚动位置和选中项
+        let navigated = self.loaded_path != path;
+        let restore = navigated && (std::mem::take(&mut self.restore_view_state) || self.loaded_path.starts_with(&path));
+        self.apply_options(options);
+        if navigated {
+            self.save_view_state();
+            self.options.filter_text.clear();
+            self.loaded_path = path.clone();
+        }
+        let show_hidden = self.options.show_hidden;
+        self.pending_scroll_offset = None;
+        self.restored_selection = None;
+        self.metadata_worker = None;
+        self.download_sources.clear();
+        self.selection.clear();
+        self.inline_rename = None;
+        self.slow_click_rename = None;
+        self.tag_revision = None;
+        self.keyboard_cursor = None;
+        self.pending_reveal = None;
+        self.thumbnail_view.reset_requests();
+
+        // 进入悬停或选中时已在后台预读过的目录时直接使用预读结果，并取消其它预读
+        let prefetched = if navigated {
+            let mut prefetcher = prefetcher();
+            let prefetched = prefetcher.take(&path, show_hidden);
+            prefetcher.cancel_all();
+            prefetched
+        } else {
+            None
+        };
+        self.all_files = prefetched
+            .or_else(|| Self::read_listing(&path, show_hidden, &AtomicBool::new(false)))
+            .unwrap_or_default();
+
+        for file in self.all_files.iter().filter(|f| f.is_dir) {
+            self.folder_icons.update(&file.path);
+        }
+
+        self.sort_files();
+        if restore {
+            self.restore_saved_view_state();
+        }
+
+        // 离开目录时丢弃（取消）之前的文件夹大小计算
+        self.folder_size_worker = None;
+        if self.folder_size_enabled {
+            self.start_folder_size_worker();
+        }
+        let folders: Vec<PathBuf> = self.all_files.iter().filter(|f| f.is_dir).map(|f| f.path.clone()).collect();
+        self.project_type_worker = Some(ProjectTypeWorker::start(folders));
+
+        // 确保图标已加载
+        if !self.icon_manager.is_loaded() {
+            let _ = self.icon_manager.load_icons();
+        }
+    }
+
+    // 读取目录条目（不排序）；也在预读线程中调用，取消时返回 None
+    fn read_listing(path: &Path, show_hidden: bool, cancelled: &AtomicBool) -> Option<Vec<FileItem>> {
+        let entries = fs::read_dir(path).ok()?;
+        let mut files = Vec::new();
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            let entry_path = entry.path();
+            let name = entry_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("未知文件")
+                .to_string();
+
+            // 跳过隐藏文件
+            if !show_hidden && Self::is_hidden_file(&entry_path, &name) {
+                continue;
+            }
+
+            // 先读取链接自身的元数据；符号链接再跟随到目标，断开的链接不再跟随
+            let link_metadata = fs::symlink_metadata(&entry_path).ok();
+            let is_symlink = link_metadata.as_ref().is_some_and(|m| m.file_type().is_symlink());
+            let metadata = if is_symlink { fs::metadata(&entry_path).ok() } else { link_metadata.clone() };
+            let is_broken_link = is_symlink && metadata.is_none();
+            let link_target = if is_symlink { fs::read_link(&entry_path).ok() } else { None };
+
+            let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+            let (size, modified_time, is_executable) = match metadata.as_ref().or(link_metadata.as_ref()) {
+                Some(metadata) if !is_broken_link => (metadata.len(), metadata.modified().ok(), !is_dir && Self::has_exec_permission(metadata)),
+                Some(metadata) => (0, metadata.modified().ok(), false),
+                None => (0, None, false),
+            };
+
+            let modified = modified_time
+                .map(|time| locale_format::current().datetime(time))
+                .unwrap_or_else(|| "未知时间".to_string());
+
+            files.push(FileItem {
+                path: entry_path,
+                name,
+                size,
+                modified,
+                modified_time,
+                is_dir,
+                folder_size: None,
+                is_executable,
+                tags: Vec::new(),
+                link_target,
+                is_broken_link,
+                project_types: Vec::new(), // 由 ProjectTypeWorker 在后台填入
+            });
+        }
+        Some(files)
+    }
+
+    // 记录当前目录的滚动位置和选中项
+    fn save_view_state(&mut self) {
+        if self.loaded_path.as_os_str().is_empty() {
+            return;
+        }
+        if self.view_states.len() >= MAX_VIEW_STATES && !self.view_states.contains_key(&self.loaded_path) {
+            if let Some(key) = self.view_states.keys().next().cloned() {
+                self.view_states.remove(&key);
+            }
+        }
+        self.view_states.insert(self.loaded_path.clone(), DirectoryViewState {
+            scroll_offset: self.nav_layout.scroll_offset,
+            selection: self.selection.clone(),
+            cursor: self.keyboard_cursor.clone(),
+        });
+    }
+
+    // 恢复刚进入的目录上次的滚动位置和选中项，已不存在的条目忽略
+    fn restore_saved_view_state(&mut self) {
+        let Some(state) = self.view_states.get(&self.loaded_path) else {
+            return;
+        };
+        let exists = |path: &PathBuf| self.all_files.iter().any(|f| &f.path == path);
+        self.selection = state.selection.iter().filter(|p| exists(p)).cloned().collect();
+        self.keyboard_cursor = state.cursor.clone().filter(exists);
+        self.restored_selection = self.keyboard_cursor.clone().or_else(|| self.selection.iter().next().cloned());
+        self.pending_scroll_offset = Some(state.scroll_offset);
+    }
+
+    // 下次刷新时恢复目录的滚动位置和选中项（后退/前进时调用）
+    pub fn restore_view_state_on_refresh(&mut self) {
+        self.restore_view_state = true;
+    }
+
+    // 取出刷新时恢复的主选中项
+    pub fn take_restored_selection(&mut self) -> Option<PathBuf> {
+        self.restored_selection.take()
+    }
+
+    // 在后台预读可能进入的文件夹
+    fn prefetch(&self, dir: &Path) {
+        prefetcher().request(dir, self.options.show_hidden);
+    }
+
+    pub fn ensure_textures(&mut self, ctx: &egui::Context) {
+        self.icon_manager.ensure_textures(ctx);
+    }
+
+    pub fn load_icons(&mut self) -> Result<(), String> {
+        self.icon_manager.load_icons()
+    }
+
+    pub fn get_icon_manager(&self) -> &super::icon_manager::IconManager {
+        &self.icon_manager
+    }
+
+    pub fn get_icon_manager_mut(&mut self) -> &mut super::icon_manager::IconManager {
+        &mut self.icon_manager
+    }
+
+    fn sort_files(&mut self) {
+        self.all_files.sort_by(|a, b| {
+            let cmp = match self.options.sort_by {
+                SortBy::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                // 文件夹按已算出的大小排序
+                SortBy::Size => a.folder_size.unwrap_or(a.size).cmp(&b.folder_size.unwrap_or(b.size)),
+                SortBy::Modified => a.modified_time.cmp(&b.modified_time),
+                SortBy::Type => {
+                    // 按扩展名、名称排序
+                    let ext = |f: &FileItem| f.path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                    ext(a).cmp(&ext(b)).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                }
+            };
+            let cmp = if self.options.sort_ascending { cmp } else { cmp.reverse() };
+
+            // 文件夹的位置不受升序/降序影响
+            let folders = match self.options.folder_placement {
+                FolderPlacement::First => b.is_dir.cmp(&a.is_dir),
+                FolderPlacement::Last => a.is_dir.cmp(&b.is_dir),
+                FolderPlacement::Mixed => std::cmp::Ordering::Equal,
+            };
+            folders.then(cmp)
+        });
+        self.apply_filter();
+    }
+
+    // 点击列头排序：再次点击同一列切换升序/降序
+    fn toggle_sort(&mut self, sort_by: SortBy) {
+        if self.options.sort_by == sort_by {
+            self.options.sort_ascending = !self.options.sort_ascending;
+        } else {
+            self.options.sort_by = sort_by;
+            self.options.sort_ascending = true;
+        }
+        self.sort_files();
+    }
+
+    // 当前的查看方式（视图模式由主程序传入）
+    pub fn capture_view_settings(&self, view_mode: ViewMode) -> FolderViewSettings {
+        FolderViewSettings {
+            view_mode,
+            sort_by: self.options.sort_by,
+            sort_ascending: self.options.sort_ascending,
+            column_ratios: [self.col_name_ratio, self.col_modified_ratio, self.col_type_ratio, self.col_size_ratio],
+            icon_zoom: self.icon_zoom,
+        }
+    }
+
+    // 恢复某目录保存的排序方式和列宽
+    pub fn restore_view_settings(&mut self, settings: &FolderViewSettings) {
+        self.options.sort_by = settings.sort_by;
+        self.options.sort_ascending = settings.sort_ascending;
+        [self.col_name_ratio, self.col_modified_ratio, self.col_type_ratio, self.col_size_ratio] = settings.column_ratios;
+        self.icon_zoom = settings.icon_zoom.clamp(*ICON_ZOOM_RANGE.start(), *ICON_ZOOM_RANGE.end());
+        self.sort_files();
+    }
+
+    // 按筛选框内容从已加载的条目中筛选，不重新读取目录
+    fn apply_filter(&mut self) {
+        let filter = self.options.filter_text.trim().to_lowercase();
+        if filter.is_empty() && self.options.tag_filter.is_none() {
+            self.files = self.all_files.clone();
+            self.hide_companions();
+            return;
+        }
+        let is_wildcard = filter.contains('*') || filter.contains('?');
+        // @字段=值：按元数据筛选，如 @model=canon、@页数=12；尚未读取的文件在后台读取，读取完成后再次筛选
+        let metadata_filter = file_metadata::parse_filter(&filter);
+        if metadata_filter.is_some() && self.metadata_worker.is_none() {
+            let missing: Vec<(PathBuf, Option<SystemTime>)> = self.all_files.iter()
+                .filter(|f| !f.is_dir && self.metadata_cache.get(&f.path).is_none_or(|(modified, _)| *modified != f.modified_time))
+                .map(|f| (f.path.clone(), f.modified_time))
+                .collect();
+            if !missing.is_empty() {
+                self.metadata_worker = Some(MetadataWorker::start(missing));
+            }
+        }
+        self.files = self.all_files.iter()
+            .filter(|f| self.options.tag_filter.is_none_or(|tag| f.tags.contains(&tag)))
+            .filter(|f| {
+                if filter.is_empty() {
+                    return true;
+                }
+                if let Some((key, value)) = metadata_filter {
+                    return self.metadata_cache.get(&f.path)
+                        .filter(|(modified, _)| *modified == f.modified_time)
+                        .and_then(|(_, rows)| file_metadata::find_value(rows, key))
+                        .is_some_and(|v| v.to_lowercase().contains(value));
+                }
+                let name = f.name.to_lowercase();
+                if is_wildcard {
+                    utils::wildcard_match(&filter, &name)
+                } else {
+                    name.contains(&filter)
+                }
+            })
+            .cloned()
+            .collect();
+        self.hide_companions();
+    }
+
+    // 内容列表上方的筛选框，显示“x / 共 y 项”；图标视图下右侧显示缩放滑块
+    fn show_filter_bar(&mut self, ui: &mut egui::Ui, view_mode: ViewMode) {
+        ui.horizontal(|ui| {
+            ui.label("筛选:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.options.filter_text)
+                    .hint_text("名称、通配符 *.rs 或 @字段=值")
+                    .desired_width(180.0),
+            );
+            let mut changed = response.changed();
+            if response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape)) && !self.options.filter_text.is_empty() {
+                self.options.filter_text.clear();
+                changed = true;
+            }
+            if !self.options.filter_text.is_empty() && ui.small_button("✖").on_hover_text("清除筛选").clicked() {
+                self.options.filter_text.clear();
+                changed = true;
+            }
+            if self.metadata_worker.is_some() {
+                ui.spinner().on_hover_text("正在读取元数据...");
+            }
+            if changed {
+                self.apply_filter();
+            }
+            if !self.options.filter_text.is_empty() || self.options.tag_filter.is_some() {
+                ui.label(format!("显示 {} / 共 {} 项", self.files.len(), self.all_files.len()));
+            }
+            if matches!(view_mode, ViewMode::LargeIcons | ViewMode::SmallIcons | ViewMode::ThumbnailIcons) {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(egui::Slider::new(&mut self.icon_zoom, ICON_ZOOM_RANGE).show_value(false))
+                        .on_hover_text("图标大小（Ctrl+滚轮）");
+                    ui.label("🔍");
+                });
+            }
+        });
+    }
+
+    // 当前列表中最近修改的几个文件（不含文件夹），新的在前。列表随文件监视更新，无需另外读取
+    fn recent_files(&self) -> Vec<&FileItem> {
+        let newest_first = |a: &&FileItem, b: &&FileItem| b.modified_time.cmp(&a.modified_time);
+        let mut files: Vec<&FileItem> = self.files.iter().filter(|f| !f.is_dir && f.modified_time.is_some()).collect();
+        if files.len() > RECENT_STRIP_COUNT {
+            files.select_nth_unstable_by(RECENT_STRIP_COUNT - 1, newest_first);
+            files.truncate(RECENT_STRIP_COUNT);
+        }
+        files.sort_by(newest_first);
+        files
+    }
+
+    // 可折叠的“最近修改”栏：单击定位到该文件，双击打开
+    fn show_recent_strip(&mut self, ui: &mut egui::Ui, selected_file: &mut Option<PathBuf>) {
+        if !self.recent_strip_enabled {
+            return;
+        }
+        let recent: Vec<(PathBuf, String, String, Option<SystemTime>)> = self.recent_files().into_iter()
+            .map(|f| (f.path.clone(), f.name.clone(), f.modified.clone(), f.modified_time))
+            .collect();
+        if recent.is_empty() {
+            return;
+        }
+        let font = egui::TextStyle::Body.resolve(ui.style());
+        let (mut reveal, mut open) = (None, None);
+        egui::CollapsingHeader::new("最近修改").id_salt("recent_strip").default_open(true).show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (path, name, modified, time) in &recent {
+                    let label = format!("{} {}", utils::get_file_icon(path), text_elide::elide_middle(ui.ctx(), name, &font, 160.0));
+                    let response = ui.add(egui::Button::new(label).small().selected(selected_file.as_ref() == Some(path)))
+                        .on_hover_text(format!("{}\n修改于 {}", name, modified));
+                    if let Some(time) = time {
+                        ui.label(egui::RichText::new(relative_time(*time)).small().weak());
+                    }
+                    ui.add_space(6.0);
+                    if response.double_clicked() {
+                        open = Some(path.clone());
+                    } else if response.clicked() {
+                        reveal = Some(path.clone());
+                    }
+                }
+            });
+        });
+        if let Some(path) = reveal {
+            self.reveal(&path, selected_file);
+        }
+        if let Some(path) = open {
+            self.mouse_strategy.handle_double_click(path);
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>, view_mode: ViewMode, preview: Option<&super::preview::Preview>) -> bool {
+        // 确保纹理已加载
+        self.icon_manager.ensure_textures(ui.ctx());
+
+        // 设置预览组件引用以支持缩略图
+        if let Some(p) = preview {
+            self.thumbnail_view.set_preview_ref(p);
+        }
+
+        self.poll_folder_sizes(ui.ctx());
+        self.poll_project_types(ui.ctx());
+        self.poll_metadata(ui.ctx());
+        self.request_folder_icons();
+        if let Some(dir) = selected_file.as_ref().filter(|p| p.is_dir()) {
+            self.prefetch(dir);
+        }
+        self.show_filter_bar(ui, view_mode);
+        self.show_recent_strip(ui, selected_file);
+
+        if self.handle_keyboard(ui, current_path, selected_file) {
+            return true;
+        }
+
+        match view_mode {
+            ViewMode::Details => self.show_details_view(ui, current_path, selected_file),
+            ViewMode::LargeIcons => self.show_icons_view(ui, current_path, selected_file, true, false),
+            ViewMode::SmallIcons => self.show_icons_view(ui, current_path, selected_file, false, false),
+            ViewMode::ThumbnailIcons => self.show_icons_view(ui, current_path, selected_file, true, true),
+            ViewMode::Tiles => self.show_tiles_view(ui, current_path, selected_file),
+        }
+    }
+
+    fn show_details_view(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
+        let mut should_navigate = false;
+
+        // 列头与可调分隔线（内容框）
+        {
+            // 列宽按上一帧各行实际得到的宽度计算（扣除滚动条和跳转条），与行完全对齐
+            let total_w = self.detail_row_width.unwrap_or_else(|| ui.available_width() - self.jump_bar_width());
+            let [name_w, modified_w, type_w, size_w] = self.column_widths(total_w);
+
+            let row_h = ui.spacing().interact_size.y * 1.2;
+            let (strip_rect, _resp) = ui.allocate_exact_size(egui::vec2(ui.available_width(), row_h), egui::Sense::hover());
+            let rect = egui::Rect::from_min_size(strip_rect.min, egui::vec2(total_w.min(strip_rect.width()), row_h));
+
+            let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::default());
+            let color = ui.visuals().text_color();
+
+            // 列头可点击排序，当前排序列显示方向箭头
+            let mut x = rect.left();
+            let mut clicked_sort = None;
+            for (title, width, sort_by) in [
+                ("名称", name_w, SortBy::Name),
+                ("修改日期", modified_w, SortBy::Modified),
+                ("类型", type_w, SortBy::Type),
+                ("大小", size_w, SortBy::Size),
+            ] {
+                let col_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + width, rect.bottom()));
+                let col_resp = ui.interact(col_rect, ui.id().with(("col_header", title)), egui::Sense::click());
+                if col_resp.clicked() {
+                    clicked_sort = Some(sort_by);
+                }
+                let text = if self.options.sort_by == sort_by {
+                    format!("{} {}", title, if self.options.sort_ascending { "▲" } else { "▼" })
+                } else {
+                    title.to_string()
+                };
+                let text_color = if col_resp.hovered() { ui.visuals().strong_text_color() } else { color };
+                ui.painter().with_clip_rect(col_rect).text(egui::pos2(col_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, text, font_id.clone(), text_color);
+                x += width;
+            }
+            if let Some(sort_by) = clicked_sort {
+                self.toggle_sort(sort_by);
+            }
+
+            let sep_w = 4.0;
+            let id1 = ui.make_persistent_id("col_sep_1");
+            let id2 = ui.make_persistent_id("col_sep_2");
+            let id3 = ui.make_persistent_id("col_sep_3");
+            let sx1 = rect.left() + name_w;
+            let sx2 = rect.left() + name_w + modified_w;
+            let sx3 = rect.left() + name_w + modified_w + type_w;
+            for (sx, id) in [(sx1, id1), (sx2, id2), (sx3, id3)] {
+                let sep_rect = egui::Rect::from_min_max(
+                    egui::pos2(sx - sep_w * 0.5, rect.top()),
+                    egui::pos2(sx + sep_w * 0.5, rect.bottom()),
+                );
+                let resp = ui.interact(sep_rect, id, egui::Sense::drag());
+                ui.painter().rect_filled(sep_rect, 0.0, ui.visuals().widgets.inactive.bg_fill.gamma_multiply(0.8));
+                if resp.dragged() {
+                    let dx = resp.drag_delta().x;
+                    let delta_ratio = dx / total_w;
+                    let min = 0.08;
+                    if id == id1 {
+                        let total = self.col_name_ratio + self.col_modified_ratio;
+                        let new_left = (self.col_name_ratio + delta_ratio).clamp(min, total - min);
+                        self.col_name_ratio = new_left;
+                        self.col_modified_ratio = total - new_left;
+                    } else if id == id2 {
+                        let total = self.col_modified_ratio + self.col_type_ratio;
+                        let new_left = (self.col_modified_ratio + delta_ratio).clamp(min, total - min);
+                        self.col_modified_ratio = new_left;
+                        self.col_type_ratio = total - new_left;
+                    } else {
+                        let total = self.col_type_ratio + self.col_size_ratio;
+                        let new_left = (self.col_type_ratio + delta_ratio).clamp(min, total - min);
+                        self.col_type_ratio = new_left;
+                        self.col_size_ratio = total - new_left;
+                    }
+                }
+            }
+        }
+
+        // 跳转条占用的宽度，行内容需要让出这部分空间
+        let jump_bar_w = self.jump_bar_width();
+        let jump_target = self.pending_jump.take();
+        let reveal_target = self.pending_reveal.take();
+
+        // 文件列表内容（按分组插入可折叠的组标题）
+        if self.options.group_by == GroupBy::Source {
+            self.update_download_sources();
+        }
+        let entries = self.detail_entries();
+        let mut toggled_group: Option<String> = None;
+        let mut moved_group: Option<String> = None;
+        // 记录每个组标题的位置，用于计算需要固定显示的当前分组
+        let mut header_positions: Vec<(&str, usize, bool, f32)> = Vec::new();
+        let mut scroll_area = egui::ScrollArea::vertical();
+        if let Some(offset) = self.pending_scroll_offset.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let scroll_output = scroll_area.show(ui, |ui| {
+            let content_origin = ui.min_rect().min;
+            // 先注册框选的拖动区域，使其位于各行之下，不遮挡行的点击
+            let band_response = ui.interact(ui.clip_rect(), ui.id().with("rubber_band"), egui::Sense::drag());
+            let mut row_rects: Vec<(usize, egui::Rect)> = Vec::new();
+            let mut band_start = if band_response.drag_started_by(egui::PointerButton::Primary) { band_response.interact_pointer_pos() } else { None };
+            let mut hovered_folder: Option<PathBuf> = None;
+            // 记录行宽供下一帧的列头使用；宽度变化（如出现滚动条）时再绘制一帧
+            let row_w = ui.available_width() - jump_bar_w;
+            if self.detail_row_width != Some(row_w) {
+                self.detail_row_width = Some(row_w);
+                ui.ctx().request_repaint();
+            }
+            for entry in &entries {
+                match entry {
+                    DetailEntry::Header { label, count, collapsed } => {
+                        let (response, move_clicked) = self.show_group_header(ui, label, *count, *collapsed, row_w);
+                        if move_clicked {
+                            moved_group = Some(label.clone());
+                        } else if response.clicked() {
+                            toggled_group = Some(label.clone());
+                        }
+                        header_positions.push((label, *count, *collapsed, response.rect.top()));
+                    }
+                    DetailEntry::Row(index) => {
+                        let was_primary = selected_file.as_ref() == Some(&self.files[*index].path);
+                        let (response, name_rect) = self.show_detail_row(ui, &self.files[*index], row_w, jump_target == Some(*index), selected_file);
+                        if reveal_target == Some(*index) {
+                            response.scroll_to_me(None);
+                        }
+                        row_rects.push((*index, response.rect));
+                        if self.inline_rename.as_ref().is_some_and(|r| r.path == self.files[*index].path) {
+                            let edit_rect = egui::Rect::from_min_max(
+                                egui::pos2(name_rect.left() + 20.0, name_rect.top() + 2.0),
+                                egui::pos2(name_rect.right() - 4.0, name_rect.bottom() - 2.0),
+                            );
+                            self.show_inline_rename_editor(ui, edit_rect);
+                        }
+                        if self.handle_item_click(ui, &response, *index, current_path, selected_file) {
+                            should_navigate = true;
+                        }
+                        self.arm_slow_click_rename(ui, &response, name_rect, *index, was_primary);
+
+                        // 从名称列开始拖动为拖放文件，从其它列开始拖动为框选
+                        if response.drag_started_by(egui::PointerButton::Primary) {
+                            let on_name = response.interact_pointer_pos().is_some_and(|pos| name_rect.contains(pos));
+                            if on_name {
+                                self.begin_file_drag(ui.ctx(), *index, selected_file);
+                            } else {
+                                band_start = response.interact_pointer_pos();
+                            }
+                        }
+                        if self.handle_folder_drop_target(ui, &response, *index) {
+                            hovered_folder = Some(self.files[*index].path.clone());
+                        }
+                    }
+                }
+            }
+
+            // 拖到列表空白处或非文件夹行上释放：放入当前目录
+            if hovered_folder.is_none() {
+                if let Some(payload) = band_response.dnd_release_payload::<DraggedFiles>() {
+                    let from_elsewhere = payload.0.iter().any(|p| p.parent() != Some(current_path.as_path()));
+                    if from_elsewhere && payload.accepts(current_path) {
+                        self.drop_request = Some(FileDropRequest {
+                            paths: payload.0.clone(),
+                            target_dir: current_path.clone(),
+                            copy: ui.input(|i| i.modifiers.command),
+                        });
+                    }
+                }
+            }
+
+            // 拖放文件时靠近列表上下边缘同样自动滚动
+            if egui::DragAndDrop::has_payload_of_type::<DraggedFiles>(ui.ctx()) {
+                if let Some(pointer) = ui.ctx().pointer_latest_pos() {
+                    if band_response.rect.x_range().contains(pointer.x) {
+                        auto_pan_near_edges(ui, band_response.rect, pointer);
+                    }
+                }
+            }
+
+            // 在列表空白处或行上拖动进行框选
+            self.handle_rubber_band(ui, band_start, content_origin, &row_rects, selected_file);
+
+            // 悬停在文件夹上足够久时自动打开
+            if self.update_spring_load(ui, hovered_folder, current_path, selected_file) {
+                should_navigate = true;
+            }
+        });
+
+        // 拖放时在指针旁显示将要移动/复制的项目数
+        if let Some(payload) = egui::DragAndDrop::payload::<DraggedFiles>(ui.ctx()) {
+            let verb = if ui.input(|i| i.modifiers.command) { "复制" } else { "移动" };
+            egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), ui.id().with("drag_files_tip"), |ui| {
+                ui.label(format!("{} {} 项", verb, payload.0.len()));
+            });
+        }
+
+        // 慢速双击的第二次单击超过双击间隔后进入就地重命名
+        if let Some((path, armed_at)) = self.slow_click_rename.clone() {
+            let delay = ui.ctx().options(|o| o.input_options.max_double_click_delay);
+            let elapsed = ui.input(|i| i.time) - armed_at;
+            if selected_file.as_ref() != Some(&path) {
+                self.slow_click_rename = None;
+            } else if elapsed > delay {
+                self.begin_inline_rename(&path);
+            } else {
+                ui.ctx().request_repaint_after(std::time::Duration::from_secs_f64(delay - elapsed));
+            }
+        }
+
+        // 当前分组的标题已滚出可见区域时，在列表顶部固定显示路径和分组标题
+        if self.sticky_header_enabled {
+            let viewport = scroll_output.inner_rect;
+            let current_group = header_positions.iter().rev().find(|(.., top)| *top < viewport.top());
+            if let Some((label, count, collapsed, _)) = current_group {
+                let row_h = ui.spacing().interact_size.y * 1.3;
+                let sticky_rect = egui::Rect::from_min_size(viewport.min, egui::vec2(viewport.width() - jump_bar_w, row_h));
+                let response = ui.interact(sticky_rect, ui.id().with("sticky_group_header"), egui::Sense::click());
+
+                let painter = ui.painter();
+                painter.rect_filled(sticky_rect, 0.0, ui.visuals().panel_fill);
+                let arrow = if *collapsed { "▶" } else { "▼" };
+                let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_default();
+                painter.with_clip_rect(sticky_rect).text(
+                    egui::pos2(sticky_rect.left() + 6.0, sticky_rect.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    format!("{} {} › {} ({})", arrow, current_path.display(), label, count),
+                    font_id,
+                    ui.visuals().strong_text_color(),
+                );
+                painter.line_segment(
+                    [sticky_rect.left_bottom(), sticky_rect.right_bottom()],
+                    egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.bg_stroke.color),
+                );
+
+                if response.clicked() {
+                    toggled_group = Some(label.to_string());
+                }
+            }
+        }
+
+        // 点击组标题切换折叠状态
+        if let Some(label) = toggled_group {
+            if !self.collapsed_groups.remove(&label) {
+                self.collapsed_groups.insert(label);
+            }
+        }
+        if let Some(label) = moved_group {
+            let paths = self.files.iter()
+                .filter(|f| self.group_key(f).1 == label)
+                .map(|f| f.path.clone())
+                .collect();
+            self.group_move_request = Some((label, paths));
+        }
+
+        self.nav_layout = NavLayout {
+            columns: 1,
+            row_height: ui.spacing().interact_size.y * 1.5 + ui.spacing().item_spacing.y,
+            viewport_height: scroll_output.inner_rect.height(),
+            scroll_offset: scroll_output.state.offset.y,
+        };
+
+        // 在列表可见区域右侧绘制跳转条
+        if self.jump_bar_enabled {
+            self.draw_jump_bar(ui, scroll_output.inner_rect);
+        }
+
+        should_navigate
+    }
+
+    // 详细信息视图的列宽（名称、修改日期、类型、大小），列头和各行共用
+    fn column_widths(&self, total_w: f32) -> [f32; 4] {
+        let widths = [
+            (self.col_name_ratio * total_w).max(60.0),
+            (self.col_modified_ratio * total_w).max(80.0),
+            (self.col_type_ratio * total_w).max(60.0),
+            (self.col_size_ratio * total_w).max(60.0),
+        ];
+        let scale = total_w / widths.iter().sum::<f32>();
+        widths.map(|w| w * scale)
+    }
+
+    // 绘制详细信息视图中的一行，返回该行的响应和名称列区域
+    fn show_detail_row(&self, ui: &mut egui::Ui, file: &FileItem, row_w: f32, scroll_here: bool, selected_file: &Option<PathBuf>) -> (egui::Response, egui::Rect) {
+        let is_selected = self.is_selected(file, selected_file);
+        let total_w = row_w;
+        let [name_w, modified_w, type_w, size_w] = self.column_widths(total_w);
+
+        let row_size = egui::vec2(total_w, ui.spacing().interact_size.y * 1.5);
+        let (rect, response) = ui.allocate_exact_size(row_size, egui::Sense::click_and_drag());
+        let response = Self::with_link_tooltip(response, file);
+
+        // 跳转条点击后滚动到对应分段的第一项
+        if scroll_here {
+            response.scroll_to_me(Some(egui::Align::TOP));
+        }
+
+        if is_selected {
+            let visuals = ui.visuals();
+            ui.painter().rect_filled(rect, 0.0, visuals.widgets.inactive.bg_fill);
+            ui.painter().rect_stroke(rect, 0.0, egui::Stroke::new(1.0, visuals.widgets.active.fg_stroke.color));
+        }
+        self.draw_keyboard_focus(ui, rect, file, selected_file);
+
+        let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::default());
+        let (color, bold) = self.row_text_style(file, ui.visuals().text_color());
+        let mut x = rect.left();
+        let painter = ui.painter();
+        // 加粗通过偏移重绘文字模拟（字体没有粗体变体）
+        // 超出列宽的名称在中间省略，保留扩展名
+        let draw_text = |clip: egui::Rect, pos: egui::Pos2, text: String| -> egui::Rect {
+            let text = text_elide::elide_middle(ui.ctx(), &text, &font_id, clip.right() - pos.x - 4.0);
+            let clipped = painter.with_clip_rect(clip);
+            if bold {
+                clipped.text(pos + egui::vec2(0.6, 0.0), egui::Align2::LEFT_CENTER, &text, font_id.clone(), color);
+            }
+            clipped.text(pos, egui::Align2::LEFT_CENTER, text, font_id.clone(), color)
+        };
+        let name_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + name_w, rect.bottom()));
+
+        // 目录使用自定义图标，EXE/DLL/TXT/代码/无格式文件使用自定义图标，其他文件使用原有emoji
+        let name_text_rect = if file.is_dir {
+            // 详细信息模式使用更小的图标 (16px)
+            if !self.draw_custom_folder_icon(ui, painter, &file.path, name_rect.left() + 14.0, rect.center().y, 16.0) {
+                self.draw_folder_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 16.0);
+            }
+            let text_x = name_rect.left() + 22.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else if self.is_exe_file(&file.path) {
+            // EXE文件使用自定义图标 (12px)
+            self.draw_exe_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
+            let text_x = name_rect.left() + 20.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else if self.is_dll_file(&file.path) {
+            // DLL文件使用自定义图标 (12px)
+            self.draw_dll_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
+            let text_x = name_rect.left() + 20.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else if self.is_txt_file(&file.path) {
+            // TXT文件使用自定义图标 (12px)
+            self.draw_txt_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
+            let text_x = name_rect.left() + 20.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else if self.is_code_file(&file.path) {
+            // 代码文件使用自定义图标 (12px)
+            self.draw_code_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
+            let text_x = name_rect.left() + 20.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else if self.is_unidentified_file(&file.path) {
+            // 无格式文件使用自定义图标 (12px)
+            self.draw_unidentified_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
+            let text_x = name_rect.left() + 20.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else if self.is_default_file(&file.path) {
+            // 默认文件使用自定义图标 (12px)
+            self.draw_default_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
+            let text_x = name_rect.left() + 20.0;
+            draw_text(name_rect, egui::pos2(text_x, rect.center().y), file.name.clone())
+        } else {
+            let name_text = format!("{} {}", utils::get_file_icon(&file.path), file.name);
+            draw_text(name_rect, egui::pos2(name_rect.left() + 6.0, rect.center().y), name_text)
+        };
+        if file.link_target.is_some() {
+            Self::draw_link_overlay(painter, egui::pos2(name_rect.left() + 3.0, rect.center().y + 8.0), 9.0, file.is_broken_link);
+        }
+        // 名称后显示标签圆点和归组的配套文件数
+        let name_painter = painter.with_clip_rect(name_rect);
+        let tags_w = tags::draw_tag_dots(&name_painter, egui::pos2(name_text_rect.right() + 6.0, rect.center().y), &file.tags);
+        let badge_x = name_text_rect.right() + 6.0 + if tags_w > 0.0 { tags_w + 4.0 } else { 0.0 };
+        let companion_w = self.draw_companion_badge(&name_painter, egui::pos2(badge_x, rect.center().y), file, ui.visuals().weak_text_color());
+        project_types::draw_badges(&name_painter, egui::pos2(badge_x + companion_w, rect.center().y), &file.project_types);
+        // 单击打开模式下悬停的名称加下划线，提示单击即可打开
+        if self.mouse_strategy.is_single_click() && response.hovered() {
+            let underline = [name_text_rect.left_bottom(), name_text_rect.right_bottom()];
+            painter.with_clip_rect(name_rect).line_segment(underline, egui::Stroke::new(1.0, color));
+        }
+        x += name_w;
+        let modified_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + modified_w, rect.bottom()));
+        draw_text(modified_rect, egui::pos2(modified_rect.left() + 6.0, rect.center().y), file.modified.clone());
+        x += modified_w;
+        let type_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + type_w, rect.bottom()));
+        let file_type = if file.is_broken_link {
+            "断开的链接".to_string()
+        } else if file.is_dir {
+            "文件夹".to_string()
+        } else {
+            file.path.extension().and_then(|e| e.to_str()).map(|s| s.to_uppercase()).unwrap_or_else(|| "文件".to_string())
+        };
+        let file_type = if file.link_target.is_some() && !file.is_broken_link {
+            format!("{} (链接)", file_type)
+        } else {
+            file_type
+        };
+        draw_text(type_rect, egui::pos2(type_rect.left() + 6.0, rect.center().y), file_type);
+        x += type_w;
+        let size_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + size_w, rect.bottom()));
+        let size_text = if !file.is_dir {
+            utils::get_file_size_str(file.size)
+        } else if let Some(size) = file.folder_size {
+            utils::get_file_size_str(size)
+        } else if self.folder_size_enabled {
+            "计算中…".to_string()
+        } else {
+            "—".to_string()
+        };
+        draw_text(size_rect, egui::pos2(size_rect.left() + 6.0, rect.center().y), size_text);
+
+        (response, name_rect)
+    }
+
+    // 就地重命名输入框：回车或点击别处提交，Esc取消
+    fn show_inline_rename_editor(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        let Some(state) = self.inline_rename.as_mut() else {
+            return;
+        };
+        let id = ui.id().with("inline_rename");
+        let response = ui.put(rect, egui::TextEdit::singleline(&mut state.text).id(id).margin(egui::vec2(2.0, 1.0)));
+
+        if state.focus_pending {
+            state.focus_pending = false;
+            response.request_focus();
+            // 与资源管理器一致：文件默认只选中主干部分，不含扩展名
+            let is_dir = state.path.is_dir();
+            let stem_len = match state.text.rfind('.') {
+                Some(dot) if dot > 0 && !is_dir => state.text[..dot].chars().count(),
+                _ => state.text.chars().count(),
+            };
+            let mut edit_state = egui::TextEdit::load_state(ui.ctx(), id).unwrap_or_default();
+            edit_state.cursor.set_char_range(Some(egui::text::CCursorRange::two(
+                egui::text::CCursor::new(0),
+                egui::text::CCursor::new(stem_len),
+            )));
+            edit_state.store(ui.ctx(), id);
+        }
+
+        if response.lost_focus() {
+            let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+            if let Some(state) = self.inline_rename.take() {
+                let unchanged = state.path.file_name().and_then(|n| n.to_str()) == Some(state.text.as_str());
+                if !cancelled && !unchanged {
+                    self.rename_commit = Some((state.path, state.text));
+                }
+            }
+        }
+    }
+
+    // 单击已是主选中项的名称（非双击）时记录时间，用于慢速双击重命名
+    fn arm_slow_click_rename(&mut self, ui: &egui::Ui, response: &egui::Response, name_rect: egui::Rect, index: usize, was_primary: bool) {
+        // 单击打开模式下单击已用于打开
+        if response.double_clicked() || self.mouse_strategy.is_single_click() {
+            self.slow_click_rename = None;
+        } else if response.clicked() {
+            let on_name = response.interact_pointer_pos().is_some_and(|pos| name_rect.contains(pos));
+            let plain_click = !ui.input(|i| i.modifiers.command);
+            self.slow_click_rename = if was_primary && on_name && plain_click && self.inline_rename.is_none() {
+                Some((self.files[index].path.clone(), ui.input(|i| i.time)))
+            } else {
+                None
+            };
+        }
+    }
+
+    // 内容框的键盘操作：方向键/Home/End/PageUp/PageDown移动，Enter打开（图片在全窗口查看器中打开），Backspace返回上一级，空格切换选中
+    // Shift+移动扩展选择，Ctrl+移动只移动焦点；返回是否需要导航
+    fn handle_keyboard(&mut self, ui: &egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
+        // 输入框（筛选框、路径栏、重命名等）获得焦点时不处理
+        if ui.ctx().wants_keyboard_input() || self.inline_rename.is_some() {
+            return false;
+        }
+
+        // Ctrl+A 全选
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::A)) {
+            self.apply_selection_command(SelectionCommand::SelectAll, selected_file);
+            return false;
+        }
+
+        // Delete 移到回收站，Shift+Delete 永久删除
+        if let Some(permanent) = ui.input(|i| i.key_pressed(egui::Key::Delete).then_some(i.modifiers.shift)) {
+            let paths = self.selected_paths(selected_file);
+            if !paths.is_empty() {
+                self.context_action = Some(if permanent { ContextAction::DeletePermanently(paths) } else { ContextAction::Delete(paths) });
+            }
+            return false;
+        }
+
+        let (pressed, modifiers) = ui.input(|i| {
+            let keys = [
+                egui::Key::ArrowUp, egui::Key::ArrowDown, egui::Key::ArrowLeft, egui::Key::ArrowRight,
+                egui::Key::Home, egui::Key::End, egui::Key::PageUp, egui::Key::PageDown,
+                egui::Key::Enter, egui::Key::Backspace, egui::Key::Space,
+            ];
+            (keys.into_iter().find(|k| i.key_pressed(*k)), i.modifiers)
+        });
+        let Some(key) = pressed else {
+            return false;
+        };
+
+        if key == egui::Key::Backspace {
+            if let Some(parent) = current_path.parent() {
+                *current_path = parent.to_path_buf();
+                *selected_file = None;
+                return true;
+            }
+            return false;
+        }
+
+        let cursor_path = self.keyboard_cursor.as_ref().or(selected_file.as_ref());
+        let current = cursor_path.and_then(|p| self.files.iter().position(|f| &f.path == p));
+
+        match key {
+            egui::Key::Enter => {
+                let Some(index) = current else {
+                    return false;
+                };
+                let file = &self.files[index];
+                if file.is_dir {
+                    *current_path = file.path.clone();
+                    *selected_file = None;
+                    self.selection.clear();
+                    return true;
+                }
+                if image_formats::is_image_file(&file.path) {
+                    self.view_image_request = Some(file.path.clone());
+                } else {
+                    self.mouse_strategy.handle_double_click(file.path.clone());
+                }
+                false
+            }
+            egui::Key::Space => {
+                if let Some(index) = current {
+                    self.toggle_selection(self.files[index].path.clone(), selected_file);
+                }
+                false
+            }
+            _ => {
+                let count = self.files.len();
+                if count == 0 {
+                    return false;
+                }
+                let columns = self.nav_layout.columns.max(1);
+                let visible_rows = (self.nav_layout.viewport_height / self.nav_layout.row_height.max(1.0)).floor() as usize;
+                let page = visible_rows.max(1) * columns;
+                let target = match (current, key) {
+                    (None, _) => 0,
+                    (Some(_), egui::Key::Home) => 0,
+                    (Some(_), egui::Key::End) => count - 1,
+                    (Some(i), egui::Key::ArrowDown) => (i + columns).min(count - 1),
+                    (Some(i), egui::Key::ArrowUp) => i.saturating_sub(columns),
+                    // 详细信息视图只有一列，左右键不移动
+                    (Some(i), egui::Key::ArrowRight) if columns > 1 => (i + 1).min(count - 1),
+                    (Some(i), egui::Key::ArrowLeft) if columns > 1 => i.saturating_sub(1),
+                    (Some(i), egui::Key::PageDown) => (i + page).min(count - 1),
+                    (Some(i), egui::Key::PageUp) => i.saturating_sub(page),
+                    (Some(i), _) => i,
+                };
+
+                // Ctrl+移动只移动焦点，保留原有选择
+                let path = self.files[target].path.clone();
+                if !modifiers.command {
+                    if modifiers.shift {
+                        self.selection.extend(selected_file.take());
+                    } else {
+                        self.selection.clear();
+                    }
+                    self.selection.insert(path.clone());
+                    *selected_file = Some(path.clone());
+                }
+                self.keyboard_cursor = Some(path);
+                self.pending_reveal = Some(target);
+                false
+            }
+        }
+    }
+
+    // 选中并滚动到指定条目（用于从报告等窗口定位文件）
+    pub fn reveal(&mut self, path: &Path, selected_file: &mut Option<PathBuf>) {
+        if let Some(index) = self.files.iter().position(|f| f.path == path) {
+            self.selection.clear();
+            self.selection.insert(path.to_path_buf());
+            *selected_file = Some(path.to_path_buf());
+            self.keyboard_cursor = Some(path.to_path_buf());
+            self.pending_reveal = Some(index);
+        }
+    }
+
+    // 状态栏用的条目数、选中项和筛选信息
+    pub fn summary(&self, selected_file: &Option<PathBuf>) -> ListSummary {
+        let selected: HashSet<PathBuf> = self.selected_paths(selected_file).into_iter().collect();
+        let selected_items = self.all_files.iter().filter(|f| selected.contains(&f.path));
+        let mut summary = ListSummary { total: self.all_files.len(), shown: self.files.len(), selected: selected.len(), ..Default::default() };
+        for file in selected_items {
+            match (file.is_dir, file.folder_size) {
+                (false, _) => summary.selected_size += file.size,
+                (true, Some(size)) => summary.selected_size += size,
+                (true, None) => summary.selected_size_partial = true,
+            }
+        }
+
+        let mut filters = Vec::new();
+        if !self.options.filter_text.trim().is_empty() {
+            filters.push(format!("筛选 \"{}\"", self.options.filter_text.trim()));
+        }
+        if let Some(tag) = self.options.tag_filter {
+            filters.push(format!("标签 {}", tag.label()));
+        }
+        if !filters.is_empty() {
+            summary.filter = Some(filters.join("，"));
+        }
+        summary
+    }
+
+    // 指针在图标视图上时，Ctrl+滚轮调整图标大小
+    fn handle_icon_zoom_input(&mut self, ui: &egui::Ui) {
+        if !ui.rect_contains_pointer(ui.max_rect()) {
+            return;
+        }
+        let zoom_delta = ui.input(|i| i.zoom_delta());
+        if zoom_delta != 1.0 {
+            self.icon_zoom = (self.icon_zoom * zoom_delta).clamp(*ICON_ZOOM_RANGE.start(), *ICON_ZOOM_RANGE.end());
+        }
+    }
+
+    // 对当前显示的条目（筛选后）执行全选、反选或取消选择
+    pub fn apply_selection_command(&mut self, command: SelectionCommand, selected_file: &mut Option<PathBuf>) {
+        let selected: HashSet<PathBuf> = self.selected_paths(selected_file).into_iter().collect();
+        let paths: Vec<PathBuf> = match command {
+            SelectionCommand::SelectAll => self.files.iter().map(|f| f.path.clone()).collect(),
+            SelectionCommand::InvertSelection => self.files.iter().filter(|f| !selected.contains(&f.path)).map(|f| f.path.clone()).collect(),
+            SelectionCommand::DeselectAll => Vec::new(),
+        };
+        // 原主选中项仍被选中时保留，否则取第一个
+        let primary = selected_file.take().filter(|p| paths.contains(p)).or_else(|| paths.first().cloned());
+        self.selection = paths.into_iter().collect();
+        *selected_file = primary;
+    }
+
+    // 切换条目的选中状态（Ctrl+单击和空格键共用），保留原来的主选中项
+    fn toggle_selection(&mut self, path: PathBuf, selected_file: &mut Option<PathBuf>) {
+        if let Some(primary) = selected_file.take() {
+            self.selection.insert(primary);
+        }
+        if self.selection.remove(&path) {
+            *selected_file = self.selection.iter().next().cloned();
+        } else {
+            self.selection.insert(path.clone());
+            *selected_file = Some(path);
+        }
+    }
+
+    // 键盘焦点不在选中项上时绘制焦点框
+    fn draw_keyboard_focus(&self, ui: &egui::Ui, rect: egui::Rect, file: &FileItem, selected_file: &Option<PathBuf>) {
+        if self.keyboard_cursor.as_ref() == Some(&file.path) && !self.is_selected(file, selected_file) {
+            ui.painter().rect_stroke(rect.shrink(1.0), 2.0, egui::Stroke::new(1.0, ui.visuals().selection.stroke.color));
+        }
+    }
+
+    // 计算让待显示条目进入可见区域所需的滚动位置（用于按行虚拟化的视图）
+    fn take_reveal_offset(&mut self, columns: usize, row_height: f32) -> Option<f32> {
+        let row = self.pending_reveal.take()? / columns.max(1);
+        let top = row as f32 * row_height;
+        let layout = self.nav_layout;
+        if top < layout.scroll_offset {
+            Some(top)
+        } else if top + row_height > layout.scroll_offset + layout.viewport_height {
+            Some(top + row_height - layout.viewport_height)
+        } else {
+            None
+        }
+    }
+
+    // 条目是否处于选中状态（主选中项或多选集合中）
+    fn is_selected(&self, file: &FileItem, selected_file: &Option<PathBuf>) -> bool {
+        selected_file.as_ref() == Some(&file.path) || self.selection.contains(&file.path)
+    }
+
+    // 处理条目的点击：单击选择、Ctrl+单击多选、双击打开、右键菜单，返回是否需要导航进入目录
+    fn handle_item_click(&mut self, ui: &egui::Ui, response: &egui::Response, index: usize, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
+        if self.handle_context_menu(response, index, current_path, selected_file) {
+            return true;
+        }
+        let file = &self.files[index];
+        if file.is_dir && response.hovered() {
+            self.prefetch(&file.path);
+        }
+        let open_click = self.mouse_strategy.is_open_click(ui, response);
+        if self.mouse_strategy.is_single_click() && response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+        if open_click && file.is_dir {
+            // 选中项保留到刷新时记录，返回上级时恢复
+            *current_path = file.path.clone();
+            *selected_file = None;
+            return true;
+        } else if open_click {
+            let path = file.path.clone();
+            self.mouse_strategy.handle_double_click(path.clone());
+            // 单击打开的文件同时成为选中项
+            if !response.double_clicked() {
+                self.selection.clear();
+                self.selection.insert(path.clone());
+                *selected_file = Some(path);
+            }
+        } else if response.clicked() {
+            let path = file.path.clone();
+            self.keyboard_cursor = Some(path.clone());
+            if ui.input(|i| i.modifiers.command) {
+                // Ctrl+单击：切换该项的选中状态
+                self.toggle_selection(path, selected_file);
+            } else {
+                self.selection.clear();
+                self.selection.insert(path.clone());
+                *selected_file = Some(path);
+            }
+        }
+        false
+    }
+
+    // 条目的右键菜单：右键未选中的条目时先只选中它；选择打开文件夹时返回 true
+    fn handle_context_menu(&mut self, response: &egui::Response, index: usize, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
+        if response.secondary_clicked() && !self.is_selected(&self.files[index], selected_file) {
+            let path = self.files[index].path.clone();
+            self.selection.clear();
+            self.selection.insert(path.clone());
+            *selected_file = Some(path);
+        }
+
+        let mut action = None;
+        response.context_menu(|ui| {
+            let file = &self.files[index];
+            let paths = self.selected_paths(selected_file);
+            action = context_menu::show_item_menu(ui, &file.path, file.is_dir, &paths, self.clipboard_available, &file.project_types);
+        });
 
-impl FileList {
-    pub fn new() -> Self {
-        Self {
-            files: Vec::new(),
-            sort_by: SortBy::Name,
-            sort_ascending: true,
-            col_name_ratio: 0.5,
-            col_modified_ratio: 0.2,
-            col_type_ratio: 0.15,
-            col_size_ratio: 0.15,
-            mouse_strategy: MouseDoubleClickStrategy::new(),
-            icon_manager: super::icon_manager::IconManager::new(),
-            thumbnail_view: ThumbnailView::new(),
+        match action {
+            Some(ContextAction::Open(path)) if path.is_dir() => {
+                *current_path = path;
+                *selected_file = None;
+                return true;
+            }
+            Some(ContextAction::Open(path)) => {
+                self.mouse_strategy.handle_double_click(path);
+            }
+            Some(action) => self.context_action = Some(action),
+            None => {}
         }
+        false
     }
 
-    pub fn refresh(&mut self, path: PathBuf, show_hidden: bool) {
-        self.files.clear();
+    // 框选：拖动时绘制选择框并选中与其相交的行，拖到列表上下边缘时自动滚动
+    fn handle_rubber_band(&mut self, ui: &mut egui::Ui, band_start: Option<egui::Pos2>, content_origin: egui::Pos2, row_rects: &[(usize, egui::Rect)], selected_file: &mut Option<PathBuf>) {
+        let viewport = ui.clip_rect();
 
-        // 使用轻量级的目录读取，避免阻塞UI
-        if let Ok(entries) = fs::read_dir(&path) {
-            for entry in entries.flatten() {
-                let entry_path = entry.path();
-                let name = entry_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("未知文件")
-                    .to_string();
+        if let Some(pos) = band_start {
+            self.rubber_band_origin = Some(pos - content_origin);
+            self.rubber_band_base = if ui.input(|i| i.modifiers.command) {
+                let mut base = self.selection.clone();
+                base.extend(selected_file.clone());
+                base
+            } else {
+                HashSet::new()
+            };
+        }
 
-                // 跳过隐藏文件
-                if !show_hidden && self.is_hidden_file(&entry_path, &name) {
-                    continue;
+        // 框选可能由空白处或行发起，统一按主键是否按住判断是否结束
+        let dragging = ui.input(|i| i.pointer.primary_down());
+        if let (Some(origin), Some(pointer)) = (self.rubber_band_origin, ui.ctx().pointer_latest_pos()) {
+            if dragging {
+                let band = egui::Rect::from_two_pos(content_origin + origin, pointer);
+
+                let mut selection = self.rubber_band_base.clone();
+                let mut first_hit = None;
+                for (index, rect) in row_rects {
+                    if rect.intersects(band) {
+                        let path = self.files[*index].path.clone();
+                        first_hit.get_or_insert_with(|| path.clone());
+                        selection.insert(path);
+                    }
                 }
+                self.selection = selection;
+                *selected_file = first_hit.or_else(|| self.rubber_band_base.iter().next().cloned());
 
-                // 使用轻量级文件类型检测，避免metadata()调用
-                let is_dir = entry_path.is_dir();
-                let size = match fs::metadata(&entry_path) {
-                    Ok(metadata) => metadata.len(),
-                    Err(_) => 0,
-                };
-                
-                // 修改时间也延迟加载
-                let modified = utils::get_file_modified_time(&entry_path)
-                    .unwrap_or_else(|| "未知时间".to_string());
-
-                self.files.push(FileItem {
-                    path: entry_path,
-                    name,
-                    size,
-                    modified,
-                    is_dir,
-                });
+                let visuals = ui.visuals();
+                ui.painter().rect(
+                    band,
+                    0.0,
+                    visuals.selection.bg_fill.gamma_multiply(0.25),
+                    egui::Stroke::new(1.0, visuals.selection.stroke.color),
+                );
+
+                auto_pan_near_edges(ui, viewport, pointer);
             }
         }
 
-        self.sort_files();
-
-        // 确保图标已加载
-        if !self.icon_manager.is_loaded() {
-            let _ = self.icon_manager.load_icons();
+        if !dragging && self.rubber_band_origin.is_some() {
+            self.rubber_band_origin = None;
+            self.rubber_band_base.clear();
         }
     }
 
-    pub fn ensure_textures(&mut self, ctx: &egui::Context) {
-        self.icon_manager.ensure_textures(ctx);
+    // 获取所有选中的条目（按列表顺序），主选中项不在多选集合中时排在最前
+    fn selected_paths(&self, selected_file: &Option<PathBuf>) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.files.iter()
+            .filter(|f| self.selection.contains(&f.path))
+            .map(|f| f.path.clone())
+            .collect();
+        if let Some(primary) = selected_file {
+            if !paths.contains(primary) {
+                paths.insert(0, primary.clone());
+            }
+        }
+        paths
     }
 
-    pub fn load_icons(&mut self) -> Result<(), String> {
-        self.icon_manager.load_icons()
+    // 恰好选中两个文件（不含文件夹）时返回这两个文件
+    pub fn selected_file_pair(&self, selected_file: &Option<PathBuf>) -> Option<(PathBuf, PathBuf)> {
+        match self.selected_paths(selected_file).as_slice() {
+            [a, b] if a.is_file() && b.is_file() => Some((a.clone(), b.clone())),
+            _ => None,
+        }
     }
 
-    pub fn get_icon_manager(&self) -> &super::icon_manager::IconManager {
-        &self.icon_manager
+    // 开始拖放：拖动已选中的项时带上全部选中项，否则只拖动该项并选中它
+    fn begin_file_drag(&mut self, ctx: &egui::Context, index: usize, selected_file: &mut Option<PathBuf>) {
+        let paths = if self.is_selected(&self.files[index], selected_file) {
+            self.selected_paths(selected_file)
+        } else {
+            let path = self.files[index].path.clone();
+            self.selection.clear();
+            self.selection.insert(path.clone());
+            *selected_file = Some(path.clone());
+            vec![path]
+        };
+        self.slow_click_rename = None;
+        egui::DragAndDrop::set_payload(ctx, DraggedFiles(paths));
     }
 
-    pub fn get_icon_manager_mut(&mut self) -> &mut super::icon_manager::IconManager {
-        &mut self.icon_manager
+    // 拖放经过文件夹行时高亮为放置目标，释放时记录拖放请求；返回该行是否为当前放置目标
+    fn handle_folder_drop_target(&mut self, ui: &egui::Ui, response: &egui::Response, index: usize) -> bool {
+        let file = &self.files[index];
+        if !file.is_dir {
+            return false;
+        }
+        let Some(payload) = response.dnd_hover_payload::<DraggedFiles>() else {
+            return false;
+        };
+        if !payload.accepts(&file.path) {
+            return false;
+        }
+
+        let visuals = ui.visuals();
+        ui.painter().rect(
+            response.rect,
+            2.0,
+            visuals.selection.bg_fill.gamma_multiply(0.3),
+            egui::Stroke::new(1.5, visuals.selection.stroke.color),
+        );
+
+        if let Some(payload) = response.dnd_release_payload::<DraggedFiles>() {
+            self.drop_request = Some(FileDropRequest {
+                paths: payload.0.clone(),
+                target_dir: file.path.clone(),
+                copy: ui.input(|i| i.modifiers.command),
+            });
+        }
+        true
     }
 
-    fn sort_files(&mut self) {
-        self.files.sort_by(|a, b| {
-            let cmp = match self.sort_by {
-                SortBy::Name => {
-                    // 文件夹排在前面
-                    match (a.is_dir, b.is_dir) {
-                        (true, false) => std::cmp::Ordering::Less,
-                        (false, true) => std::cmp::Ordering::Greater,
-                        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-                    }
-                }
-                SortBy::Size => {
-                    // 文件夹排在前面，然后按大小排序
-                    match (a.is_dir, b.is_dir) {
-                        (true, false) => std::cmp::Ordering::Less,
-                        (false, true) => std::cmp::Ordering::Greater,
-                        _ => a.size.cmp(&b.size),
-                    }
+    // 弹簧文件夹：拖放悬停在同一文件夹上超过 SPRING_LOAD_DELAY 后进入该文件夹，返回是否需要导航
+    fn update_spring_load(&mut self, ui: &egui::Ui, hovered_folder: Option<PathBuf>, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
+        let Some(folder) = hovered_folder else {
+            self.spring_load = None;
+            return false;
+        };
+        let now = ui.input(|i| i.time);
+        match &self.spring_load {
+            Some((armed, since)) if *armed == folder => {
+                let elapsed = now - since;
+                if elapsed >= SPRING_LOAD_DELAY {
+                    self.spring_load = None;
+                    *current_path = folder;
+                    *selected_file = None;
+                    return true;
                 }
-                SortBy::Modified => a.modified.cmp(&b.modified),
-            };
-
-            if self.sort_ascending {
-                cmp
-            } else {
-                cmp.reverse()
+                ui.ctx().request_repaint_after(std::time::Duration::from_secs_f64(SPRING_LOAD_DELAY - elapsed));
             }
-        });
+            _ => {
+                self.spring_load = Some((folder, now));
+                ui.ctx().request_repaint_after(std::time::Duration::from_secs_f64(SPRING_LOAD_DELAY));
+            }
+        }
+        false
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>, view_mode: ViewMode, preview: Option<&super::preview::Preview>) -> bool {
-        // 确保纹理已加载
-        self.icon_manager.ensure_textures(ui.ctx());
+    // 生成详细信息视图的显示顺序，分组时在每组前插入组标题
+    fn detail_entries(&self) -> Vec<DetailEntry> {
+        if self.options.group_by == GroupBy::None {
+            return (0..self.files.len()).map(DetailEntry::Row).collect();
+        }
 
-        // 设置预览组件引用以支持缩略图
-        if let Some(p) = preview {
-            self.thumbnail_view.set_preview_ref(p);
+        // 组内保持当前排序，组之间按 (优先级, 标签) 排列
+        let mut groups: BTreeMap<(u8, String), Vec<usize>> = BTreeMap::new();
+        for (index, file) in self.files.iter().enumerate() {
+            groups.entry(self.group_key(file)).or_default().push(index);
         }
 
-        match view_mode {
-            ViewMode::Details => self.show_details_view(ui, current_path, selected_file),
-            ViewMode::LargeIcons => self.show_icons_view(ui, current_path, selected_file, true, false),
-            ViewMode::SmallIcons => self.show_icons_view(ui, current_path, selected_file, false, false),
-            ViewMode::ThumbnailIcons => self.show_icons_view(ui, current_path, selected_file, true, true),
+        let mut entries = Vec::new();
+        for ((_, label), indices) in groups {
+            let collapsed = self.collapsed_groups.contains(&label);
+            entries.push(DetailEntry::Header { label, count: indices.len(), collapsed });
+            if !collapsed {
+                entries.extend(indices.into_iter().map(DetailEntry::Row));
+            }
         }
+        entries
     }
 
-    fn show_details_view(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
-        let mut should_navigate = false;
-
-        // 列头与可调分隔线（内容框）
-        {
-            let total_w = ui.available_width();
-            let name_w = (self.col_name_ratio * total_w).max(60.0);
-            let modified_w = (self.col_modified_ratio * total_w).max(80.0);
-            let type_w = (self.col_type_ratio * total_w).max(60.0);
-            let size_w = (self.col_size_ratio * total_w).max(60.0);
-            let sum = name_w + modified_w + type_w + size_w;
-            let scale = total_w / sum;
-            let name_w = name_w * scale;
-            let modified_w = modified_w * scale;
-            let type_w = type_w * scale;
-            let size_w = size_w * scale;
-
-            let row_h = ui.spacing().interact_size.y * 1.2;
-            let (rect, _resp) = ui.allocate_exact_size(egui::vec2(total_w, row_h), egui::Sense::hover());
-
-            let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::default());
-            let color = ui.visuals().text_color();
-
-            let mut x = rect.left();
-            let painter = ui.painter();
-            let name_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + name_w, rect.bottom()));
-            painter.with_clip_rect(name_rect).text(egui::pos2(name_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, "名称", font_id.clone(), color);
-            x += name_w;
-            let modified_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + modified_w, rect.bottom()));
-            painter.with_clip_rect(modified_rect).text(egui::pos2(modified_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, "修改日期", font_id.clone(), color);
-            x += modified_w;
-            let type_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + type_w, rect.bottom()));
-            painter.with_clip_rect(type_rect).text(egui::pos2(type_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, "类型", font_id.clone(), color);
-            x += type_w;
-            let size_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + size_w, rect.bottom()));
-            painter.with_clip_rect(size_rect).text(egui::pos2(size_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, "大小", font_id.clone(), color);
-
-            let sep_w = 4.0;
-            let id1 = ui.make_persistent_id("col_sep_1");
-            let id2 = ui.make_persistent_id("col_sep_2");
-            let id3 = ui.make_persistent_id("col_sep_3");
-            let sx1 = rect.left() + name_w;
-            let sx2 = rect.left() + name_w + modified_w;
-            let sx3 = rect.left() + name_w + modified_w + type_w;
-            for (sx, id) in [(sx1, id1), (sx2, id2), (sx3, id3)] {
-                let sep_rect = egui::Rect::from_min_max(
-                    egui::pos2(sx - sep_w * 0.5, rect.top()),
-                    egui::pos2(sx + sep_w * 0.5, rect.bottom()),
-                );
-                let resp = ui.interact(sep_rect, id, egui::Sense::drag());
-                ui.painter().rect_filled(sep_rect, 0.0, ui.visuals().widgets.inactive.bg_fill.gamma_multiply(0.8));
-                if resp.dragged() {
-                    let dx = resp.drag_delta().x;
-                    let delta_ratio = dx / total_w;
-                    let min = 0.08;
-                    if id == id1 {
-                        let total = self.col_name_ratio + self.col_modified_ratio;
-                        let new_left = (self.col_name_ratio + delta_ratio).clamp(min, total - min);
-                        self.col_name_ratio = new_left;
-                        self.col_modified_ratio = total - new_left;
-                    } else if id == id2 {
-                        let total = self.col_modified_ratio + self.col_type_ratio;
-                        let new_left = (self.col_modified_ratio + delta_ratio).clamp(min, total - min);
-                        self.col_modified_ratio = new_left;
-                        self.col_type_ratio = total - new_left;
-                    } else {
-                        let total = self.col_type_ratio + self.col_size_ratio;
-                        let new_left = (self.col_type_ratio + delta_ratio).clamp(min, total - min);
-                        self.col_type_ratio = new_left;
-                        self.col_size_ratio = total - new_left;
+    // 计算文件所属分组：(排序优先级, 组标签)
+    fn group_key(&self, file: &FileItem) -> (u8, String) {
+        match self.options.group_by {
+            GroupBy::Type => {
+                if file.is_dir {
+                    (0, "文件夹".to_string())
+                } else {
+                    match file.path.extension().and_then(|e| e.to_str()) {
+                        Some(ext) => (1, format!("{} 文件", ext.to_uppercase())),
+                        None => (2, "文件".to_string()),
                     }
                 }
             }
+            GroupBy::Modified => {
+                let now = chrono::Local::now();
+                match file.modified_time.map(chrono::DateTime::<chrono::Local>::from) {
+                    Some(time) if time.date_naive() == now.date_naive() => (0, "今天".to_string()),
+                    Some(time) if now.signed_duration_since(time).num_days() < 7 => (1, "本周".to_string()),
+                    Some(_) => (2, "更早".to_string()),
+                    None => (3, "未知时间".to_string()),
+                }
+            }
+            GroupBy::Letter => match file.name.chars().next() {
+                Some(c) if c.is_ascii_alphabetic() => (0, c.to_ascii_uppercase().to_string()),
+                _ => (1, "#".to_string()),
+            },
+            GroupBy::Source => match self.download_sources.get(&file.path) {
+                _ if file.is_dir => (2, "文件夹".to_string()),
+                Some(Some(host)) => (0, host.clone()),
+                _ => (1, "未知来源".to_string()),
+            },
+            GroupBy::None => (0, String::new()),
         }
+    }
 
-        // 文件列表内容
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            for file in &self.files {
-                let is_selected = selected_file.as_ref().map_or(false, |p| p == &file.path);
-                let total_w = ui.available_width();
-                let name_w = (self.col_name_ratio * total_w).max(60.0);
-                let modified_w = (self.col_modified_ratio * total_w).max(80.0);
-                let type_w = (self.col_type_ratio * total_w).max(60.0);
-                let size_w = (self.col_size_ratio * total_w).max(60.0);
-                let sum = name_w + modified_w + type_w + size_w;
-                let scale = total_w / sum;
-                let name_w = name_w * scale;
-                let modified_w = modified_w * scale;
-                let type_w = type_w * scale;
-                let size_w = size_w * scale;
-
-                let row_size = egui::vec2(total_w, ui.spacing().interact_size.y * 1.5);
-                let (rect, response) = ui.allocate_exact_size(row_size, egui::Sense::click());
-
-                if is_selected {
-                    let visuals = ui.visuals();
-                    ui.painter().rect_filled(rect, 0.0, visuals.widgets.inactive.bg_fill);
-                    ui.painter().rect_stroke(rect, 0.0, egui::Stroke::new(1.0, visuals.widgets.active.fg_stroke.color));
-                }
+    // 读取还没有记录的文件的下载来源（只在按来源分组时读取）
+    fn update_download_sources(&mut self) {
+        for file in self.files.iter().filter(|f| !f.is_dir) {
+            if !self.download_sources.contains_key(&file.path) {
+                self.download_sources.insert(file.path.clone(), downloads::download_source(&file.path));
+            }
+        }
+    }
 
-                let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::default());
-                let color = ui.visuals().text_color();
-                let mut x = rect.left();
-                let painter = ui.painter();
-                let name_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + name_w, rect.bottom()));
+    // 绘制可折叠的组标题，右侧有“移动到”按钮；返回标题的响应和是否点击了移动按钮
+    fn show_group_header(&self, ui: &mut egui::Ui, label: &str, count: usize, collapsed: bool, row_w: f32) -> (egui::Response, bool) {
+        let row_size = egui::vec2(row_w, ui.spacing().interact_size.y * 1.3);
+        let (rect, response) = ui.allocate_exact_size(row_size, egui::Sense::click());
 
-                // 目录使用自定义图标，EXE/DLL/TXT/代码/无格式文件使用自定义图标，其他文件使用原有emoji
-                if file.is_dir {
-                    // 详细信息模式使用更小的图标 (16px)
-                    self.draw_folder_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 16.0);
-                    let text_x = name_rect.left() + 22.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else if self.is_exe_file(&file.path) {
-                    // EXE文件使用自定义图标 (12px)
-                    self.draw_exe_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
-                    let text_x = name_rect.left() + 20.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else if self.is_dll_file(&file.path) {
-                    // DLL文件使用自定义图标 (12px)
-                    self.draw_dll_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
-                    let text_x = name_rect.left() + 20.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else if self.is_txt_file(&file.path) {
-                    // TXT文件使用自定义图标 (12px)
-                    self.draw_txt_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
-                    let text_x = name_rect.left() + 20.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else if self.is_code_file(&file.path) {
-                    // 代码文件使用自定义图标 (12px)
-                    self.draw_code_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
-                    let text_x = name_rect.left() + 20.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else if self.is_unidentified_file(&file.path) {
-                    // 无格式文件使用自定义图标 (12px)
-                    self.draw_unidentified_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
-                    let text_x = name_rect.left() + 20.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else if self.is_default_file(&file.path) {
-                    // 默认文件使用自定义图标 (12px)
-                    self.draw_default_icon_sized(painter, name_rect.left() + 6.0, rect.center().y, 12.0);
-                    let text_x = name_rect.left() + 20.0;
-                    painter.with_clip_rect(name_rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id.clone(), color);
-                } else {
-                    let name_text = format!("{} {}", utils::get_file_icon(&file.path), file.name);
-                    painter.with_clip_rect(name_rect).text(egui::pos2(name_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, name_text, font_id.clone(), color);
-                }
-                x += name_w;
-                let modified_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + modified_w, rect.bottom()));
-                painter.with_clip_rect(modified_rect).text(egui::pos2(modified_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, file.modified.clone(), font_id.clone(), color);
-                x += modified_w;
-                let type_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + type_w, rect.bottom()));
-                let file_type = if file.is_dir {
-                    "文件夹".to_string()
-                } else {
-                    file.path.extension().and_then(|e| e.to_str()).map(|s| s.to_uppercase()).unwrap_or_else(|| "文件".to_string())
-                };
-                painter.with_clip_rect(type_rect).text(egui::pos2(type_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, file_type, font_id.clone(), color);
-                x += type_w;
-                let size_rect = egui::Rect::from_min_max(egui::pos2(x, rect.top()), egui::pos2(x + size_w, rect.bottom()));
-                let size_text = utils::get_file_size_str(file.size);
-                painter.with_clip_rect(size_rect).text(egui::pos2(size_rect.left() + 6.0, rect.center().y), egui::Align2::LEFT_CENTER, size_text, font_id.clone(), color);
+        let painter = ui.painter();
+        if response.hovered() {
+            painter.rect_filled(rect, 0.0, ui.visuals().widgets.hovered.weak_bg_fill);
+        }
 
-                let button_response = response;
+        let arrow = if collapsed { "▶" } else { "▼" };
+        let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_default();
+        let color = ui.visuals().strong_text_color();
+        painter.with_clip_rect(rect).text(
+            egui::pos2(rect.left() + 6.0, rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            format!("{} {} ({})", arrow, label, count),
+            font_id,
+            color,
+        );
+        painter.line_segment(
+            [rect.left_bottom(), rect.right_bottom()],
+            egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.bg_stroke.color),
+        );
+
+        // 悬停时显示，整组移动到其它文件夹
+        let button_rect = egui::Rect::from_min_size(
+            egui::pos2(rect.right() - 90.0, rect.top() + 2.0),
+            egui::vec2(84.0, rect.height() - 4.0),
+        );
+        let show_button = response.hovered() || ui.rect_contains_pointer(button_rect);
+        let move_clicked = show_button && ui.put(button_rect, egui::Button::new("移动到…").small()).clicked();
+
+        (response, move_clicked)
+    }
 
-                // 处理点击事件
-                if button_response.double_clicked() && file.is_dir {
-                    *current_path = file.path.clone();
-                    *selected_file = None;
-                    should_navigate = true;
-                } else if button_response.double_clicked() && !file.is_dir {
-                    self.mouse_strategy.handle_double_click(file.path.clone());
-                } else if button_response.clicked() {
-                    *selected_file = Some(file.path.clone());
-                }
+    // 跳转条宽度，按修改时间排序时使用较宽的日期模式
+    fn jump_bar_width(&self) -> f32 {
+        if !self.jump_bar_enabled {
+            0.0
+        } else if matches!(self.options.sort_by, SortBy::Modified) {
+            JUMP_BAR_DATE_WIDTH
+        } else {
+            JUMP_BAR_LETTER_WIDTH
+        }
+    }
+
+    // 生成跳转条分段：(标签, 该分段第一个条目的索引)
+    fn jump_bar_sections(&self) -> Vec<(String, usize)> {
+        let mut sections: Vec<(String, usize)> = Vec::new();
+
+        for (index, file) in self.files.iter().enumerate() {
+            let key = match self.options.sort_by {
+                // 按修改时间排序时以 年.月 分段，例如 "25.11"；显示的日期格式随区域变化，这里直接由时间计算
+                SortBy::Modified => file.modified_time
+                    .map(|time| chrono::DateTime::<chrono::Local>::from(time).format("%y.%m").to_string())
+                    .unwrap_or_else(|| "?".to_string()),
+                // 其他排序方式以首字母分段，非英文字母统一归入 #
+                _ => match file.name.chars().next() {
+                    Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase().to_string(),
+                    _ => "#".to_string(),
+                },
+            };
+
+            // 文件夹在前时同一字母可能出现两次，只保留第一次出现的位置
+            if !sections.iter().any(|(k, _)| k == &key) {
+                sections.push((key, index));
             }
-        });
+        }
 
-        should_navigate
+        sections
     }
 
-    fn show_icons_view(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>, is_large: bool, use_thumbnails: bool) -> bool {
-        let mut should_navigate = false;
-
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            let available_width = ui.available_width();
+    // 绘制跳转条并处理点击/拖动
+    fn draw_jump_bar(&mut self, ui: &mut egui::Ui, list_rect: egui::Rect) {
+        let sections = self.jump_bar_sections();
+        if sections.len() < 2 {
+            return;
+        }
 
-            // 根据大图标还是小图标设置参数
-            let (icon_size, item_size, columns) = if is_large {
-                (32.0, 80.0, (available_width / 100.0).max(1.0) as usize)
+        // 让出滚动条的位置，避免与滚动条拖动冲突
+        let scroll_style = &ui.spacing().scroll;
+        let right = list_rect.right() - scroll_style.bar_width - scroll_style.bar_outer_margin;
+        let bar_rect = egui::Rect::from_min_max(
+            egui::pos2(right - self.jump_bar_width(), list_rect.top()),
+            egui::pos2(right, list_rect.bottom()),
+        );
+
+        let response = ui.interact(bar_rect, ui.id().with("jump_bar"), egui::Sense::click_and_drag());
+        let painter = ui.painter();
+        painter.rect_filled(bar_rect, 3.0, ui.visuals().extreme_bg_color);
+
+        let slot_h = bar_rect.height() / sections.len() as f32;
+        let font_id = egui::FontId::proportional(slot_h.clamp(6.0, 11.0));
+        let hover_index = response.hover_pos()
+            .map(|pos| (((pos.y - bar_rect.top()) / slot_h) as usize).min(sections.len() - 1));
+
+        for (i, (label, _)) in sections.iter().enumerate() {
+            let center = egui::pos2(bar_rect.center().x, bar_rect.top() + slot_h * (i as f32 + 0.5));
+            let color = if hover_index == Some(i) {
+                ui.visuals().strong_text_color()
             } else {
-                (16.0, 50.0, (available_width / 60.0).max(1.0) as usize)
+                ui.visuals().weak_text_color()
             };
+            painter.text(center, egui::Align2::CENTER_CENTER, label, font_id.clone(), color);
+        }
 
-            // 获取滚动区域的可见区域
-            let scroll_area_rect = ui.max_rect();
-            let visible_min_y = scroll_area_rect.min.y;
-            let visible_max_y = scroll_area_rect.max.y;
-
-            // 计算可见范围内的项目
-            let items_per_row = columns;
-            let row_height = item_size + 4.0; // 包括间距
-            
-            // 计算可见行范围
-            let start_row = ((visible_min_y - ui.cursor().top()) / row_height).floor().max(0.0) as usize;
-            let end_row = ((visible_max_y - ui.cursor().top()) / row_height).ceil() as usize + 1; // +1 作为缓冲
-            
-            let start_index = start_row * items_per_row;
-            let end_index = (end_row * items_per_row).min(self.files.len());
-
-            // 网格布局
-            ui.horizontal_wrapped(|ui| {
-                for i in start_index..end_index {
-                    let file = &self.files[i];
-                    let is_selected = selected_file.as_ref().map_or(false, |p| p == &file.path);
-
-                    ui.add_space(4.0);
-
-                    // 创建图标和名称的容器
-                    let (rect, response) = ui.allocate_exact_size(
-                        egui::vec2(item_size, item_size),
-                        egui::Sense::click()
-                    );
-
-                    // 绘制选中背景
-                    if is_selected {
-                        let visuals = ui.visuals();
-                        ui.painter().rect_filled(rect, 4.0, visuals.widgets.inactive.bg_fill);
-                        ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, visuals.widgets.active.fg_stroke.color));
-                    }
+        if response.clicked() || response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let i = (((pos.y - bar_rect.top()) / slot_h).max(0.0) as usize).min(sections.len() - 1);
+                self.pending_jump = Some(sections[i].1);
+                ui.ctx().request_repaint();
+            }
+        }
+    }
 
-                    let painter = ui.painter();
-                    let center_y = rect.center().y;
-                    let center_x = rect.center().x;
-                    let font_id = if is_large {
-                        ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::new(12.0, egui::FontFamily::Proportional))
-                    } else {
-                        ui.style().text_styles.get(&egui::TextStyle::Small).cloned().unwrap_or_else(|| egui::FontId::new(10.0, egui::FontFamily::Proportional))
-                    };
-                    let color = ui.visuals().text_color();
-
-                    // 绘制图标
-                    if use_thumbnails && is_large && self.thumbnail_view.is_image_file(&file.path) {
-                        // 缩略图模式且为图片文件：优先显示缩略图
-                        let thumbnail_size = 50.0 * 0.8; // 40px
-                        let icon_y = rect.top() + (item_size * 0.15) + (thumbnail_size * 0.5);
-                        if !self.thumbnail_view.draw_thumbnail_if_available(ui, painter, center_x, icon_y, thumbnail_size, &file.path) {
-                            // 缩略图不可用，显示默认图标
-                            let icon_text = utils::get_file_icon(&file.path);
-                            painter.text(egui::pos2(center_x, icon_y), egui::Align2::CENTER_CENTER, icon_text, font_id.clone(), color);
+    fn show_icons_view(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>, is_large: bool, use_thumbnails: bool) -> bool {
+        let mut should_navigate = false;
+
+        let available_width = ui.available_width();
+        self.handle_icon_zoom_input(ui);
+        let zoom = self.icon_zoom;
+
+        // 根据缩略图/大图标/小图标设置参数，再按缩放比例连续调整
+        let (item_size, cell_width) = if use_thumbnails {
+            (110.0, 124.0)
+        } else if is_large {
+            (80.0, 100.0)
+        } else {
+            (50.0, 60.0)
+        };
+        let item_size = item_size * zoom;
+        let columns = (available_width / (cell_width * zoom)).max(1.0) as usize;
+        let font_id = if is_large {
+            ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::new(12.0, egui::FontFamily::Proportional))
+        } else {
+            ui.style().text_styles.get(&egui::TextStyle::Small).cloned().unwrap_or_else(|| egui::FontId::new(10.0, egui::FontFamily::Proportional))
+        };
+        // 文字随缩放比例适度变化，避免过小或过大
+        let font_id = egui::FontId::new(font_id.size * zoom.clamp(0.8, 1.5), font_id.family);
+        // 文件名显示两行时，格子加高一行文字
+        let name_line_count = if self.two_line_names { 2 } else { 1 };
+        let name_line_height = ui.fonts(|fonts| fonts.row_height(&font_id));
+        let item_height = item_size + (name_line_count - 1) as f32 * name_line_height;
+
+        // 按行虚拟化：只绘制可见行，缩略图也只为可见项请求加载
+        let total_rows = self.files.len().div_ceil(columns);
+        let row_height = item_height + ui.spacing().item_spacing.y;
+        let mut scroll_area = egui::ScrollArea::vertical();
+        if let Some(offset) = self.take_reveal_offset(columns, row_height).or_else(|| self.pending_scroll_offset.take()) {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let scroll_output = scroll_area.show_rows(ui, item_height, total_rows, |ui, row_range| {
+            for row in row_range {
+                let start_index = row * columns;
+                let end_index = (start_index + columns).min(self.files.len());
+
+                ui.horizontal(|ui| {
+                    for i in start_index..end_index {
+                        let file = &self.files[i];
+                        let is_selected = self.is_selected(file, selected_file);
+
+                        ui.add_space(4.0);
+
+                        // 创建图标和名称的容器
+                        let (rect, response) = ui.allocate_exact_size(
+                            egui::vec2(item_size, item_height),
+                            egui::Sense::click()
+                        );
+                        let response = Self::with_link_tooltip(response, file);
+
+                        // 绘制选中背景
+                        if is_selected {
+                            let visuals = ui.visuals();
+                            ui.painter().rect_filled(rect, 4.0, visuals.widgets.inactive.bg_fill);
+                            ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, visuals.widgets.active.fg_stroke.color));
                         }
-                    } else if file.is_dir {
-                        // 使用自定义文件夹图标，确保图标和文字的中轴线对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的64px图标 (51.2px)
-                            let icon_size = 64.0 * 0.8; // 51.2px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_folder_icon_scaled(painter, center_x, icon_y, icon_size);
+                        self.draw_keyboard_focus(ui, rect, file, selected_file);
+
+                        let painter = ui.painter();
+                        let center_y = rect.center().y;
+                        let center_x = rect.center().x;
+                        let color = ui.visuals().text_color();
+
+                        // 绘制图标
+                        if use_thumbnails && self.thumbnail_view.is_image_file(&file.path) {
+                            // 缩略图模式且为图片文件：优先显示缩略图
+                            let thumbnail_size = item_size * 0.7;
+                            let icon_y = rect.top() + (item_size * 0.15) + (thumbnail_size * 0.5);
+                            if !self.thumbnail_view.draw_thumbnail_if_available(ui, painter, center_x, icon_y, thumbnail_size, &file.path) {
+                                // 缩略图尚未生成：请求后台加载，先显示默认图标
+                                self.thumbnail_view.request_thumbnail_preload(&file.path);
+                                let icon_text = utils::get_file_icon(&file.path);
+                                painter.text(egui::pos2(center_x, icon_y), egui::Align2::CENTER_CENTER, icon_text, font_id.clone(), color);
+                                // 预加载线程不会主动通知界面，定时重绘以便缩略图就绪后显示
+                                ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+                            }
+                        } else if file.is_dir {
+                            // 使用自定义文件夹图标，确保图标和文字的中轴线对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的64px图标 (51.2px)
+                                let icon_size = 64.0 * 0.8 * zoom; // 51.2px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                if !self.draw_custom_folder_icon(ui, painter, &file.path, center_x, icon_y, icon_size) {
+                                    self.draw_folder_icon_scaled(painter, center_x, icon_y, icon_size);
+                                }
+                            } else {
+                                // 小图标模式：使用32px图标，确保对齐
+                                let icon_size = 32.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                if !self.draw_custom_folder_icon(ui, painter, &file.path, center_x, icon_y, icon_size) {
+                                    self.draw_folder_icon_scaled(painter, center_x, icon_y, icon_size);
+                                }
+                            }
+                        } else if self.is_exe_file(&file.path) {
+                            // 绘制EXE文件图标，与文件夹图标对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的50px图标 (40px)
+                                let icon_size = 50.0 * 0.8 * zoom; // 40px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_exe_icon_scaled(painter, center_x, icon_y, icon_size);
+                            } else {
+                                // 小图标模式：使用25px图标
+                                let icon_size = 25.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_exe_icon_scaled(painter, center_x, icon_y, icon_size);
+                            }
+                        } else if self.is_dll_file(&file.path) {
+                            // 绘制DLL文件图标，与文件夹图标对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的50px图标 (40px)
+                                let icon_size = 50.0 * 0.8 * zoom; // 40px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_dll_icon_scaled(painter, center_x, icon_y, icon_size);
+                            } else {
+                                // 小图标模式：使用25px图标
+                                let icon_size = 25.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_dll_icon_scaled(painter, center_x, icon_y, icon_size);
+                            }
+                        } else if self.is_txt_file(&file.path) {
+                            // 绘制TXT文件图标，与文件夹图标对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的50px图标 (40px)
+                                let icon_size = 50.0 * 0.8 * zoom; // 40px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_txt_icon_scaled(painter, center_x, icon_y, icon_size);
+                            } else {
+                                // 小图标模式：使用25px图标
+                                let icon_size = 25.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_txt_icon_scaled(painter, center_x, icon_y, icon_size);
+                            }
+                        } else if self.is_code_file(&file.path) {
+                            // 绘制代码文件图标，与文件夹图标对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的50px图标 (40px)
+                                let icon_size = 50.0 * 0.8 * zoom; // 40px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_code_icon_scaled(painter, center_x, icon_y, icon_size);
+                            } else {
+                                // 小图标模式：使用25px图标
+                                let icon_size = 25.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_code_icon_scaled(painter, center_x, icon_y, icon_size);
+                            }
+                        } else if self.is_unidentified_file(&file.path) {
+                            // 绘制无格式文件图标，与文件夹图标对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的50px图标 (40px)
+                                let icon_size = 50.0 * 0.8 * zoom; // 40px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_unidentified_icon_scaled(painter, center_x, icon_y, icon_size);
+                            } else {
+                                // 小图标模式：使用25px图标
+                                let icon_size = 25.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_unidentified_icon_scaled(painter, center_x, icon_y, icon_size);
+                            }
+                        } else if self.is_default_file(&file.path) {
+                            // 绘制默认文件图标，与文件夹图标对齐
+                            if is_large {
+                                // 大图标模式：使用80%大小的50px图标 (40px)
+                                let icon_size = 50.0 * 0.8 * zoom; // 40px
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_default_icon_scaled(painter, center_x, icon_y, icon_size);
+                            } else {
+                                // 小图标模式：使用25px图标
+                                let icon_size = 25.0 * zoom;
+                                let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
+                                self.draw_default_icon_scaled(painter, center_x, icon_y, icon_size);
+                            }
                         } else {
-                            // 小图标模式：使用32px图标，确保对齐
-                            let icon_size = 32.0;
+                            // 其他文件类型：显示emoji图标
+                            let icon_text = utils::get_file_icon(&file.path);
+                            let icon_size = zoom * if is_large { 32.0 * 0.8 } else { 16.0 };
                             let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_folder_icon(painter, center_x - (icon_size * 0.5), icon_y, super::icon_manager::IconSize::Small);
+                            painter.text(egui::pos2(center_x, icon_y), egui::Align2::CENTER_CENTER, icon_text, font_id.clone(), color);
                         }
-                    } else if self.is_exe_file(&file.path) {
-                        // 绘制EXE文件图标，与文件夹图标对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的50px图标 (40px)
-                            let icon_size = 50.0 * 0.8; // 40px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_exe_icon_scaled(painter, center_x, icon_y, icon_size);
+
+                        // 绘制文件名，确保与图标的中轴线对齐
+                        let icon_height = if use_thumbnails && self.thumbnail_view.is_image_file(&file.path) {
+                            item_size * 0.7
+                        } else if file.is_dir {
+                            zoom * if is_large { 64.0 * 0.8 } else { 32.0 }
+                        } else if self.is_exe_file(&file.path) {
+                            zoom * if is_large { 50.0 * 0.8 } else { 25.0 }
+                        } else if self.is_dll_file(&file.path) {
+                            zoom * if is_large { 50.0 * 0.8 } else { 25.0 }
+                        } else if self.is_txt_file(&file.path) {
+                            zoom * if is_large { 50.0 * 0.8 } else { 25.0 }
+                        } else if self.is_code_file(&file.path) {
+                            zoom * if is_large { 50.0 * 0.8 } else { 25.0 }
+                        } else if self.is_unidentified_file(&file.path) {
+                            zoom * if is_large { 50.0 * 0.8 } else { 25.0 }
+                        } else if self.is_default_file(&file.path) {
+                            zoom * if is_large { 50.0 * 0.8 } else { 25.0 }
                         } else {
-                            // 小图标模式：使用25px图标
-                            let icon_size = 25.0;
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_exe_icon_scaled(painter, center_x, icon_y, icon_size);
+                            zoom * if is_large { 32.0 * 0.8 } else { 16.0 }
+                        };
+                        if file.link_target.is_some() {
+                            let overlay_size = zoom.sqrt() * if is_large { 14.0 } else { 10.0 };
+                            let overlay_pos = egui::pos2(center_x - icon_height * 0.5, rect.top() + (item_size * 0.15) + icon_height - overlay_size * 0.5);
+                            Self::draw_link_overlay(painter, overlay_pos, overlay_size, file.is_broken_link);
                         }
-                    } else if self.is_dll_file(&file.path) {
-                        // 绘制DLL文件图标，与文件夹图标对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的50px图标 (40px)
-                            let icon_size = 50.0 * 0.8; // 40px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_dll_icon_scaled(painter, center_x, icon_y, icon_size);
-                        } else {
-                            // 小图标模式：使用25px图标
-                            let icon_size = 25.0;
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_dll_icon_scaled(painter, center_x, icon_y, icon_size);
+                        let name_y = rect.top() + (item_size * 0.15) + icon_height + 8.0; // 图标下方8px间距
+                        let name_pos = egui::pos2(center_x, name_y);
+
+                        // 按实际文字宽度折行或在中间省略，宽字符也不会超出格子
+                        let name_lines = text_elide::elide_lines(ui.ctx(), &file.name, &font_id, item_size - 4.0, name_line_count);
+                        let mut name_text_rect = egui::Rect::NOTHING;
+                        for (line_index, line) in name_lines.into_iter().enumerate() {
+                            let line_pos = name_pos + egui::vec2(0.0, line_index as f32 * name_line_height);
+                            name_text_rect = painter.text(line_pos, egui::Align2::CENTER_CENTER, line, font_id.clone(), color);
                         }
-                    } else if self.is_txt_file(&file.path) {
-                        // 绘制TXT文件图标，与文件夹图标对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的50px图标 (40px)
-                            let icon_size = 50.0 * 0.8; // 40px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_txt_icon_scaled(painter, center_x, icon_y, icon_size);
-                        } else {
-                            // 小图标模式：使用25px图标
-                            let icon_size = 25.0;
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_txt_icon_scaled(painter, center_x, icon_y, icon_size);
+                        tags::draw_tag_dots(painter, egui::pos2(name_text_rect.right() + 3.0, name_text_rect.center().y), &file.tags);
+
+                        // 处理点击事件
+                        if self.handle_item_click(ui, &response, i, current_path, selected_file) {
+                            should_navigate = true;
                         }
-                    } else if self.is_code_file(&file.path) {
-                        // 绘制代码文件图标，与文件夹图标对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的50px图标 (40px)
-                            let icon_size = 50.0 * 0.8; // 40px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_code_icon_scaled(painter, center_x, icon_y, icon_size);
-                        } else {
-                            // 小图标模式：使用25px图标
-                            let icon_size = 25.0;
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_code_icon_scaled(painter, center_x, icon_y, icon_size);
+                    }
+                });
+            }
+        });
+        self.nav_layout = NavLayout {
+            columns,
+            row_height,
+            viewport_height: scroll_output.inner_rect.height(),
+            scroll_offset: scroll_output.state.offset.y,
+        };
+
+        should_navigate
+    }
+
+    // 平铺视图：中等图标，右侧依次显示名称、类型和大小，按可用宽度自动换行排列
+    fn show_tiles_view(&mut self, ui: &mut egui::Ui, current_path: &mut PathBuf, selected_file: &mut Option<PathBuf>) -> bool {
+        const TILE_MIN_WIDTH: f32 = 220.0;
+        const TILE_HEIGHT: f32 = 56.0;
+        const TILE_GAP: f32 = 4.0;
+        const ICON_SIZE: f32 = 36.0;
+
+        let mut should_navigate = false;
+        let available_width = ui.available_width();
+        let columns = ((available_width + TILE_GAP) / (TILE_MIN_WIDTH + TILE_GAP)).floor().max(1.0) as usize;
+        // 平铺块拉伸以填满整行
+        let tile_width = ((available_width - TILE_GAP * (columns as f32 - 1.0)) / columns as f32).max(TILE_MIN_WIDTH.min(available_width));
+
+        let total_rows = self.files.len().div_ceil(columns);
+        let row_height = TILE_HEIGHT + ui.spacing().item_spacing.y;
+        let mut scroll_area = egui::ScrollArea::vertical();
+        if let Some(offset) = self.take_reveal_offset(columns, row_height).or_else(|| self.pending_scroll_offset.take()) {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let scroll_output = scroll_area.show_rows(ui, TILE_HEIGHT, total_rows, |ui, row_range| {
+            for row in row_range {
+                let start_index = row * columns;
+                let end_index = (start_index + columns).min(self.files.len());
+
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = TILE_GAP;
+                    for i in start_index..end_index {
+                        let file = &self.files[i];
+                        let is_selected = self.is_selected(file, selected_file);
+
+                        let (rect, response) = ui.allocate_exact_size(egui::vec2(tile_width, TILE_HEIGHT), egui::Sense::click());
+                        let response = Self::with_link_tooltip(response, file);
+
+                        if is_selected {
+                            let visuals = ui.visuals();
+                            ui.painter().rect_filled(rect, 4.0, visuals.widgets.inactive.bg_fill);
+                            ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(1.0, visuals.widgets.active.fg_stroke.color));
+                        } else if response.hovered() {
+                            ui.painter().rect_filled(rect, 4.0, ui.visuals().widgets.hovered.weak_bg_fill);
                         }
-                    } else if self.is_unidentified_file(&file.path) {
-                        // 绘制无格式文件图标，与文件夹图标对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的50px图标 (40px)
-                            let icon_size = 50.0 * 0.8; // 40px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_unidentified_icon_scaled(painter, center_x, icon_y, icon_size);
+                        self.draw_keyboard_focus(ui, rect, file, selected_file);
+
+                        let painter = ui.painter();
+                        let icon_center = egui::pos2(rect.left() + 8.0 + ICON_SIZE * 0.5, rect.center().y);
+                        if file.is_dir {
+                            if !self.draw_custom_folder_icon(ui, painter, &file.path, icon_center.x, icon_center.y, ICON_SIZE) {
+                                self.draw_folder_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE);
+                            }
+                        } else if self.is_exe_file(&file.path) {
+                            self.draw_exe_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE * 0.8);
+                        } else if self.is_dll_file(&file.path) {
+                            self.draw_dll_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE * 0.8);
+                        } else if self.is_txt_file(&file.path) {
+                            self.draw_txt_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE * 0.8);
+                        } else if self.is_code_file(&file.path) {
+                            self.draw_code_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE * 0.8);
+                        } else if self.is_unidentified_file(&file.path) {
+                            self.draw_unidentified_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE * 0.8);
+                        } else if self.is_default_file(&file.path) {
+                            self.draw_default_icon_scaled(painter, icon_center.x, icon_center.y, ICON_SIZE * 0.8);
                         } else {
-                            // 小图标模式：使用25px图标
-                            let icon_size = 25.0;
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_unidentified_icon_scaled(painter, center_x, icon_y, icon_size);
+                            painter.text(icon_center, egui::Align2::CENTER_CENTER, utils::get_file_icon(&file.path), egui::FontId::proportional(ICON_SIZE * 0.7), ui.visuals().text_color());
                         }
-                    } else if self.is_default_file(&file.path) {
-                        // 绘制默认文件图标，与文件夹图标对齐
-                        if is_large {
-                            // 大图标模式：使用80%大小的50px图标 (40px)
-                            let icon_size = 50.0 * 0.8; // 40px
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_default_icon_scaled(painter, center_x, icon_y, icon_size);
-                        } else {
-                            // 小图标模式：使用25px图标
-                            let icon_size = 25.0;
-                            let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                            self.draw_default_icon_scaled(painter, center_x, icon_y, icon_size);
+                        if file.link_target.is_some() {
+                            Self::draw_link_overlay(painter, egui::pos2(icon_center.x - ICON_SIZE * 0.5, icon_center.y + ICON_SIZE * 0.35), 12.0, file.is_broken_link);
                         }
-                    } else {
-                        // 其他文件类型：显示emoji图标
-                        let icon_text = utils::get_file_icon(&file.path);
-                        let icon_size = if is_large { 32.0 * 0.8 } else { 16.0 };
-                        let icon_y = rect.top() + (item_size * 0.15) + (icon_size * 0.5);
-                        painter.text(egui::pos2(center_x, icon_y), egui::Align2::CENTER_CENTER, icon_text, font_id.clone(), color);
-                    }
 
-                    // 绘制文件名，确保与图标的中轴线对齐
-                    let icon_height = if file.is_dir {
-                        if is_large { 64.0 * 0.8 } else { 32.0 }
-                    } else if self.is_exe_file(&file.path) {
-                        if is_large { 50.0 * 0.8 } else { 25.0 }
-                    } else if self.is_dll_file(&file.path) {
-                        if is_large { 50.0 * 0.8 } else { 25.0 }
-                    } else if self.is_txt_file(&file.path) {
-                        if is_large { 50.0 * 0.8 } else { 25.0 }
-                    } else if self.is_code_file(&file.path) {
-                        if is_large { 50.0 * 0.8 } else { 25.0 }
-                    } else if self.is_unidentified_file(&file.path) {
-                        if is_large { 50.0 * 0.8 } else { 25.0 }
-                    } else if self.is_default_file(&file.path) {
-                        if is_large { 50.0 * 0.8 } else { 25.0 }
-                    } else {
-                        if is_large { 32.0 * 0.8 } else { 16.0 }
-                    };
-                    let name_y = rect.top() + (item_size * 0.15) + icon_height + 8.0; // 图标下方8px间距
-                    let name_pos = egui::pos2(center_x, name_y);
-
-                    let display_name = if file.name.len() > 10 {
-                        // 安全地截断字符串，避免在UTF-8字符中间截断
-                        let mut char_count = 0;
-                        let mut byte_end = 0;
-                        for (i, _) in file.name.char_indices() {
-                            if char_count >= 7 {
-                                break;
-                            }
-                            char_count += 1;
-                            byte_end = i;
+                        // 名称、类型、大小三行文字
+                        let text_left = rect.left() + 16.0 + ICON_SIZE;
+                        let text_rect = egui::Rect::from_min_max(egui::pos2(text_left, rect.top()), egui::pos2(rect.right() - 4.0, rect.bottom()));
+                        let clipped = painter.with_clip_rect(text_rect);
+                        let body = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_default();
+                        let small = ui.style().text_styles.get(&egui::TextStyle::Small).cloned().unwrap_or_default();
+                        let weak = ui.visuals().weak_text_color();
+
+                        let file_type = if file.is_broken_link {
+                            "断开的链接".to_string()
+                        } else if file.is_dir {
+                            "文件夹".to_string()
+                        } else {
+                            file.path.extension().and_then(|e| e.to_str()).map(|e| format!("{} 文件", e.to_uppercase())).unwrap_or_else(|| "文件".to_string())
+                        };
+                        let size_text = if !file.is_dir {
+                            utils::get_file_size_str(file.size)
+                        } else if let Some(size) = file.folder_size {
+                            utils::get_file_size_str(size)
+                        } else {
+                            String::new()
+                        };
+
+                        let name = text_elide::elide_middle(ui.ctx(), &file.name, &body, text_rect.width());
+                        let name_rect = clipped.text(egui::pos2(text_left, rect.top() + 14.0), egui::Align2::LEFT_CENTER, name, body, ui.visuals().text_color());
+                        let tags_w = tags::draw_tag_dots(&clipped, egui::pos2(name_rect.right() + 4.0, name_rect.center().y), &file.tags);
+                        let companion_w = self.draw_companion_badge(&clipped, egui::pos2(name_rect.right() + 8.0 + tags_w, name_rect.center().y), file, weak);
+                        project_types::draw_badges(&clipped, egui::pos2(name_rect.right() + 8.0 + tags_w + companion_w, name_rect.center().y), &file.project_types);
+                        clipped.text(egui::pos2(text_left, rect.top() + 31.0), egui::Align2::LEFT_CENTER, file_type, small.clone(), weak);
+                        clipped.text(egui::pos2(text_left, rect.top() + 45.0), egui::Align2::LEFT_CENTER, size_text, small, weak);
+
+                        if self.handle_item_click(ui, &response, i, current_path, selected_file) {
+                            should_navigate = true;
                         }
-                        format!("{}...", &file.name[..byte_end])
-                    } else {
-                        file.name.clone()
-                    };
-                    painter.text(name_pos, egui::Align2::CENTER_CENTER, display_name, font_id, color);
-
-                    // 处理点击事件
-                    if response.double_clicked() && file.is_dir {
-                        *current_path = file.path.clone();
-                        *selected_file = None;
-                        should_navigate = true;
-                    } else if response.double_clicked() && !file.is_dir {
-                        self.mouse_strategy.handle_double_click(file.path.clone());
-                    } else if response.clicked() {
-                        *selected_file = Some(file.path.clone());
-                    }
-
-                    // 每行显示指定数量的项目后换行
-                    if ((i - start_index + 1) % columns == 0) {
-                        ui.end_row();
                     }
-                }
-            });
+                });
+            }
         });
+        self.nav_layout = NavLayout {
+            columns,
+            row_height,
+            viewport_height: scroll_output.inner_rect.height(),
+            scroll_offset: scroll_output.state.offset.y,
+        };
 
         should_navigate
     }
 
     // 检查文件是否为隐藏文件
-    fn is_hidden_file(&self, file_path: &PathBuf, file_name: &str) -> bool {
+    fn is_hidden_file(file_path: &Path, file_name: &str) -> bool {
         // Unix/Linux系统：以.开头的文件
         if file_name.starts_with('.') {
             return true;
@@ -604,9 +2470,11 @@ impl FileList {
         let mut should_refresh_content = false;  // 单击目录时刷新内容框
         let mut should_navigate_directory = false;  // 双击目录时目录框导航
         let mut should_open_file = false;  // 双击文件时打开文件
+        self.request_folder_icons();
 
         // 文件列表 - 不包含ScrollArea，由调用者提供
-        for file in &self.files {
+        for index in 0..self.files.len() {
+            let file = &self.files[index];
             let is_selected = selected_file.as_ref().map_or(false, |p| p == &file.path);
 
             let total_w = ui.available_width();
@@ -622,46 +2490,54 @@ impl FileList {
             let font_id = ui.style().text_styles.get(&egui::TextStyle::Body).cloned().unwrap_or_else(|| egui::FontId::default());
             let color = ui.visuals().text_color();
             let painter = ui.painter();
+            // 超出目录框宽度的名称在中间省略
+            let elide_name = |text_x: f32, name: &str| text_elide::elide_middle(ui.ctx(), name, &font_id, rect.right() - text_x - 4.0);
             if file.is_dir {
                 // 目录框也使用小图标 (16px)
-                self.draw_folder_icon_sized(painter, rect.left() + 6.0, rect.center().y, 16.0);
+                if !self.draw_custom_folder_icon(ui, painter, &file.path, rect.left() + 14.0, rect.center().y, 16.0) {
+                    self.draw_folder_icon_sized(painter, rect.left() + 6.0, rect.center().y, 16.0);
+                }
                 let text_x = rect.left() + 22.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else if self.is_exe_file(&file.path) {
                 // 目录框EXE文件使用小图标 (12px)
                 self.draw_exe_icon_sized(painter, rect.left() + 6.0, rect.center().y, 12.0);
                 let text_x = rect.left() + 20.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else if self.is_dll_file(&file.path) {
                 // 目录框DLL文件使用小图标 (12px)
                 self.draw_dll_icon_sized(painter, rect.left() + 6.0, rect.center().y, 12.0);
                 let text_x = rect.left() + 20.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else if self.is_txt_file(&file.path) {
                 // 目录框TXT文件使用小图标 (12px)
                 self.draw_txt_icon_sized(painter, rect.left() + 6.0, rect.center().y, 12.0);
                 let text_x = rect.left() + 20.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else if self.is_code_file(&file.path) {
                 // 目录框代码文件使用小图标 (12px)
                 self.draw_code_icon_sized(painter, rect.left() + 6.0, rect.center().y, 12.0);
                 let text_x = rect.left() + 20.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else if self.is_unidentified_file(&file.path) {
                 // 目录框无格式文件使用小图标 (12px)
                 self.draw_unidentified_icon_sized(painter, rect.left() + 6.0, rect.center().y, 12.0);
                 let text_x = rect.left() + 20.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else if self.is_default_file(&file.path) {
                 // 目录框默认文件使用小图标 (12px)
                 self.draw_default_icon_sized(painter, rect.left() + 6.0, rect.center().y, 12.0);
                 let text_x = rect.left() + 20.0;
-                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, file.name.clone(), font_id, color);
+                painter.with_clip_rect(rect).text(egui::pos2(text_x, rect.center().y), egui::Align2::LEFT_CENTER, elide_name(text_x, &file.name), font_id, color);
             } else {
-                painter.with_clip_rect(rect).text(rect.left_center() + egui::vec2(6.0, 0.0), egui::Align2::LEFT_CENTER, format!("{} {}", utils::get_file_icon(&file.path), file.name), font_id, color);
+                let name_text = format!("{} {}", utils::get_file_icon(&file.path), file.name);
+                painter.with_clip_rect(rect).text(rect.left_center() + egui::vec2(6.0, 0.0), egui::Align2::LEFT_CENTER, elide_name(rect.left() + 6.0, &name_text), font_id, color);
             }
 
             let button_response = response;
+            if file.is_dir && button_response.hovered() {
+                self.prefetch(&file.path);
+            }
 
             // 处理点击事件 - 目录框特殊逻辑
             if button_response.double_clicked() && file.is_dir {
@@ -680,6 +2556,9 @@ impl FileList {
                 // 单击文件：仅选择
                 *selected_file = Some(file.path.clone());
             }
+
+            // 从内容框拖来的项目可以放到目录框的文件夹上
+            self.handle_folder_drop_target(ui, &button_response, index);
         }
 
         (should_refresh_content, should_navigate_directory, should_open_file)
@@ -706,6 +2585,28 @@ impl FileList {
         }
     }
 
+    // 文件夹有自定义图标且已加载时绘制它并返回 true，否则由调用者绘制默认文件夹图标
+    fn draw_custom_folder_icon(&self, ui: &egui::Ui, painter: &egui::Painter, dir: &Path, center_x: f32, center_y: f32, size: f32) -> bool {
+        let Some(icon) = self.folder_icons.get(dir) else {
+            return false;
+        };
+        if self.thumbnail_view.draw_thumbnail_if_available(ui, painter, center_x, center_y, size, icon) {
+            return true;
+        }
+        // 预加载线程不会主动通知界面，定时重绘以便图标就绪后显示
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        false
+    }
+
+    // 为显示中的文件夹请求加载自定义图标（同一路径只请求一次）
+    fn request_folder_icons(&mut self) {
+        for file in self.files.iter().filter(|f| f.is_dir) {
+            if let Some(icon) = self.folder_icons.get(&file.path) {
+                self.thumbnail_view.request_thumbnail_preload(icon);
+            }
+        }
+    }
+
     fn draw_folder_icon_sized(&self, painter: &egui::Painter, x: f32, y: f32, size: f32) {
         // 使用32px纹理，但缩放到指定大小
         if let Some(texture) = self.icon_manager.get_folder_texture(super::icon_manager::IconSize::Small) {
@@ -1141,4 +3042,30 @@ impl FileList {
             );
         }
     }
+}
+
+// 距今的时间，如“5 分钟前”
+fn relative_time(time: SystemTime) -> String {
+    let seconds = SystemTime::now().duration_since(time).map(|d| d.as_secs()).unwrap_or(0);
+    match seconds {
+        0..=59 => "刚刚".to_string(),
+        60..=3599 => format!("{} 分钟前", seconds / 60),
+        3600..=86399 => format!("{} 小时前", seconds / 3600),
+        _ => format!("{} 天前", seconds / 86400),
+    }
+}
+
+// 指针位于可见区域上下边缘附近时滚动所在的ScrollArea，越靠近（或越过）边缘滚动越快
+fn auto_pan_near_edges(ui: &egui::Ui, viewport: egui::Rect, pointer: egui::Pos2) {
+    let top_depth = viewport.top() + AUTO_PAN_EDGE - pointer.y;
+    let bottom_depth = pointer.y - (viewport.bottom() - AUTO_PAN_EDGE);
+    let delta = if top_depth > 0.0 {
+        (top_depth / AUTO_PAN_EDGE).min(2.0) * AUTO_PAN_MAX_SPEED * 0.5
+    } else if bottom_depth > 0.0 {
+        -(bottom_depth / AUTO_PAN_EDGE).min(2.0) * AUTO_PAN_MAX_SPEED * 0.5
+    } else {
+        return;
+    };
+    ui.scroll_with_delta(egui::vec2(0.0, delta));
+    ui.ctx().request_repaint();
 }
\ No newline at end of file
@@ -0,0 +1,413 @@
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use crossbeam_channel::{self, Receiver};
+use super::{album_preview, image_formats, video_thumbnail};
+
+pub type MetadataRows = Vec<(String, String)>;
+
+// 一种文件格式的元数据读取器；支持新格式时实现此 trait 并在 MetadataRegistry::new 中注册，
+// 属性窗口、预览栏和筛选框都会自动使用
+pub trait MetadataProvider: Send + Sync {
+    fn supports(&self, path: &Path) -> bool;
+    fn read(&self, path: &Path) -> MetadataRows;
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// 图片的尺寸和 EXIF 字段
+struct ImageProvider;
+
+impl MetadataProvider for ImageProvider {
+    fn supports(&self, path: &Path) -> bool {
+        image_formats::is_image_file(path) || has_extension(path, &["tif", "tiff"])
+    }
+
+    fn read(&self, path: &Path) -> MetadataRows {
+        let mut rows = Vec::new();
+        if let Some((width, height)) = image_formats::image_dimensions(path) {
+            rows.push(("尺寸".to_string(), format!("{} x {} 像素", width, height)));
+        }
+        if let Some(exif) = read_exif(path) {
+            for field in exif.fields().filter(|f| f.ifd_num == exif::In::PRIMARY) {
+                rows.push((field.tag.to_string(), field.display_value().with_unit(&exif).to_string()));
+            }
+        }
+        rows
+    }
+}
+
+fn read_exif(path: &Path) -> Option<exif::Exif> {
+    let file = fs::File::open(path).ok()?;
+    exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()
+}
+
+// GPS 经纬度换算为十进制度数，南纬和西经为负
+fn gps_coordinate(exif: &exif::Exif, tag: exif::Tag, ref_tag: exif::Tag, negative: u8) -> Option<f64> {
+    let exif::Value::Rational(parts) = &exif.get_field(tag, exif::In::PRIMARY)?.value else {
+        return None;
+    };
+    if parts.len() < 3 {
+        return None;
+    }
+    let degrees = parts[0].to_f64() + parts[1].to_f64() / 60.0 + parts[2].to_f64() / 3600.0;
+    let reference = exif.get_field(ref_tag, exif::In::PRIMARY).and_then(|f| match &f.value {
+        exif::Value::Ascii(values) => values.first().and_then(|v| v.first().copied()),
+        _ => None,
+    });
+    Some(if reference == Some(negative) { -degrees } else { degrees })
+}
+
+// 照片的常用 EXIF 字段：相机、镜头、曝光参数、拍摄时间和 GPS 位置，图片预览的 EXIF 信息使用
+pub fn photo_exif(path: &Path) -> MetadataRows {
+    let Some(exif) = read_exif(path) else {
+        return Vec::new();
+    };
+    let text = |tag: exif::Tag| {
+        exif.get_field(tag, exif::In::PRIMARY)
+            .map(|f| f.display_value().with_unit(&exif).to_string().trim_matches('"').trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    // 很多相机的型号已包含厂商名
+    let camera = match (text(exif::Tag::Make), text(exif::Tag::Model)) {
+        (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    };
+    let fields = [
+        ("相机", camera),
+        ("镜头", text(exif::Tag::LensModel)),
+        ("拍摄时间", text(exif::Tag::DateTimeOriginal).or_else(|| text(exif::Tag::DateTime))),
+        ("曝光时间", text(exif::Tag::ExposureTime)),
+        ("光圈", text(exif::Tag::FNumber)),
+        ("ISO", text(exif::Tag::PhotographicSensitivity)),
+        ("焦距", text(exif::Tag::FocalLength)),
+        ("闪光灯", text(exif::Tag::Flash)),
+    ];
+    let mut rows: MetadataRows = fields.into_iter()
+        .filter_map(|(label, value)| value.map(|v| (label.to_string(), v)))
+        .collect();
+    let latitude = gps_coordinate(&exif, exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef, b'S');
+    let longitude = gps_coordinate(&exif, exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef, b'W');
+    if let (Some(lat), Some(lon)) = (latitude, longitude) {
+        rows.push(("GPS 位置".to_string(), format!("{:.6}, {:.6}", lat, lon)));
+        if let Some(altitude) = text(exif::Tag::GPSAltitude) {
+            rows.push(("海拔".to_string(), altitude));
+        }
+    }
+    rows
+}
+
+// 音频标签、时长和采样信息
+struct AudioProvider;
+
+impl MetadataProvider for AudioProvider {
+    fn supports(&self, path: &Path) -> bool {
+        album_preview::is_audio_file(path)
+    }
+
+    fn read(&self, path: &Path) -> MetadataRows {
+        album_preview::read_tag_rows(path)
+    }
+}
+
+// 视频的时长、分辨率和编码，依赖 ffprobe（随 ffmpeg 安装）
+struct VideoProvider;
+
+// 解析 ffprobe -of compact=p=0 的输出：每行一个流或容器，字段形如 key=value 并以 | 分隔
+fn parse_ffprobe(output: &str) -> MetadataRows {
+    let mut rows = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<(&str, &str)> = line.split('|').filter_map(|f| f.split_once('=')).collect();
+        let get = |key: &str| fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v).filter(|v| !v.is_empty() && *v != "N/A");
+        match get("codec_type") {
+            Some("video") => {
+                if let (Some(w), Some(h)) = (get("width"), get("height")) {
+                    rows.push(("分辨率".to_string(), format!("{} x {}", w, h)));
+                }
+                if let Some(codec) = get("codec_name") {
+                    rows.push(("视频编码".to_string(), codec.to_string()));
+                }
+            }
+            Some("audio") => {
+                if let Some(codec) = get("codec_name") {
+                    rows.push(("音频编码".to_string(), codec.to_string()));
+                }
+            }
+            Some(_) => {}
+            None => {
+                if let Some(duration) = get("duration").and_then(|d| d.parse::<f64>().ok()) {
+                    rows.insert(0, ("时长".to_string(), album_preview::format_duration(duration)));
+                }
+            }
+        }
+    }
+    rows
+}
+
+impl MetadataProvider for VideoProvider {
+    fn supports(&self, path: &Path) -> bool {
+        video_thumbnail::is_video_file(path)
+    }
+
+    fn read(&self, path: &Path) -> MetadataRows {
+        let output = Command::new("ffprobe")
+            .args(["-v", "error", "-show_entries", "format=duration:stream=codec_type,codec_name,width,height", "-of", "compact=p=0"])
+            .arg(path)
+            .stderr(Stdio::null())
+            .output();
+        match output {
+            Ok(output) if output.status.success() => parse_ffprobe(&String::from_utf8_lossy(&output.stdout)),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// PDF 的版本、页数和文档信息字典中的字面字符串；只读取文件开头部分
+struct PdfProvider;
+
+const PDF_READ_LIMIT: u64 = 8 * 1024 * 1024;
+
+fn pdf_literal(data: &str, key: &str) -> Option<String> {
+    let start = data.find(&format!("/{} (", key))? + key.len() + 3;
+    let end = data[start..].find(')')?;
+    let value = data[start..start + end].trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn parse_pdf(data: &[u8]) -> MetadataRows {
+    let text = String::from_utf8_lossy(data);
+    let mut rows = Vec::new();
+    if let Some(version) = text.strip_prefix("%PDF-").and_then(|rest| rest.get(..3)) {
+        rows.push(("PDF 版本".to_string(), version.to_string()));
+    }
+    // 页面对象为 /Type /Page，页面树为 /Type /Pages
+    let compact = text.replace("/Type /Page", "/Type/Page");
+    let pages = compact.match_indices("/Type/Page").filter(|(i, m)| !compact[i + m.len()..].starts_with('s')).count();
+    if pages > 0 {
+        rows.push(("页数".to_string(), pages.to_string()));
+    }
+    for (key, label) in [("Title", "标题"), ("Author", "作者"), ("Creator", "创建程序"), ("Producer", "生成程序")] {
+        if let Some(value) = pdf_literal(&text, key) {
+            rows.push((label.to_string(), value));
+        }
+    }
+    rows
+}
+
+impl MetadataProvider for PdfProvider {
+    fn supports(&self, path: &Path) -> bool {
+        has_extension(path, &["pdf"])
+    }
+
+    fn read(&self, path: &Path) -> MetadataRows {
+        let mut data = Vec::new();
+        match fs::File::open(path).and_then(|file| file.take(PDF_READ_LIMIT).read_to_end(&mut data)) {
+            Ok(_) => parse_pdf(&data),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+// ELF 可执行文件和库的文件头：位数、字节序、类型和架构；按文件头魔数识别，不看扩展名
+struct ElfProvider;
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+fn read_header(path: &Path, len: usize) -> Option<Vec<u8>> {
+    if !path.is_file() {
+        return None;
+    }
+    let mut header = vec![0u8; len];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    Some(header)
+}
+
+fn parse_elf(header: &[u8]) -> MetadataRows {
+    if header.len() < 20 || header[..4] != ELF_MAGIC {
+        return Vec::new();
+    }
+    let little_endian = header[5] == 1;
+    let half = |offset: usize| {
+        let bytes = [header[offset], header[offset + 1]];
+        if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) }
+    };
+    let class = match header[4] {
+        1 => "32 位",
+        2 => "64 位",
+        _ => "未知",
+    };
+    let kind = match half(16) {
+        1 => "可重定位文件",
+        2 => "可执行文件",
+        3 => "共享对象 / PIE 可执行文件",
+        4 => "核心转储",
+        _ => "未知",
+    };
+    let machine = match half(18) {
+        0x03 => "x86".to_string(),
+        0x3e => "x86-64".to_string(),
+        0x28 => "ARM".to_string(),
+        0xb7 => "AArch64".to_string(),
+        0xf3 => "RISC-V".to_string(),
+        0x08 => "MIPS".to_string(),
+        0x14 => "PowerPC".to_string(),
+        0x15 => "PowerPC64".to_string(),
+        other => format!("0x{:x}", other),
+    };
+    vec![
+        ("ELF 类型".to_string(), kind.to_string()),
+        ("架构".to_string(), machine),
+        ("位数".to_string(), class.to_string()),
+        ("字节序".to_string(), if little_endian { "小端" } else { "大端" }.to_string()),
+    ]
+}
+
+impl MetadataProvider for ElfProvider {
+    fn supports(&self, path: &Path) -> bool {
+        read_header(path, 4).is_some_and(|h| h[..] == ELF_MAGIC)
+    }
+
+    fn read(&self, path: &Path) -> MetadataRows {
+        read_header(path, 20).map(|h| parse_elf(&h)).unwrap_or_default()
+    }
+}
+
+// 所有元数据读取器的注册表
+pub struct MetadataRegistry {
+    providers: Vec<Box<dyn MetadataProvider>>,
+}
+
+impl MetadataRegistry {
+    pub fn new() -> Self {
+        Self {
+            providers: vec![
+                Box::new(ImageProvider),
+                Box::new(AudioProvider),
+                Box::new(VideoProvider),
+                Box::new(PdfProvider),
+                Box::new(ElfProvider),
+            ],
+        }
+    }
+
+    // 合并所有支持该文件的读取器的结果；文件夹没有元数据
+    pub fn read(&self, path: &Path) -> MetadataRows {
+        if path.is_dir() {
+            return Vec::new();
+        }
+        self.providers.iter()
+            .filter(|p| p.supports(path))
+            .flat_map(|p| p.read(path))
+            .collect()
+    }
+}
+
+// 全局注册表
+pub fn registry() -> &'static MetadataRegistry {
+    static REGISTRY: OnceLock<MetadataRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetadataRegistry::new)
+}
+
+// 后台读取一组文件的元数据（可能调用 ffprobe、ffmpeg），结果连同读取时的修改时间逐个返回；释放时取消
+pub struct MetadataWorker {
+    receiver: Receiver<(PathBuf, Option<SystemTime>, MetadataRows)>,
+    cancelled: Arc<AtomicBool>,
+    remaining: usize,
+}
+
+impl MetadataWorker {
+    pub fn start(files: Vec<(PathBuf, Option<SystemTime>)>) -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let remaining = files.len();
+        let cancel_flag = cancelled.clone();
+        std::thread::spawn(move || {
+            for (path, modified) in files {
+                if cancel_flag.load(Ordering::Relaxed) {
+                    return;
+                }
+                let rows = registry().read(&path);
+                if sender.send((path, modified, rows)).is_err() {
+                    return;
+                }
+            }
+        });
+        Self { receiver, cancelled, remaining }
+    }
+
+    // 取出已读取完成的结果（不阻塞）
+    pub fn poll(&mut self) -> Vec<(PathBuf, Option<SystemTime>, MetadataRows)> {
+        let results: Vec<_> = self.receiver.try_iter().collect();
+        self.remaining = self.remaining.saturating_sub(results.len());
+        results
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.remaining > 0
+    }
+}
+
+impl Drop for MetadataWorker {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// 筛选框中的 @字段=值 语法：返回字段名和值；没有 @ 前缀的文字按名称筛选
+pub fn parse_filter(filter: &str) -> Option<(&str, &str)> {
+    filter.strip_prefix('@')?
+        .split_once('=')
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .filter(|(key, _)| !key.is_empty())
+}
+
+// 在已读取的元数据中按字段名（不区分大小写）查找，筛选框的 @字段=值 语法使用
+pub fn find_value<'a>(rows: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    rows.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(parse_filter("@model=Canon"), Some(("model", "Canon")));
+        assert_eq!(parse_filter("@ 页数 = 12"), Some(("页数", "12")));
+        // 没有前缀时按名称筛选，文件名中的 = 不再被当作字段
+        assert_eq!(parse_filter("a=b.txt"), None);
+        assert_eq!(parse_filter("@=b"), None);
+    }
+
+    #[test]
+    fn test_pdf_elf_and_ffprobe_parsing() {
+        let pdf = b"%PDF-1.7\n1 0 obj << /Type /Pages /Count 2 >>\n2 0 obj << /Type /Page >>\n3 0 obj <</Type/Page>>\n4 0 obj << /Title (Report) /Author (Li) >>";
+        let rows = parse_pdf(pdf);
+        assert_eq!(find_value(&rows, "PDF 版本"), Some("1.7"));
+        assert_eq!(find_value(&rows, "页数"), Some("2"));
+        assert_eq!(find_value(&rows, "标题"), Some("Report"));
+
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+        elf.extend([0u8; 8]);
+        elf.extend([3, 0, 0x3e, 0]);
+        let rows = parse_elf(&elf);
+        assert_eq!(find_value(&rows, "架构"), Some("x86-64"));
+        assert_eq!(find_value(&rows, "位数"), Some("64 位"));
+        assert!(parse_elf(b"not an elf file at all").is_empty());
+
+        let rows = parse_ffprobe("codec_name=h264|codec_type=video|width=1920|height=1080\ncodec_name=aac|codec_type=audio|width=N/A|height=N/A\nduration=75.0\n");
+        assert_eq!(find_value(&rows, "时长"), Some("1:15"));
+        assert_eq!(find_value(&rows, "分辨率"), Some("1920 x 1080"));
+        assert_eq!(find_value(&rows, "音频编码"), Some("aac"));
+    }
+}
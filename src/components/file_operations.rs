@@ -1,13 +1,65 @@
 use std::path::{Path, PathBuf};
+use std::fmt;
 use std::fs;
 use std::io;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use crossbeam_channel::{self, Receiver, Sender};
 use eframe::egui;
+use super::storage_profile::{self, CopyTuning};
+use super::archive_create::{write_archive, CompressRequest};
+use super::archive_extract::extract_archive;
+use super::elevated::ElevatedTask;
 
-// 文件操作管理器
+// 文件操作管理器。复制、移动和删除在后台线程执行，立即返回 OperationHandle
 pub struct FileOperations {
     clipboard: Option<ClipboardData>,
     last_error: Option<String>,
+    job_queue: Arc<Mutex<Vec<CopyJob>>>, // 本次会话的复制任务队列，后台线程完成时更新状态
+    pending_duplicate: Option<CopyJob>,  // 等待用户决定（合并/跳过）的重复任务
+}
+
+// 复制任务队列中的一项
+#[derive(Clone, Debug)]
+pub struct CopyJob {
+    pub source_paths: Vec<PathBuf>,
+    pub target_dir: PathBuf,
+    pub status: JobStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobStatus {
+    Pending,   // 等待或正在执行
+    Completed, // 已完成
+    Failed,    // 执行失败或已取消
+}
+
+// 重复粘贴时的处理方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DuplicatePasteAction {
+    Merge,     // 合并：只复制目标中缺少或大小不同的文件
+    Skip,      // 跳过：不再复制
+    CopyAgain, // 仍然复制：生成带序号的新副本
+}
+
+impl CopyJob {
+    fn new(source_paths: Vec<PathBuf>, target_dir: &Path) -> Self {
+        Self {
+            source_paths,
+            target_dir: target_dir.to_path_buf(),
+            status: JobStatus::Pending,
+        }
+    }
+
+    // 来源集合（不计顺序）与目标目录都相同即视为同一任务
+    fn is_same_as(&self, source_paths: &[PathBuf], target_dir: &Path) -> bool {
+        self.target_dir == target_dir
+            && self.source_paths.len() == source_paths.len()
+            && source_paths.iter().all(|p| self.source_paths.contains(p))
+    }
 }
 
 #[derive(Clone)]
@@ -22,11 +74,352 @@ pub enum OperationType {
     Cut,
 }
 
-#[derive(Debug)]
-pub enum FileOperationResult {
-    Success,
-    Error(String),
-    NeedsConfirmation(String), // 用于删除操作的确认
+// 后台操作的种类
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OperationKind {
+    Copy,
+    Move,
+    Delete,
+    Trash,
+    Compress,
+    Extract,
+    Chmod,
+}
+
+impl OperationKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            OperationKind::Copy => "复制",
+            OperationKind::Move => "移动",
+            OperationKind::Delete => "删除",
+            OperationKind::Trash => "移到回收站",
+            OperationKind::Compress => "压缩",
+            OperationKind::Extract => "解压",
+            OperationKind::Chmod => "修改权限",
+        }
+    }
+}
+
+// 操作进度：开始时统计总数，之后每处理一个文件（或复制一段数据）发送一次
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OperationProgress {
+    pub current: Option<PathBuf>, // 最近处理的文件
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+impl OperationProgress {
+    // 完成的比例；有字节数时按字节计算，否则按文件数
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total > 0 {
+            (self.bytes_done as f64 / self.bytes_total as f64).min(1.0) as f32
+        } else if self.files_total > 0 {
+            (self.files_done as f32 / self.files_total as f32).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+// 处理失败的文件及原因
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub message: String,
+    pub permission_denied: bool, // 权限不足，可以用管理员权限重试
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OperationError {
+    Cancelled,             // 用户取消，已完成的部分保留
+    Failed(Vec<FileError>), // 部分文件失败，其余文件已处理
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationError::Cancelled => write!(f, "已取消"),
+            OperationError::Failed(errors) => {
+                write!(f, "{} 个项目失败", errors.len())?;
+                for error in errors.iter().take(3) {
+                    write!(f, "\n{}", error)?;
+                }
+                if errors.len() > 3 {
+                    write!(f, "\n...")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub type OperationResult = Result<(), OperationError>;
+
+// 取消令牌，可复制到其它线程
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// 计算速度时使用的时间窗口
+const RATE_WINDOW: Duration = Duration::from_secs(3);
+
+// 正在后台执行的操作：读取进度、取消，完成后取得结果
+pub struct OperationHandle {
+    kind: OperationKind,
+    progress: Receiver<OperationProgress>,
+    result: Receiver<(OperationResult, Option<ElevatedTask>)>,
+    cancel: CancelToken,
+    latest: OperationProgress,
+    started: Instant,
+    samples: VecDeque<(Instant, u64)>, // 最近几秒的 (时间, 已处理字节数)，用于计算速度
+    retry: Option<ElevatedTask>,       // 结束后，对权限不足的项目以管理员权限重试的方式
+}
+
+impl OperationHandle {
+    pub fn kind(&self) -> OperationKind {
+        self.kind
+    }
+
+    // 取出已发送的进度，返回最新的一次
+    pub fn progress(&mut self) -> &OperationProgress {
+        while let Ok(progress) = self.progress.try_recv() {
+            self.latest = progress;
+        }
+        let now = Instant::now();
+        if self.samples.back().is_none_or(|(time, _)| now - *time >= Duration::from_millis(200)) {
+            self.samples.push_back((now, self.latest.bytes_done));
+        }
+        while self.samples.len() > 2 && self.samples.front().is_some_and(|(time, _)| now - *time > RATE_WINDOW) {
+            self.samples.pop_front();
+        }
+        &self.latest
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // 最近几秒的平均速度（字节/秒），采样不足时返回 None
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        let seconds = (last.0 - first.0).as_secs_f64();
+        (seconds >= 0.5).then(|| last.1.saturating_sub(first.1) as f64 / seconds)
+    }
+
+    // 按当前速度估算的剩余时间
+    pub fn remaining(&self) -> Option<Duration> {
+        let rate = self.bytes_per_second().filter(|rate| *rate > 0.0)?;
+        let remaining = self.latest.bytes_total.saturating_sub(self.latest.bytes_done);
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    pub fn elevated_retry(&self) -> Option<&ElevatedTask> {
+        self.retry.as_ref()
+    }
+
+
+    // 操作结束后返回结果（只返回一次），仍在进行时返回 None
+    pub fn try_result(&mut self) -> Option<OperationResult> {
+        match self.result.try_recv() {
+            Ok((result, retry)) => {
+                self.retry = retry;
+                Some(result)
+            }
+            Err(crossbeam_channel::TryRecvError::Empty) => None,
+            Err(crossbeam_channel::TryRecvError::Disconnected) => Some(Err(OperationError::Failed(vec![FileError {
+                path: self.latest.current.clone().unwrap_or_default(),
+                message: "操作线程异常退出".to_string(),
+                permission_denied: false,
+            }]))),
+        }
+    }
+}
+
+// 后台线程中的操作状态，复制时由多个线程共享
+struct OperationContext {
+    progress: Mutex<OperationProgress>,
+    sender: Sender<OperationProgress>,
+    cancel: CancelToken,
+    errors: Mutex<Vec<FileError>>,
+    targets: Mutex<Vec<(PathBuf, PathBuf)>>, // (顶层项目, 目标路径)
+}
+
+impl OperationContext {
+    fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut OperationProgress)) {
+        let mut progress = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+        change(&mut progress);
+        // 界面来不及读取时丢弃中间的进度
+        let _ = self.sender.try_send(progress.clone());
+    }
+
+    fn add_bytes(&self, bytes: u64) {
+        self.update(|progress| progress.bytes_done += bytes);
+    }
+
+    fn file_done(&self, path: &Path) {
+        self.update(|progress| {
+            progress.files_done += 1;
+            progress.current = Some(path.to_path_buf());
+        });
+    }
+
+    fn error(&self, path: &Path, message: impl Into<String>) {
+        self.push_error(FileError { path: path.to_path_buf(), message: message.into(), permission_denied: false });
+    }
+
+    // 系统调用的错误；权限不足时可以用管理员权限重试
+    fn io_error(&self, path: &Path, error: io::Error) {
+        let permission_denied = error.kind() == io::ErrorKind::PermissionDenied;
+        self.push_error(FileError { path: path.to_path_buf(), message: error.to_string(), permission_denied });
+    }
+
+    fn push_error(&self, error: FileError) {
+        self.errors.lock().unwrap_or_else(|e| e.into_inner()).push(error);
+    }
+
+    // 记录顶层项目选定的目标路径，以管理员权限重试时写到同一位置
+    fn record_target(&self, source: &Path, target: PathBuf) {
+        self.targets.lock().unwrap_or_else(|e| e.into_inner()).push((source.to_path_buf(), target));
+    }
+
+    fn has_errors(&self) -> bool {
+        !self.errors.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+    }
+
+    fn error_count(&self) -> usize {
+        self.errors.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    // 操作结果，以及对权限不足的项目以管理员权限重试的方式
+    fn finish(self, kind: OperationKind, sources: &[PathBuf]) -> (OperationResult, Option<ElevatedTask>) {
+        let errors = self.errors.into_inner().unwrap_or_else(|e| e.into_inner());
+        if self.cancel.is_cancelled() {
+            return (Err(OperationError::Cancelled), None);
+        }
+        if errors.is_empty() {
+            return (Ok(()), None);
+        }
+        let denied = |source: &Path| errors.iter().any(|e| e.permission_denied && e.path.starts_with(source));
+        let targets = self.targets.into_inner().unwrap_or_else(|e| e.into_inner());
+        let denied_targets = || targets.iter().filter(|(source, _)| denied(source)).cloned().collect::<Vec<_>>();
+        let retry = match kind {
+            OperationKind::Copy => Some(ElevatedTask::Copy(denied_targets())),
+            OperationKind::Move => Some(ElevatedTask::Move(denied_targets())),
+            OperationKind::Delete => Some(ElevatedTask::Delete(sources.iter().filter(|p| denied(p)).cloned().collect())),
+            // 移到回收站不提供管理员重试：以 root 执行只能永久删除，会把可恢复的操作变成不可恢复
+            OperationKind::Trash => None,
+            _ => None,
+        };
+        (Err(OperationError::Failed(errors)), retry.filter(|task| !task.is_empty()))
+    }
+}
+
+// 统计文件夹中的文件数和总大小（不跟随符号链接）
+fn count_tree(path: &Path, cancel: &CancelToken) -> (usize, u64) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return (1, 0);
+    };
+    if !metadata.is_dir() {
+        return (1, metadata.len());
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return (0, 0);
+    };
+    let mut total = (0, 0);
+    for entry in entries.flatten() {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let (files, bytes) = count_tree(&entry.path(), cancel);
+        total = (total.0 + files, total.1 + bytes);
+    }
+    total
+}
+
+// 在后台线程中执行操作：先统计总量，再调用 work
+fn spawn_operation(kind: OperationKind, sources: Vec<PathBuf>, work: impl FnOnce(&OperationContext) + Send + 'static) -> OperationHandle {
+    let (progress_sender, progress_receiver) = crossbeam_channel::bounded(256);
+    let (result_sender, result_receiver) = crossbeam_channel::bounded(1);
+    let cancel = CancelToken::default();
+    let context = OperationContext {
+        progress: Mutex::new(OperationProgress::default()),
+        sender: progress_sender,
+        cancel: cancel.clone(),
+        errors: Mutex::new(Vec::new()),
+        targets: Mutex::new(Vec::new()),
+    };
+    std::thread::spawn(move || {
+        // 移动和移到回收站只是重命名，按项目计数
+        let (files, bytes) = if matches!(kind, OperationKind::Move | OperationKind::Trash) {
+            (sources.len(), 0)
+        } else {
+            sources.iter().fold((0, 0), |(files, bytes), source| {
+                let (f, b) = count_tree(source, &context.cancel);
+                (files + f, bytes + b)
+            })
+        };
+        context.update(|progress| {
+            progress.files_total = files;
+            progress.bytes_total = bytes;
+        });
+        work(&context);
+        let _ = result_sender.send(context.finish(kind, &sources));
+    });
+    OperationHandle {
+        kind,
+        progress: progress_receiver,
+        result: result_receiver,
+        cancel,
+        latest: OperationProgress::default(),
+        started: Instant::now(),
+        samples: VecDeque::new(),
+        retry: None,
+    }
+}
+
+// 在后台修改权限：apply 逐项修改，通过 report 报告每个项目的结果，cancelled 返回是否已取消
+pub fn spawn_chmod(
+    path: PathBuf,
+    apply: impl FnOnce(&mut dyn FnMut(&Path, Result<(), String>), &dyn Fn() -> bool) + Send + 'static,
+) -> OperationHandle {
+    spawn_operation(OperationKind::Chmod, vec![path], move |ctx| {
+        let mut report = |path: &Path, result: Result<(), String>| match result {
+            Ok(()) => ctx.file_done(path),
+            Err(message) => ctx.error(path, message),
+        };
+        apply(&mut report, &|| ctx.is_cancelled());
+    })
+}
+
+// 粘贴的开始结果：已在后台执行，或同一复制任务重复、需要用户选择合并还是跳过
+pub enum PasteStart {
+    Started(OperationHandle),
+    NeedsConfirmation(String),
 }
 
 impl FileOperations {
@@ -34,6 +427,8 @@ impl FileOperations {
         Self {
             clipboard: None,
             last_error: None,
+            job_queue: Arc::new(Mutex::new(Vec::new())),
+            pending_duplicate: None,
         }
     }
 
@@ -55,43 +450,68 @@ impl FileOperations {
         self.last_error = None;
     }
 
+    // 向剪贴板追加项目（如一并粘贴的配套文件），保持原来的复制或剪切方式
+    pub fn extend_clipboard(&mut self, paths: Vec<PathBuf>) {
+        if let Some(clipboard) = &mut self.clipboard {
+            clipboard.source_paths.extend(paths);
+        }
+    }
+
     // 粘贴剪贴板内容到目标目录
-    pub fn paste_from_clipboard(&mut self, target_dir: &Path) -> FileOperationResult {
-        if let Some(clipboard_data) = &self.clipboard.clone() {
-            match clipboard_data.operation {
-                OperationType::Copy => {
-                    for source_path in &clipboard_data.source_paths {
-                        if let Err(e) = self.copy_recursive(source_path, target_dir) {
-                            return FileOperationResult::Error(format!("复制失败: {}", e));
-                        }
-                    }
-                    FileOperationResult::Success
+    pub fn paste_from_clipboard(&mut self, target_dir: &Path) -> Result<PasteStart, String> {
+        let Some(clipboard_data) = self.clipboard.clone() else {
+            return Err("剪贴板为空".to_string());
+        };
+        // 不能把文件夹粘贴到其自身或子文件夹，否则复制时会不断复制刚创建的副本
+        if clipboard_data.source_paths.iter().any(|source_path| target_dir.starts_with(source_path)) {
+            return Err("不能将文件夹粘贴到其自身或子文件夹中".to_string());
+        }
+        match clipboard_data.operation {
+            OperationType::Copy => {
+                let job = CopyJob::new(clipboard_data.source_paths, target_dir);
+
+                // 同一来源已粘贴到同一目标：询问合并还是跳过，避免重复复制
+                if let Some(status) = self.find_duplicate_job(&job.source_paths, target_dir) {
+                    let state = if status == JobStatus::Pending { "正在进行" } else { "已完成" };
+                    let message = format!(
+                        "将 {} 个项目复制到 {} 的相同任务{}。\n合并：只复制目标中缺少或有变化的文件；跳过：不再复制。",
+                        job.source_paths.len(),
+                        target_dir.display(),
+                        state
+                    );
+                    self.pending_duplicate = Some(job);
+                    return Ok(PasteStart::NeedsConfirmation(message));
                 }
-                OperationType::Cut => {
-                    for source_path in &clipboard_data.source_paths {
-                        if let Err(e) = self.move_file(source_path, target_dir) {
-                            return FileOperationResult::Error(format!("移动失败: {}", e));
+
+                Ok(PasteStart::Started(self.run_copy_job(job, false)))
+            }
+            OperationType::Cut => {
+                // 剪切后清空剪贴板
+                self.clipboard = None;
+                let target_dir = target_dir.to_path_buf();
+                let sources = clipboard_data.source_paths;
+                Ok(PasteStart::Started(spawn_operation(OperationKind::Move, sources.clone(), move |ctx| {
+                    for source_path in &sources {
+                        if ctx.is_cancelled() {
+                            break;
                         }
+                        move_file(ctx, source_path, &target_dir);
                     }
-                    // 剪切后清空剪贴板
-                    self.clipboard = None;
-                    FileOperationResult::Success
-                }
+                })))
             }
-        } else {
-            FileOperationResult::Error("剪贴板为空".to_string())
         }
     }
 
-    // 重命名文件/文件夹
-    pub fn rename_file(&self, old_path: &Path, new_name: &str) -> FileOperationResult {
+    // 重命名文件/文件夹（立即完成，不在后台执行）
+    pub fn rename_file(&self, old_path: &Path, new_name: &str) -> Result<(), FileError> {
+        let error = |message: String, permission_denied: bool| FileError { path: old_path.to_path_buf(), message, permission_denied };
         if new_name.is_empty() {
-            return FileOperationResult::Error("文件名不能为空".to_string());
+            return Err(error("文件名不能为空".to_string(), false));
         }
 
         // 检查新文件名是否包含非法字符
         if self.contains_invalid_chars(new_name) {
-            return FileOperationResult::Error("文件名包含非法字符".to_string());
+            return Err(error("文件名包含非法字符".to_string(), false));
         }
 
         let new_path = old_path.parent()
@@ -100,43 +520,169 @@ impl FileOperations {
 
         // 检查目标文件是否已存在
         if new_path.exists() {
-            return FileOperationResult::Error("目标文件已存在".to_string());
+            return Err(error("目标文件已存在".to_string(), false));
         }
 
-        match fs::rename(old_path, &new_path) {
-            Ok(_) => FileOperationResult::Success,
-            Err(e) => FileOperationResult::Error(format!("重命名失败: {}", e)),
+        fs::rename(old_path, &new_path).map_err(|e| error(format!("重命名失败: {}", e), e.kind() == io::ErrorKind::PermissionDenied))
+    }
+
+    // 处理重复粘贴的用户选择；跳过时返回 None
+    pub fn resolve_duplicate_paste(&mut self, action: DuplicatePasteAction) -> Result<Option<OperationHandle>, String> {
+        let Some(job) = self.pending_duplicate.take() else {
+            return Err("没有待处理的重复任务".to_string());
+        };
+        Ok(match action {
+            DuplicatePasteAction::Skip => None,
+            DuplicatePasteAction::Merge => Some(self.run_copy_job(job, true)),
+            DuplicatePasteAction::CopyAgain => Some(self.run_copy_job(job, false)),
+        })
+    }
+
+    // 查找队列中尚未失败、且目标中仍有其结果的相同任务，返回其状态
+    fn find_duplicate_job(&self, source_paths: &[PathBuf], target_dir: &Path) -> Option<JobStatus> {
+        let copied_before = source_paths.iter().any(|p| {
+            p.file_name().is_some_and(|name| target_dir.join(name).exists())
+        });
+        if !copied_before {
+            return None;
         }
+        let queue = self.job_queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.iter().find(|job| job.status != JobStatus::Failed && job.is_same_as(source_paths, target_dir)).map(|job| job.status)
     }
 
-    // 删除文件/文件夹（需要确认）
-    pub fn delete_files(&self, paths: &[PathBuf]) -> FileOperationResult {
-        if paths.is_empty() {
-            return FileOperationResult::Error("没有选择要删除的文件".to_string());
+    // 在后台执行复制任务并记录到队列，merge 为 true 时合并到已有目标
+    fn run_copy_job(&mut self, mut job: CopyJob, merge: bool) -> OperationHandle {
+        job.status = JobStatus::Pending;
+        {
+            // 同一任务在队列中只保留一项
+            let mut queue = self.job_queue.lock().unwrap_or_else(|e| e.into_inner());
+            queue.retain(|j| !j.is_same_as(&job.source_paths, &job.target_dir));
+            queue.push(job.clone());
         }
+        let queue = self.job_queue.clone();
+        spawn_operation(OperationKind::Copy, job.source_paths.clone(), move |ctx| {
+            let tuning = storage_profile::tuning_for_operation(&job.source_paths, &job.target_dir);
+            for source_path in &job.source_paths {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                if merge {
+                    if let Some(name) = source_path.file_name() {
+                        ctx.record_target(source_path, job.target_dir.join(name));
+                    }
+                    merge_recursive(ctx, source_path, &job.target_dir, tuning);
+                } else if let Some(target) = copy_recursive(ctx, source_path, &job.target_dir, tuning) {
+                    ctx.record_target(source_path, target);
+                }
+            }
+            let status = if ctx.is_cancelled() || ctx.has_errors() { JobStatus::Failed } else { JobStatus::Completed };
+            let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(queued) = queue.iter_mut().find(|j| j.is_same_as(&job.source_paths, &job.target_dir)) {
+                queued.status = status;
+            }
+        })
+    }
 
-        let file_names: Vec<String> = paths.iter()
-            .filter_map(|p| p.file_name().and_then(|n| n.to_str()))
-            .map(|s| s.to_string())
-            .collect();
+    // 拖放文件到目标目录（复制或移动）
+    pub fn drop_files(&self, paths: &[PathBuf], target_dir: &Path, copy: bool) -> Result<OperationHandle, String> {
+        // 不能把文件夹放入其自身或子文件夹
+        if paths.iter().any(|source_path| target_dir.starts_with(source_path)) {
+            return Err("不能将文件夹放入其自身或子文件夹中".to_string());
+        }
+        let target_dir = target_dir.to_path_buf();
+        let sources = paths.to_vec();
+        if copy {
+            return Ok(spawn_operation(OperationKind::Copy, sources.clone(), move |ctx| {
+                let tuning = storage_profile::tuning_for_operation(&sources, &target_dir);
+                for source_path in &sources {
+                    if ctx.is_cancelled() {
+                        break;
+                    }
+                    if let Some(target) = copy_recursive(ctx, source_path, &target_dir, tuning) {
+                        ctx.record_target(source_path, target);
+                    }
+                }
+            }));
+        }
+        Ok(spawn_operation(OperationKind::Move, sources.clone(), move |ctx| {
+            for source_path in &sources {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                // 已在目标目录中的项目无需移动；目标中已有同名项目时跳过，不覆盖
+                if source_path.parent() == Some(target_dir.as_path()) {
+                    ctx.file_done(source_path);
+                    continue;
+                }
+                if let Some(name) = source_path.file_name() {
+                    if target_dir.join(name).symlink_metadata().is_ok() {
+                        ctx.error(source_path, format!("目标位置已存在: {}", name.to_string_lossy()));
+                        continue;
+                    }
+                }
+                move_file(ctx, source_path, &target_dir);
+            }
+        }))
+    }
 
-        let message = if paths.len() == 1 {
-            format!("确定要删除 \"{}\" 吗？", file_names[0])
-        } else {
-            format!("确定要删除这 {} 个项目吗？", paths.len())
-        };
+    // 删除文件/文件夹（调用前应已确认）
+    pub fn delete_files(&self, paths: &[PathBuf]) -> OperationHandle {
+        let sources = paths.to_vec();
+        spawn_operation(OperationKind::Delete, sources.clone(), move |ctx| {
+            for path in &sources {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                remove_recursive(ctx, path);
+            }
+        })
+    }
 
-        FileOperationResult::NeedsConfirmation(message)
+    // 把文件/文件夹移到回收站（调用前应已确认）
+    pub fn trash_files(&self, paths: &[PathBuf]) -> OperationHandle {
+        let sources = paths.to_vec();
+        spawn_operation(OperationKind::Trash, sources.clone(), move |ctx| {
+            for path in &sources {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                match super::trash::move_to_trash(path) {
+                    Ok(()) => ctx.file_done(path),
+                    Err(e) => ctx.io_error(path, e),
+                }
+            }
+        })
     }
 
-    // 执行实际的删除操作
-    pub fn confirm_delete(&self, paths: &[PathBuf]) -> FileOperationResult {
-        for path in paths {
-            if let Err(e) = self.remove_recursive(path) {
-                return FileOperationResult::Error(format!("删除失败: {}", e));
+    // 把文件/文件夹压缩为新的压缩包
+    pub fn compress_files(&self, request: CompressRequest) -> OperationHandle {
+        spawn_operation(OperationKind::Compress, request.sources.clone(), move |ctx| {
+            let result = write_archive(&request, &mut |bytes| ctx.add_bytes(bytes), &mut |path| ctx.file_done(path), &|| ctx.is_cancelled());
+            if let Err(e) = result {
+                if !ctx.is_cancelled() {
+                    ctx.io_error(&request.archive, e);
+                }
             }
-        }
-        FileOperationResult::Success
+        })
+    }
+
+    // 把压缩包解压到 dest；按读取的压缩包字节数显示进度
+    pub fn extract_archive(&self, archive: PathBuf, dest: PathBuf) -> OperationHandle {
+        spawn_operation(OperationKind::Extract, vec![archive.clone()], move |ctx| {
+            let result = extract_archive(
+                &archive,
+                &dest,
+                &mut |bytes| ctx.add_bytes(bytes),
+                &mut |path| ctx.update(|progress| progress.current = Some(path.to_path_buf())),
+                &mut |path, message| ctx.error(path, message),
+                &|| ctx.is_cancelled(),
+            );
+            match result {
+                Ok(()) => ctx.file_done(&archive),
+                Err(e) if !ctx.is_cancelled() => ctx.io_error(&archive, e),
+                Err(_) => {}
+            }
+        })
     }
 
     // 显示重命名对话框
@@ -179,41 +725,16 @@ impl FileOperations {
         result
     }
 
-    // 显示删除确认对话框
-    pub fn show_delete_confirmation_dialog(&mut self, ctx: &egui::Context, message: &str) -> Option<bool> {
-        let mut result = None;
-        let mut open = true;
-
-        egui::Window::new("确认删除")
-            .collapsible(false)
-            .resizable(false)
-            .open(&mut open)
-            .show(ctx, |ui| {
-                ui.label(message);
-                ui.separator();
-
-                ui.horizontal(|ui| {
-                    if ui.button("确定").clicked() {
-                        result = Some(true);
-                    }
-                    if ui.button("取消").clicked() {
-                        result = Some(false);
-                    }
-                });
-            });
-
-        if !open {
-            result = Some(false);
-        }
-
-        result
-    }
-
     // 获取最后一个错误
     pub fn get_last_error(&self) -> Option<String> {
         self.last_error.clone()
     }
 
+    // 剪贴板内容，用于在粘贴前检查是否需要确认
+    pub fn clipboard(&self) -> Option<&ClipboardData> {
+        self.clipboard.as_ref()
+    }
+
     // 检查剪贴板是否有内容
     pub fn has_clipboard_content(&self) -> bool {
         self.clipboard.is_some()
@@ -233,138 +754,431 @@ impl FileOperations {
         }
     }
 
-    // 私有辅助方法
+    // 检查文件名是否包含非法字符
+    fn contains_invalid_chars(&self, name: &str) -> bool {
+        #[cfg(target_os = "windows")]
+        {
+            let invalid_chars = ['<', '>', ':', '"', '|', '?', '*'];
+            name.chars().any(|c| invalid_chars.contains(&c)) || name.contains('/') || name.contains('\\')
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            name.contains('/')
+        }
+    }
+}
+
+// 以下在后台线程中执行：出错的文件记录到 ctx 后继续处理其余文件，取消后尽快返回
+
+// 递归复制文件/文件夹，返回选定的目标路径（即使随后复制失败）
+fn copy_recursive(ctx: &OperationContext, source: &Path, target_dir: &Path, tuning: CopyTuning) -> Option<PathBuf> {
+    if ctx.is_cancelled() {
+        return None;
+    }
+    let Some(file_name) = source.file_name() else {
+        ctx.error(source, "无效的源路径");
+        return None;
+    };
+
+    let target_path = target_dir.join(file_name);
 
-    // 递归复制文件/文件夹
-    fn copy_recursive(&self, source: &Path, target_dir: &Path) -> io::Result<()> {
-        let file_name = source.file_name().ok_or_else(|| {
-            io::Error::new(io::ErrorKind::InvalidInput, "无效的源路径")
-        })?;
+    // 检查源是否存在
+    if !source.exists() {
+        ctx.error(source, "源文件不存在");
+        return None;
+    }
 
-        let target_path = target_dir.join(file_name);
+    // 如果目标已存在，生成新的文件名
+    let final_target_path = if target_path.exists() {
+        match generate_unique_name(&target_path) {
+            Ok(path) => path,
+            Err(e) => {
+                ctx.io_error(source, e);
+                return None;
+            }
+        }
+    } else {
+        target_path
+    };
+    copy_to(ctx, source, &final_target_path, tuning);
+    Some(final_target_path)
+}
 
-        // 检查源是否存在
-        if !source.exists() {
-            return Err(io::Error::new(io::ErrorKind::NotFound, "源文件不存在"));
+// 把文件/文件夹复制为 final_target_path
+fn copy_to(ctx: &OperationContext, source: &Path, final_target_path: &Path, tuning: CopyTuning) {
+    if source.is_dir() {
+        // 创建目标目录
+        if let Err(e) = fs::create_dir_all(final_target_path) {
+            ctx.io_error(source, e);
+            return;
         }
 
-        // 如果目标已存在，生成新的文件名
-        let final_target_path = if target_path.exists() {
-            self.generate_unique_name(&target_path)?
-        } else {
-            target_path
+        // 复制目录内容：子目录逐个递归，文件按设备类型并行复制
+        let entries = match fs::read_dir(source) {
+            Ok(entries) => entries,
+            Err(e) => {
+                ctx.io_error(source, e);
+                return;
+            }
         };
+        let mut child_files = Vec::new();
+        for entry in entries {
+            match entry {
+                Ok(entry) => {
+                    let child_source = entry.path();
+                    if child_source.is_dir() {
+                        copy_recursive(ctx, &child_source, final_target_path, tuning);
+                    } else {
+                        child_files.push(child_source);
+                    }
+                }
+                Err(e) => ctx.io_error(source, e),
+            }
+        }
 
-        if source.is_dir() {
-            // 创建目标目录
-            fs::create_dir_all(&final_target_path)?;
-
-            // 复制目录内容
-            for entry in fs::read_dir(source)? {
-                let entry = entry?;
-                let child_source = entry.path();
-                self.copy_recursive(&child_source, &final_target_path)?;
+        // 启用 io_uring 引擎时批量复制文件，失败（如内核不支持）时回退到标准引擎
+        #[cfg(all(feature = "io-uring", target_os = "linux"))]
+        {
+            let pairs: Vec<(PathBuf, PathBuf)> = child_files
+                .iter()
+                .filter_map(|f| f.file_name().map(|name| (f.clone(), final_target_path.join(name))))
+                .collect();
+            // 每批完成后报告进度；出错时只对尚未完成的文件回退
+            let mut copied = std::collections::HashSet::new();
+            let result = super::uring_copy::copy_files(&pairs, tuning.buffer_size, &|| ctx.is_cancelled(), &mut |file| {
+                ctx.add_bytes(fs::metadata(file).map(|m| m.len()).unwrap_or(0));
+                ctx.file_done(file);
+                copied.insert(file.to_path_buf());
+            });
+            if result.is_ok() || ctx.is_cancelled() {
+                return;
             }
-        } else {
-            // 复制文件，使用缓冲方式避免文件被占用的问题
-            self.copy_file_with_buffer(source, &final_target_path)?;
+            child_files.retain(|file| !copied.contains(file));
         }
 
-        Ok(())
+        for_each_parallel(&child_files, tuning, |child| {
+            copy_recursive(ctx, child, final_target_path, tuning);
+        });
+    } else {
+        // 复制文件，使用缓冲方式避免文件被占用的问题
+        match copy_file_with_buffer(ctx, source, final_target_path, tuning.buffer_size) {
+            Ok(()) => ctx.file_done(source),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => ctx.io_error(source, e),
+        }
     }
+}
 
-    // 带缓冲的文件复制，避免文件被占用的问题
-    fn copy_file_with_buffer(&self, source: &Path, target: &Path) -> io::Result<()> {
-        use std::fs::File;
-        use std::io::{Read, Write, BufReader, BufWriter};
-
-        let mut source_file = BufReader::new(File::open(source)?);
-        let mut target_file = BufWriter::new(File::create(target)?);
+// 合并复制：目标中已存在且大小相同的文件跳过，其余复制（覆盖）
+fn merge_recursive(ctx: &OperationContext, source: &Path, target_dir: &Path, tuning: CopyTuning) {
+    if ctx.is_cancelled() {
+        return;
+    }
+    let Some(file_name) = source.file_name() else {
+        ctx.error(source, "无效的源路径");
+        return;
+    };
+    let target_path = target_dir.join(file_name);
 
-        let mut buffer = [0; 8192];
-        loop {
-            let bytes_read = source_file.read(&mut buffer)?;
-            if bytes_read == 0 {
-                break;
+    if source.is_dir() {
+        if let Err(e) = fs::create_dir_all(&target_path) {
+            ctx.io_error(source, e);
+            return;
+        }
+        match fs::read_dir(source) {
+            Ok(entries) => {
+                for entry in entries {
+                    match entry {
+                        Ok(entry) => merge_recursive(ctx, &entry.path(), &target_path, tuning),
+                        Err(e) => ctx.io_error(source, e),
+                    }
+                }
             }
-            target_file.write_all(&buffer[..bytes_read])?;
+            Err(e) => ctx.io_error(source, e),
         }
+    } else {
+        let unchanged = match (fs::metadata(source), fs::metadata(&target_path)) {
+            (Ok(src), Ok(dst)) => dst.is_file() && src.len() == dst.len(),
+            _ => false,
+        };
+        if unchanged {
+            ctx.add_bytes(fs::metadata(source).map(|m| m.len()).unwrap_or(0));
+            ctx.file_done(source);
+            return;
+        }
+        match copy_file_with_buffer(ctx, source, &target_path, tuning.buffer_size) {
+            Ok(()) => ctx.file_done(source),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => ctx.io_error(source, e),
+        }
+    }
+}
 
-        target_file.flush()?;
-        Ok(())
+// 按调优参数处理同一目录下的多个文件，parallelism 为1时顺序处理
+fn for_each_parallel(files: &[PathBuf], tuning: CopyTuning, op: impl Fn(&Path) + Sync) {
+    if tuning.parallelism <= 1 || files.len() < 2 {
+        files.iter().for_each(|f| op(f));
+        return;
     }
+    let chunk_size = files.len().div_ceil(tuning.parallelism);
+    std::thread::scope(|scope| {
+        for chunk in files.chunks(chunk_size) {
+            scope.spawn(|| chunk.iter().for_each(|f| op(f)));
+        }
+    });
+}
+
+// 带缓冲的文件复制，缓冲区大小由设备类型决定；取消时删除未复制完的目标文件
+fn copy_file_with_buffer(ctx: &OperationContext, source: &Path, target: &Path, buffer_size: usize) -> io::Result<()> {
+    use std::fs::File;
+    use std::io::{Read, Write, BufReader, BufWriter};
 
-    // 生成唯一的文件名
-    fn generate_unique_name(&self, path: &Path) -> io::Result<PathBuf> {
-        if !path.exists() {
-            return Ok(path.to_path_buf());
+    let mut source_file = BufReader::with_capacity(buffer_size, File::open(source)?);
+    let mut target_file = BufWriter::with_capacity(buffer_size, File::create(target)?);
+
+    let mut buffer = vec![0; buffer_size];
+    loop {
+        if ctx.is_cancelled() {
+            drop(target_file);
+            let _ = fs::remove_file(target);
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "已取消"));
+        }
+        let bytes_read = source_file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
         }
+        target_file.write_all(&buffer[..bytes_read])?;
+        ctx.add_bytes(bytes_read as u64);
+    }
 
-        let parent = path.parent().unwrap_or_else(|| Path::new("."));
-        let file_stem = path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("file");
-        let extension = path.extension()
-            .and_then(|s| s.to_str());
+    target_file.flush()?;
+    Ok(())
+}
 
-        let mut counter = 1;
-        loop {
-            let new_name = if let Some(ext) = extension {
-                format!("{}_{}.{}", file_stem, counter, ext)
-            } else {
-                format!("{}_{}", file_stem, counter)
-            };
+// 生成唯一的文件名
+fn generate_unique_name(path: &Path) -> io::Result<PathBuf> {
+    if !path.exists() {
+        return Ok(path.to_path_buf());
+    }
 
-            let new_path = parent.join(new_name);
-            if !new_path.exists() {
-                return Ok(new_path);
-            }
-            counter += 1;
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_stem = path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("file");
+    let extension = path.extension()
+        .and_then(|s| s.to_str());
 
-            // 防止无限循环
-            if counter > 9999 {
-                return Err(io::Error::new(io::ErrorKind::Other, "无法生成唯一文件名"));
-            }
+    let mut counter = 1;
+    loop {
+        let new_name = if let Some(ext) = extension {
+            format!("{}_{}.{}", file_stem, counter, ext)
+        } else {
+            format!("{}_{}", file_stem, counter)
+        };
+
+        let new_path = parent.join(new_name);
+        if !new_path.exists() {
+            return Ok(new_path);
+        }
+        counter += 1;
+
+        // 防止无限循环
+        if counter > 9999 {
+            return Err(io::Error::new(io::ErrorKind::Other, "无法生成唯一文件名"));
         }
     }
+}
 
-    // 移动文件/文件夹
-    fn move_file(&self, source: &Path, target_dir: &Path) -> io::Result<()> {
-        let file_name = source.file_name().ok_or_else(|| {
-            io::Error::new(io::ErrorKind::InvalidInput, "无效的源路径")
-        })?;
+// 移动文件/文件夹
+fn move_file(ctx: &OperationContext, source: &Path, target_dir: &Path) {
+    let Some(file_name) = source.file_name() else {
+        ctx.error(source, "无效的源路径");
+        return;
+    };
 
-        let target_path = target_dir.join(file_name);
-        fs::rename(source, &target_path)?;
-        Ok(())
+    let target_path = target_dir.join(file_name);
+    ctx.record_target(source, target_path.clone());
+    match fs::rename(source, &target_path) {
+        Ok(()) => ctx.file_done(source),
+        // 跨文件系统（如移到其他驱动器）不能重命名，改为复制后删除
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => move_across_devices(ctx, source, target_dir, &target_path),
+        Err(e) => ctx.io_error(source, e),
     }
+}
 
-    // 递归删除文件/文件夹
-    fn remove_recursive(&self, path: &Path) -> io::Result<()> {
-        if path.is_dir() {
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
-                let child_path = entry.path();
-                self.remove_recursive(&child_path)?;
+// 复制到目标后删除源；出错或取消时删除已复制的部分，保留源
+fn move_across_devices(ctx: &OperationContext, source: &Path, target_dir: &Path, target_path: &Path) {
+    if target_path.symlink_metadata().is_ok() {
+        ctx.error(source, format!("目标位置已存在: {}", target_path.display()));
+        return;
+    }
+    // 移动按项目计数，复制时按文件报告进度，补上此项目中的文件数和大小
+    let (files, bytes) = count_tree(source, &ctx.cancel);
+    ctx.update(|progress| {
+        progress.files_total = (progress.files_total + files).saturating_sub(1);
+        progress.bytes_total += bytes;
+    });
+
+    let errors_before = ctx.error_count();
+    let source_metadata = match fs::symlink_metadata(source) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            ctx.io_error(source, e);
+            return;
+        }
+    };
+    // 符号链接按链接本身移动，不复制其指向的内容
+    #[cfg(unix)]
+    if source_metadata.file_type().is_symlink() {
+        match fs::read_link(source).and_then(|link| std::os::unix::fs::symlink(link, target_path)) {
+            Ok(()) => ctx.file_done(source),
+            Err(e) => {
+                ctx.io_error(source, e);
+                return;
             }
-            fs::remove_dir(path)?;
-        } else {
-            fs::remove_file(path)?;
         }
-        Ok(())
+    }
+    if !source_metadata.file_type().is_symlink() {
+        let tuning = storage_profile::tuning_for_operation(&[source.to_path_buf()], target_dir);
+        copy_to(ctx, source, target_path, tuning);
     }
 
-    // 检查文件名是否包含非法字符
-    fn contains_invalid_chars(&self, name: &str) -> bool {
-        #[cfg(target_os = "windows")]
-        {
-            let invalid_chars = ['<', '>', ':', '"', '|', '?', '*'];
-            name.chars().any(|c| invalid_chars.contains(&c)) || name.contains('/') || name.contains('\\')
+    if ctx.is_cancelled() || ctx.error_count() > errors_before {
+        let _ = if target_path.symlink_metadata().is_ok_and(|m| m.is_dir()) { fs::remove_dir_all(target_path) } else { fs::remove_file(target_path) };
+        return;
+    }
+    let removed = if source_metadata.is_dir() { fs::remove_dir_all(source) } else { fs::remove_file(source) };
+    if let Err(e) = removed {
+        ctx.io_error(source, e);
+    }
+}
+
+// 递归删除文件/文件夹；不进入指向文件夹的符号链接，只删除链接本身
+fn remove_recursive(ctx: &OperationContext, path: &Path) {
+    if ctx.is_cancelled() {
+        return;
+    }
+    let is_dir = fs::symlink_metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+    if !is_dir {
+        let size = fs::symlink_metadata(path).map(|m| m.len()).unwrap_or(0);
+        match fs::remove_file(path) {
+            Ok(()) => {
+                ctx.add_bytes(size);
+                ctx.file_done(path);
+            }
+            Err(e) => ctx.io_error(path, e),
         }
+        return;
+    }
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries {
+                match entry {
+                    Ok(entry) => remove_recursive(ctx, &entry.path()),
+                    Err(e) => ctx.io_error(path, e),
+                }
+            }
+        }
+        Err(e) => {
+            ctx.io_error(path, e);
+            return;
+        }
+    }
+    if ctx.is_cancelled() {
+        return;
+    }
+    if let Err(e) = fs::remove_dir(path) {
+        ctx.io_error(path, e);
+    }
+}
 
-        #[cfg(not(target_os = "windows"))]
-        {
-            name.contains('/')
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    // 等待后台操作结束
+    fn finish(handle: &mut OperationHandle) -> OperationResult {
+        loop {
+            if let Some(result) = handle.try_result() {
+                return result;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
         }
     }
+
+    #[test]
+    fn test_duplicate_paste_merge() {
+        let root = TestDir::new("dup");
+        let source = root.join("src_dir");
+        let target = root.join("dst");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(source.join("a.txt"), "a").unwrap();
+
+        let mut ops = FileOperations::new();
+        ops.copy_to_clipboard(vec![source.clone()]);
+        let Ok(PasteStart::Started(mut handle)) = ops.paste_from_clipboard(&target) else {
+            panic!("第一次粘贴应直接开始");
+        };
+        assert_eq!(finish(&mut handle), Ok(()));
+        assert_eq!(handle.progress().files_done, 1);
+
+        // 第二次粘贴相同来源到相同目标：需要用户确认
+        fs::write(source.join("b.txt"), "b").unwrap();
+        assert!(matches!(ops.paste_from_clipboard(&target), Ok(PasteStart::NeedsConfirmation(_))));
+
+        // 合并只补齐缺少的文件，不产生 src_dir_1 副本
+        let mut handle = ops.resolve_duplicate_paste(DuplicatePasteAction::Merge).unwrap().unwrap();
+        assert_eq!(finish(&mut handle), Ok(()));
+        assert!(target.join("src_dir").join("b.txt").exists());
+        assert!(!target.join("src_dir_1").exists());
+        assert_eq!(ops.job_queue.lock().unwrap().len(), 1);
+        assert_eq!(ops.job_queue.lock().unwrap()[0].status, JobStatus::Completed);
+
+        // 删除时报告进度，不存在的文件作为单个文件的错误返回
+        let mut handle = ops.delete_files(&[target.join("src_dir"), target.join("missing")]);
+        let Err(OperationError::Failed(errors)) = finish(&mut handle) else {
+            panic!("应报告不存在的文件");
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, target.join("missing"));
+        assert_eq!(handle.progress().files_done, 2);
+        assert!(!target.join("src_dir").exists());
+
+        // 取消后不再处理
+        fs::write(source.join("c.txt"), "c").unwrap();
+        let mut handle = ops.drop_files(&[source], &target, true).unwrap();
+        handle.cancel_token().cancel();
+        assert_eq!(finish(&mut handle), Err(OperationError::Cancelled));
+
+        // 拖放移动不覆盖目标中的同名文件
+        fs::write(root.join("x.txt"), "new").unwrap();
+        fs::write(target.join("x.txt"), "old").unwrap();
+        let mut handle = ops.drop_files(&[root.join("x.txt")], &target, false).unwrap();
+        assert!(matches!(finish(&mut handle), Err(OperationError::Failed(errors)) if errors.len() == 1));
+        assert_eq!(fs::read_to_string(target.join("x.txt")).unwrap(), "old");
+        assert!(root.join("x.txt").exists());
+    }
+
+    #[test]
+    fn test_paste_into_itself() {
+        let root = TestDir::new("paste_self");
+        let source = root.join("folder");
+        fs::create_dir_all(source.join("sub")).unwrap();
+
+        let mut ops = FileOperations::new();
+        ops.copy_to_clipboard(vec![source.clone()]);
+        assert!(ops.paste_from_clipboard(&source).is_err());
+        assert!(ops.paste_from_clipboard(&source.join("sub")).is_err());
+        assert!(ops.job_queue.lock().unwrap().is_empty());
+        assert!(!source.join("folder").exists() && !source.join("sub").join("folder").exists());
+
+        // 粘贴到上级文件夹仍然可以
+        let Ok(PasteStart::Started(mut handle)) = ops.paste_from_clipboard(&root) else {
+            panic!("粘贴到上级文件夹应直接开始");
+        };
+        assert_eq!(finish(&mut handle), Ok(()));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,311 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use crossbeam_channel::{self, Receiver};
+use crate::utils;
+use super::gitignore::GitIgnore;
+use super::locale_format;
+
+// 报告中的一个文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub ignored: bool, // 被 .gitignore 忽略（构建产物、依赖等）
+}
+
+// 最大的和最旧的 N 个文件
+#[derive(Debug, Clone, Default)]
+struct ReportLists {
+    largest: Vec<ReportEntry>,
+    oldest: Vec<ReportEntry>,
+}
+
+impl ReportLists {
+    fn new(files: Vec<ReportEntry>, limit: usize) -> Self {
+        let mut largest = files.clone();
+        largest.sort_by_key(|e| std::cmp::Reverse(e.size));
+        largest.truncate(limit);
+        // 没有修改时间的文件排在最后
+        let mut oldest: Vec<ReportEntry> = files.into_iter().filter(|e| e.modified.is_some()).collect();
+        oldest.sort_by_key(|e| e.modified);
+        oldest.truncate(limit);
+        Self { largest, oldest }
+    }
+
+    fn remove(&mut self, path: &Path) {
+        self.largest.retain(|e| e.path != path);
+        self.oldest.retain(|e| e.path != path);
+    }
+}
+
+// 扫描结果；遵循 .gitignore 时另外分别统计源文件和被忽略的文件
+#[derive(Debug, Clone, Default)]
+struct ReportResult {
+    all: ReportLists,
+    source: ReportLists,
+    ignored: ReportLists,
+    scanned: usize,
+    source_size: u64,
+    ignored_size: u64,
+    ignored_count: usize,
+}
+
+// 报告中对文件的操作，由主程序执行
+pub enum ReportAction {
+    Reveal(PathBuf), // 在内容框中定位该文件
+    Delete(PathBuf),
+}
+
+#[derive(PartialEq)]
+enum ReportTab {
+    Largest,
+    Oldest,
+}
+
+// 遵循 .gitignore 时显示哪些文件
+#[derive(Clone, Copy, PartialEq)]
+enum ReportScope {
+    All,
+    Source,
+    Ignored,
+}
+
+// 递归扫描文件夹，不跟随符号链接；取消时返回 None。
+// 遵循 .gitignore 时被忽略文件夹中的全部内容都算作被忽略，.git 文件夹也算作被忽略
+fn scan(root: &Path, limit: usize, respect_gitignore: bool, cancelled: &AtomicBool) -> Option<ReportResult> {
+    let mut files = Vec::new();
+    let root_ignore = respect_gitignore.then(|| GitIgnore::for_dir(root));
+    let mut pending = vec![(root.to_path_buf(), root_ignore, false)];
+    while let Some((dir, ignore, dir_ignored)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let ignore = match &ignore {
+            Some(ignore) if !dir_ignored && dir != root => Some(GitIgnore::with_dir(ignore, &dir)),
+            _ => ignore,
+        };
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path();
+            let ignored = dir_ignored
+                || ignore.as_ref().is_some_and(|ignore| {
+                    (metadata.is_dir() && entry.file_name() == ".git") || ignore.is_ignored(&path, metadata.is_dir())
+                });
+            if metadata.is_dir() {
+                pending.push((path, ignore.clone(), ignored));
+            } else if metadata.is_file() {
+                files.push(ReportEntry { path, size: metadata.len(), modified: metadata.modified().ok(), ignored });
+            }
+        }
+    }
+
+    let scanned = files.len();
+    let (ignored, source): (Vec<ReportEntry>, Vec<ReportEntry>) = if respect_gitignore {
+        files.iter().cloned().partition(|e| e.ignored)
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    Some(ReportResult {
+        source_size: source.iter().map(|e| e.size).sum(),
+        ignored_size: ignored.iter().map(|e| e.size).sum(),
+        ignored_count: ignored.len(),
+        all: ReportLists::new(files, limit),
+        source: ReportLists::new(source, limit),
+        ignored: ReportLists::new(ignored, limit),
+        scanned,
+    })
+}
+
+// 大文件/旧文件报告窗口
+pub struct FileReport {
+    receiver: Option<Receiver<ReportResult>>,
+    cancelled: Arc<AtomicBool>,
+    root: PathBuf,
+    limit: usize,
+    result: Option<ReportResult>,
+    tab: ReportTab,
+    respect_gitignore: bool, // 按 .gitignore 区分源文件和构建产物
+    scope: ReportScope,
+    pending_delete: Option<PathBuf>, // 等待确认删除的文件
+}
+
+impl FileReport {
+    pub fn new() -> Self {
+        Self {
+            receiver: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            root: PathBuf::new(),
+            limit: 50,
+            result: None,
+            tab: ReportTab::Largest,
+            respect_gitignore: false,
+            scope: ReportScope::All,
+            pending_delete: None,
+        }
+    }
+
+    // 在后台扫描文件夹
+    pub fn start(&mut self, root: PathBuf) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.cancelled = Arc::new(AtomicBool::new(false));
+        self.root = root.clone();
+        self.result = None;
+        self.pending_delete = None;
+
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let cancel_flag = self.cancelled.clone();
+        let (limit, respect_gitignore) = (self.limit, self.respect_gitignore);
+        std::thread::spawn(move || {
+            if let Some(result) = scan(&root, limit, respect_gitignore, &cancel_flag) {
+                let _ = sender.send(result);
+            }
+        });
+        self.receiver = Some(receiver);
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.receiver.is_some() || self.result.is_some()
+    }
+
+    // 显示报告窗口，返回用户选择的操作
+    pub fn show_report_dialog(&mut self, ctx: &egui::Context) -> Option<ReportAction> {
+        if let Some(receiver) = &self.receiver {
+            if let Ok(result) = receiver.try_recv() {
+                self.result = Some(result);
+                self.receiver = None;
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+
+        let mut open = true;
+        let mut action = None;
+        let mut rescan = false;
+
+        egui::Window::new("大文件和旧文件")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_size(egui::Vec2::new(680.0, 440.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("文件夹: {}", self.root.display()));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.add_enabled(self.receiver.is_none(), egui::Button::new("重新扫描")).clicked() {
+                            rescan = true;
+                        }
+                        ui.add(egui::DragValue::new(&mut self.limit).range(10..=1000));
+                        ui.label("显示数量:");
+                        if ui.checkbox(&mut self.respect_gitignore, "遵循 .gitignore")
+                            .on_hover_text("按 .gitignore 分别统计源文件和构建产物、依赖等被忽略的文件")
+                            .changed()
+                        {
+                            rescan = true;
+                        }
+                    });
+                });
+                ui.separator();
+
+                let Some(result) = &self.result else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("正在扫描...");
+                    });
+                    return;
+                };
+
+                if self.respect_gitignore {
+                    ui.horizontal(|ui| {
+                        let source_count = result.scanned - result.ignored_count;
+                        ui.selectable_value(&mut self.scope, ReportScope::All, "全部");
+                        ui.selectable_value(&mut self.scope, ReportScope::Source, format!("源文件 {}（{} 个）", utils::get_file_size_str(result.source_size), source_count));
+                        ui.selectable_value(&mut self.scope, ReportScope::Ignored, format!("被忽略 {}（{} 个）", utils::get_file_size_str(result.ignored_size), result.ignored_count));
+                    });
+                }
+                let lists = match self.scope {
+                    _ if !self.respect_gitignore => &result.all,
+                    ReportScope::All => &result.all,
+                    ReportScope::Source => &result.source,
+                    ReportScope::Ignored => &result.ignored,
+                };
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.tab, ReportTab::Largest, format!("最大的 {} 个文件", lists.largest.len()));
+                    ui.selectable_value(&mut self.tab, ReportTab::Oldest, format!("最旧的 {} 个文件", lists.oldest.len()));
+                    ui.label(egui::RichText::new(format!("共扫描 {} 个文件", result.scanned)).weak());
+                });
+                ui.separator();
+
+                let entries = match self.tab {
+                    ReportTab::Largest => &lists.largest,
+                    ReportTab::Oldest => &lists.oldest,
+                };
+                egui::ScrollArea::vertical().max_height(ui.available_height() - 36.0).show(ui, |ui| {
+                    egui::Grid::new("file_report_grid").striped(true).num_columns(4).show(ui, |ui| {
+                        for entry in entries {
+                            let relative = entry.path.strip_prefix(&self.root).unwrap_or(&entry.path);
+                            // 全部文件中被忽略的显示为灰色
+                            let text = egui::RichText::new(relative.to_string_lossy());
+                            let text = if entry.ignored && self.scope == ReportScope::All { text.weak() } else { text };
+                            ui.label(text).on_hover_text(entry.path.to_string_lossy());
+                            ui.label(utils::get_file_size_str(entry.size));
+                            let modified = entry.modified
+                                .map(|t| locale_format::current().datetime(t))
+                                .unwrap_or_else(|| "未知时间".to_string());
+                            ui.label(modified);
+                            ui.horizontal(|ui| {
+                                if ui.small_button("定位").clicked() {
+                                    action = Some(ReportAction::Reveal(entry.path.clone()));
+                                }
+                                if ui.small_button("删除").clicked() {
+                                    self.pending_delete = Some(entry.path.clone());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if let Some(path) = self.pending_delete.clone() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        ui.label(format!("确定要把 \"{}\" 移到回收站吗？", name));
+                        if ui.button("确认删除").clicked() {
+                            action = Some(ReportAction::Delete(path.clone()));
+                            self.pending_delete = None;
+                        }
+                        if ui.button("取消").clicked() {
+                            self.pending_delete = None;
+                        }
+                    });
+                }
+            });
+
+        // 删除后从报告中移除该文件
+        if let (Some(ReportAction::Delete(path)), Some(result)) = (&action, self.result.as_mut()) {
+            for lists in [&mut result.all, &mut result.source, &mut result.ignored] {
+                lists.remove(path);
+            }
+        }
+        if rescan {
+            self.start(self.root.clone());
+        }
+        if !open {
+            self.cancelled.store(true, Ordering::Relaxed);
+            self.receiver = None;
+            self.result = None;
+        }
+        action
+    }
+}
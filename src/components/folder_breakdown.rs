@@ -0,0 +1,224 @@
+use eframe::egui;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver};
+use crate::utils;
+
+// 列出的最大文件数
+const LARGEST_COUNT: usize = 10;
+// 条形图中单独列出的扩展名数量，其余合并为“其他”
+const EXTENSION_COUNT: usize = 8;
+// 条形图各段的颜色
+const BAR_COLORS: [egui::Color32; EXTENSION_COUNT + 1] = [
+    egui::Color32::from_rgb(66, 133, 244),
+    egui::Color32::from_rgb(219, 68, 55),
+    egui::Color32::from_rgb(244, 180, 0),
+    egui::Color32::from_rgb(15, 157, 88),
+    egui::Color32::from_rgb(171, 71, 188),
+    egui::Color32::from_rgb(0, 172, 193),
+    egui::Color32::from_rgb(255, 112, 67),
+    egui::Color32::from_rgb(158, 157, 36),
+    egui::Color32::from_rgb(120, 120, 120),
+];
+
+// 文件夹深度扫描的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FolderBreakdown {
+    pub total_size: u64,
+    pub file_count: usize,
+    pub largest: Vec<(PathBuf, u64)>,          // 最大的文件，从大到小
+    pub extensions: Vec<(String, u64, usize)>, // 各扩展名的总大小和文件数，从大到小
+}
+
+fn extension_key(path: &Path) -> String {
+    path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+// 递归扫描文件夹，不跟随符号链接；scanned 记录已扫描的文件数，取消时返回 None
+fn scan(root: &Path, scanned: &AtomicUsize, cancelled: &AtomicBool) -> Option<FolderBreakdown> {
+    let mut largest: BinaryHeap<Reverse<(u64, PathBuf)>> = BinaryHeap::new();
+    let mut extensions: HashMap<String, (u64, usize)> = HashMap::new();
+    let mut result = FolderBreakdown::default();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                let path = entry.path();
+                let size = metadata.len();
+                result.total_size += size;
+                result.file_count += 1;
+                let total = extensions.entry(extension_key(&path)).or_default();
+                total.0 += size;
+                total.1 += 1;
+                // 小顶堆只保留最大的几个
+                largest.push(Reverse((size, path)));
+                if largest.len() > LARGEST_COUNT {
+                    largest.pop();
+                }
+                scanned.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    result.largest = largest.into_sorted_vec().into_iter().map(|Reverse((size, path))| (path, size)).collect();
+    result.extensions = extensions.into_iter().map(|(ext, (size, count))| (ext, size, count)).collect();
+    result.extensions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Some(result)
+}
+
+// 后台进行的深度扫描，释放时取消
+pub struct FolderScan {
+    root: PathBuf,
+    receiver: Option<Receiver<Option<FolderBreakdown>>>,
+    scanned: Arc<AtomicUsize>,
+    cancelled: Arc<AtomicBool>,
+    result: Option<FolderBreakdown>,
+}
+
+impl FolderScan {
+    pub fn start(root: PathBuf, ctx: &egui::Context) -> Self {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let scanned = Arc::new(AtomicUsize::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (thread_root, thread_scanned, thread_cancelled, ctx) = (root.clone(), scanned.clone(), cancelled.clone(), ctx.clone());
+        std::thread::spawn(move || {
+            let _ = sender.send(scan(&thread_root, &thread_scanned, &thread_cancelled));
+            ctx.request_repaint();
+        });
+        Self { root, receiver: Some(receiver), scanned, cancelled, result: None }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // 绘制扫描进度或结果；点击取消或关闭时返回 false
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        if let Some(result) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.receiver = None;
+            self.result = result;
+        }
+        let mut keep = true;
+        match &self.result {
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("正在扫描… 已统计 {} 个文件", self.scanned.load(Ordering::Relaxed)));
+                    if ui.small_button("取消").clicked() {
+                        keep = false;
+                    }
+                });
+                // 进度数字需要持续刷新
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+            }
+            Some(result) => {
+                ui.horizontal(|ui| {
+                    ui.strong(format!("共 {}，{} 个文件", utils::get_file_size_str(result.total_size), result.file_count));
+                    if ui.small_button("✖").on_hover_text("关闭统计").clicked() {
+                        keep = false;
+                    }
+                });
+                show_breakdown(ui, &self.root, result);
+            }
+        }
+        keep
+    }
+}
+
+impl Drop for FolderScan {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// 各扩展名所占比例的条形图和最大的文件列表
+fn show_breakdown(ui: &mut egui::Ui, root: &Path, result: &FolderBreakdown) {
+    if result.file_count == 0 {
+        ui.label("文件夹中没有文件");
+        return;
+    }
+    // 前几个扩展名单独显示，其余合并
+    let mut segments: Vec<(String, u64, usize)> = result.extensions.iter().take(EXTENSION_COUNT).cloned().collect();
+    let (other_size, other_count) = result.extensions.iter().skip(EXTENSION_COUNT).fold((0, 0), |(s, c), e| (s + e.1, c + e.2));
+    if other_count > 0 {
+        segments.push(("其他".to_string(), other_size, other_count));
+    }
+    let total = result.total_size.max(1) as f32;
+
+    let width = ui.available_width().min(400.0);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 14.0), egui::Sense::hover());
+    let mut x = rect.left();
+    for (index, (_, size, _)) in segments.iter().enumerate() {
+        let segment_width = rect.width() * *size as f32 / total;
+        let segment = egui::Rect::from_min_size(egui::pos2(x, rect.top()), egui::vec2(segment_width, rect.height()));
+        ui.painter().rect_filled(segment, 0.0, BAR_COLORS[index]);
+        x += segment_width;
+    }
+
+    egui::Grid::new("folder_breakdown_extensions").num_columns(4).show(ui, |ui| {
+        for (index, (ext, size, count)) in segments.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let (swatch, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                ui.painter().rect_filled(swatch, 2.0, BAR_COLORS[index]);
+                ui.label(if ext.is_empty() { "(无扩展名)".to_string() } else if index == EXTENSION_COUNT { ext.clone() } else { format!(".{}", ext) });
+            });
+            ui.label(utils::get_file_size_str(*size));
+            ui.label(format!("{:.1}%", *size as f32 * 100.0 / total));
+            ui.label(egui::RichText::new(format!("{} 个", count)).weak());
+            ui.end_row();
+        }
+    });
+
+    ui.add_space(4.0);
+    ui.label(format!("最大的 {} 个文件:", result.largest.len()));
+    egui::Grid::new("folder_breakdown_largest").num_columns(2).striped(true).show(ui, |ui| {
+        for (path, size) in &result.largest {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            ui.label(relative.display().to_string()).on_hover_text(path.display().to_string());
+            ui.label(utils::get_file_size_str(*size));
+            ui.end_row();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_scan_breakdown() {
+        let root = TestDir::new("folder_breakdown");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("a.txt"), vec![0u8; 100]).unwrap();
+        fs::write(root.join("sub/b.TXT"), vec![0u8; 50]).unwrap();
+        fs::write(root.join("sub/c.png"), vec![0u8; 300]).unwrap();
+        fs::write(root.join("README"), vec![0u8; 10]).unwrap();
+
+        let scanned = AtomicUsize::new(0);
+        let result = scan(&root, &scanned, &AtomicBool::new(false)).unwrap();
+        assert_eq!((result.total_size, result.file_count), (460, 4));
+        assert_eq!(scanned.load(Ordering::Relaxed), 4);
+        assert_eq!(result.largest[0], (root.join("sub/c.png"), 300));
+        assert_eq!(result.extensions, vec![
+            ("png".to_string(), 300, 1),
+            ("txt".to_string(), 150, 2),
+            (String::new(), 10, 1),
+        ]);
+        assert!(scan(&root, &scanned, &AtomicBool::new(true)).is_none());
+    }
+}
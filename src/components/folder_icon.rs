@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::image_formats;
+
+// 文件夹中按顺序查找的封面图片
+const ICON_FILE_NAMES: [&str; 8] = [
+    "folder.jpg", "folder.png", "Folder.jpg", "folder.jpeg",
+    "cover.jpg", "cover.png", "Cover.jpg", "cover.jpeg",
+];
+// 缓存的文件夹数量上限，超过时整体清空
+const MAX_CACHED: usize = 4096;
+
+// 可以作为图标显示的图片格式（与缩略图支持的格式一致）
+fn is_image_path(path: &Path) -> bool {
+    image_formats::is_image_file(path)
+}
+
+// 读取 .directory 文件 [Desktop Entry] 段中的 Icon= 值
+fn parse_directory_icon(content: &str) -> Option<String> {
+    let mut in_entry = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry {
+            if let Some(value) = line.strip_prefix("Icon=") {
+                let value = value.trim();
+                return (!value.is_empty()).then(|| value.to_string());
+            }
+        }
+    }
+    None
+}
+
+// 查找文件夹的自定义图标：先看 .directory 的 Icon=（只支持图片路径，图标主题名忽略），
+// 再看 folder.jpg / cover.jpg 等封面图片
+pub fn find_folder_icon(dir: &Path) -> Option<PathBuf> {
+    if let Some(value) = fs::read_to_string(dir.join(".directory")).ok().as_deref().and_then(parse_directory_icon) {
+        let icon = dir.join(value); // 绝对路径时 join 直接返回它
+        if is_image_path(&icon) && icon.is_file() {
+            return Some(icon);
+        }
+    }
+    ICON_FILE_NAMES.iter().map(|name| dir.join(name)).find(|path| path.is_file())
+}
+
+// 文件夹自定义图标的查找缓存，避免每帧访问文件系统
+pub struct FolderIconCache {
+    enabled: bool,
+    icons: HashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl FolderIconCache {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            icons: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.icons.clear();
+        }
+    }
+
+    // 重新查找文件夹的图标并写入缓存（刷新目录时调用，以便发现新放入的封面）
+    pub fn update(&mut self, dir: &Path) {
+        if !self.enabled {
+            return;
+        }
+        if self.icons.len() >= MAX_CACHED {
+            self.icons.clear();
+        }
+        self.icons.insert(dir.to_path_buf(), find_folder_icon(dir));
+    }
+
+    // 已缓存的图标路径，不访问文件系统
+    pub fn get(&self, dir: &Path) -> Option<&Path> {
+        self.icons.get(dir).and_then(|icon| icon.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_find_folder_icon() {
+        let root = TestDir::new("folder_icon");
+        assert_eq!(find_folder_icon(&root), None);
+
+        fs::write(root.join("cover.jpg"), "").unwrap();
+        assert_eq!(find_folder_icon(&root), Some(root.join("cover.jpg")));
+
+        // .directory 中的图片路径优先，图标主题名被忽略
+        fs::write(root.join("art.png"), "").unwrap();
+        fs::write(root.join(".directory"), "[Desktop Entry]\nIcon=art.png\n").unwrap();
+        assert_eq!(find_folder_icon(&root), Some(root.join("art.png")));
+        fs::write(root.join(".directory"), "[Desktop Entry]\nIcon=folder-music\n").unwrap();
+        assert_eq!(find_folder_icon(&root), Some(root.join("cover.jpg")));
+    }
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver};
+
+// 后台计算文件夹大小，结果通过通道逐个返回；离开目录时取消
+pub struct FolderSizeWorker {
+    receiver: Receiver<(PathBuf, u64)>,
+    cancelled: Arc<AtomicBool>,
+    remaining: usize, // 尚未返回结果的文件夹数量
+}
+
+impl FolderSizeWorker {
+    // 为给定的文件夹启动后台计算线程
+    pub fn start(folders: Vec<PathBuf>) -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let remaining = folders.len();
+
+        let cancel_flag = cancelled.clone();
+        std::thread::spawn(move || {
+            for folder in folders {
+                let Some(size) = dir_size(&folder, &cancel_flag) else {
+                    return; // 已取消
+                };
+                if sender.send((folder, size)).is_err() {
+                    return; // 接收端已释放
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            cancelled,
+            remaining,
+        }
+    }
+
+    // 取出已计算完成的结果（不阻塞）
+    pub fn poll(&mut self) -> Vec<(PathBuf, u64)> {
+        let results: Vec<_> = self.receiver.try_iter().collect();
+        self.remaining = self.remaining.saturating_sub(results.len());
+        results
+    }
+
+    // 是否还有文件夹在计算中
+    pub fn is_running(&self) -> bool {
+        self.remaining > 0
+    }
+}
+
+impl Drop for FolderSizeWorker {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// 递归统计文件夹大小，不跟随符号链接；取消时返回 None
+fn dir_size(path: &Path, cancelled: &AtomicBool) -> Option<u64> {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue; // 无权限等错误时跳过该目录
+        };
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                total += metadata.len();
+            }
+        }
+    }
+    Some(total)
+}
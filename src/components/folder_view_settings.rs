@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use super::file_list::{SortBy, ViewMode};
+
+// 单个目录的查看方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderViewSettings {
+    pub view_mode: ViewMode,
+    pub sort_by: SortBy,
+    pub sort_ascending: bool,
+    pub column_ratios: [f32; 4], // 名称/修改日期/类型/大小 列宽比例
+    #[serde(default = "default_icon_zoom")]
+    pub icon_zoom: f32,          // 图标视图的缩放比例
+}
+
+fn default_icon_zoom() -> f32 {
+    1.0
+}
+
+// 按目录路径保存的查看方式，存放在配置目录的 folder_views.json 中
+pub struct FolderViewStore {
+    file_path: Option<PathBuf>,
+    entries: HashMap<String, FolderViewSettings>,
+    dirty: bool,
+}
+
+impl FolderViewStore {
+    // 从配置文件加载，文件不存在或损坏时从空记录开始
+    pub fn load() -> Self {
+        let file_path = dirs::config_dir().map(|dir| dir.join("file-explorer").join("folder_views.json"));
+        let entries = file_path
+            .as_ref()
+            .and_then(|path| crate::utils::load_json(path))
+            .unwrap_or_default();
+
+        Self {
+            file_path,
+            entries,
+            dirty: false,
+        }
+    }
+
+    pub fn get(&self, dir: &Path) -> Option<&FolderViewSettings> {
+        self.entries.get(&dir.to_string_lossy().to_string())
+    }
+
+    pub fn set(&mut self, dir: &Path, settings: FolderViewSettings) {
+        self.entries.insert(dir.to_string_lossy().to_string(), settings);
+        self.dirty = true;
+    }
+
+    // 有修改时写回配置文件
+    pub fn save_if_dirty(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if let Err(e) = crate::utils::save_json(path, &self.entries) {
+            eprintln!("保存目录查看方式失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use eframe::egui;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+// 每帧在 UI 线程上做可推迟工作（上传纹理、清理缓存等）的时间预算
+const FRAME_BUDGET: Duration = Duration::from_millis(6);
+
+#[derive(Clone, Copy)]
+struct BudgetState {
+    frame: u64,
+    started: Instant,
+    spent_any: bool,
+}
+
+thread_local! {
+    static STATE: Cell<Option<BudgetState>> = const { Cell::new(None) };
+}
+
+// 请求在本帧做一项可推迟的工作：从本帧第一项工作起超过预算后返回 false，
+// 并请求重绘以便下一帧继续；每帧至少允许一项，保证工作总能完成
+pub fn try_spend(ctx: &egui::Context) -> bool {
+    let frame = ctx.cumulative_pass_nr();
+    let now = Instant::now();
+    let state = match STATE.get() {
+        Some(state) if state.frame == frame => state,
+        _ => BudgetState { frame, started: now, spent_any: false },
+    };
+    let allowed = !state.spent_any || now.duration_since(state.started) < FRAME_BUDGET;
+    STATE.set(Some(BudgetState { spent_any: state.spent_any || allowed, ..state }));
+    if !allowed {
+        ctx.request_repaint();
+    }
+    allowed
+}
+
@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::utils;
+
+// .gitignore 中的一条规则
+#[derive(Debug, Clone, PartialEq)]
+struct IgnoreRule {
+    segments: Vec<String>, // 按 / 分隔的通配符，** 匹配任意层
+    negated: bool,         // 以 ! 开头，重新包含
+    dir_only: bool,        // 以 / 结尾，只匹配文件夹
+}
+
+fn parse_rules(text: &str) -> Vec<IgnoreRule> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            if line.is_empty() {
+                return None;
+            }
+            // 不含 / 的规则匹配任意层的名称，含 / 的规则相对于 .gitignore 所在文件夹
+            let anchored = line.contains('/');
+            let mut segments: Vec<String> = line.trim_start_matches('/').split('/').map(str::to_string).collect();
+            if !anchored {
+                segments.insert(0, "**".to_string());
+            }
+            Some(IgnoreRule { segments, negated, dir_only })
+        })
+        .collect()
+}
+
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            match_segments(rest, path) || (!path.is_empty() && match_segments(pattern, &path[1..]))
+        }
+        Some((first, rest)) => !path.is_empty() && utils::wildcard_match(first, path[0]) && match_segments(rest, &path[1..]),
+    }
+}
+
+// 一个文件夹的 .gitignore 规则，连同上层文件夹的规则；内层规则优先
+pub struct GitIgnore {
+    parent: Option<Arc<GitIgnore>>,
+    base: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl GitIgnore {
+    // 从所在仓库的根目录（含 .git 的上层文件夹）起加载到 dir 为止的各层 .gitignore
+    pub fn for_dir(dir: &Path) -> Arc<GitIgnore> {
+        let repo_root = dir.ancestors().find(|ancestor| ancestor.join(".git").exists()).unwrap_or(dir);
+        let mut chain: Vec<&Path> = dir.ancestors().take_while(|ancestor| ancestor.starts_with(repo_root)).collect();
+        chain.reverse();
+        let mut ignore = Arc::new(GitIgnore { parent: None, base: repo_root.to_path_buf(), rules: Vec::new() });
+        for ancestor in chain {
+            ignore = GitIgnore::with_dir(&ignore, ancestor);
+        }
+        ignore
+    }
+
+    // 进入子文件夹：有 .gitignore 时加入其规则
+    pub fn with_dir(this: &Arc<GitIgnore>, dir: &Path) -> Arc<GitIgnore> {
+        match fs::read_to_string(dir.join(".gitignore")) {
+            Ok(text) => Arc::new(GitIgnore { parent: Some(this.clone()), base: dir.to_path_buf(), rules: parse_rules(&text) }),
+            Err(_) => this.clone(),
+        }
+    }
+
+    // 最后一条匹配的规则决定是否忽略；本层没有匹配时交给上层
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if let Ok(relative) = path.strip_prefix(&self.base) {
+            let components: Vec<&str> = relative.iter().filter_map(|c| c.to_str()).collect();
+            let matched = self.rules.iter().rev()
+                .find(|rule| (is_dir || !rule.dir_only) && match_segments(&rule.segments, &components));
+            if let Some(rule) = matched {
+                return !rule.negated;
+            }
+        }
+        self.parent.as_ref().is_some_and(|parent| parent.is_ignored(path, is_dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitignore_rules() {
+        let ignore = GitIgnore {
+            parent: None,
+            base: PathBuf::from("/repo"),
+            rules: parse_rules("# 构建产物\ntarget/\n*.log\n!keep.log\n/docs/*.pdf\nnode_modules\nbuild/**/cache\n"),
+        };
+        assert!(ignore.is_ignored(Path::new("/repo/target"), true));
+        assert!(!ignore.is_ignored(Path::new("/repo/target"), false));
+        assert!(ignore.is_ignored(Path::new("/repo/src/debug.log"), false));
+        assert!(!ignore.is_ignored(Path::new("/repo/keep.log"), false));
+        assert!(ignore.is_ignored(Path::new("/repo/docs/manual.pdf"), false));
+        assert!(!ignore.is_ignored(Path::new("/repo/src/docs/manual.pdf"), false));
+        assert!(ignore.is_ignored(Path::new("/repo/web/node_modules"), true));
+        assert!(ignore.is_ignored(Path::new("/repo/build/a/b/cache"), true));
+        assert!(!ignore.is_ignored(Path::new("/repo/src/main.rs"), false));
+    }
+}
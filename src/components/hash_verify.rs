@@ -0,0 +1,235 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver};
+use super::file_hashes::{hash_file, FileHashes, HashAlgorithm};
+
+// 与文件放在一起的校验文件：先找 "文件名.sha256" 这类单独的校验文件，再找整个文件夹的清单
+const SIDECAR_EXTENSIONS: [&str; 6] = ["sha256", "sha256sum", "sha1", "sha1sum", "md5", "md5sum"];
+const LIST_NAMES: [&str; 6] = ["SHA256SUMS", "SHA256SUMS.txt", "sha256sum.txt", "SHA1SUMS", "MD5SUMS", "md5sum.txt"];
+// 读取的校验文件大小上限
+const MAX_CHECKSUM_FILE: u64 = 1024 * 1024;
+
+fn is_hash(token: &str) -> bool {
+    HashAlgorithm::from_hex(token).is_some()
+}
+
+// 一行中的校验值：支持单独的哈希、sha256sum 的 "哈希  文件名" 和 BSD 的 "SHA256 (文件名) = 哈希"
+fn line_hash(line: &str) -> Option<String> {
+    line.split(|c: char| c.is_whitespace() || c == '=' || c == ':')
+        .find(|token| is_hash(token))
+        .map(str::to_ascii_lowercase)
+}
+
+// 这一行是否属于 file_name
+fn line_names_file(line: &str, file_name: &str) -> bool {
+    let line = line.trim_end();
+    line.contains(&format!("({})", file_name))
+        || line.strip_suffix(file_name).is_some_and(|rest| rest.ends_with([' ', '*', '/']))
+}
+
+// 从校验文件的内容中找出 file_name 的校验值；single 为 true 时（文件名.sha256）只有一个值也可以不写文件名
+fn expected_from_text(text: &str, file_name: &str, single: bool) -> Option<String> {
+    let hashes: Vec<(&str, String)> = text.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line_hash(line).map(|hash| (line, hash)))
+        .collect();
+    hashes.iter()
+        .find(|(line, _)| line_names_file(line, file_name))
+        .or_else(|| hashes.first().filter(|_| single && hashes.len() == 1))
+        .map(|(_, hash)| hash.clone())
+}
+
+// 查找文件旁边的校验文件，返回校验文件和其中记录的值
+fn find_sidecar(path: &Path) -> Option<(PathBuf, String)> {
+    let dir = path.parent()?;
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let read = |candidate: &Path| {
+        fs::metadata(candidate).ok().filter(|m| m.is_file() && m.len() <= MAX_CHECKSUM_FILE)?;
+        fs::read_to_string(candidate).ok()
+    };
+    let sidecars = SIDECAR_EXTENSIONS.iter().map(|ext| (dir.join(format!("{}.{}", file_name, ext)), true));
+    let lists = LIST_NAMES.iter().map(|name| (dir.join(name), false));
+    sidecars.chain(lists).find_map(|(candidate, single)| {
+        let expected = expected_from_text(&read(&candidate)?, &file_name, single)?;
+        Some((candidate, expected))
+    })
+}
+
+// 正在后台计算的校验值，释放时取消
+struct HashJob {
+    algorithm: HashAlgorithm,
+    receiver: Receiver<Result<FileHashes, String>>,
+    progress: Arc<AtomicU64>,
+    cancelled: Arc<AtomicBool>,
+    total: u64,
+}
+
+impl Drop for HashJob {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// "校验" 窗口：按粘贴的值或旁边的 .sha256/.md5 文件中的值识别算法，只计算该算法的校验值并比较
+pub struct HashVerifyDialog {
+    path: Option<PathBuf>,
+    size: u64,
+    expected: String,
+    sidecar: Option<PathBuf>, // 自动填入的校验值来自的文件
+    job: Option<HashJob>,
+    result: Option<(HashAlgorithm, Result<String, String>)>,
+}
+
+impl HashVerifyDialog {
+    pub fn new() -> Self {
+        Self { path: None, size: 0, expected: String::new(), sidecar: None, job: None, result: None }
+    }
+
+    fn start(&mut self, path: &Path, algorithm: HashAlgorithm, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let progress = Arc::new(AtomicU64::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (thread_path, thread_progress, thread_cancelled, ctx) = (path.to_path_buf(), progress.clone(), cancelled.clone(), ctx.clone());
+        std::thread::spawn(move || {
+            let _ = sender.send(hash_file(&thread_path, &[algorithm], &thread_progress, &thread_cancelled).map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+        self.job = Some(HashJob { algorithm, receiver, progress, cancelled, total: self.size });
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn open(&mut self, path: PathBuf, ctx: &egui::Context) {
+        let (sidecar, expected) = find_sidecar(&path).map(|(file, hash)| (Some(file), hash)).unwrap_or_default();
+        self.sidecar = sidecar;
+        self.expected = expected;
+        self.result = None;
+        self.job = None;
+        self.size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if let Some(algorithm) = HashAlgorithm::from_hex(&self.expected) {
+            self.start(&path, algorithm, ctx);
+        }
+        self.path = Some(path);
+    }
+
+    pub fn show_hash_verify_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        if let Some(job) = &self.job {
+            if let Ok(result) = job.receiver.try_recv() {
+                let value = result.map(|hashes| hashes.into_iter().map(|(_, value)| value).next().unwrap_or_default());
+                self.result = Some((job.algorithm, value));
+                self.job = None;
+            }
+        }
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("校验文件")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(520.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("文件: {}", path.file_name().unwrap_or_default().to_string_lossy()));
+                ui.horizontal(|ui| {
+                    ui.label("期望的校验值:");
+                    ui.add(egui::TextEdit::singleline(&mut self.expected)
+                        .desired_width(380.0)
+                        .hint_text("粘贴 MD5、SHA-1 或 SHA-256")
+                        .font(egui::TextStyle::Monospace));
+                });
+                if let Some(sidecar) = &self.sidecar {
+                    ui.label(egui::RichText::new(format!("已从 {} 读取", sidecar.file_name().unwrap_or_default().to_string_lossy())).weak());
+                }
+                ui.separator();
+
+                let expected = line_hash(&self.expected);
+                // 粘贴的值换成其它算法时重新计算
+                if let Some(algorithm) = expected.as_deref().and_then(HashAlgorithm::from_hex) {
+                    let computed = self.job.as_ref().map(|job| job.algorithm).or(self.result.as_ref().map(|(algorithm, _)| *algorithm));
+                    if computed != Some(algorithm) {
+                        self.result = None;
+                        self.start(&path, algorithm, ui.ctx());
+                    }
+                }
+                match (&self.job, &self.result) {
+                    (Some(job), _) => {
+                        let done = job.progress.load(Ordering::Relaxed);
+                        let fraction = if job.total == 0 { 0.0 } else { done as f32 / job.total as f32 };
+                        ui.add(egui::ProgressBar::new(fraction).show_percentage().text("正在计算..."));
+                        ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+                    }
+                    (None, Some((algorithm, Ok(actual)))) => {
+                        egui::Grid::new("hash_verify_grid").num_columns(2).show(ui, |ui| {
+                            ui.label(algorithm.name());
+                            ui.label(egui::RichText::new(actual).monospace().small());
+                            ui.end_row();
+                        });
+                        ui.add_space(4.0);
+                        match &expected {
+                            None if self.expected.trim().is_empty() => {
+                                ui.label("粘贴校验值后自动比较");
+                            }
+                            None => {
+                                ui.colored_label(ui.visuals().warn_fg_color, "无法识别的校验值，应为 32、40 或 64 位十六进制");
+                            }
+                            Some(expected) => {
+                                let text = if expected == actual {
+                                    egui::RichText::new(format!("✔ 一致（{}）：文件完整", algorithm.name())).color(egui::Color32::from_rgb(40, 160, 60))
+                                } else {
+                                    egui::RichText::new(format!("✖ 不一致（{}）：文件已损坏或被修改", algorithm.name())).color(ui.visuals().error_fg_color)
+                                };
+                                ui.label(text.strong().size(16.0));
+                            }
+                        }
+                    }
+                    (None, Some((_, Err(e)))) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("无法读取文件: {}", e));
+                    }
+                    (None, None) if self.expected.trim().is_empty() => {
+                        ui.label("粘贴校验值后自动计算并比较");
+                    }
+                    (None, None) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, "无法识别的校验值，应为 32、40 或 64 位十六进制");
+                    }
+                }
+                ui.separator();
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("关闭").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !open || close {
+            self.path = None;
+            self.job = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_from_text() {
+        let hash = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let sums = format!("{}  other.iso\n{} *ubuntu.iso\n", "0".repeat(64), hash.to_uppercase());
+        assert_eq!(expected_from_text(&sums, "ubuntu.iso", false), Some(hash.to_string()));
+        assert_eq!(expected_from_text(&sums, "missing.iso", false), None);
+        assert_eq!(expected_from_text(&format!("SHA256 (ubuntu.iso) = {}", hash), "ubuntu.iso", false), Some(hash.to_string()));
+        // 单独的校验文件可以只有一个值
+        assert_eq!(expected_from_text(&format!("{}\n", hash), "ubuntu.iso", true), Some(hash.to_string()));
+        assert_eq!(line_hash("md5: d41d8cd98f00b204e9800998ecf8427e"), Some("d41d8cd98f00b204e9800998ecf8427e".to_string()));
+        assert!(is_hash("d41d8cd98f00b204e9800998ecf8427e") && !is_hash("d41d8cd98f00b204"));
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+// 超过此尺寸的图片不当作图标
+const MAX_ICON_SIZE: u32 = 1024;
+
+// 深色主题下单色图标的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecolorMode {
+    Auto,     // 检测到单色图标时反色
+    Invert,   // 总是反色
+    Original, // 保持原样
+}
+
+impl RecolorMode {
+    pub const ALL: [RecolorMode; 3] = [RecolorMode::Auto, RecolorMode::Invert, RecolorMode::Original];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RecolorMode::Auto => "自动",
+            RecolorMode::Invert => "反色",
+            RecolorMode::Original => "原样",
+        }
+    }
+}
+
+// 单个文件的设置，保存在 ~/.local/share/file-explorer/icon_recolor.json；自动的不记录
+static OVERRIDES: Mutex<Option<HashMap<PathBuf, RecolorMode>>> = Mutex::new(None);
+// 每次修改设置后加一，预览据此丢弃已生成的纹理
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn overrides_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("file-explorer").join("icon_recolor.json"))
+}
+
+fn load_overrides() -> HashMap<PathBuf, RecolorMode> {
+    overrides_file()
+        .and_then(|path| crate::utils::load_json(&path))
+        .unwrap_or_default()
+}
+
+pub fn mode(path: &Path) -> RecolorMode {
+    let Ok(mut overrides) = OVERRIDES.lock() else {
+        return RecolorMode::Auto;
+    };
+    overrides.get_or_insert_with(load_overrides).get(path).copied().unwrap_or(RecolorMode::Auto)
+}
+
+pub fn set_mode(path: &Path, mode: RecolorMode) {
+    let Ok(mut overrides) = OVERRIDES.lock() else {
+        return;
+    };
+    let overrides = overrides.get_or_insert_with(load_overrides);
+    if mode == RecolorMode::Auto {
+        overrides.remove(path);
+    } else {
+        overrides.insert(path.to_path_buf(), mode);
+    }
+    REVISION.fetch_add(1, Ordering::Relaxed);
+
+    let Some(file) = overrides_file() else {
+        return;
+    };
+    if let Err(e) = crate::utils::save_json(&file, overrides) {
+        eprintln!("保存图标反色设置失败: {}", e);
+    }
+}
+
+pub fn revision() -> u64 {
+    REVISION.load(Ordering::Relaxed)
+}
+
+// 单色图标：有透明背景，不透明部分几乎都是深色且不带彩色（黑色线条图标、符号图标等）
+pub fn is_monochrome_icon(image: &image::RgbaImage) -> bool {
+    if image.width() > MAX_ICON_SIZE || image.height() > MAX_ICON_SIZE {
+        return false;
+    }
+    let (mut transparent, mut opaque, mut dark) = (0u64, 0u64, 0u64);
+    for pixel in image.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a < 16 {
+            transparent += 1;
+        } else if a >= 128 {
+            opaque += 1;
+            let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+            if max < 96 && max - min < 32 {
+                dark += 1;
+            }
+        }
+    }
+    let total = image.width() as u64 * image.height() as u64;
+    opaque > 0 && transparent * 10 >= total && dark * 100 >= opaque * 95
+}
+
+// 按主题和文件设置处理图片：需要反色时返回反色后的副本（预乘 alpha），否则返回 None
+pub fn prepare(path: &Path, image: &image::RgbaImage, dark_mode: bool) -> Option<image::RgbaImage> {
+    if !dark_mode {
+        return None;
+    }
+    let invert = match mode(path) {
+        RecolorMode::Auto => is_monochrome_icon(image),
+        RecolorMode::Invert => true,
+        RecolorMode::Original => false,
+    };
+    if !invert {
+        return None;
+    }
+    let mut inverted = image.clone();
+    for pixel in inverted.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let premultiply = |c: u8| ((255 - c) as u32 * a as u32 / 255) as u8;
+        pixel.0 = [premultiply(r), premultiply(g), premultiply(b), a];
+    }
+    Some(inverted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_monochrome_icon() {
+        // 透明背景上的黑色方块
+        let mut icon = image::RgbaImage::new(16, 16);
+        for y in 4..12 {
+            for x in 4..12 {
+                icon.put_pixel(x, y, image::Rgba([10, 10, 10, 255]));
+            }
+        }
+        assert!(is_monochrome_icon(&icon));
+        let inverted = prepare(Path::new("/nonexistent/icon.png"), &icon, true).unwrap();
+        assert_eq!(inverted.get_pixel(5, 5).0, [245, 245, 245, 255]);
+        assert!(prepare(Path::new("/nonexistent/icon.png"), &icon, false).is_none());
+
+        // 彩色或不透明的图片不处理
+        for x in 4..12 {
+            icon.put_pixel(x, 5, image::Rgba([200, 30, 30, 255]));
+        }
+        assert!(!is_monochrome_icon(&icon));
+        let photo = image::RgbaImage::from_pixel(16, 16, image::Rgba([20, 20, 20, 255]));
+        assert!(!is_monochrome_icon(&photo));
+    }
+}
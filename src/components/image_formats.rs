@@ -0,0 +1,83 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use super::raw_image;
+
+// 可以预览和生成缩略图的图片格式，其它模块都通过这里判断，不再各自维护扩展名列表
+
+// 由 image 库直接解码的格式
+const DECODED_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "gif", "bmp", "ico", "webp"];
+// 没有纯 Rust 解码器的格式，通过外部工具（ffmpeg 或 ImageMagick）转换为 PNG 后解码
+// 相机 RAW 格式由 raw_image 取出内嵌预览
+const CONVERTED_EXTENSIONS: [&str; 3] = ["avif", "heic", "heif"];
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase())
+}
+
+fn needs_conversion(path: &Path) -> bool {
+    extension(path).is_some_and(|ext| CONVERTED_EXTENSIONS.contains(&ext.as_str()))
+}
+
+pub fn is_image_file(path: &Path) -> bool {
+    extension(path).is_some_and(|ext| DECODED_EXTENSIONS.contains(&ext.as_str()) || CONVERTED_EXTENSIONS.contains(&ext.as_str()))
+        || raw_image::is_raw_file(path)
+}
+
+// 依次尝试外部工具，把图片转换为 PNG 输出到标准输出
+fn convert_with_external_tool(path: &Path) -> Result<image::DynamicImage, String> {
+    let mut ffmpeg = Command::new("ffmpeg");
+    ffmpeg.args(["-v", "error", "-i"]).arg(path).args(["-frames:v", "1", "-f", "image2pipe", "-vcodec", "png", "-"]);
+    let mut magick = Command::new("magick");
+    magick.arg(path).arg("png:-");
+    let mut convert = Command::new("convert");
+    convert.arg(path).arg("png:-");
+
+    for mut command in [ffmpeg, magick, convert] {
+        let output = command.stdin(Stdio::null()).stderr(Stdio::null()).output();
+        if let Ok(output) = output {
+            if output.status.success() && !output.stdout.is_empty() {
+                return image::load_from_memory(&output.stdout).map_err(|e| e.to_string());
+            }
+        }
+    }
+    Err(format!("无法解码 {}：需要安装支持该格式的 ffmpeg 或 ImageMagick", path.display()))
+}
+
+// 打开图片文件，AVIF/HEIC 通过外部工具解码，RAW 使用内嵌预览；应在后台线程调用
+pub fn open_image(path: &Path) -> Result<image::DynamicImage, String> {
+    if raw_image::is_raw_file(path) {
+        raw_image::open_raw(path)
+    } else if needs_conversion(path) {
+        convert_with_external_tool(path)
+    } else {
+        image::open(path).map_err(|e| e.to_string())
+    }
+}
+
+// 图片尺寸；直接解码的格式只读取文件头。RAW 的内嵌预览尺寸不是照片尺寸，由 EXIF 字段给出
+pub fn image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    if raw_image::is_raw_file(path) {
+        None
+    } else if needs_conversion(path) {
+        open_image(path).ok().map(|image| (image.width(), image.height()))
+    } else {
+        image::image_dimensions(path).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_image_file() {
+        assert!(is_image_file(Path::new("photo.JPG")));
+        assert!(is_image_file(Path::new("photo.webp")));
+        assert!(is_image_file(Path::new("photo.heic")));
+        assert!(needs_conversion(Path::new("photo.AVIF")));
+        assert!(!needs_conversion(Path::new("photo.png")));
+        assert!(!is_image_file(Path::new("notes.txt")));
+        assert!(!is_image_file(Path::new("README")));
+        assert!(is_image_file(Path::new("DSC_0001.NEF")));
+    }
+}
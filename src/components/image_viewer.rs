@@ -0,0 +1,261 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use crossbeam_channel::{self, Receiver, Sender};
+use super::preview::Preview;
+use super::{icon_recolor, image_formats};
+
+// 保留的原图纹理数量：当前图片和前后各一张
+const KEEP_TEXTURES: usize = 3;
+
+// 后台线程先送回原图尺寸，解码完成后再送回图片
+enum Decoded {
+    Size(PathBuf, Option<(u32, u32)>),
+    Image(PathBuf, Result<egui::ColorImage, String>),
+}
+
+// 全窗口图片查看器：黑色背景上显示当前文件夹的图片，方向键切换，Esc 返回。
+// 先显示预加载器中的缩略图，原图在后台解码，并预先解码前后各一张
+pub struct ImageViewer {
+    show: bool,
+    images: Vec<PathBuf>,
+    index: usize,
+    textures: HashMap<PathBuf, egui::TextureHandle>, // 已解码的原图，只保留当前和前后各一张
+    sizes: HashMap<PathBuf, Option<(u32, u32)>>,      // 原图尺寸，用于放大缩略图，与纹理一样只保留前后几张
+    loading: HashSet<PathBuf>,
+    failed: HashMap<PathBuf, String>,
+    sender: Sender<Decoded>,
+    receiver: Receiver<Decoded>,
+    closed: Option<PathBuf>, // 关闭时正在显示的图片，由主程序取走后选中
+}
+
+impl ImageViewer {
+    pub fn new() -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        Self {
+            show: false,
+            images: Vec::new(),
+            index: 0,
+            textures: HashMap::new(),
+            sizes: HashMap::new(),
+            loading: HashSet::new(),
+            failed: HashMap::new(),
+            sender,
+            receiver,
+            closed: None,
+        }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show
+    }
+
+    // 打开查看器；images 为当前文件夹中按列表顺序排列的图片
+    pub fn open(&mut self, path: PathBuf, images: Vec<PathBuf>) {
+        self.images = images;
+        self.index = match self.images.iter().position(|p| *p == path) {
+            Some(index) => index,
+            None => {
+                self.images = vec![path];
+                0
+            }
+        };
+        self.failed.clear();
+        self.show = true;
+    }
+
+    pub fn take_closed(&mut self) -> Option<PathBuf> {
+        self.closed.take()
+    }
+
+    fn current(&self) -> Option<&PathBuf> {
+        self.images.get(self.index)
+    }
+
+    fn close(&mut self) {
+        self.closed = self.current().cloned();
+        self.show = false;
+        self.textures.clear();
+        self.sizes.clear();
+    }
+
+    fn step(&mut self, forward: bool) {
+        if self.images.is_empty() {
+            return;
+        }
+        let count = self.images.len();
+        self.index = if forward { (self.index + 1) % count } else { (self.index + count - 1) % count };
+    }
+
+    // 取走查看器使用的按键，需在其它组件处理键盘之前调用
+    pub fn handle_input(&mut self, ctx: &egui::Context) {
+        if !self.show {
+            return;
+        }
+        let consume = |key: egui::Key| ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, key));
+        if consume(egui::Key::Escape) {
+            self.close();
+            return;
+        }
+        let next = [egui::Key::ArrowRight, egui::Key::ArrowDown, egui::Key::PageDown, egui::Key::Space].into_iter().any(&consume);
+        let previous = [egui::Key::ArrowLeft, egui::Key::ArrowUp, egui::Key::PageUp, egui::Key::Backspace].into_iter().any(&consume);
+        if next {
+            self.step(true);
+        } else if previous {
+            self.step(false);
+        }
+        if consume(egui::Key::Home) {
+            self.index = 0;
+        }
+        if consume(egui::Key::End) {
+            self.index = self.images.len().saturating_sub(1);
+        }
+    }
+
+    // 在后台解码原图；黑色背景上单色图标按深色主题反色
+    fn decode(&mut self, path: &Path, ctx: &egui::Context) {
+        if self.textures.contains_key(path) || self.loading.contains(path) || self.failed.contains_key(path) {
+            return;
+        }
+        self.loading.insert(path.to_path_buf());
+        let (path, sender, ctx) = (path.to_path_buf(), self.sender.clone(), ctx.clone());
+        let max_side = ctx.input(|i| i.max_texture_side) as u32;
+        std::thread::spawn(move || {
+            // 尺寸只需读取文件头，先送回用于放大缩略图
+            let _ = sender.send(Decoded::Size(path.clone(), image_formats::image_dimensions(&path)));
+            ctx.request_repaint();
+            let image = image_formats::open_image(&path).map(|image| {
+                let image = if image.width() > max_side || image.height() > max_side {
+                    image.resize(max_side, max_side, image::imageops::FilterType::Triangle)
+                } else {
+                    image
+                };
+                let rgba = image.to_rgba8();
+                let size = [rgba.width() as usize, rgba.height() as usize];
+                match icon_recolor::prepare(&path, &rgba, true) {
+                    Some(recolored) => egui::ColorImage::from_rgba_premultiplied(size, recolored.as_raw()),
+                    None => egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()),
+                }
+            });
+            let _ = sender.send(Decoded::Image(path, image));
+            ctx.request_repaint();
+        });
+    }
+
+    // 接收解码结果，请求当前和前后图片，丢弃离得较远的纹理
+    fn update(&mut self, ctx: &egui::Context, preview: &Preview) {
+        for decoded in self.receiver.try_iter().collect::<Vec<_>>() {
+            let (path, image) = match decoded {
+                Decoded::Size(path, size) => {
+                    self.sizes.insert(path, size);
+                    continue;
+                }
+                Decoded::Image(path, image) => (path, image),
+            };
+            self.loading.remove(&path);
+            match image {
+                Ok(image) => {
+                    let texture = ctx.load_texture(format!("image_viewer_{}", path.display()), image, egui::TextureOptions::LINEAR);
+                    self.textures.insert(path, texture);
+                }
+                Err(e) => {
+                    self.failed.insert(path, e);
+                }
+            }
+        }
+
+        let count = self.images.len();
+        let nearby: Vec<PathBuf> = [0, 1, count.saturating_sub(1)]
+            .into_iter()
+            .filter_map(|offset| self.images.get((self.index + offset) % count.max(1)).cloned())
+            .collect();
+        for path in &nearby {
+            if !preview.preloader.is_cached(path) {
+                let _ = preview.preloader.sender.send(path.clone());
+            }
+            self.decode(path, ctx);
+        }
+        if self.textures.len() > KEEP_TEXTURES {
+            self.textures.retain(|path, _| nearby.contains(path));
+        }
+        if self.sizes.len() > KEEP_TEXTURES {
+            self.sizes.retain(|path, _| nearby.contains(path) || self.loading.contains(path));
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, preview: &Preview) {
+        if !self.show {
+            return;
+        }
+        self.update(ctx, preview);
+        let Some(path) = self.current().cloned() else {
+            self.close();
+            return;
+        };
+
+        let screen = ctx.screen_rect();
+        let mut step = None;
+        let mut close = false;
+        egui::Area::new(egui::Id::new("image_viewer"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(screen.min)
+            .show(ctx, |ui| {
+                // 占满窗口并挡住下面的界面
+                let response = ui.allocate_rect(screen, egui::Sense::click());
+                let painter = ui.painter_at(screen);
+                painter.rect_filled(screen, 0.0, egui::Color32::BLACK);
+
+                let texture = self.textures.get(&path).cloned()
+                    .or_else(|| preview.preloader.get_cached_thumbnail(&path, ctx).map(|(texture, _)| texture));
+                match texture {
+                    Some(texture) => {
+                        // 缩略图按原图尺寸放大，原图解码后无缝替换
+                        // 尺寸未读到时先按缩略图本身的尺寸显示
+                        let size = self.sizes.get(&path).copied().flatten();
+                        let [width, height] = texture.size();
+                        let (width, height) = size.unwrap_or((width as u32, height as u32));
+                        let image_size = egui::vec2(width as f32, height as f32);
+                        let area = screen.shrink(24.0);
+                        let scale = (area.width() / image_size.x).min(area.height() / image_size.y).min(1.0);
+                        let rect = egui::Rect::from_center_size(area.center(), image_size * scale);
+                        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                        painter.image(texture.id(), rect, uv, egui::Color32::WHITE);
+                    }
+                    None => {
+                        let text = match self.failed.get(&path) {
+                            Some(error) => format!("无法显示图片: {}", error),
+                            None => "正在加载...".to_string(),
+                        };
+                        painter.text(screen.center(), egui::Align2::CENTER_CENTER, text, egui::FontId::proportional(18.0), egui::Color32::GRAY);
+                    }
+                }
+
+                // 底部显示文件名和序号
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                painter.text(
+                    screen.center_bottom() - egui::vec2(0.0, 12.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    format!("{}    {} / {}    ←/→ 切换  Esc 返回", name, self.index + 1, self.images.len()),
+                    egui::FontId::proportional(14.0),
+                    egui::Color32::from_gray(200),
+                );
+
+                // 单击左右两侧切换图片
+                if response.clicked() {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        step = Some(pos.x >= screen.center().x);
+                    }
+                }
+                let close_rect = egui::Rect::from_min_size(screen.right_top() + egui::vec2(-44.0, 8.0), egui::vec2(36.0, 36.0));
+                if ui.put(close_rect, egui::Button::new(egui::RichText::new("✕").size(18.0)).frame(false)).on_hover_text("返回 (Esc)").clicked() {
+                    close = true;
+                }
+            });
+
+        if close {
+            self.close();
+        } else if let Some(forward) = step {
+            self.step(forward);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+// 日期的书写顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateStyle {
+    Chinese,     // 2024/03/05 14:30，详细格式为 2024年3月5日
+    MonthFirst,  // 03/05/2024 02:30 PM（美国）
+    DayFirst,    // 05/03/2024 14:30（英国、法国等）
+    DayFirstDot, // 05.03.2024 14:30（德国、俄罗斯等）
+    Iso,         // 2024-03-05 14:30（C/POSIX 或无法识别的区域）
+}
+
+// 按系统区域设置格式化日期、文件大小和数量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleFormat {
+    date_style: DateStyle,
+    group_separator: &'static str, // 千位分隔符，为空时不分组
+    decimal_separator: char,
+    china_time: bool, // TZ=CST 时按中国标准时间（UTC+8）显示
+}
+
+// 取第一个非空的区域变量，去掉编码部分，如 "zh_CN.UTF-8" → "zh_CN"
+fn locale_name(values: &[Option<&str>]) -> String {
+    let value = values.iter().flatten().find(|v| !v.is_empty()).copied().unwrap_or("");
+    value.split(['.', '@']).next().unwrap_or("").to_string()
+}
+
+impl LocaleFormat {
+    // 由环境变量的值构造：time/numeric 为按优先级排列的 LC_ALL、LC_TIME 或 LC_NUMERIC、LANG
+    fn from_env_values(time: &[Option<&str>], numeric: &[Option<&str>], tz: Option<&str>) -> Self {
+        let time_locale = locale_name(time);
+        let language = time_locale.split('_').next().unwrap_or("");
+        let date_style = match language {
+            "zh" | "ja" | "ko" => DateStyle::Chinese,
+            "en" if time_locale == "en_US" => DateStyle::MonthFirst,
+            "en" | "fr" | "es" | "it" | "pt" | "nl" | "el" => DateStyle::DayFirst,
+            "de" | "ru" | "pl" | "cs" | "uk" | "fi" | "nb" | "tr" => DateStyle::DayFirstDot,
+            _ => DateStyle::Iso,
+        };
+
+        let numeric_locale = locale_name(numeric);
+        let (group_separator, decimal_separator) = match numeric_locale.split('_').next().unwrap_or("") {
+            "zh" | "ja" | "ko" | "en" => (",", '.'),
+            "de" | "es" | "it" | "pt" | "nl" | "el" | "tr" => (".", ','),
+            // 法语等使用窄不换行空格分组
+            "fr" | "ru" | "pl" | "cs" | "uk" | "fi" | "nb" | "sv" => ("\u{202f}", ','),
+            _ => ("", '.'),
+        };
+
+        // glibc 不认识单独的 "CST"，会按 UTC 计算；中文环境下这通常是指中国标准时间
+        let china_time = tz.is_some_and(|tz| tz.trim_start_matches(':') == "CST") && language == "zh";
+        Self { date_style, group_separator, decimal_separator, china_time }
+    }
+
+    fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        let (all, time, numeric, lang) = (var("LC_ALL"), var("LC_TIME"), var("LC_NUMERIC"), var("LANG"));
+        let tz = var("TZ");
+        Self::from_env_values(
+            &[all.as_deref(), time.as_deref(), lang.as_deref()],
+            &[all.as_deref(), numeric.as_deref(), lang.as_deref()],
+            tz.as_deref(),
+        )
+    }
+
+    fn local_time(&self, time: SystemTime) -> DateTime<FixedOffset> {
+        let utc = DateTime::<Utc>::from(time);
+        match FixedOffset::east_opt(8 * 3600).filter(|_| self.china_time) {
+            Some(offset) => utc.with_timezone(&offset),
+            None => utc.with_timezone(&Local).fixed_offset(),
+        }
+    }
+
+    // 列表中使用的日期时间，各区域长度固定以便对齐
+    pub fn datetime(&self, time: SystemTime) -> String {
+        let pattern = match self.date_style {
+            DateStyle::Chinese => "%Y/%m/%d %H:%M",
+            DateStyle::MonthFirst => "%m/%d/%Y %I:%M %p",
+            DateStyle::DayFirst => "%d/%m/%Y %H:%M",
+            DateStyle::DayFirstDot => "%d.%m.%Y %H:%M",
+            DateStyle::Iso => "%Y-%m-%d %H:%M",
+        };
+        self.local_time(time).format(pattern).to_string()
+    }
+
+    // 属性中使用的完整日期时间，附带 UTC 偏移（CST 等缩写有歧义，不使用）
+    pub fn datetime_long(&self, time: SystemTime) -> String {
+        let pattern = match self.date_style {
+            DateStyle::Chinese => "%Y年%-m月%-d日 %H:%M:%S",
+            DateStyle::MonthFirst => "%m/%d/%Y %I:%M:%S %p",
+            DateStyle::DayFirst => "%d/%m/%Y %H:%M:%S",
+            DateStyle::DayFirstDot => "%d.%m.%Y %H:%M:%S",
+            DateStyle::Iso => "%Y-%m-%d %H:%M:%S",
+        };
+        let local = self.local_time(time);
+        format!("{} (UTC{})", local.format(pattern), local.format("%:z"))
+    }
+
+    // 按区域插入千位分隔符，如 1234567 → "1,234,567"
+    pub fn count(&self, value: u64) -> String {
+        let digits = value.to_string();
+        if self.group_separator.is_empty() {
+            return digits;
+        }
+        let mut result = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                result.push_str(self.group_separator);
+            }
+            result.push(digit);
+        }
+        result
+    }
+
+    // 文件大小，小数点按区域显示，如 "1.5 MB" 或 "1,5 MB"
+    pub fn size(&self, size: u64) -> String {
+        const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+        let mut size_f = size as f64;
+        let mut unit_index = 0;
+
+        while size_f >= 1024.0 && unit_index < UNITS.len() - 1 {
+            size_f /= 1024.0;
+            unit_index += 1;
+        }
+
+        if unit_index == 0 {
+            format!("{} {}", size, UNITS[unit_index])
+        } else {
+            format!("{:.1} {}", size_f, UNITS[unit_index]).replacen('.', &self.decimal_separator.to_string(), 1)
+        }
+    }
+}
+
+// 当前进程的区域格式，启动时按环境变量确定
+pub fn current() -> &'static LocaleFormat {
+    static FORMAT: OnceLock<LocaleFormat> = OnceLock::new();
+    FORMAT.get_or_init(LocaleFormat::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_formats() {
+        let chinese = LocaleFormat::from_env_values(&[None, Some("zh_CN.UTF-8")], &[Some("zh_CN.UTF-8")], Some("CST"));
+        assert_eq!((chinese.date_style, chinese.china_time), (DateStyle::Chinese, true));
+        assert_eq!(chinese.count(1234567), "1,234,567");
+        assert_eq!(chinese.size(1536), "1.5 KB");
+        // 2024-03-05 06:30:05 UTC 在 TZ=CST 时显示为北京时间
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_709_620_205);
+        assert_eq!(chinese.datetime(time), "2024/03/05 14:30");
+        assert_eq!(chinese.datetime_long(time), "2024年3月5日 14:30:05 (UTC+08:00)");
+
+        // LC_ALL 优先于 LC_TIME 和 LANG；数字格式可以单独设置
+        let german = LocaleFormat::from_env_values(&[Some("de_DE.UTF-8"), Some("en_US")], &[None, Some("de_DE"), Some("en_US")], Some("CST"));
+        assert_eq!((german.date_style, german.china_time), (DateStyle::DayFirstDot, false));
+        assert_eq!(german.count(1234), "1.234");
+        assert_eq!(german.size(1536), "1,5 KB");
+
+        let posix = LocaleFormat::from_env_values(&[Some("C")], &[None], None);
+        assert_eq!(posix.date_style, DateStyle::Iso);
+        assert_eq!(posix.count(1234), "1234");
+    }
+}
@@ -1,35 +1,59 @@
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use dirs;
-use super::file_operations::{FileOperations, FileOperationResult};
-use super::create_operations::generate_default_folder_name;
-use super::help::HelpSystem;
+use super::checksum::MANIFEST_NAME;
+use super::tags::TagColor;
+use super::file_list::{GroupBy, SelectionCommand, ViewMode};
+use super::context_menu::ContextAction;
+use super::app_event::{AppEvent, EventQueue, ToolCommand};
 
-pub fn show_menu_bar(
-    ui: &mut egui::Ui,
-    current_path: &mut PathBuf,
-    show_hidden: &mut bool,
-    file_operations: &mut FileOperations,
-    selected_file: &Option<PathBuf>,
-    help_system: &mut HelpSystem,
-    view_mode: &mut super::file_list::ViewMode,
-    show_drive_capacity: &mut bool,
-    show_capacity_size: &mut bool,
-) -> (bool, bool, bool, bool, bool) {
-    let mut needs_refresh = false;
-    let mut should_paste = false;
-    let mut should_rename = false;
-    let mut should_delete = false;
-    let mut should_create_folder = false;
+// 菜单栏显示所需的状态和直接勾选修改的视图选项；其它操作作为事件放入队列
+pub struct MenuBarState<'a> {
+    pub current_path: &'a Path,
+    pub selected_file: &'a Option<PathBuf>,
+    pub selected_tags: &'a [TagColor],
+    pub can_undo_photo_organize: bool,
+    pub show_hidden: &'a mut bool,
+    pub view_mode: &'a mut ViewMode,
+    pub group_by: &'a mut GroupBy,
+    pub sticky_group_header: &'a mut bool,
+    pub group_companions: &'a mut bool,
+    pub show_drive_capacity: &'a mut bool,
+    pub show_capacity_size: &'a mut bool,
+    pub show_jump_bar: &'a mut bool,
+    pub show_recent_strip: &'a mut bool,
+    pub directory_pane_auto_hide: &'a mut bool,
+    pub preview_pane_hidden: &'a mut bool,
+    pub compute_folder_sizes: &'a mut bool,
+}
 
+pub fn show_menu_bar(ui: &mut egui::Ui, state: MenuBarState, events: &mut EventQueue) {
+    let MenuBarState {
+        current_path,
+        selected_file,
+        selected_tags,
+        can_undo_photo_organize,
+        show_hidden,
+        view_mode,
+        group_by,
+        sticky_group_header,
+        group_companions,
+        show_drive_capacity,
+        show_capacity_size,
+        show_jump_bar,
+        show_recent_strip,
+        directory_pane_auto_hide,
+        preview_pane_hidden,
+        compute_folder_sizes,
+    } = state;
     egui::menu::bar(ui, |ui| {
         ui.menu_button("文件", |ui| {
             if ui.button("新建文件夹").clicked() {
-                should_create_folder = true;
+                events.emit(AppEvent::CreateFolder);
                 ui.close_menu();
             }
             if ui.button("刷新").clicked() {
-                // TODO: 实现刷新功能
+                events.emit(AppEvent::RefreshAll);
                 ui.close_menu();
             }
             ui.separator();
@@ -42,31 +66,19 @@ pub fn show_menu_bar(
             // 复制按钮
             if let Some(ref path) = selected_file {
                 if ui.button("复制").clicked() {
-                    file_operations.copy_to_clipboard(vec![path.clone()]);
+                    events.emit(AppEvent::FileAction(ContextAction::Copy(vec![path.clone()])));
                     ui.close_menu();
                 }
 
                 // 重命名按钮
                 if ui.button("重命名").clicked() {
-                    should_rename = true;
+                    events.emit(AppEvent::FileAction(ContextAction::Rename(path.clone())));
                     ui.close_menu();
                 }
 
                 // 删除按钮
                 if ui.button("删除").clicked() {
-                    if let Some(ref path) = selected_file {
-                        match file_operations.delete_files(&[path.clone()]) {
-                            FileOperationResult::NeedsConfirmation(_) => {
-                                should_delete = true;
-                            }
-                            FileOperationResult::Error(msg) => {
-                                eprintln!("删除错误: {}", msg);
-                            }
-                            FileOperationResult::Success => {
-                                // 这个情况不应该发生，删除总是需要确认
-                            }
-                        }
-                    }
+                    events.emit(AppEvent::FileAction(ContextAction::Delete(vec![path.clone()])));
                     ui.close_menu();
                 }
             } else {
@@ -76,40 +88,95 @@ pub fn show_menu_bar(
                 ui.add_enabled(false, egui::Button::new("删除"));
             }
 
+            // 标签子菜单：勾选/取消选中文件的颜色标签
+            ui.add_enabled_ui(selected_file.is_some(), |ui| {
+                ui.menu_button("标签", |ui| {
+                    let Some(path) = selected_file else {
+                        return;
+                    };
+                    for tag in TagColor::ALL {
+                        let mut checked = selected_tags.contains(&tag);
+                        let text = egui::RichText::new(format!("● {}", tag.label())).color(tag.color32());
+                        if ui.checkbox(&mut checked, text).clicked() {
+                            events.emit(AppEvent::ToggleTag(path.clone(), tag));
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("清除标签").clicked() {
+                        events.emit(AppEvent::ClearTags(path.clone()));
+                        ui.close_menu();
+                    }
+                });
+            });
+
             // 粘贴按钮（只要剪贴板有内容就可用）
             // 注意：这里简化处理，假设有剪贴板内容时就可用
             // 在实际使用中，你可能需要调用 file_operations.has_clipboard_content()
             if ui.button("粘贴").clicked() {
-                // 粘贴功能需要在主程序中处理，因为需要知道当前路径
-                should_paste = true;
+                events.emit(AppEvent::FileAction(ContextAction::Paste(current_path.to_path_buf())));
                 ui.close_menu();
             }
 
             ui.separator();
-            if ui.button("全选").clicked() {
-                // TODO: 实现全选功能
-                ui.close_menu();
+            // 选择命令由主程序交给内容框执行
+            for (command, label) in [
+                (SelectionCommand::SelectAll, "全选"),
+                (SelectionCommand::InvertSelection, "反向选择"),
+                (SelectionCommand::DeselectAll, "全部取消"),
+            ] {
+                if ui.button(label).clicked() {
+                    events.emit(AppEvent::Selection(command));
+                    ui.close_menu();
+                }
             }
         });
 
         ui.menu_button("查看", |ui| {
             if ui.checkbox(show_hidden, "显示隐藏文件").changed() {
-                needs_refresh = true;
+                events.emit(AppEvent::RefreshAll);
                 ui.close_menu();
             }
             ui.separator();
             if ui.button("详细信息").clicked() {
-                *view_mode = super::file_list::ViewMode::Details;
+                *view_mode = ViewMode::Details;
                 ui.close_menu();
             }
             if ui.button("大图标").clicked() {
-                *view_mode = super::file_list::ViewMode::LargeIcons;
+                *view_mode = ViewMode::LargeIcons;
                 ui.close_menu();
             }
             if ui.button("小图标").clicked() {
-                *view_mode = super::file_list::ViewMode::SmallIcons;
+                *view_mode = ViewMode::SmallIcons;
+                ui.close_menu();
+            }
+            if ui.button("缩略图").clicked() {
+                *view_mode = ViewMode::ThumbnailIcons;
                 ui.close_menu();
             }
+            if ui.button("平铺").clicked() {
+                *view_mode = ViewMode::Tiles;
+                ui.close_menu();
+            }
+            ui.menu_button("分组依据", |ui| {
+                for (value, label) in [
+                    (GroupBy::None, "不分组"),
+                    (GroupBy::Type, "类型"),
+                    (GroupBy::Modified, "修改日期"),
+                    (GroupBy::Letter, "首字母"),
+                    (GroupBy::Source, "下载来源"),
+                ] {
+                    if ui.radio_value(group_by, value, label).clicked() {
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui.checkbox(sticky_group_header, "固定当前分组标题").changed() {
+                    ui.close_menu();
+                }
+                if ui.checkbox(group_companions, "字幕等配套文件归到主文件下").changed() {
+                    ui.close_menu();
+                }
+            });
             ui.separator();
             if ui.checkbox(show_drive_capacity, "硬盘容量").changed() {
                 ui.close_menu();
@@ -117,54 +184,113 @@ pub fn show_menu_bar(
             if ui.checkbox(show_capacity_size, "容量大小").changed() {
                 ui.close_menu();
             }
+            if ui.checkbox(show_jump_bar, "跳转条").changed() {
+                ui.close_menu();
+            }
+            if ui.checkbox(show_recent_strip, "最近修改").changed() {
+                ui.close_menu();
+            }
+            if ui.checkbox(directory_pane_auto_hide, "自动隐藏目录栏 (F9)").changed() {
+                ui.close_menu();
+            }
+            if ui.checkbox(preview_pane_hidden, "隐藏预览栏 (F11)").changed() {
+                ui.close_menu();
+            }
+            if ui.checkbox(compute_folder_sizes, "计算文件夹大小").changed() {
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("选项...").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::OpenSettings));
+                ui.close_menu();
+            }
         });
 
         ui.menu_button("转到", |ui| {
+            if ui.button("起始页").clicked() {
+                events.emit(AppEvent::OpenStartPage);
+                ui.close_menu();
+            }
             if ui.button("主页").clicked() {
                 if let Some(home_dir) = dirs::home_dir() {
-                    *current_path = home_dir;
-                    needs_refresh = true;
+                    events.emit(AppEvent::Navigate(home_dir));
                 }
                 ui.close_menu();
             }
             if ui.button("桌面").clicked() {
                 if let Some(desktop_dir) = dirs::desktop_dir() {
-                    *current_path = desktop_dir;
-                    needs_refresh = true;
+                    events.emit(AppEvent::Navigate(desktop_dir));
                 }
                 ui.close_menu();
             }
             if ui.button("文档").clicked() {
                 if let Some(doc_dir) = dirs::document_dir() {
-                    *current_path = doc_dir;
-                    needs_refresh = true;
+                    events.emit(AppEvent::Navigate(doc_dir));
                 }
                 ui.close_menu();
             }
             if ui.button("下载").clicked() {
                 if let Some(download_dir) = dirs::download_dir() {
-                    *current_path = download_dir;
-                    needs_refresh = true;
+                    events.emit(AppEvent::Navigate(download_dir));
                 }
                 ui.close_menu();
             }
             ui.separator();
             if ui.button("上一级").clicked() {
-                if let Some(parent) = current_path.parent() {
-                    *current_path = parent.to_path_buf();
-                    needs_refresh = true;
-                }
+                events.emit(AppEvent::NavigateUp);
+                ui.close_menu();
+            }
+        });
+
+        ui.menu_button("工具", |ui| {
+            // 选中文件夹时作用于该文件夹，否则作用于当前目录
+            let target_dir = selected_file.as_ref().filter(|p| p.is_dir()).cloned().unwrap_or_else(|| current_path.to_path_buf());
+            if ui.button("生成校验清单 (SHA256SUMS)").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::GenerateChecksums(target_dir.clone())));
+                ui.close_menu();
+            }
+            if ui.button("按清单校验文件夹").clicked() {
+                // 选中的文件视为清单，否则使用目标文件夹中的 SHA256SUMS
+                let manifest = selected_file.as_ref().filter(|p| p.is_file()).cloned().unwrap_or_else(|| target_dir.join(MANIFEST_NAME));
+                events.emit(AppEvent::Tool(ToolCommand::VerifyChecksums(manifest)));
+                ui.close_menu();
+            }
+            if ui.button("查找重复文件...").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::FindDuplicates(target_dir.clone())));
+                ui.close_menu();
+            }
+            if ui.button("查找断开的符号链接...").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::FindBrokenLinks(current_path.to_path_buf())));
+                ui.close_menu();
+            }
+            if ui.button("磁盘使用分析...").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::AnalyzeDiskUsage(target_dir.clone())));
+                ui.close_menu();
+            }
+            if ui.button("大文件和旧文件报告...").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::FileReport(current_path.to_path_buf())));
+                ui.close_menu();
+            }
+            if ui.button("暂存篮...").clicked() {
+                events.emit(AppEvent::OpenBasket);
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("整理照片到日期文件夹...").clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::OrganizePhotos(current_path.to_path_buf())));
+                ui.close_menu();
+            }
+            if ui.add_enabled(can_undo_photo_organize, egui::Button::new("撤销照片整理")).clicked() {
+                events.emit(AppEvent::Tool(ToolCommand::UndoPhotoOrganize));
                 ui.close_menu();
             }
         });
 
         ui.menu_button("帮助", |ui| {
             if ui.button("关于").clicked() {
-                help_system.show_about();
+                events.emit(AppEvent::Tool(ToolCommand::ShowAbout));
                 ui.close_menu();
             }
         });
     });
-
-    (needs_refresh, should_paste, should_rename, should_delete, should_create_folder)
 }
\ No newline at end of file
@@ -10,6 +10,68 @@ pub mod icon_manager;
 pub mod app_icon;
 pub mod drive_bar;
 pub mod thumbnail_view;
+pub mod storage_profile;
+pub mod folder_view_settings;
+pub mod folder_size;
+pub mod settings;
+pub mod checksum;
+pub mod tags;
+pub mod photo_organizer;
+pub mod duplicate_finder;
+pub mod broken_links;
+pub mod file_report;
+pub mod confirmation;
+pub mod status_bar;
+pub mod quick_jump;
+pub mod folder_icon;
+pub mod album_preview;
+pub mod context_menu;
+pub mod properties;
+pub mod open_with;
+pub mod video_thumbnail;
+pub mod file_metadata;
+pub mod multi_copy;
+pub mod prefetch;
+pub mod frame_budget;
+pub mod preview_handlers;
+pub mod app_event;
+pub mod audio_player;
+pub mod compare;
+pub mod image_formats;
+pub mod raw_image;
+pub mod archive_preview;
+pub mod office_preview;
+pub mod icon_recolor;
+pub mod image_viewer;
+pub mod companions;
+pub mod project_types;
+pub mod gitignore;
+pub mod text_encoding;
+pub mod start_page;
+pub mod file_hashes;
+pub mod desktop_entry;
+pub mod elf_info;
+pub mod text_viewer;
+pub mod text_elide;
+pub mod locale_format;
+pub mod operation_progress;
+pub mod trash;
+pub mod system_clipboard;
+pub mod elevated;
+pub mod create_link;
+pub mod archive_create;
+pub mod archive_extract;
+pub mod hash_verify;
+pub mod disk_usage;
+pub mod mouse_gesture;
+pub mod folder_breakdown;
+pub mod qr_code;
+pub mod screenshot_inbox;
+pub mod downloads;
+pub mod basket;
+pub mod bookmarks;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod uring_copy;
 
 pub use file_list::*;
 pub use preview::*;
@@ -21,4 +83,35 @@ pub use create_operations::*;
 pub use help::*;
 pub use icon_manager::*;
 pub use drive_bar::*;
-pub use thumbnail_view::*;
\ No newline at end of file
+pub use thumbnail_view::*;
+pub use folder_view_settings::*;
+pub use settings::*;
+pub use checksum::*;
+pub use tags::{TagColor, TagStore};
+pub use photo_organizer::PhotoOrganizer;
+pub use duplicate_finder::DuplicateFinder;
+pub use broken_links::BrokenLinkFinder;
+pub use file_report::{FileReport, ReportAction};
+pub use confirmation::{ConfirmDialog, PendingOperation};
+pub use status_bar::ListSummary;
+pub use quick_jump::QuickJump;
+pub use context_menu::ContextAction;
+pub use properties::PropertiesDialog;
+pub use open_with::OpenWithDialog;
+pub use multi_copy::MultiCopyDialog;
+pub use app_event::{AppEvent, EventQueue, ToolCommand};
+pub use screenshot_inbox::ScreenshotInbox;
+pub use downloads::GroupMoveDialog;
+pub use basket::FileBasket;
+pub use bookmarks::BookmarkSlots;
+pub use image_viewer::ImageViewer;
+pub use companions::{CompanionChoice, CompanionDialog};
+pub use start_page::StartPage;
+pub use text_viewer::TextViewer;
+pub use operation_progress::OperationProgressDialog;
+pub use create_link::CreateLinkDialog;
+pub use archive_create::CompressDialog;
+pub use archive_extract::ExtractDialog;
+pub use hash_verify::HashVerifyDialog;
+pub use disk_usage::DiskUsageAnalyzer;
+pub use elevated::{ElevatedRetryDialog, ElevatedTask};
\ No newline at end of file
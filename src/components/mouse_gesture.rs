@@ -0,0 +1,257 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+// 每一段笔画至少移动的距离，较短的抖动不计
+const MIN_SEGMENT: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GestureDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl GestureDirection {
+    fn arrow(self) -> &'static str {
+        match self {
+            GestureDirection::Left => "←",
+            GestureDirection::Right => "→",
+            GestureDirection::Up => "↑",
+            GestureDirection::Down => "↓",
+        }
+    }
+}
+
+// 手势可以触发的导航动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GestureAction {
+    Back,    // 后退（目录框没有历史，与上级目录相同）
+    Forward, // 前进
+    Up,      // 上级目录
+    Refresh, // 刷新
+}
+
+impl GestureAction {
+    pub const ALL: [GestureAction; 4] = [GestureAction::Back, GestureAction::Forward, GestureAction::Up, GestureAction::Refresh];
+
+    fn label(self) -> &'static str {
+        match self {
+            GestureAction::Back => "后退",
+            GestureAction::Forward => "前进",
+            GestureAction::Up => "上级目录",
+            GestureAction::Refresh => "刷新",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GestureBinding {
+    pub stroke: Vec<GestureDirection>,
+    pub action: GestureAction,
+}
+
+// 鼠标手势设置：按住右键拖动画出笔画
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GestureSettings {
+    pub enabled: bool,
+    pub bindings: Vec<GestureBinding>,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bindings: vec![
+                GestureBinding { stroke: vec![GestureDirection::Left], action: GestureAction::Back },
+                GestureBinding { stroke: vec![GestureDirection::Up], action: GestureAction::Up },
+                GestureBinding { stroke: vec![GestureDirection::Down], action: GestureAction::Refresh },
+            ],
+        }
+    }
+}
+
+impl GestureSettings {
+    // 笔画对应的动作
+    pub fn action_for(&self, stroke: &[GestureDirection]) -> Option<GestureAction> {
+        self.bindings.iter().find(|binding| binding.stroke == stroke).map(|binding| binding.action)
+    }
+
+    fn stroke_of(&self, action: GestureAction) -> Option<&[GestureDirection]> {
+        self.bindings.iter().find(|binding| binding.action == action).map(|binding| binding.stroke.as_slice())
+    }
+
+    // 为动作设置新笔画；其它动作原来使用同一笔画的解除绑定
+    fn bind(&mut self, action: GestureAction, stroke: Vec<GestureDirection>) {
+        self.bindings.retain(|binding| binding.action != action && binding.stroke != stroke);
+        self.bindings.push(GestureBinding { stroke, action });
+    }
+}
+
+fn stroke_text(stroke: &[GestureDirection]) -> String {
+    stroke.iter().map(|direction| direction.arrow()).collect::<Vec<_>>().join(" ")
+}
+
+// 把指针轨迹拆成方向序列：每移动够一段按主要方向记一笔，与上一笔相同时合并
+fn stroke_directions(points: &[egui::Pos2]) -> Vec<GestureDirection> {
+    let mut directions: Vec<GestureDirection> = Vec::new();
+    let Some(mut anchor) = points.first().copied() else {
+        return directions;
+    };
+    for point in &points[1..] {
+        let delta = *point - anchor;
+        if delta.length() < MIN_SEGMENT {
+            continue;
+        }
+        let direction = if delta.x.abs() > delta.y.abs() {
+            if delta.x < 0.0 { GestureDirection::Left } else { GestureDirection::Right }
+        } else if delta.y < 0.0 {
+            GestureDirection::Up
+        } else {
+            GestureDirection::Down
+        };
+        if directions.last() != Some(&direction) {
+            directions.push(direction);
+        }
+        anchor = *point;
+    }
+    directions
+}
+
+// 绘制手势轨迹，并在末端显示已识别的方向
+fn draw_trail(painter: &egui::Painter, points: &[egui::Pos2], color: egui::Color32) {
+    if points.len() < 2 {
+        return;
+    }
+    painter.add(egui::Shape::line(points.to_vec(), egui::Stroke::new(3.0, color)));
+    let directions = stroke_directions(points);
+    if let (Some(end), false) = (points.last(), directions.is_empty()) {
+        painter.text(*end + egui::vec2(12.0, -12.0), egui::Align2::LEFT_BOTTOM, stroke_text(&directions), egui::FontId::proportional(18.0), color);
+    }
+}
+
+// 在窗口中识别右键拖动的手势；只在没有被对话框等窗口遮挡的区域内开始
+#[derive(Default)]
+pub struct GestureTracker {
+    trail: Vec<egui::Pos2>,
+    active: bool,
+}
+
+impl GestureTracker {
+    // 每帧调用；松开右键时返回识别出的笔画和起点
+    pub fn update(&mut self, ctx: &egui::Context, area: egui::Rect) -> Option<(Vec<GestureDirection>, egui::Pos2)> {
+        let (pressed, down, released, pos) = ctx.input(|i| (
+            i.pointer.button_pressed(egui::PointerButton::Secondary),
+            i.pointer.button_down(egui::PointerButton::Secondary),
+            i.pointer.button_released(egui::PointerButton::Secondary),
+            i.pointer.latest_pos(),
+        ));
+        if pressed {
+            let on_panel = |pos: egui::Pos2| ctx.layer_id_at(pos).is_none_or(|layer| layer.order == egui::Order::Background);
+            self.active = pos.is_some_and(|pos| area.contains(pos) && on_panel(pos));
+            self.trail = pos.into_iter().filter(|_| self.active).collect();
+        }
+        if !self.active {
+            return None;
+        }
+        if down {
+            if let Some(pos) = pos.filter(|pos| self.trail.last() != Some(pos)) {
+                self.trail.push(pos);
+            }
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("mouse_gesture_trail")));
+            draw_trail(&painter, &self.trail, ctx.style().visuals.selection.stroke.color);
+        }
+        if released || !down {
+            self.active = false;
+            let trail = std::mem::take(&mut self.trail);
+            let directions = stroke_directions(&trail);
+            return (!directions.is_empty()).then(|| (directions, trail[0]));
+        }
+        None
+    }
+}
+
+// 设置中的手势录制器：点击“录制”后在下方区域按住鼠标画出笔画
+#[derive(Default)]
+pub struct GestureRecorder {
+    recording: Option<GestureAction>,
+    trail: Vec<egui::Pos2>,
+}
+
+impl GestureRecorder {
+    pub fn show(&mut self, ui: &mut egui::Ui, settings: &mut GestureSettings) {
+        ui.checkbox(&mut settings.enabled, "启用鼠标手势（在内容框或目录栏中按住右键拖动）");
+        ui.add_enabled_ui(settings.enabled, |ui| {
+            egui::Grid::new("mouse_gesture_grid").num_columns(3).show(ui, |ui| {
+                for action in GestureAction::ALL {
+                    ui.label(action.label());
+                    match settings.stroke_of(action) {
+                        Some(stroke) => ui.monospace(stroke_text(stroke)),
+                        None => ui.weak("未设置"),
+                    };
+                    ui.horizontal(|ui| {
+                        let recording = self.recording == Some(action);
+                        if ui.selectable_label(recording, if recording { "录制中…" } else { "录制" }).clicked() {
+                            self.recording = if recording { None } else { Some(action) };
+                            self.trail.clear();
+                        }
+                        if settings.stroke_of(action).is_some() && ui.small_button("清除").clicked() {
+                            settings.bindings.retain(|binding| binding.action != action);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+            if let Some(action) = self.recording {
+                self.show_pad(ui, settings, action);
+            }
+        });
+    }
+
+    // 录制区域：左键或右键拖动均可，松开后保存笔画
+    fn show_pad(&mut self, ui: &mut egui::Ui, settings: &mut GestureSettings, action: GestureAction) {
+        ui.label(format!("在下方区域画出“{}”的手势", action.label()));
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(320.0, 160.0), egui::Sense::drag());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+        if response.drag_started() {
+            self.trail.clear();
+        }
+        if response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.trail.push(pos);
+            }
+        }
+        draw_trail(&painter, &self.trail, ui.visuals().selection.stroke.color);
+        if response.drag_stopped() {
+            let stroke = stroke_directions(&std::mem::take(&mut self.trail));
+            if !stroke.is_empty() {
+                settings.bind(action, stroke);
+                self.recording = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stroke_directions() {
+        let points: Vec<egui::Pos2> = [(100.0, 100.0), (90.0, 102.0), (60.0, 105.0), (40.0, 104.0)].iter().map(|&(x, y)| egui::pos2(x, y)).collect();
+        assert_eq!(stroke_directions(&points), vec![GestureDirection::Left]);
+        // 先下后右
+        let points: Vec<egui::Pos2> = [(0.0, 0.0), (2.0, 40.0), (3.0, 80.0), (45.0, 82.0), (90.0, 80.0)].iter().map(|&(x, y)| egui::pos2(x, y)).collect();
+        assert_eq!(stroke_directions(&points), vec![GestureDirection::Down, GestureDirection::Right]);
+        // 抖动不算手势
+        assert!(stroke_directions(&[egui::pos2(0.0, 0.0), egui::pos2(5.0, 3.0)]).is_empty());
+
+        let mut settings = GestureSettings::default();
+        assert_eq!(settings.action_for(&[GestureDirection::Up]), Some(GestureAction::Up));
+        settings.bind(GestureAction::Forward, vec![GestureDirection::Up]);
+        assert_eq!(settings.action_for(&[GestureDirection::Up]), Some(GestureAction::Forward));
+        assert_eq!(settings.stroke_of(GestureAction::Up), None);
+    }
+}
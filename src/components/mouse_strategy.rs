@@ -1,12 +1,46 @@
+use eframe::egui;
 use std::path::PathBuf;
 use std::process::Command;
+use serde::{Deserialize, Serialize};
+
+// 打开文件/文件夹的鼠标操作
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ClickOpenMode {
+    #[default]
+    DoubleClick, // 单击选择，双击打开
+    SingleClick, // 单击打开，Ctrl+单击选择
+}
 
 // 鼠标双击策略
-pub struct MouseDoubleClickStrategy;
+pub struct MouseDoubleClickStrategy {
+    open_mode: ClickOpenMode,
+}
 
 impl MouseDoubleClickStrategy {
     pub fn new() -> Self {
-        Self
+        Self { open_mode: ClickOpenMode::DoubleClick }
+    }
+
+    pub fn set_open_mode(&mut self, mode: ClickOpenMode) {
+        self.open_mode = mode;
+    }
+
+    pub fn is_single_click(&self) -> bool {
+        self.open_mode == ClickOpenMode::SingleClick
+    }
+
+    // 按当前模式判断这次点击是否应打开条目（单击模式下带修饰键的单击用于选择）。
+    // 单击模式下习惯性的双击只在第一次单击时打开，第二次单击不再重复打开
+    pub fn is_open_click(&self, ui: &egui::Ui, response: &egui::Response) -> bool {
+        match self.open_mode {
+            ClickOpenMode::DoubleClick => response.double_clicked(),
+            ClickOpenMode::SingleClick => {
+                response.clicked()
+                    && !response.double_clicked()
+                    && !response.triple_clicked()
+                    && !ui.input(|i| i.modifiers.command || i.modifiers.shift)
+            }
+        }
     }
     
     // 处理文件双击事件
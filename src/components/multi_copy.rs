@@ -0,0 +1,504 @@
+use eframe::egui;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver, Sender};
+use sha2::{Digest, Sha256};
+use sysinfo::Disks;
+use crate::utils;
+use super::checksum::sha256_file;
+
+const BUFFER_SIZE: usize = 1024 * 1024;
+
+// 后台复制线程发送的事件
+enum FanOutEvent {
+    Total(u64), // 展开源文件夹后得到的总字节数
+    Progress { read: u64, written: Vec<u64>, current: String },
+    DestinationFailed(usize, String),
+    Finished(Result<Vec<Option<String>>, String>), // 每个目标的错误（None 表示成功）
+}
+
+// 待复制的一项：源路径和在目标中的相对路径
+struct CopyEntry {
+    source: PathBuf,
+    relative: Vec<PathBuf>, // 每个目标各自的相对路径（顶层名称可能因重名而不同）
+    is_dir: bool,
+    size: u64,
+}
+
+// 目标中已有同名项时追加序号，如 "report (1).pdf"
+fn unique_name(dest: &Path, name: &str) -> PathBuf {
+    let path = Path::new(name);
+    if !dest.join(path).exists() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| PathBuf::from(format!("{} ({}){}", stem, n, ext)))
+        .find(|candidate| !dest.join(candidate).exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+// 展开源路径为文件夹和文件列表（文件夹在其内容之前），不进入符号链接指向的文件夹
+fn collect_entries(sources: &[PathBuf], destinations: &[PathBuf]) -> Vec<CopyEntry> {
+    let mut entries = Vec::new();
+    for source in sources {
+        let Some(name) = source.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let tops: Vec<PathBuf> = destinations.iter().map(|dest| unique_name(dest, &name)).collect();
+        let mut pending = vec![(source.clone(), PathBuf::new())];
+        while let Some((path, relative)) = pending.pop() {
+            let relative_for = |top: &PathBuf| top.join(&relative);
+            let Ok(link_metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if link_metadata.is_dir() {
+                entries.push(CopyEntry { source: path.clone(), relative: tops.iter().map(relative_for).collect(), is_dir: true, size: 0 });
+                if let Ok(children) = fs::read_dir(&path) {
+                    let mut children: Vec<_> = children.flatten().collect();
+                    children.sort_by_key(|c| std::cmp::Reverse(c.file_name()));
+                    pending.extend(children.into_iter().map(|c| (c.path(), relative.join(c.file_name()))));
+                }
+            } else if let Some(metadata) = fs::metadata(&path).ok().filter(|m| m.is_file()) {
+                entries.push(CopyEntry { source: path.clone(), relative: tops.iter().map(relative_for).collect(), is_dir: false, size: metadata.len() });
+            }
+        }
+    }
+    entries
+}
+
+// 读取一次源文件，同时写入所有仍有效的目标；返回源文件内容的 SHA256
+fn fan_out_file(
+    source: &Path,
+    targets: &mut [Option<(PathBuf, File)>],
+    read: &mut u64,
+    written: &mut [u64],
+    on_progress: &mut dyn FnMut(u64, &[u64]),
+    on_error: &mut dyn FnMut(usize, String),
+    cancelled: &AtomicBool,
+) -> io::Result<String> {
+    let mut file = File::open(source)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "已取消"));
+        }
+        let count = file.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[..count]);
+        *read += count as u64;
+        for (index, slot) in targets.iter_mut().enumerate() {
+            let Some((target, out)) = slot else {
+                continue;
+            };
+            match out.write_all(&buffer[..count]) {
+                Ok(()) => written[index] += count as u64,
+                Err(e) => {
+                    on_error(index, format!("写入 {} 失败: {}", target.display(), e));
+                    *slot = None;
+                }
+            }
+        }
+        on_progress(*read, written);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// 删除取消时未复制完的文件或文件夹
+fn remove_partial(path: &Path) {
+    let result = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(_) => return,
+    };
+    if let Err(e) = result {
+        eprintln!("删除未完成的副本 {} 失败: {}", path.display(), e);
+    }
+}
+
+// 把 sources 复制到每个目标文件夹；某个目标出错时只停止该目标，其余继续。
+// 取消时删除正在复制的顶层项目在各目标中的不完整副本
+fn run_fan_out(sources: &[PathBuf], destinations: &[PathBuf], verify: bool, sender: &Sender<FanOutEvent>, cancelled: &AtomicBool) -> Result<Vec<Option<String>>, String> {
+    let entries = collect_entries(sources, destinations);
+    let _ = sender.send(FanOutEvent::Total(entries.iter().map(|e| e.size).sum()));
+    let mut partial: Vec<PathBuf> = Vec::new();
+    let cancel = |partial: &[PathBuf]| {
+        partial.iter().for_each(|path| remove_partial(path));
+        Err("操作已取消".to_string())
+    };
+    let mut errors: Vec<Option<String>> = vec![None; destinations.len()];
+    let mut read = 0u64;
+    let mut written = vec![0u64; destinations.len()];
+
+    let fail = |errors: &mut Vec<Option<String>>, index: usize, message: String| {
+        if errors[index].is_none() {
+            let _ = sender.send(FanOutEvent::DestinationFailed(index, message.clone()));
+            errors[index] = Some(message);
+        }
+    };
+
+    for entry in &entries {
+        if cancelled.load(Ordering::Relaxed) {
+            return cancel(&partial);
+        }
+        let live: Vec<usize> = (0..destinations.len()).filter(|&i| errors[i].is_none()).collect();
+        if live.is_empty() {
+            break;
+        }
+        let target_of = |i: usize| destinations[i].join(&entry.relative[i]);
+        if sources.contains(&entry.source) {
+            partial = live.iter().map(|&i| target_of(i)).collect();
+        }
+
+        if entry.is_dir {
+            for &i in &live {
+                if let Err(e) = fs::create_dir_all(target_of(i)) {
+                    fail(&mut errors, i, format!("创建文件夹 {} 失败: {}", target_of(i).display(), e));
+                }
+            }
+            continue;
+        }
+
+        let current = entry.source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let mut targets: Vec<Option<(PathBuf, File)>> = (0..destinations.len()).map(|_| None).collect();
+        for &i in &live {
+            match File::create(target_of(i)) {
+                Ok(file) => targets[i] = Some((target_of(i), file)),
+                Err(e) => fail(&mut errors, i, format!("创建 {} 失败: {}", target_of(i).display(), e)),
+            }
+        }
+
+        let mut write_errors = Vec::new();
+        let hash = fan_out_file(
+            &entry.source,
+            &mut targets,
+            &mut read,
+            &mut written,
+            &mut |read, written| {
+                let _ = sender.send(FanOutEvent::Progress { read, written: written.to_vec(), current: current.clone() });
+            },
+            &mut |i, message| write_errors.push((i, message)),
+            cancelled,
+        );
+        for (i, message) in write_errors {
+            fail(&mut errors, i, message);
+        }
+        let hash = match hash {
+            Ok(hash) => hash,
+            Err(_) if cancelled.load(Ordering::Relaxed) => return cancel(&partial),
+            Err(e) => return Err(format!("读取 {} 失败: {}", entry.source.display(), e)),
+        };
+        // 关闭文件后再校验，保证数据已写入
+        let finished: Vec<usize> = targets.iter().enumerate().filter(|(_, t)| t.is_some()).map(|(i, _)| i).collect();
+        drop(targets);
+        if verify {
+            for i in finished {
+                match sha256_file(&target_of(i), cancelled) {
+                    Ok(actual) if actual == hash => {}
+                    Ok(_) => fail(&mut errors, i, format!("校验不一致: {}", target_of(i).display())),
+                    Err(_) if cancelled.load(Ordering::Relaxed) => return cancel(&partial),
+                    Err(e) => fail(&mut errors, i, format!("校验 {} 失败: {}", target_of(i).display(), e)),
+                }
+            }
+        }
+    }
+    Ok(errors)
+}
+
+// 复制任务的进度
+struct FanOutJob {
+    receiver: Receiver<FanOutEvent>,
+    cancelled: Arc<AtomicBool>,
+    total: u64,
+    read: u64,
+    written: Vec<u64>,
+    failed: Vec<Option<String>>,
+    current: String,
+}
+
+impl Drop for FanOutJob {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// "复制到多个位置" 窗口：每个源文件只读取一次，同时写入所有目标文件夹
+pub struct MultiCopyDialog {
+    sources: Vec<PathBuf>,
+    destinations: Vec<PathBuf>,
+    new_destination: String,
+    mount_points: Vec<PathBuf>,
+    verify: bool,
+    error: Option<String>,
+    job: Option<FanOutJob>,
+    result: Option<Result<Vec<Option<String>>, String>>,
+}
+
+impl MultiCopyDialog {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            destinations: Vec::new(),
+            new_destination: String::new(),
+            mount_points: Vec::new(),
+            verify: true,
+            error: None,
+            job: None,
+            result: None,
+        }
+    }
+
+    pub fn open(&mut self, sources: Vec<PathBuf>) {
+        self.sources = sources;
+        self.new_destination.clear();
+        self.error = None;
+        self.result = None;
+        // 已挂载的磁盘可一键添加为目标
+        let disks = Disks::new_with_refreshed_list();
+        self.mount_points = disks.list().iter().map(|d| d.mount_point().to_path_buf()).collect();
+        self.mount_points.sort();
+        self.mount_points.dedup();
+    }
+
+    pub fn is_showing(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    fn add_destination(&mut self, path: PathBuf) {
+        self.error = None;
+        if !path.is_dir() {
+            self.error = Some(format!("不是文件夹: {}", path.display()));
+        } else if self.sources.iter().any(|s| path.starts_with(s)) {
+            self.error = Some("不能复制到源文件夹自身或其子文件夹中".to_string());
+        } else if !self.destinations.contains(&path) {
+            self.destinations.push(path);
+        }
+    }
+
+    fn start(&mut self) {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sources, destinations, verify) = (self.sources.clone(), self.destinations.clone(), self.verify);
+
+        let cancel_flag = cancelled.clone();
+        std::thread::spawn(move || {
+            let result = run_fan_out(&sources, &destinations, verify, &sender, &cancel_flag);
+            let _ = sender.send(FanOutEvent::Finished(result));
+        });
+
+        self.job = Some(FanOutJob {
+            receiver,
+            cancelled,
+            total: 0,
+            read: 0,
+            written: vec![0; self.destinations.len()],
+            failed: vec![None; self.destinations.len()],
+            current: String::new(),
+        });
+    }
+
+    // 显示窗口；返回 true 表示复制刚结束（需要刷新列表）
+    pub fn show_multi_copy_dialog(&mut self, ctx: &egui::Context) -> bool {
+        let mut finished = false;
+        if let Some(job) = &mut self.job {
+            for event in job.receiver.try_iter() {
+                match event {
+                    FanOutEvent::Total(total) => job.total = total,
+                    FanOutEvent::Progress { read, written, current } => {
+                        job.read = read;
+                        job.written = written;
+                        job.current = current;
+                    }
+                    FanOutEvent::DestinationFailed(index, message) => job.failed[index] = Some(message),
+                    FanOutEvent::Finished(result) => {
+                        finished = true;
+                        self.result = Some(result);
+                    }
+                }
+            }
+            if finished {
+                self.job = None;
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+
+        let mut open = true;
+        let mut close = false;
+        let mut start = false;
+        let mut add: Option<PathBuf> = None;
+        egui::Window::new("复制到多个位置")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(460.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let source_label = match self.sources.as_slice() {
+                    [single] => single.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    sources => format!("{} 项", sources.len()),
+                };
+                ui.label(format!("复制: {}", source_label));
+                ui.separator();
+
+                if let Some(job) = &self.job {
+                    let fraction = |done: u64| if job.total == 0 { 0.0 } else { done as f32 / job.total as f32 };
+                    ui.label(format!("读取 {} / {}", utils::get_file_size_str(job.read), utils::get_file_size_str(job.total)));
+                    ui.add(egui::ProgressBar::new(fraction(job.read)));
+                    for (index, dest) in self.destinations.iter().enumerate() {
+                        ui.label(dest.display().to_string());
+                        match &job.failed[index] {
+                            Some(message) => {
+                                ui.colored_label(ui.visuals().error_fg_color, message);
+                            }
+                            None => {
+                                ui.add(egui::ProgressBar::new(fraction(job.written[index])).show_percentage());
+                            }
+                        }
+                    }
+                    ui.label(egui::RichText::new(&job.current).weak());
+                    ui.separator();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("取消").clicked() {
+                            job.cancelled.store(true, Ordering::Relaxed);
+                        }
+                    });
+                } else if let Some(result) = &self.result {
+                    match result {
+                        Ok(errors) => {
+                            for (dest, error) in self.destinations.iter().zip(errors) {
+                                match error {
+                                    None => ui.label(format!("✔ {}", dest.display())),
+                                    Some(message) => ui.colored_label(ui.visuals().error_fg_color, format!("✖ {}: {}", dest.display(), message)),
+                                };
+                            }
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e);
+                        }
+                    }
+                    ui.separator();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("确定").clicked() {
+                            close = true;
+                        }
+                    });
+                } else {
+                    ui.label("目标文件夹:");
+                    let mut remove = None;
+                    for (index, dest) in self.destinations.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✖").on_hover_text("移除").clicked() {
+                                remove = Some(index);
+                            }
+                            ui.label(dest.display().to_string());
+                        });
+                    }
+                    if let Some(index) = remove {
+                        self.destinations.remove(index);
+                    }
+                    ui.horizontal(|ui| {
+                        let response = ui.add(egui::TextEdit::singleline(&mut self.new_destination).hint_text("输入文件夹路径").desired_width(300.0));
+                        let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if (ui.button("添加").clicked() || entered) && !self.new_destination.trim().is_empty() {
+                            add = Some(PathBuf::from(self.new_destination.trim()));
+                        }
+                    });
+                    if !self.mount_points.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("磁盘:");
+                            for mount in &self.mount_points {
+                                if ui.small_button(mount.display().to_string()).clicked() {
+                                    add = Some(mount.clone());
+                                }
+                            }
+                        });
+                    }
+                    if let Some(error) = &self.error {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    ui.checkbox(&mut self.verify, "写入后校验 (SHA-256)");
+                    ui.separator();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("取消").clicked() {
+                            close = true;
+                        }
+                        if ui.add_enabled(!self.destinations.is_empty(), egui::Button::new("开始复制")).clicked() {
+                            start = true;
+                        }
+                    });
+                }
+            });
+
+        if let Some(path) = add {
+            self.add_destination(path);
+            if self.error.is_none() {
+                self.new_destination.clear();
+            }
+        }
+        if start {
+            self.start();
+        }
+        if !open || close {
+            // 释放任务即取消
+            self.job = None;
+            self.result = None;
+            self.sources.clear();
+            self.destinations.clear();
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_fan_out_to_two_destinations() {
+        let root = TestDir::new("fan_out");
+        let source = root.join("src");
+        let (a, b) = (root.join("a"), root.join("b"));
+        fs::create_dir_all(source.join("sub")).unwrap();
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(source.join("one.txt"), "hello").unwrap();
+        fs::write(source.join("sub").join("two.txt"), "world").unwrap();
+        // b 中已有同名文件夹，复制结果改名为 "src (1)"
+        fs::create_dir_all(b.join("src")).unwrap();
+
+        let (sender, _receiver) = crossbeam_channel::unbounded();
+        let cancelled = AtomicBool::new(false);
+        let errors = run_fan_out(std::slice::from_ref(&source), &[a.clone(), b.clone()], true, &sender, &cancelled).unwrap();
+        assert_eq!(errors, vec![None, None]);
+        assert_eq!(fs::read_to_string(a.join("src").join("sub").join("two.txt")).unwrap(), "world");
+        assert_eq!(fs::read_to_string(b.join("src (1)").join("one.txt")).unwrap(), "hello");
+
+        // 复制中途取消：收到第一次进度后取消，删除不完整的副本
+        fs::write(source.join("big.bin"), vec![0u8; 3 * BUFFER_SIZE]).unwrap();
+        let c = root.join("c");
+        fs::create_dir_all(&c).unwrap();
+        let (sender, receiver) = crossbeam_channel::bounded(0);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        let canceller = std::thread::spawn(move || {
+            for event in receiver {
+                if matches!(event, FanOutEvent::Progress { .. }) {
+                    flag.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+        assert!(run_fan_out(std::slice::from_ref(&source), std::slice::from_ref(&c), false, &sender, &cancelled).is_err());
+        drop(sender);
+        canceller.join().unwrap();
+        assert!(!c.join("src").exists());
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+// 文档文字最多提取的字符数
+const MAX_TEXT_CHARS: usize = 100_000;
+// 表格预览只显示第一个工作表的前若干行、列
+pub const MAX_ROWS: usize = 100;
+pub const MAX_COLUMNS: usize = 26;
+// 读取的单个 XML 文件大小上限，防止压缩炸弹
+const MAX_XML_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OfficeFormat {
+    Docx,
+    Odt,
+    Xlsx,
+}
+
+impl OfficeFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "docx" => Some(Self::Docx),
+            "odt" => Some(Self::Odt),
+            "xlsx" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+}
+
+pub enum OfficeContent {
+    Text { text: String, truncated: bool },
+    Sheet { name: String, rows: Vec<Vec<String>>, truncated: bool },
+}
+
+pub fn is_office_file(path: &Path) -> bool {
+    OfficeFormat::from_path(path).is_some()
+}
+
+type OfficeArchive = zip::ZipArchive<BufReader<fs::File>>;
+
+fn read_entry(archive: &mut OfficeArchive, name: &str) -> Result<String, String> {
+    let entry = archive.by_name(name).map_err(|_| format!("文档中缺少 {}", name))?;
+    let mut xml = String::new();
+    entry.take(MAX_XML_SIZE).read_to_string(&mut xml).map_err(|e| e.to_string())?;
+    Ok(xml)
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    let value = element.try_get_attribute(name).ok()??;
+    value.unescape_value().ok().map(|v| v.to_string())
+}
+
+// 段落文字：docx 的 w:t 与 odt 的 text:p / text:h 中的文字，段落之间换行
+fn document_text(xml: &str, format: OfficeFormat) -> (String, bool) {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    // docx 只有 w:t 中的是正文；odt 正文中的文字都在段落或标题中
+    let mut depth = 0usize;
+    loop {
+        let event = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(event) => event,
+        };
+        match (format, event) {
+            (OfficeFormat::Docx, Event::Start(e)) if e.local_name().as_ref() == b"t" => depth += 1,
+            (OfficeFormat::Docx, Event::End(e)) if e.local_name().as_ref() == b"t" => depth = depth.saturating_sub(1),
+            (OfficeFormat::Docx, Event::Empty(e)) => match e.local_name().as_ref() {
+                // 段落属性中的制表位定义也叫 w:tab，带有 w:val
+                b"tab" if attribute(&e, "w:val").is_none() => text.push('\t'),
+                b"br" | b"cr" | b"p" => text.push('\n'),
+                _ => {}
+            },
+            (OfficeFormat::Docx, Event::End(e)) if e.local_name().as_ref() == b"p" => text.push('\n'),
+            (OfficeFormat::Odt, Event::Start(e)) if matches!(e.local_name().as_ref(), b"p" | b"h") => depth += 1,
+            (OfficeFormat::Odt, Event::End(e)) if matches!(e.local_name().as_ref(), b"p" | b"h") => {
+                depth = depth.saturating_sub(1);
+                text.push('\n');
+            }
+            (OfficeFormat::Odt, Event::Empty(e)) => match e.local_name().as_ref() {
+                b"tab" => text.push('\t'),
+                b"line-break" => text.push('\n'),
+                // text:s 表示连续空格，c 为个数
+                b"s" => {
+                    let count = attribute(&e, "text:c").and_then(|c| c.parse().ok()).unwrap_or(1usize);
+                    text.extend(std::iter::repeat_n(' ', count.min(100)));
+                }
+                b"p" | b"h" => text.push('\n'),
+                _ => {}
+            },
+            (_, Event::Text(e)) if depth > 0 => {
+                if let Ok(content) = e.unescape() {
+                    text.push_str(&content);
+                }
+            }
+            _ => {}
+        }
+        if text.len() > MAX_TEXT_CHARS * 4 {
+            break;
+        }
+    }
+    let truncated = text.chars().count() > MAX_TEXT_CHARS;
+    if truncated {
+        text = text.chars().take(MAX_TEXT_CHARS).collect();
+    }
+    (text.trim_end().to_string(), truncated)
+}
+
+// xlsx 的共享字符串表，单元格中的文字按序号引用
+fn shared_strings(xml: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let (mut in_text, mut in_phonetic) = (false, false);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            // 注音（rPh）不是单元格内容
+            Ok(Event::Text(e)) if in_text && !in_phonetic => {
+                if let Ok(content) = e.unescape() {
+                    current.push_str(&content);
+                }
+            }
+            _ => {}
+        }
+    }
+    strings
+}
+
+// 单元格引用中的列号，如 "AB12" -> 27
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: Vec<u8> = reference.bytes().take_while(|b| b.is_ascii_alphabetic()).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let number = letters.iter().fold(0usize, |n, b| n * 26 + (b.to_ascii_uppercase() - b'A' + 1) as usize);
+    Some(number - 1)
+}
+
+// 列标题：0 -> A，26 -> AA
+pub fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        name.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+// 读取工作表的前 MAX_ROWS 行，返回各行单元格文字和是否截断
+fn sheet_rows(xml: &str, strings: &[String]) -> (Vec<Vec<String>>, bool) {
+    let mut reader = Reader::from_str(xml);
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut truncated = false;
+    let mut row_index = 0usize;
+    // 当前单元格的列号和类型
+    let mut cell: Option<(usize, String)> = None;
+    let mut next_column = 0usize;
+    let mut value = String::new();
+    let mut in_value = false;
+    loop {
+        let event = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(event) => event,
+        };
+        match event {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"row" => {
+                // 空行不写入文件，按行号补齐
+                let number = attribute(&e, "r").and_then(|r| r.parse::<usize>().ok()).unwrap_or(row_index + 1);
+                row_index = number.max(row_index + 1);
+                if row_index > MAX_ROWS {
+                    truncated = true;
+                    break;
+                }
+                rows.resize(row_index, Vec::new());
+                next_column = 0;
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"c" => {
+                let column = attribute(&e, "r").and_then(|r| column_index(&r)).unwrap_or(next_column);
+                cell = Some((column, attribute(&e, "t").unwrap_or_default()));
+                value.clear();
+            }
+            Event::Start(e) if matches!(e.local_name().as_ref(), b"v" | b"t") => in_value = true,
+            Event::End(e) if matches!(e.local_name().as_ref(), b"v" | b"t") => in_value = false,
+            Event::Text(e) if in_value => {
+                if let Ok(content) = e.unescape() {
+                    value.push_str(&content);
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"c" => {
+                let Some((column, kind)) = cell.take() else {
+                    continue;
+                };
+                next_column = column + 1;
+                if column >= MAX_COLUMNS {
+                    truncated = true;
+                    continue;
+                }
+                let text = match kind.as_str() {
+                    "s" => value.trim().parse::<usize>().ok().and_then(|i| strings.get(i)).cloned().unwrap_or_default(),
+                    "b" => if value.trim() == "1" { "TRUE" } else { "FALSE" }.to_string(),
+                    _ => value.clone(),
+                };
+                if let Some(row) = rows.last_mut() {
+                    if row.len() <= column {
+                        row.resize(column + 1, String::new());
+                    }
+                    row[column] = text;
+                }
+            }
+            _ => {}
+        }
+    }
+    // 末尾的空行不显示
+    while rows.last().is_some_and(|row| row.iter().all(|c| c.is_empty())) {
+        rows.pop();
+    }
+    (rows, truncated)
+}
+
+// 工作簿中第一个工作表的名称和文件路径
+fn first_sheet(archive: &mut OfficeArchive) -> Result<(String, String), String> {
+    let workbook = read_entry(archive, "xl/workbook.xml")?;
+    let mut reader = Reader::from_str(&workbook);
+    let (name, id) = loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => return Err("工作簿中没有工作表".to_string()),
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"sheet" => {
+                break (attribute(&e, "name").unwrap_or_default(), attribute(&e, "r:id").unwrap_or_default());
+            }
+            _ => {}
+        }
+    };
+
+    // 通过关系文件找到工作表路径，找不到时使用默认的 sheet1.xml
+    let mut targets = HashMap::new();
+    if let Ok(rels) = read_entry(archive, "xl/_rels/workbook.xml.rels") {
+        let mut reader = Reader::from_str(&rels);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Eof) | Err(_) => break,
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"Relationship" => {
+                    if let (Some(id), Some(target)) = (attribute(&e, "Id"), attribute(&e, "Target")) {
+                        targets.insert(id, target);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let path = match targets.get(&id) {
+        Some(target) if target.starts_with('/') => target.trim_start_matches('/').to_string(),
+        Some(target) => format!("xl/{}", target),
+        None => "xl/worksheets/sheet1.xml".to_string(),
+    };
+    Ok((name, path))
+}
+
+// 提取文档文字或第一个工作表的内容；应在后台线程调用
+pub fn load_office(path: &Path) -> Result<OfficeContent, String> {
+    let format = OfficeFormat::from_path(path).ok_or_else(|| "不是支持的文档格式".to_string())?;
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| format!("文档已损坏或已加密: {}", e))?;
+    match format {
+        OfficeFormat::Docx | OfficeFormat::Odt => {
+            let name = if format == OfficeFormat::Docx { "word/document.xml" } else { "content.xml" };
+            let (text, truncated) = document_text(&read_entry(&mut archive, name)?, format);
+            Ok(OfficeContent::Text { text, truncated })
+        }
+        OfficeFormat::Xlsx => {
+            // 只有数字的表格没有共享字符串表
+            let strings = read_entry(&mut archive, "xl/sharedStrings.xml").map(|xml| shared_strings(&xml)).unwrap_or_default();
+            let (name, sheet_path) = first_sheet(&mut archive)?;
+            let (rows, truncated) = sheet_rows(&read_entry(&mut archive, &sheet_path)?, &strings);
+            Ok(OfficeContent::Sheet { name, rows, truncated })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_office_extraction() {
+        let docx = r#"<w:document xmlns:w="x"><w:body>
+<w:p><w:r><w:t>Hello</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">A &amp; B</w:t></w:r></w:p>
+<w:p><w:r><w:t>第二段</w:t></w:r></w:p></w:body></w:document>"#;
+        assert_eq!(document_text(docx, OfficeFormat::Docx), ("Hello\tA & B\n第二段".to_string(), false));
+
+        let odt = r#"<office:text><text:h>标题</text:h><text:p>a<text:s text:c="2"/>b</text:p><text:p/><text:p>c</text:p></office:text>"#;
+        assert_eq!(document_text(odt, OfficeFormat::Odt).0, "标题\na  b\n\nc");
+
+        let strings = shared_strings(r#"<sst><si><t>名称</t></si><si><r><t>富</t></r><r><t>文本</t></r><rPh><t>フ</t></rPh></si></sst>"#);
+        assert_eq!(strings, vec!["名称".to_string(), "富文本".to_string()]);
+
+        let sheet = r#"<worksheet><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="C1" t="s"><v>1</v></c></row>
+<row r="3"><c r="B3"><v>42</v></c><c r="C3" t="b"><v>1</v></c></row>
+</sheetData></worksheet>"#;
+        let (rows, truncated) = sheet_rows(sheet, &strings);
+        assert!(!truncated);
+        assert_eq!(rows, vec![
+            vec!["名称".to_string(), String::new(), "富文本".to_string()],
+            vec![],
+            vec![String::new(), "42".to_string(), "TRUE".to_string()],
+        ]);
+
+        assert_eq!(column_index("AB12"), Some(27));
+        assert_eq!((column_name(0), column_name(27)), ("A".to_string(), "AB".to_string()));
+    }
+}
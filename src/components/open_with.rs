@@ -0,0 +1,490 @@
+use eframe::egui;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crossbeam_channel::Receiver;
+use sha2::{Digest, Sha256};
+use super::desktop_entry;
+
+// mimeapps.list 中记录默认程序的段
+const DEFAULT_SECTION: &str = "[Default Applications]";
+
+// .desktop 文件中声明的应用程序
+#[derive(Debug, Clone, PartialEq)]
+struct DesktopApp {
+    id: String, // .desktop 文件名，mimeapps.list 中用它指代程序
+    name: String,
+    exec: String,
+    mime_types: Vec<String>,
+}
+
+// 解析 .desktop 文件的 [Desktop Entry] 段；隐藏的或非应用程序条目返回 None
+fn parse_desktop_entry(content: &str) -> Option<DesktopApp> {
+    let mut in_entry = false;
+    let (mut name, mut exec, mut mime_types) = (None, None, Vec::new());
+    let mut is_application = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Exec" => exec = Some(value.trim().to_string()),
+            "MimeType" => mime_types = value.split(';').filter(|m| !m.is_empty()).map(str::to_string).collect(),
+            "Type" => is_application = value.trim() == "Application",
+            "NoDisplay" | "Hidden" if value.trim() == "true" => return None,
+            _ => {}
+        }
+    }
+    if !is_application {
+        return None;
+    }
+    Some(DesktopApp { id: String::new(), name: name?, exec: exec?, mime_types })
+}
+
+// 按优先级排列的路径列表环境变量，未设置时使用默认值
+fn xdg_dirs(var: &str, default: &str) -> Vec<PathBuf> {
+    let value = std::env::var(var).ok().filter(|v| !v.is_empty()).unwrap_or_else(|| default.to_string());
+    value.split(':').filter(|d| !d.is_empty()).map(PathBuf::from).collect()
+}
+
+// 应用程序目录：$XDG_DATA_HOME 和 $XDG_DATA_DIRS 下的 applications，前面的优先
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir().into_iter().collect();
+    dirs.extend(xdg_dirs("XDG_DATA_DIRS", "/usr/local/share:/usr/share"));
+    dirs.into_iter().map(|d| d.join("applications")).collect()
+}
+
+// 读取系统和用户的应用程序列表，用户目录中的同名 .desktop 文件优先
+fn load_desktop_apps() -> Vec<DesktopApp> {
+    let mut seen = HashSet::new();
+    let mut apps = Vec::new();
+    for dir in application_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("desktop") || !seen.insert(entry.file_name()) {
+                continue;
+            }
+            if let Some(mut app) = fs::read_to_string(&path).ok().as_deref().and_then(parse_desktop_entry) {
+                app.id = entry.file_name().to_string_lossy().to_string();
+                apps.push(app);
+            }
+        }
+    }
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+// 查询文件的 MIME 类型（依赖 xdg-mime，不可用时返回 None）
+fn query_mime_type(path: &Path) -> Option<String> {
+    let output = Command::new("xdg-mime").args(["query", "filetype"]).arg(path).output().ok()?;
+    let mime = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !mime.is_empty()).then_some(mime)
+}
+
+// 某个 MIME 类型在 mimeapps.list 中的关联
+#[derive(Debug, Default, PartialEq)]
+struct MimeAssociations {
+    defaults: Vec<String>,
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+// 读取一个 mimeapps.list 中 mime 的条目，追加到 associations（先读取的文件优先）
+fn parse_mimeapps(text: &str, mime: &str, associations: &mut MimeAssociations) {
+    let mut section = "";
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim() != mime {
+            continue;
+        }
+        let list = match section {
+            DEFAULT_SECTION => &mut associations.defaults,
+            "[Added Associations]" => &mut associations.added,
+            "[Removed Associations]" => &mut associations.removed,
+            _ => continue,
+        };
+        list.extend(value.split(';').map(str::trim).filter(|id| !id.is_empty()).map(str::to_string));
+    }
+}
+
+// 用户的 mimeapps.list，设为默认程序时写入这里
+fn user_mimeapps() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("mimeapps.list"))
+}
+
+// 按规范的顺序读取各处的 mimeapps.list：用户配置、系统配置、各应用程序目录
+fn load_associations(mime: &str) -> MimeAssociations {
+    let mut files: Vec<PathBuf> = user_mimeapps().into_iter().collect();
+    files.extend(xdg_dirs("XDG_CONFIG_DIRS", "/etc/xdg").into_iter().map(|d| d.join("mimeapps.list")));
+    files.extend(application_dirs().into_iter().map(|d| d.join("mimeapps.list")));
+    let mut associations = MimeAssociations::default();
+    for file in files {
+        if let Ok(text) = fs::read_to_string(&file) {
+            parse_mimeapps(&text, mime, &mut associations);
+        }
+    }
+    associations
+}
+
+// 在 mimeapps.list 的内容中把 mime 的默认程序设为 desktop_id，其它内容保持不变
+fn set_default_application(text: &str, mime: &str, desktop_id: &str) -> String {
+    let entry = format!("{}={};", mime, desktop_id);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    let Some(header) = lines.iter().position(|line| line.trim() == DEFAULT_SECTION) else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(DEFAULT_SECTION.to_string());
+        lines.push(entry);
+        return lines.join("\n") + "\n";
+    };
+    let end = lines[header + 1..].iter().position(|line| line.trim().starts_with('[')).map_or(lines.len(), |i| header + 1 + i);
+    let existing = (header + 1..end).find(|&i| lines[i].split_once('=').is_some_and(|(key, _)| key.trim() == mime));
+    match existing {
+        Some(index) => lines[index] = entry,
+        None => lines.insert(header + 1, entry),
+    }
+    lines.join("\n") + "\n"
+}
+
+fn save_default_application(mime: &str, desktop_id: &str) -> Result<(), String> {
+    let path = user_mimeapps().ok_or_else(|| "无法确定配置目录".to_string())?;
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, set_default_application(&text, mime, desktop_id)).map_err(|e| format!("无法写入 {}: {}", path.display(), e))
+}
+
+// 自定义命令的 .desktop 文件名和内容：文件名带有命令的哈希，同一程序的不同命令互不覆盖；
+// existing 为已有文件的内容，其中的 MimeType 与 mime 合并
+fn custom_app_entry(command: &str, mime: &str, existing: Option<&str>) -> Result<(String, String), String> {
+    let words = desktop_entry::exec_arguments(command, None);
+    let program = words.first().ok_or_else(|| "命令为空".to_string())?;
+    let name = Path::new(program).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| program.clone());
+    let digest: String = Sha256::digest(command.as_bytes()).iter().take(4).map(|b| format!("{:02x}", b)).collect();
+    let id = format!("userapp-{}-{}.desktop", name.replace(|c: char| !c.is_ascii_alphanumeric(), "-"), digest);
+    let exec = if desktop_entry::takes_files(command) { command.to_string() } else { format!("{} %f", command) };
+    let mut mime_types = existing.and_then(parse_desktop_entry).map(|app| app.mime_types).unwrap_or_default();
+    if !mime_types.iter().any(|m| m == mime) {
+        mime_types.push(mime.to_string());
+    }
+    let content = format!("[Desktop Entry]\nType=Application\nName={}\nExec={}\nMimeType={};\n", name, exec, mime_types.join(";"));
+    Ok((id, content))
+}
+
+// 为输入的命令创建 .desktop 文件，以便记为默认程序，返回其文件名
+fn create_custom_app(command: &str, mime: &str) -> Result<String, String> {
+    let dir = dirs::data_dir().ok_or_else(|| "无法确定数据目录".to_string())?.join("applications");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let (id, _) = custom_app_entry(command, mime, None)?;
+    let existing = fs::read_to_string(dir.join(&id)).ok();
+    let (id, content) = custom_app_entry(command, mime, existing.as_deref())?;
+    fs::write(dir.join(&id), content).map_err(|e| format!("无法创建 {}: {}", id, e))?;
+    Ok(id)
+}
+
+// 通过 zenity 或 kdialog 选择程序文件；取消时返回 Ok(None)
+fn pick_program() -> Result<Option<PathBuf>, String> {
+    let pickers: [(&str, &[&str]); 2] = [
+        ("zenity", &["--file-selection", "--title=选择程序", "--filename=/usr/bin/"]),
+        ("kdialog", &["--getopenfilename", "/usr/bin"]),
+    ];
+    for (program, args) in pickers {
+        let Ok(output) = Command::new(program).args(args).output() else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return Ok((output.status.success() && !path.is_empty()).then(|| PathBuf::from(path)));
+    }
+    Err("浏览程序需要安装 zenity 或 kdialog，也可以直接输入命令".to_string())
+}
+
+// 把 Exec 行展开为程序和参数：%f %F %u %U 替换为文件路径，其它字段代码去掉，
+// 没有文件字段代码时把路径追加在最后
+fn build_command(exec: &str, path: &Path) -> Option<(String, Vec<String>)> {
+    let mut args = desktop_entry::exec_arguments(exec, Some(path));
+    if args.is_empty() {
+        return None;
+    }
+    if !desktop_entry::takes_files(exec) {
+        args.push(path.to_string_lossy().to_string());
+    }
+    let program = args.remove(0);
+    Some((program, args))
+}
+
+// 命令中有空格的路径加上引号
+fn quote_program(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if text.contains(char::is_whitespace) {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// 选中的程序：已安装的应用程序或输入的命令
+#[derive(Debug, Clone, PartialEq)]
+enum OpenWithChoice {
+    App(String), // .desktop 文件名
+    Command(String),
+}
+
+// "打开方式" 对话框：从已安装的应用程序中选择，或输入命令
+pub struct OpenWithDialog {
+    target: Option<PathBuf>,
+    apps: Option<Vec<DesktopApp>>, // 第一次打开对话框时读取
+    mime_type: Option<String>,
+    associations: MimeAssociations,
+    filter: String,
+    command: String,
+    selected: Option<String>, // 选中的应用程序
+    remember: bool,           // 设为此类文件的默认程序
+    picker: Option<Receiver<Result<Option<PathBuf>, String>>>, // 正在浏览程序文件
+    error: Option<String>,
+}
+
+impl OpenWithDialog {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            apps: None,
+            mime_type: None,
+            associations: MimeAssociations::default(),
+            filter: String::new(),
+            command: String::new(),
+            selected: None,
+            remember: false,
+            picker: None,
+            error: None,
+        }
+    }
+
+    pub fn open(&mut self, path: PathBuf) {
+        if self.apps.is_none() {
+            self.apps = Some(load_desktop_apps());
+        }
+        self.mime_type = query_mime_type(&path);
+        self.associations = self.mime_type.as_deref().map(load_associations).unwrap_or_default();
+        self.selected = self.associations.defaults.first().cloned();
+        self.filter.clear();
+        self.remember = false;
+        self.error = None;
+        self.target = Some(path);
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.target.is_some()
+    }
+
+    fn launch(&mut self, choice: &OpenWithChoice) -> bool {
+        let Some(target) = &self.target else {
+            return false;
+        };
+        let exec = match choice {
+            OpenWithChoice::App(id) => self.apps.iter().flatten().find(|app| &app.id == id).map(|app| app.exec.clone()).unwrap_or_default(),
+            OpenWithChoice::Command(command) => command.clone(),
+        };
+        let Some((program, args)) = build_command(&exec, target) else {
+            self.error = Some("命令为空".to_string());
+            return false;
+        };
+        if let Err(e) = Command::new(&program).args(&args).spawn() {
+            self.error = Some(format!("无法启动 {}: {}", program, e));
+            return false;
+        }
+        if let (true, Some(mime)) = (self.remember, self.mime_type.clone()) {
+            let result = match choice {
+                OpenWithChoice::App(id) => save_default_application(&mime, id),
+                OpenWithChoice::Command(command) => create_custom_app(command, &mime).and_then(|id| {
+                    // 新建的 .desktop 文件下次打开对话框时出现在列表中
+                    self.apps = None;
+                    save_default_application(&mime, &id)
+                }),
+            };
+            if let Err(e) = result {
+                self.error = Some(format!("已打开，但无法设为默认程序: {}", e));
+                return false;
+            }
+        }
+        true
+    }
+
+    fn start_picker(&mut self, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(pick_program());
+            ctx.request_repaint();
+        });
+        self.picker = Some(receiver);
+    }
+
+    pub fn show_open_with_dialog(&mut self, ctx: &egui::Context) {
+        let Some(target) = self.target.clone() else {
+            return;
+        };
+        if let Some(result) = self.picker.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.picker = None;
+            match result {
+                Ok(Some(path)) => {
+                    self.command = quote_program(&path);
+                    self.selected = None;
+                }
+                Ok(None) => {}
+                Err(e) => self.error = Some(e),
+            }
+        }
+        let mut open = true;
+        let mut chosen: Option<OpenWithChoice> = None;
+        let mut browse = false;
+
+        egui::Window::new("打开方式")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_size(egui::Vec2::new(420.0, 420.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("选择用于打开 \"{}\" 的程序", target.file_name().unwrap_or_default().to_string_lossy()));
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text("搜索应用程序").desired_width(f32::INFINITY));
+
+                let filter = self.filter.to_lowercase();
+                let apps: Vec<&DesktopApp> = self.apps.iter().flatten()
+                    .filter(|app| filter.is_empty() || app.name.to_lowercase().contains(&filter))
+                    .collect();
+                // 默认程序和 mimeapps.list 中关联的、声明支持该文件类型的程序排在前面
+                let associations = &self.associations;
+                let rank = |app: &DesktopApp| {
+                    let position = |list: &[String]| list.iter().position(|id| id == &app.id);
+                    if let Some(index) = position(&associations.defaults) {
+                        Some(index)
+                    } else if let Some(index) = position(&associations.added) {
+                        Some(associations.defaults.len() + index)
+                    } else {
+                        let declared = self.mime_type.as_ref().is_some_and(|m| app.mime_types.contains(m));
+                        (declared && !associations.removed.contains(&app.id)).then_some(usize::MAX)
+                    }
+                };
+                let (mut recommended, others): (Vec<&DesktopApp>, Vec<&DesktopApp>) = apps.into_iter().partition(|app| rank(app).is_some());
+                recommended.sort_by_key(|app| rank(app));
+
+                egui::ScrollArea::vertical().max_height(ui.available_height() - 110.0).show(ui, |ui| {
+                    for (heading, list) in [("推荐的程序", &recommended), ("其它程序", &others)] {
+                        if list.is_empty() {
+                            continue;
+                        }
+                        ui.label(egui::RichText::new(heading).strong());
+                        for app in list {
+                            let is_default = associations.defaults.first() == Some(&app.id);
+                            let label = if is_default { format!("{}（默认）", app.name) } else { app.name.clone() };
+                            let response = ui.selectable_label(self.selected.as_ref() == Some(&app.id), label).on_hover_text(&app.exec);
+                            if response.clicked() {
+                                self.selected = Some(app.id.clone());
+                            }
+                            if response.double_clicked() {
+                                chosen = Some(OpenWithChoice::App(app.id.clone()));
+                            }
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("命令:");
+                    if ui.text_edit_singleline(&mut self.command).changed() {
+                        self.selected = None;
+                    }
+                    if ui.add_enabled(self.picker.is_none(), egui::Button::new("浏览...")).clicked() {
+                        browse = true;
+                    }
+                });
+                let mime_label = self.mime_type.as_deref().unwrap_or("未知类型");
+                ui.add_enabled(self.mime_type.is_some(), egui::Checkbox::new(&mut self.remember, format!("始终用此程序打开 {} 文件", mime_label)));
+                ui.horizontal(|ui| {
+                    let choice = match (&self.selected, self.command.trim()) {
+                        (Some(id), _) => Some(OpenWithChoice::App(id.clone())),
+                        (None, "") => None,
+                        (None, command) => Some(OpenWithChoice::Command(command.to_string())),
+                    };
+                    if ui.add_enabled(choice.is_some(), egui::Button::new("打开")).clicked() {
+                        chosen = choice;
+                    }
+                    ui.label(egui::RichText::new("双击程序即可打开；命令中可用 %f 表示文件路径").weak());
+                });
+            });
+
+        if browse {
+            self.start_picker(ctx);
+        }
+        if let Some(choice) = chosen {
+            if self.launch(&choice) {
+                self.target = None;
+            }
+        }
+        if !open {
+            self.target = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_desktop_entry_and_command() {
+        let app = parse_desktop_entry("[Desktop Entry]\nType=Application\nName=Viewer\nExec=viewer --new %U\nMimeType=image/png;image/jpeg;\n").unwrap();
+        assert_eq!(app.mime_types, vec!["image/png", "image/jpeg"]);
+        assert!(parse_desktop_entry("[Desktop Entry]\nType=Application\nName=X\nExec=x\nNoDisplay=true\n").is_none());
+
+        let path = Path::new("/tmp/a b.png");
+        assert_eq!(build_command(&app.exec, path), Some(("viewer".to_string(), vec!["--new".to_string(), "/tmp/a b.png".to_string()])));
+        assert_eq!(build_command("gimp", path), Some(("gimp".to_string(), vec!["/tmp/a b.png".to_string()])));
+        assert_eq!(build_command(&quote_program(Path::new("/opt/My App/run")), path), Some(("/opt/My App/run".to_string(), vec!["/tmp/a b.png".to_string()])));
+
+        // 同一程序的不同命令使用不同的文件，同一命令再次使用时合并 MimeType
+        let (id, content) = custom_app_entry("gimp --new", "image/png", None).unwrap();
+        assert_ne!(id, custom_app_entry("gimp", "image/png", None).unwrap().0);
+        let (merged_id, merged) = custom_app_entry("gimp --new", "image/jpeg", Some(&content)).unwrap();
+        assert_eq!(merged_id, id);
+        assert_eq!(parse_desktop_entry(&merged).unwrap().mime_types, vec!["image/png", "image/jpeg"]);
+    }
+
+    #[test]
+    fn test_mimeapps_list() {
+        let text = "[Default Applications]\nimage/png=viewer.desktop;gimp.desktop;\n\n[Added Associations]\nimage/png=krita.desktop;\n[Removed Associations]\nimage/png=old.desktop\n";
+        let mut associations = MimeAssociations::default();
+        parse_mimeapps(text, "image/png", &mut associations);
+        assert_eq!(associations.defaults, vec!["viewer.desktop", "gimp.desktop"]);
+        assert_eq!((associations.added, associations.removed), (vec!["krita.desktop".to_string()], vec!["old.desktop".to_string()]));
+
+        let updated = set_default_application(text, "image/png", "krita.desktop");
+        assert!(updated.starts_with("[Default Applications]\nimage/png=krita.desktop;\n\n[Added Associations]"));
+        let added = set_default_application(&updated, "text/plain", "gedit.desktop");
+        assert!(added.starts_with("[Default Applications]\ntext/plain=gedit.desktop;\nimage/png=krita.desktop;\n"));
+        assert_eq!(set_default_application("", "text/plain", "gedit.desktop"), "[Default Applications]\ntext/plain=gedit.desktop;\n");
+    }
+}
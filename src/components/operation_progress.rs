@@ -0,0 +1,123 @@
+use eframe::egui;
+use std::time::Duration;
+use crate::utils;
+use super::file_operations::OperationHandle;
+use super::locale_format;
+
+// 操作进行超过这个时间后自动打开进度窗口
+const AUTO_OPEN_DELAY: Duration = Duration::from_secs(1);
+
+// 剩余时间，如“1 小时 5 分”“2 分 30 秒”
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{} 秒", seconds),
+        60..=3599 => format!("{} 分 {} 秒", seconds / 60, seconds % 60),
+        _ => format!("{} 小时 {} 分", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+// 后台复制、移动和删除的进度窗口：速度、已完成的文件、当前文件和剩余时间
+pub struct OperationProgressDialog {
+    show_dialog: bool,
+    dismissed: bool, // 用户关闭窗口后，本批操作结束前不再自动打开
+}
+
+impl OperationProgressDialog {
+    pub fn new() -> Self {
+        Self { show_dialog: false, dismissed: false }
+    }
+
+    // 从状态栏重新打开
+    pub fn open(&mut self) {
+        self.show_dialog = true;
+        self.dismissed = false;
+    }
+
+    // 每帧调用：较慢的操作自动打开窗口，全部结束后关闭
+    pub fn show_progress_dialog(&mut self, ctx: &egui::Context, operations: &mut [OperationHandle]) {
+        if operations.is_empty() {
+            self.show_dialog = false;
+            self.dismissed = false;
+            return;
+        }
+        if !self.dismissed && operations.iter().any(|handle| handle.elapsed() >= AUTO_OPEN_DELAY) {
+            self.show_dialog = true;
+        }
+        if !self.show_dialog {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("文件操作")
+            .collapsible(true)
+            .resizable(false)
+            .default_width(420.0)
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -36.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                for (index, handle) in operations.iter_mut().enumerate() {
+                    if index > 0 {
+                        ui.separator();
+                    }
+                    ui.push_id(index, |ui| show_operation(ui, handle));
+                }
+            });
+        if !open {
+            self.show_dialog = false;
+            self.dismissed = true;
+        }
+    }
+}
+
+fn show_operation(ui: &mut egui::Ui, handle: &mut OperationHandle) {
+    let format = locale_format::current();
+    let label = handle.kind().label();
+    let progress = handle.progress().clone();
+    let cancelled = handle.cancel_token().is_cancelled();
+
+    ui.horizontal(|ui| {
+        ui.strong(label);
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.add_enabled(!cancelled, egui::Button::new(if cancelled { "正在取消…" } else { "取消" })).clicked() {
+                handle.cancel_token().cancel();
+            }
+        });
+    });
+    ui.add(egui::ProgressBar::new(progress.fraction()).show_percentage());
+
+    let mut detail = format!("{}/{} 个文件", format.count(progress.files_done as u64), format.count(progress.files_total as u64));
+    if progress.bytes_total > 0 {
+        detail.push_str(&format!("，{} / {}", utils::get_file_size_str(progress.bytes_done), utils::get_file_size_str(progress.bytes_total)));
+    }
+    ui.label(detail);
+
+    // 移动只是重命名，没有字节数，不显示速度
+    if progress.bytes_total > 0 {
+        let speed = handle.bytes_per_second()
+            .map(|rate| format!("{}/s", utils::get_file_size_str(rate as u64)))
+            .unwrap_or_else(|| "计算中…".to_string());
+        let remaining = handle.remaining()
+            .map(format_duration)
+            .unwrap_or_else(|| "计算中…".to_string());
+        ui.label(format!("速度 {}，剩余约 {}", speed, remaining));
+    }
+    ui.label(egui::RichText::new(format!("已用时间 {}", format_duration(handle.elapsed()))).weak());
+
+    if let Some(current) = &progress.current {
+        let name = current.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        ui.label(format!("当前: {}", name)).on_hover_text(current.display().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(12)), "12 秒");
+        assert_eq!(format_duration(Duration::from_secs(150)), "2 分 30 秒");
+        assert_eq!(format_duration(Duration::from_secs(3900)), "1 小时 5 分");
+    }
+}
@@ -0,0 +1,255 @@
+use eframe::egui;
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+// 参与整理的图片和视频扩展名
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "bmp", "webp", "heic", "heif", "tif", "tiff", "dng", "cr2", "nef", "arw",
+    "mp4", "mov", "avi", "mkv", "m4v", "3gp", "wmv",
+];
+
+// 一次计划中的移动
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMove {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub from_exif: bool, // 日期来自 EXIF（否则来自修改时间）
+}
+
+fn is_media_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| MEDIA_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// 读取 EXIF 拍摄日期（年, 月），优先 DateTimeOriginal
+fn exif_year_month(path: &Path) -> Option<(u16, u8)> {
+    let file = fs::File::open(path).ok()?;
+    let exif = exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()?;
+    [exif::Tag::DateTimeOriginal, exif::Tag::DateTimeDigitized, exif::Tag::DateTime]
+        .iter()
+        .find_map(|tag| {
+            let field = exif.get_field(*tag, exif::In::PRIMARY)?;
+            let exif::Value::Ascii(ref values) = field.value else {
+                return None;
+            };
+            let date = exif::DateTime::from_ascii(values.first()?).ok()?;
+            (date.year > 0 && (1..=12).contains(&date.month)).then_some((date.year, date.month))
+        })
+}
+
+// 修改时间的（年, 月），按本地时区
+fn mtime_year_month(path: &Path) -> Option<(u16, u8)> {
+    use chrono::Datelike;
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let date = chrono::DateTime::<chrono::Local>::from(modified);
+    Some((date.year() as u16, date.month() as u8))
+}
+
+// 目标已存在时追加序号，如 "IMG_0001 (1).jpg"
+fn unique_target(target: PathBuf, reserved: &[PlannedMove]) -> PathBuf {
+    let taken = |p: &Path| p.exists() || reserved.iter().any(|m| m.target == p);
+    if !taken(&target) {
+        return target;
+    }
+    let stem = target.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = target.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| target.with_file_name(format!("{} ({}){}", stem, n, ext)))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or(target)
+}
+
+// 生成整理计划：当前文件夹（不递归）中的图片/视频移动到 YYYY/MM 子文件夹
+pub fn plan_organize(dir: &Path) -> Vec<PlannedMove> {
+    let mut sources: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_file() && is_media_file(p)).collect())
+        .unwrap_or_default();
+    sources.sort();
+
+    let mut plan = Vec::new();
+    for source in sources {
+        let (date, from_exif) = match exif_year_month(&source) {
+            Some(date) => (Some(date), true),
+            None => (mtime_year_month(&source), false),
+        };
+        let Some((year, month)) = date else {
+            continue;
+        };
+        let Some(name) = source.file_name() else {
+            continue;
+        };
+        let target = dir.join(format!("{:04}", year)).join(format!("{:02}", month)).join(name);
+        let target = unique_target(target, &plan);
+        plan.push(PlannedMove { source, target, from_exif });
+    }
+    plan
+}
+
+// 执行计划，返回已完成的移动（出错时停止，已完成部分仍可撤销）
+pub fn execute_plan(plan: &[PlannedMove]) -> (Vec<PlannedMove>, Option<String>) {
+    let mut done = Vec::new();
+    for item in plan {
+        if let Some(parent) = item.target.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                return (done, Some(format!("创建文件夹 {} 失败: {}", parent.display(), e)));
+            }
+        }
+        if item.target.exists() {
+            return (done, Some(format!("目标已存在: {}", item.target.display())));
+        }
+        if let Err(e) = fs::rename(&item.source, &item.target) {
+            return (done, Some(format!("移动 {} 失败: {}", item.source.display(), e)));
+        }
+        done.push(item.clone());
+    }
+    (done, None)
+}
+
+// 撤销：按相反顺序移回原位置，并删除整理时产生的空文件夹
+pub fn undo_moves(moves: &[PlannedMove]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for item in moves.iter().rev() {
+        if item.source.exists() {
+            errors.push(format!("原位置已有同名文件: {}", item.source.display()));
+            continue;
+        }
+        if let Err(e) = fs::rename(&item.target, &item.source) {
+            errors.push(format!("移回 {} 失败: {}", item.source.display(), e));
+        }
+    }
+
+    // 月份文件夹和年份文件夹为空时删除（remove_dir 只会删除空文件夹）
+    for item in moves {
+        let month_dir = item.target.parent();
+        let year_dir = month_dir.and_then(Path::parent);
+        for dir in [month_dir, year_dir].into_iter().flatten() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+// 照片整理对话框：预览计划（试运行），确认后执行，支持撤销上次整理
+pub struct PhotoOrganizer {
+    show_dialog: bool,
+    dir: PathBuf,
+    plan: Vec<PlannedMove>,
+    last_run: Vec<PlannedMove>, // 上次执行的移动，用于撤销
+    message: Option<String>,
+}
+
+impl PhotoOrganizer {
+    pub fn new() -> Self {
+        Self {
+            show_dialog: false,
+            dir: PathBuf::new(),
+            plan: Vec::new(),
+            last_run: Vec::new(),
+            message: None,
+        }
+    }
+
+    // 为文件夹生成整理计划并打开预览
+    pub fn open(&mut self, dir: &Path) {
+        self.dir = dir.to_path_buf();
+        self.plan = plan_organize(dir);
+        self.message = None;
+        self.show_dialog = true;
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.last_run.is_empty()
+    }
+
+    // 撤销上次整理，返回是否需要刷新列表
+    pub fn undo_last(&mut self) -> bool {
+        if self.last_run.is_empty() {
+            return false;
+        }
+        let moves = std::mem::take(&mut self.last_run);
+        if let Err(e) = undo_moves(&moves) {
+            eprintln!("撤销照片整理时出错:\n{}", e);
+        }
+        true
+    }
+
+    // 显示预览对话框，执行后返回 true 以便刷新列表
+    pub fn show_organizer_dialog(&mut self, ctx: &egui::Context) -> bool {
+        let mut open = true;
+        let mut executed = false;
+
+        egui::Window::new("整理照片到日期文件夹")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_size(egui::Vec2::new(560.0, 380.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!("文件夹: {}", self.dir.display()));
+                if let Some(message) = &self.message {
+                    ui.colored_label(ui.visuals().warn_fg_color, message);
+                }
+
+                if self.plan.is_empty() {
+                    ui.label("没有需要整理的图片或视频。");
+                } else {
+                    let exif_count = self.plan.iter().filter(|m| m.from_exif).count();
+                    ui.label(format!(
+                        "预览：将移动 {} 个文件（{} 个按 EXIF 日期，{} 个按修改时间）",
+                        self.plan.len(),
+                        exif_count,
+                        self.plan.len() - exif_count
+                    ));
+                    ui.separator();
+                    egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                        for item in &self.plan {
+                            let source = item.source.strip_prefix(&self.dir).unwrap_or(&item.source);
+                            let target = item.target.strip_prefix(&self.dir).unwrap_or(&item.target);
+                            let suffix = if item.from_exif { "" } else { "（修改时间）" };
+                            ui.label(format!("{}  →  {}{}", source.display(), target.display(), suffix));
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("取消").clicked() {
+                            self.show_dialog = false;
+                        }
+                        if ui.add_enabled(!self.plan.is_empty(), egui::Button::new("开始整理")).clicked() {
+                            let (done, error) = execute_plan(&self.plan);
+                            executed = !done.is_empty();
+                            self.last_run = done;
+                            match error {
+                                Some(e) => {
+                                    // 出错时保留对话框，显示错误并更新剩余计划
+                                    self.plan = plan_organize(&self.dir);
+                                    self.message = Some(e);
+                                }
+                                None => self.show_dialog = false,
+                            }
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            self.show_dialog = false;
+        }
+
+        executed
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use crossbeam_channel::{self, Receiver, Sender};
+
+// 同时进行的预读数量上限
+const MAX_IN_FLIGHT: usize = 2;
+// 缓存的目录数量上限
+const MAX_CACHED: usize = 8;
+// 预读结果的有效期；文件内容变化不会改变目录的修改时间，所以不能无限期使用
+const MAX_AGE: Duration = Duration::from_secs(10);
+
+// 读取目录的函数：路径、是否包含隐藏文件、取消标志；取消时返回 None
+pub type ListingReader<T> = fn(&Path, bool, &AtomicBool) -> Option<Vec<T>>;
+
+type ListingKey = (PathBuf, bool);
+
+struct Prefetched<T> {
+    dir_modified: Option<SystemTime>,
+    fetched_at: Instant,
+    items: Vec<T>,
+}
+
+// 预读线程的结果：任务的取消标志用来识别是否仍是当前的任务，读取失败或取消时没有结果
+type PrefetchResult<T> = (ListingKey, Arc<AtomicBool>, Option<Prefetched<T>>);
+
+fn dir_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// 在后台预读可能进入的文件夹（悬停或选中），进入时直接使用预读结果
+pub struct DirectoryPrefetcher<T> {
+    reader: ListingReader<T>,
+    sender: Sender<PrefetchResult<T>>,
+    receiver: Receiver<PrefetchResult<T>>,
+    in_flight: HashMap<ListingKey, Arc<AtomicBool>>,
+    cache: HashMap<ListingKey, Prefetched<T>>,
+}
+
+impl<T: Send + 'static> DirectoryPrefetcher<T> {
+    pub fn new(reader: ListingReader<T>) -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        Self {
+            reader,
+            sender,
+            receiver,
+            in_flight: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    fn poll(&mut self) {
+        for (key, token, prefetched) in self.receiver.try_iter() {
+            // 已取消的任务的结果直接丢弃；读取失败时只结束任务，之后可以再次请求
+            if !self.in_flight.get(&key).is_some_and(|current| Arc::ptr_eq(current, &token)) {
+                continue;
+            }
+            self.in_flight.remove(&key);
+            if let Some(prefetched) = prefetched {
+                if self.cache.len() >= MAX_CACHED {
+                    if let Some(oldest) = self.cache.iter().min_by_key(|(_, p)| p.fetched_at).map(|(k, _)| k.clone()) {
+                        self.cache.remove(&oldest);
+                    }
+                }
+                self.cache.insert(key, prefetched);
+            }
+        }
+    }
+
+    // 请求预读；已缓存、正在读取或并发已满时忽略
+    pub fn request(&mut self, path: &Path, show_hidden: bool) {
+        self.poll();
+        let key = (path.to_path_buf(), show_hidden);
+        if self.in_flight.len() >= MAX_IN_FLIGHT || self.in_flight.contains_key(&key) {
+            return;
+        }
+        if self.cache.get(&key).is_some_and(|p| p.fetched_at.elapsed() < MAX_AGE) {
+            return;
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.in_flight.insert(key.clone(), cancelled.clone());
+        let (reader, sender) = (self.reader, self.sender.clone());
+        std::thread::spawn(move || {
+            let modified = dir_modified(&key.0);
+            let prefetched = reader(&key.0, key.1, &cancelled).map(|items| Prefetched { dir_modified: modified, fetched_at: Instant::now(), items });
+            let _ = sender.send((key, cancelled, prefetched));
+        });
+    }
+
+    // 取出预读结果；目录在预读后有变化或结果过期时返回 None
+    pub fn take(&mut self, path: &Path, show_hidden: bool) -> Option<Vec<T>> {
+        self.poll();
+        let prefetched = self.cache.remove(&(path.to_path_buf(), show_hidden))?;
+        let fresh = prefetched.fetched_at.elapsed() < MAX_AGE
+            && prefetched.dir_modified.is_some()
+            && prefetched.dir_modified == dir_modified(path);
+        fresh.then_some(prefetched.items)
+    }
+
+    // 导航到其它位置时取消所有预读并清空缓存
+    pub fn cancel_all(&mut self) {
+        for cancelled in self.in_flight.values() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        self.in_flight.clear();
+        self.cache.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    fn read_names(path: &Path, _show_hidden: bool, _cancelled: &AtomicBool) -> Option<Vec<String>> {
+        let entries = fs::read_dir(path).ok()?;
+        Some(entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+    }
+
+    #[test]
+    fn test_prefetch_and_invalidate() {
+        let root = TestDir::new("prefetch");
+        fs::write(root.join("a.txt"), "a").unwrap();
+
+        let mut prefetcher = DirectoryPrefetcher::new(read_names);
+        prefetcher.request(&root, false);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while prefetcher.cache.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            prefetcher.poll();
+        }
+        assert_eq!(prefetcher.take(&root, false), Some(vec!["a.txt".to_string()]));
+        // 取出后不再缓存，另一种隐藏文件设置也没有结果
+        assert_eq!(prefetcher.take(&root, false), None);
+        assert_eq!(prefetcher.take(&root, true), None);
+
+        // 读取失败的任务结束后不再占用并发数
+        prefetcher.request(&root.join("missing"), false);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !prefetcher.in_flight.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            prefetcher.poll();
+        }
+        assert!(prefetcher.in_flight.is_empty());
+    }
+}
@@ -9,6 +9,16 @@ use crossbeam_channel::{self, Sender, Receiver};
 use crate::utils;
 use image::GenericImageView;
 use sysinfo::System;
+use super::album_preview::{self, AlbumInfo};
+use super::{frame_budget, icon_recolor, image_formats, raw_image, text_encoding, video_thumbnail};
+use super::preview_handlers::PreviewHandlers;
+use super::compare::{self, CompareView};
+use super::folder_breakdown::FolderScan;
+use super::file_hashes::FileHashPanel;
+use super::{file_metadata, project_types, properties};
+
+// 视频文件夹联系表中显示的视频数量
+const CONTACT_SHEET_COUNT: usize = 9;
 
 // 缓存的纹理结构
 #[derive(Clone)]
@@ -47,10 +57,31 @@ fn calculate_cache_sizes() -> (usize, usize) {
     (preload_cache_size, main_cache_size)
 }
 
+// 预览图片的最大放大倍数
+const MAX_IMAGE_ZOOM: f32 = 16.0;
+
+// 图片预览的缩放状态；zoom 为 None 时缩放到适应预览栏
+#[derive(Default)]
+pub struct ImageView {
+    pub zoom: Option<f32>,
+    pub pan: egui::Vec2,
+    pub full_texture: Option<egui::TextureHandle>, // 按需加载的原始分辨率图片
+    pub full_size: Option<(u32, u32)>,            // 原图尺寸
+    pub needs_full: bool,                          // 当前缩放下缩略图不够清晰
+    pub loading_full: bool,
+    pub open_viewer: bool,                         // 双击图片，请求打开全窗口查看器
+}
+
 pub struct Preview {
     current_file: Option<PathBuf>,
     current_folder: Option<PathBuf>,  // 添加当前文件夹跟踪
     preview_content: String,
+    handlers: PreviewHandlers,          // 文件类型对应的预览处理器
+    compare: Option<CompareView>,       // 拖入第二个文件后的比较模式
+    folder_scan: Option<FolderScan>,    // 文件夹预览中的深度扫描（总大小、最大文件、按扩展名统计）
+    selected_pair: Option<(PathBuf, PathBuf)>, // 上次看到的两项选择
+    compare_from_selection: bool,       // 当前比较是因选中两个文本文件而自动开始的
+    hash_panel: FileHashPanel,          // 信息页中的 MD5/SHA-1/SHA-256
     file_info: FileInfo,
     image_texture: Option<egui::TextureHandle>,
     image_size: Option<(u32, u32)>,
@@ -64,8 +95,8 @@ pub struct Preview {
     // 多线程预加载 - 直接包含，不再使用Option
     pub preloader: ThumbnailPreloader,
     // 异步文件夹预览
-    folder_preview_sender: Option<Sender<(String, Vec<PathBuf>)>>,
-    folder_preview_receiver: Option<Receiver<(String, Vec<PathBuf>)>>,
+    folder_preview_sender: Option<Sender<FolderPreview>>,
+    folder_preview_receiver: Option<Receiver<FolderPreview>>,
     // 文件信息通道
     file_info_sender: Option<Sender<FileInfo>>,
     file_info_receiver: Option<Receiver<FileInfo>>,
@@ -77,8 +108,64 @@ pub struct Preview {
     // 图片流预览状态
     image_stream_scroll: f32,
     image_stream_paths: Vec<PathBuf>,
+    video_stream_paths: Vec<PathBuf>, // 文件夹中的视频，用于联系表
     selected_image_index: Option<usize>,
     pending_image_load: Option<PathBuf>,
+    // 音乐专辑预览（文件夹中有音频文件时在后台读取标签）
+    album_sender: Option<Sender<AlbumInfo>>,
+    album_receiver: Option<Receiver<AlbumInfo>>,
+    album: Option<AlbumInfo>,
+    album_cover: Option<egui::TextureHandle>,
+    // 分页：当前页，以及各页按需生成的内容
+    tab: PreviewTab,
+    preview_generated: bool,
+    metadata_rows: Option<InfoRows>,
+    permission_rows: Option<Result<InfoRows, String>>,
+    // 生成现有纹理时的主题和图标反色设置版本，变化后重新生成
+    recolor_state: (bool, u64),
+}
+
+// 元数据页和权限页的内容：（名称, 值）
+type InfoRows = Vec<(String, String)>;
+
+// 预览栏的分页
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PreviewTab {
+    Info,
+    Preview,
+    Metadata,
+    Permissions,
+}
+
+impl PreviewTab {
+    const ALL: [PreviewTab; 4] = [PreviewTab::Info, PreviewTab::Preview, PreviewTab::Metadata, PreviewTab::Permissions];
+
+    fn label(self) -> &'static str {
+        match self {
+            PreviewTab::Info => "信息",
+            PreviewTab::Preview => "预览",
+            PreviewTab::Metadata => "元数据",
+            PreviewTab::Permissions => "权限",
+        }
+    }
+}
+
+// 以两列表格显示名称和值
+fn show_rows(ui: &mut egui::Ui, id: &str, rows: &[(String, String)]) {
+    egui::Grid::new(id).num_columns(2).striped(true).spacing([12.0, 4.0]).show(ui, |ui| {
+        for (name, value) in rows {
+            ui.label(name);
+            ui.label(value);
+            ui.end_row();
+        }
+    });
+}
+
+// 后台读取的文件夹预览：文字列表以及其中的图片和视频
+struct FolderPreview {
+    content: String,
+    image_paths: Vec<PathBuf>,
+    video_paths: Vec<PathBuf>,
 }
 
 struct LoadingResult {
@@ -101,6 +188,7 @@ struct FileInfo {
     size: String,
     modified: String,
     file_type: String,
+    encoding: Option<&'static str>, // 文本文件检测到的编码
 }
 
 // 多线程缩略图预加载器
@@ -206,6 +294,15 @@ impl ThumbnailPreloader {
     // 文件大小检查现在在工作线程中进行，避免阻塞UI
 
     pub fn get_cached_thumbnail(&self, path: &Path, ctx: &egui::Context) -> Option<(egui::TextureHandle, (u32, u32))> {
+        self.cached_thumbnail(path, ctx, false)
+    }
+
+    // 绘制缩略图网格时使用：本帧预算用完时不再上传新纹理，返回 None 并在下一帧继续
+    pub fn get_cached_thumbnail_budgeted(&self, path: &Path, ctx: &egui::Context) -> Option<(egui::TextureHandle, (u32, u32))> {
+        self.cached_thumbnail(path, ctx, true)
+    }
+
+    fn cached_thumbnail(&self, path: &Path, ctx: &egui::Context, budgeted: bool) -> Option<(egui::TextureHandle, (u32, u32))> {
         let cache_key = path.to_string_lossy().to_string();
 
         // 检查纹理缓存
@@ -218,6 +315,12 @@ impl ThumbnailPreloader {
         // 如果纹理缓存没有，检查预加载缓存
         if let Ok(cache_guard) = self.cache.lock() {
             if let Some((rgba_img, size)) = cache_guard.get(&cache_key) {
+                if budgeted && !frame_budget::try_spend(ctx) {
+                    return None;
+                }
+                // 深色主题下单色图标反色
+                let recolored = icon_recolor::prepare(path, rgba_img, ctx.style().visuals.dark_mode);
+                let rgba_img = recolored.as_ref().unwrap_or(rgba_img);
                 // 在主线程创建纹理
                 let color_image = egui::ColorImage::from_rgba_premultiplied(
                     [rgba_img.width() as usize, rgba_img.height() as usize],
@@ -268,7 +371,12 @@ impl ThumbnailPreloader {
     }
 
     fn generate_thumbnail(path: &Path) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
-        let img = image::open(path)?;
+        // 视频取第一帧画面（磁盘缓存），其它按图片打开
+        let img = if video_thumbnail::is_video_file(path) {
+            video_thumbnail::video_frame(path)?
+        } else {
+            image_formats::open_image(path)?
+        };
 
         // 统一生成400px缩略图用于预加载
         let thumbnail_size = 400;
@@ -286,6 +394,25 @@ impl ThumbnailPreloader {
     }
 }
 
+// 拖动手柄：拖动或点击时把文件路径按 shell 引用后复制，可直接粘贴到终端作为参数。
+// egui 无法发起拖到其它程序的系统拖放，因此通过剪贴板传递
+fn show_shell_path_handle(ui: &mut egui::Ui, path: &Path) {
+    let response = ui.add(egui::Label::new(egui::RichText::new("⠿").weak()).sense(egui::Sense::click_and_drag()))
+        .on_hover_text("拖动或点击：复制加引号的路径，在终端中粘贴即可作为参数");
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+    }
+    if response.drag_started() || response.clicked() {
+        ui.ctx().copy_text(project_types::shell_quote(&path.to_string_lossy()));
+    }
+    if response.dragged() {
+        egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), response.id.with("shell_path_tip"), |ui| {
+            ui.label(format!("已复制 {}", project_types::shell_quote(&path.to_string_lossy())));
+            ui.label(egui::RichText::new("在终端中按 Ctrl+Shift+V 粘贴").weak());
+        });
+    }
+}
+
 impl Preview {
     pub fn new() -> Self {
         // 创建异步文件夹预览通道
@@ -294,6 +421,9 @@ impl Preview {
         // 创建文件信息通道
         let (file_info_sender, file_info_receiver) = crossbeam_channel::unbounded();
 
+        // 创建专辑信息通道
+        let (album_sender, album_receiver) = crossbeam_channel::unbounded();
+
         // 计算动态缓存大小
         let (_, main_cache_size) = calculate_cache_sizes();
 
@@ -301,6 +431,12 @@ impl Preview {
             current_file: None,
             current_folder: None,  // 初始化当前文件夹跟踪
             preview_content: String::new(),
+            handlers: PreviewHandlers::new(),
+            compare: None,
+            folder_scan: None,
+            selected_pair: None,
+            compare_from_selection: false,
+            hash_panel: FileHashPanel::new(),
             file_info: FileInfo::default(),
             image_texture: None,
             image_size: None,
@@ -319,8 +455,18 @@ impl Preview {
             // 图片流预览状态初始化
             image_stream_scroll: 0.0,
             image_stream_paths: Vec::new(),
+            video_stream_paths: Vec::new(),
             selected_image_index: None,
             pending_image_load: None,
+            album_sender: Some(album_sender),
+            album_receiver: Some(album_receiver),
+            album: None,
+            album_cover: None,
+            tab: PreviewTab::Preview,
+            preview_generated: false,
+            metadata_rows: None,
+            permission_rows: None,
+            recolor_state: (true, 0),
         }
     }
 
@@ -374,12 +520,9 @@ impl Preview {
                     let path = entry.path();
 
                     // 快速检查文件扩展名，避免不必要的操作
-                    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
-                        let ext_lower = ext.to_lowercase();
-                        if matches!(ext_lower.as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp") {
-                            paths.push(path);
-                            image_count += 1;
-                        }
+                    if image_formats::is_image_file(&path) {
+                        paths.push(path);
+                        image_count += 1;
                     }
                 }
 
@@ -404,6 +547,9 @@ impl Preview {
     pub fn clear(&mut self) {
         self.current_file = None;
         self.preview_content.clear();
+        self.handlers.clear();
+        self.compare = None;
+        self.folder_scan = None;
         self.file_info = FileInfo::default();
         self.image_texture = None;
         self.image_size = None;
@@ -443,77 +589,22 @@ impl Preview {
 
         self.current_file = Some(path.clone());
         self.preview_content.clear();
+        self.handlers.clear();
+        self.compare = None;
+        self.folder_scan = None;
         self.image_texture = None;
         self.image_size = None;
         self.is_loading = false;
-
-        // 检查是否为文件夹
-        if path.is_dir() {
-            // 异步生成文件夹预览
-            self.generate_folder_preview(&path);
-            // 立即开始预加载文件夹中的图片
-            self.preload_folder_images(&path);
-        } else {
-            // 对于文件，也触发预加载其所在文件夹的图片
-            if let Some(parent) = path.parent() {
-                self.preload_folder_images(parent);
-            }
-            // 检查文件类型
-            match path.extension().and_then(|ext| ext.to_str()) {
-                Some("txt") | Some("rs") | Some("js") | Some("py") | Some("html") |
-                Some("css") | Some("json") | Some("xml") | Some("md") => {
-                    // 文本文件预览
-                    self.generate_text_preview(&path);
-                }
-                Some("jpg") | Some("jpeg") | Some("png") | Some("gif") | Some("bmp") => {
-                    // 图片文件预览 - 简化逻辑
-                    let mut found = false;
-
-                    // 1. 先检查预加载缓存（最快）
-                    if let Some((texture, size)) = self.preloader.get_cached_thumbnail(&path, ctx) {
-                        self.image_texture = Some(texture);
-                        self.image_size = Some(size);
-                        self.preview_content = format!(
-                            "图片预览\n\n尺寸: {} x {} 像素\n格式: {}",
-                            size.0,
-                            size.1,
-                            path.extension()
-                                .and_then(|ext| ext.to_str())
-                                .map(|ext| ext.to_uppercase())
-                                .unwrap_or_else(|| "未知".to_string())
-                        );
-                        self.is_loading = false;
-                        found = true;
-                    }
-
-                    // 2. 如果预加载缓存没有，检查普通缓存
-                    if !found {
-                        if let Some((texture, size)) = self.get_cached_image(&path) {
-                            self.image_texture = Some(texture);
-                            self.image_size = Some(size);
-                            self.preview_content = format!(
-                                "图片预览\n\n尺寸: {} x {} 像素\n格式: {}",
-                                size.0,
-                                size.1,
-                                path.extension()
-                                    .and_then(|ext| ext.to_str())
-                                    .map(|ext| ext.to_uppercase())
-                                    .unwrap_or_else(|| "未知".to_string())
-                            );
-                            self.is_loading = false;
-                        } else {
-                            // 3. 没有缓存，启动异步加载
-                            self.is_loading = true;
-                            self.preview_content = "正在加载图片...".to_string();
-                            self.start_async_loading(path.clone(), ctx.clone());
-                        }
-                    }
-                }
-                _ => {
-                    // 其他文件类型
-                    self.preview_content = "此文件类型不支持预览".to_string();
-                }
-            }
+        self.album = None;
+        self.album_cover = None;
+        self.video_stream_paths.clear();
+        self.preview_generated = false;
+        self.metadata_rows = None;
+        self.permission_rows = None;
+
+        // 预览内容较重，只在预览页打开时生成
+        if self.tab == PreviewTab::Preview {
+            self.generate_preview_content(path.clone(), ctx);
         }
 
         // 异步获取文件信息（避免阻塞UI）
@@ -535,6 +626,9 @@ impl Preview {
                     .map(|ext| ext.to_uppercase())
                     .unwrap_or_else(|| "文件".to_string())
             };
+            if path_clone.is_file() {
+                file_info.encoding = text_encoding::detect_file(&path_clone);
+            }
             
             // 通过通道发送文件信息
             if let Some(sender) = file_info_sender {
@@ -548,13 +642,104 @@ impl Preview {
         self.file_info.modified = "计算中...".to_string();
     }
 
+    // 生成预览页的内容：文件夹列表、文本、图片等
+    fn generate_preview_content(&mut self, path: PathBuf, ctx: &egui::Context) {
+        self.preview_generated = true;
+
+        // 检查是否为文件夹
+        if path.is_dir() {
+            // 异步生成文件夹预览
+            self.generate_folder_preview(&path);
+            // 立即开始预加载文件夹中的图片
+            self.preload_folder_images(&path);
+        } else {
+            // 对于文件，也触发预加载其所在文件夹的图片
+            if let Some(parent) = path.parent() {
+                self.preload_folder_images(parent);
+            }
+            // 按扩展名选择已注册的预览处理器
+            let mut handlers = std::mem::take(&mut self.handlers);
+            let handled = handlers.load(self, &path, ctx);
+            self.handlers = handlers;
+            if !handled {
+                self.preview_content = "此文件类型不支持预览".to_string();
+            }
+        }
+    }
+
+    // 图片预览：依次使用预加载缓存、主缓存，都没有时异步加载；由图片预览处理器调用
+    pub fn load_image_preview(&mut self, path: &Path, ctx: &egui::Context) {
+        // 图片文件预览 - 简化逻辑
+        let mut found = false;
+
+        // 1. 先检查预加载缓存（最快）
+        if let Some((texture, size)) = self.preloader.get_cached_thumbnail(path, ctx) {
+            self.image_texture = Some(texture);
+            self.image_size = Some(size);
+            self.preview_content = format!(
+                "图片预览\n\n尺寸: {} x {} 像素\n格式: {}",
+                size.0,
+                size.1,
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.to_uppercase())
+                    .unwrap_or_else(|| "未知".to_string())
+            );
+            self.is_loading = false;
+            found = true;
+        }
+
+        // 2. 如果预加载缓存没有，检查普通缓存
+        if !found {
+            if let Some((texture, size)) = self.get_cached_image(path) {
+                self.image_texture = Some(texture);
+                self.image_size = Some(size);
+                self.preview_content = format!(
+                    "图片预览\n\n尺寸: {} x {} 像素\n格式: {}",
+                    size.0,
+                    size.1,
+                    path.extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(|ext| ext.to_uppercase())
+                        .unwrap_or_else(|| "未知".to_string())
+                );
+                self.is_loading = false;
+            } else {
+                // 3. 没有缓存，启动异步加载
+                self.is_loading = true;
+                self.preview_content = "正在加载图片...".to_string();
+                self.start_async_loading(path.to_path_buf(), ctx.clone());
+            }
+        }
+    }
+
     // 在每帧更新时调用，用于处理异步加载结果和延迟预加载
     pub fn update(&mut self, ctx: &egui::Context) {
+        // 切换主题或修改图标反色设置后丢弃已生成的纹理，当前图片重新加载
+        let recolor_state = (ctx.style().visuals.dark_mode, icon_recolor::revision());
+        if recolor_state != self.recolor_state {
+            self.recolor_state = recolor_state;
+            self.texture_cache.clear();
+            if let Ok(mut texture_cache) = self.preloader.texture_cache.lock() {
+                texture_cache.clear();
+            }
+            if self.image_texture.is_some() {
+                self.pending_image_load = self.current_file.take();
+            }
+        }
+
         // 首先处理文件夹预览通道
         if let Some(receiver) = &self.folder_preview_receiver {
-            while let Ok((preview_content, image_paths)) = receiver.try_recv() {
-                self.preview_content = preview_content;
-                self.image_stream_paths = image_paths;
+            while let Ok(folder) = receiver.try_recv() {
+                self.preview_content = folder.content;
+                self.image_stream_paths = folder.image_paths;
+                // 联系表中的视频画面交给缩略图工作线程生成
+                for path in folder.video_paths.iter().take(CONTACT_SHEET_COUNT) {
+                    if !self.preloader.is_cached(path) {
+                        let _ = self.preloader.sender.send(path.clone());
+                    }
+                }
+                self.video_stream_paths = folder.video_paths;
             }
         }
 
@@ -565,6 +750,29 @@ impl Preview {
             }
         }
 
+        // 处理专辑信息通道，只接受当前文件夹的结果
+        if let Some(receiver) = &self.album_receiver {
+            while let Ok(album) = receiver.try_recv() {
+                if self.current_file.as_ref() != Some(&album.folder) {
+                    continue;
+                }
+                self.album_cover = album.cover.as_ref().map(|cover| {
+                    let size = [cover.width() as usize, cover.height() as usize];
+                    ctx.load_texture(
+                        format!("album_cover_{}", album.folder.display()),
+                        egui::ColorImage::from_rgba_unmultiplied(size, cover),
+                        egui::TextureOptions::default(),
+                    )
+                });
+                self.album = Some(album);
+            }
+        }
+
+        // 缓存超出上限时在预算允许的帧中清理
+        if self.texture_cache.len() > self.max_main_cache_size && frame_budget::try_spend(ctx) {
+            self.cleanup_cache();
+        }
+
         // 处理图片加载请求
         if let Some(image_path) = self.pending_image_load.take() {
             self.load_preview(image_path, ctx);
@@ -598,6 +806,8 @@ impl Preview {
                             let current_file_clone = current_file.clone();
                             if let Some(img_rgba) = &result.img_rgba {
                                 if let Some((width, height)) = result.size {
+                                    let recolored = icon_recolor::prepare(&current_file_clone, img_rgba, ctx.style().visuals.dark_mode);
+                                    let img_rgba = recolored.as_ref().unwrap_or(img_rgba);
                                     // 从RgbaImage创建ColorImage，避免额外的数据拷贝
                                     let img_size = [img_rgba.width() as usize, img_rgba.height() as usize];
                                     let color_image = egui::ColorImage::from_rgba_premultiplied(img_size, img_rgba);
@@ -625,7 +835,7 @@ impl Preview {
                                     );
                                 }
                             } else if let Some(error) = &result.error {
-                                self.preview_content = format!("无法加载图片: {}", error);
+                                self.preview_content = error.clone();
                                 self.image_texture = None;
                                 self.image_size = None;
                             }
@@ -652,26 +862,6 @@ impl Preview {
         }
     }
 
-    fn generate_preview(&mut self, path: &Path, ctx: &egui::Context) {
-        if path.is_dir() {
-            self.generate_folder_preview(path);
-        } else {
-            match path.extension().and_then(|ext| ext.to_str()) {
-                Some("txt") | Some("rs") | Some("js") | Some("py") | Some("html") |
-                Some("css") | Some("json") | Some("xml") | Some("md") => {
-                    self.generate_text_preview(path);
-                }
-                Some("jpg") | Some("jpeg") | Some("png") | Some("gif") | Some("bmp") => {
-                    // 图片预览逻辑已在前面的load_preview方法中处理
-                    // 这里不需要重复处理，避免无限递归
-                }
-                _ => {
-                    self.preview_content = "此文件类型不支持预览".to_string();
-                }
-            }
-        }
-    }
-
     fn generate_folder_preview(&mut self, path: &Path) {
         // 显示加载状态，避免UI卡顿
         self.preview_content = "正在加载文件夹内容...".to_string();
@@ -684,6 +874,7 @@ impl Preview {
         
         // 克隆路径和发送器用于异步操作
         let path = path.to_path_buf();
+        let album_sender = self.album_sender.clone();
         if let Some(sender) = self.folder_preview_sender.clone() {
             
             // 在后台线程中读取文件夹内容
@@ -691,6 +882,8 @@ impl Preview {
                 let mut folders = Vec::new();
                 let mut files = Vec::new();
                 let mut image_paths = Vec::new();
+                let mut video_paths = Vec::new();
+                let mut has_audio = false;
                 
                 // 在后台线程中执行文件系统操作
                 if let Ok(entries) = fs::read_dir(&path) {
@@ -707,14 +900,13 @@ impl Preview {
                             folders.push(name);
                         } else {
                             files.push(name);
+                            has_audio |= album_preview::is_audio_file(&entry_path);
+                            if video_thumbnail::is_video_file(&entry_path) {
+                                video_paths.push(entry_path.clone());
+                            }
                             // 检查是否为图片文件
-                            if let Some(ext) = entry_path.extension() {
-                                if let Some(ext_str) = ext.to_str() {
-                                    let ext_lower = ext_str.to_lowercase();
-                                    if matches!(ext_lower.as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp") {
-                                        image_paths.push(entry_path.clone());
-                                    }
-                                }
+                            if image_formats::is_image_file(&entry_path) {
+                                image_paths.push(entry_path.clone());
                             }
                         }
                     }
@@ -739,7 +931,15 @@ impl Preview {
                 };
                 
                 // 通过通道发送预览内容回主线程
-                let _ = sender.send((preview_content, image_paths));
+                video_paths.sort();
+                let _ = sender.send(FolderPreview { content: preview_content, image_paths, video_paths });
+
+                // 有音频文件时再读取标签，完成后以专辑视图替换文字列表
+                if has_audio {
+                    if let (Some(album_sender), Some(album)) = (album_sender, album_preview::read_album(&path)) {
+                        let _ = album_sender.send(album);
+                    }
+                }
             });
         }
     }
@@ -754,192 +954,376 @@ impl Preview {
             let mut count = 0;
             for entry in entries.flatten().take(20) { // 限制数量，优先处理前20个
                 let path = entry.path();
-                if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
-                    let ext_lower = ext.to_lowercase();
-                    if matches!(ext_lower.as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp") {
-                        // 立即发送到预加载器
-                        let _ = preloader_sender.send(path);
-                        count += 1;
-                    }
+                if image_formats::is_image_file(&path) {
+                    // 立即发送到预加载器
+                    let _ = preloader_sender.send(path);
+                    count += 1;
                 }
             }
             println!("立即预加载了 {} 个图片", count);
         }
     }
 
-    fn generate_text_preview(&mut self, path: &Path) {
-        if let Ok(content) = fs::read_to_string(path) {
-            // 限制预览长度
-            let lines: Vec<&str> = content.lines().collect();
-            let preview_lines = lines.iter().take(100).collect::<Vec<_>>();
-
-            self.preview_content = if lines.len() > 100 {
-                format!(
-                    "文本预览 (前100行，共{}行):\n\n{}",
-                    lines.len(),
-                    preview_lines.iter().map(|&&line| line).collect::<Vec<_>>().join("\n")
-                )
-            } else {
-                format!(
-                    "文本预览 ({}行):\n\n{}",
-                    lines.len(),
-                    preview_lines.iter().map(|&&line| line).collect::<Vec<_>>().join("\n")
-                )
-            };
-        } else {
-            self.preview_content = "无法读取文件内容".to_string();
+    
+    // 进入比较模式：A 为当前预览的文件，B 为拖入的文件
+    pub fn start_compare(&mut self, left: PathBuf, right: PathBuf, ctx: &egui::Context) {
+        self.compare = Some(CompareView::new(left, right, ctx));
+        self.compare_from_selection = false;
+    }
+
+    // 恰好选中两个文本文件时自动比较；选择改变后退出自动开始的比较
+    pub fn sync_selected_pair(&mut self, pair: Option<(PathBuf, PathBuf)>, ctx: &egui::Context) {
+        if pair == self.selected_pair {
+            return;
+        }
+        self.selected_pair = pair.clone();
+        match pair {
+            Some((left, right)) if compare::is_text_file(&left) && compare::is_text_file(&right) => {
+                self.start_compare(left, right, ctx);
+                self.compare_from_selection = true;
+            }
+            _ => {
+                if std::mem::take(&mut self.compare_from_selection) {
+                    self.compare = None;
+                }
+            }
         }
     }
 
-    
+    // 比较模式下选中参与比较的任一文件时保持比较
+    pub fn compare_involves(&self, path: &Path) -> bool {
+        self.compare.as_ref().is_some_and(|c| c.involves(path))
+    }
+
     pub fn show(&mut self, ui: &mut egui::Ui) {
+        if let Some(compare) = &mut self.compare {
+            if !compare.show(ui) {
+                self.compare = None;
+            }
+            return;
+        }
         let current_file_clone = self.current_file.clone();
         if let Some(path) = &current_file_clone {
-            ui.vertical(|ui| {
-                // 文件信息
-                ui.group(|ui| {
-                    ui.heading("文件信息");
-                    ui.label(format!("名称: {}", path.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("未知文件")));
-                    ui.label(format!("类型: {}", self.file_info.file_type));
-                    ui.label(format!("大小: {}", self.file_info.size));
-                    ui.label(format!("修改时间: {}", self.file_info.modified));
-                });
+            ui.horizontal(|ui| {
+                for tab in PreviewTab::ALL {
+                    ui.selectable_value(&mut self.tab, tab, tab.label());
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| show_shell_path_handle(ui, path));
+            });
+            ui.separator();
+
+            // 各页内容只在该页打开时生成，切换文件后重新生成
+            ui.vertical(|ui| match self.tab {
+                PreviewTab::Info => self.show_info_tab(ui, path),
+                PreviewTab::Preview => {
+                    if !self.preview_generated {
+                        let ctx = ui.ctx().clone();
+                        self.generate_preview_content(path.clone(), &ctx);
+                    }
+                    self.show_preview_tab(ui);
+                }
+                PreviewTab::Metadata => {
+                    let rows = self.metadata_rows.get_or_insert_with(|| file_metadata::registry().read(path));
+                    if rows.is_empty() {
+                        ui.label("没有可显示的元数据");
+                    } else {
+                        show_rows(ui, "preview_metadata_grid", rows);
+                    }
+                }
+                PreviewTab::Permissions => {
+                    match self.permission_rows.get_or_insert_with(|| properties::permission_rows(path)) {
+                        Ok(rows) => show_rows(ui, "preview_permissions_grid", rows),
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e.as_str());
+                        }
+                    }
+                }
+            });
+        } else {
+            ui.label("选择一个文件查看预览");
+        }
+    }
 
-                ui.separator();
+    // 信息页：名称、位置、类型、大小、修改时间和校验值
+    fn show_info_tab(&mut self, ui: &mut egui::Ui, path: &Path) {
+        ui.group(|ui| {
+            ui.heading("文件信息");
+            ui.label(format!("名称: {}", path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("未知文件")));
+            if let Some(parent) = path.parent() {
+                ui.label(format!("位置: {}", parent.display()));
+            }
+            ui.label(format!("类型: {}", self.file_info.file_type));
+            ui.label(format!("大小: {}", self.file_info.size));
+            ui.label(format!("修改时间: {}", self.file_info.modified));
+            if let Some(encoding) = self.file_info.encoding {
+                ui.label(format!("编码: {}", encoding));
+            }
+        });
+        self.hash_panel.show(ui, path);
+    }
 
-                // 预览内容
-                if let Some(texture) = &self.image_texture {
-                    // 显示图片
-                    ui.vertical(|ui| {
-                        ui.label("图片预览:");
-
-                        // 检查纹理尺寸是否有效
-                        let texture_size = texture.size();
-                        if texture_size[0] > 0 && texture_size[1] > 0 {
-                            // 限制最大显示尺寸
-                            let max_size = ui.available_size() - egui::vec2(20.0, 20.0);
-                            let mut image_size = egui::vec2(texture_size[0] as f32, texture_size[1] as f32);
-
-                            // 缩放图片以适应可用空间
-                            let scale = (max_size.x / image_size.x).min(max_size.y / image_size.y).min(1.0);
-                            image_size *= scale;
+    // 显示图片预览；加载中或出错时显示提示文字。
+    // 滚轮缩放、拖动平移、在适应和 100% 之间切换，双击打开全窗口查看器；view 保存缩放状态和按需加载的原图
+    pub fn show_image_preview(&self, ui: &mut egui::Ui, view: &mut ImageView) {
+        let Some(thumbnail) = &self.image_texture else {
+            ui.monospace(&self.preview_content);
+            return;
+        };
+        let texture = view.full_texture.as_ref().unwrap_or(thumbnail);
+        let texture_size = texture.size();
+        if texture_size[0] == 0 || texture_size[1] == 0 {
+            ui.label("纹理数据无效");
+            return;
+        }
+        // 按原图尺寸计算缩放比例，原图还没加载时先放大缩略图
+        let (width, height) = view.full_size.unwrap_or((texture_size[0] as u32, texture_size[1] as u32));
+        let image_size = egui::vec2(width as f32, height as f32);
+        let max_size = ui.available_size() - egui::vec2(20.0, 20.0);
+        if max_size.x <= 0.0 || max_size.y <= 0.0 {
+            return;
+        }
+        let fit_scale = (max_size.x / image_size.x).min(max_size.y / image_size.y).min(1.0);
 
-                            // 确保缩放后的尺寸是有效的
-                            if image_size.x > 0.0 && image_size.y > 0.0 {
-                                let result = ui.add(
-                                    egui::Image::from_texture(egui::load::SizedTexture::new(
-                                        texture.id(),
-                                        image_size,
-                                    ))
-                                );
-
-                                // 如果图片渲染出错，显示错误信息
-                                if result.hovered() {
-                                    ui.label("图片渲染正常");
-                                }
-                            } else {
-                                ui.label("图片尺寸无效");
-                            }
+        ui.horizontal(|ui| {
+            let scale = view.zoom.unwrap_or(fit_scale);
+            ui.label(format!("{} x {} 像素，{:.0}%", width, height, scale * 100.0));
+            if ui.selectable_label(view.zoom.is_none(), "适应").clicked() {
+                view.zoom = None;
+            }
+            if ui.selectable_label(view.zoom == Some(1.0), "100%").clicked() {
+                view.zoom = Some(1.0);
+                view.pan = egui::Vec2::ZERO;
+            }
+        });
 
-                            // 显示图片信息
-                            if let Some((width, height)) = self.image_size {
-                                ui.label(format!("实际尺寸: {} x {} 像素", width, height));
-                                ui.label(format!("显示尺寸: {:.0} x {:.0} 像素", image_size.x, image_size.y));
-                            }
-                        } else {
-                            ui.label("纹理数据无效");
-                        }
-                    });
-                } else if !self.preview_content.is_empty() {
-                    ui.monospace(&self.preview_content);
-                    
-                    // 显示图片流预览（如果有图片）
-                    if !self.image_stream_paths.is_empty() {
-                        ui.separator();
-                        ui.heading("图片预览");
-                        
-                        // 显示加载状态和进度
-                        let cached_count = self.image_stream_paths.iter()
-                            .filter(|path| self.preloader.is_cached(path))
-                            .count();
-                        let total_count = self.image_stream_paths.len();
-                        
-                        if cached_count < total_count {
-                            ui.label(format!("正在加载图片: {}/{} 已缓存", cached_count, total_count));
-                            // 强制请求重绘，确保加载状态及时更新
-                            ui.ctx().request_repaint();
+        // 视口宽度占满预览栏，高度与适应时的图片一致，缩放时布局不跳动
+        let viewport_size = egui::vec2(max_size.x, image_size.y * fit_scale);
+        let (viewport, response) = ui.allocate_exact_size(viewport_size, egui::Sense::click_and_drag());
+        if response.double_clicked() {
+            view.open_viewer = true;
+        }
+        if response.hovered() {
+            // 适应大小时向下滚动仍然滚动预览栏；其余情况取走滚动量用于缩放
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll > 0.0 || (scroll < 0.0 && view.zoom.is_some()) {
+                ui.input_mut(|i| i.smooth_scroll_delta.y = 0.0);
+                let old = view.zoom.unwrap_or(fit_scale);
+                let new = (old * (scroll * 0.002).exp()).clamp(fit_scale.min(0.05), MAX_IMAGE_ZOOM);
+                // 以鼠标位置为中心缩放
+                if let Some(pointer) = response.hover_pos() {
+                    let anchor = pointer - viewport.center();
+                    view.pan = (view.pan - anchor) * (new / old) + anchor;
+                }
+                view.zoom = if new <= fit_scale { None } else { Some(new) };
+            }
+        }
+        let scale = match view.zoom {
+            Some(zoom) => zoom,
+            None => {
+                view.pan = egui::Vec2::ZERO;
+                fit_scale
+            }
+        };
+        if response.dragged() && view.zoom.is_some() {
+            view.pan += response.drag_delta();
+        }
+        let shown = image_size * scale;
+        // 图片比视口大时不能拖出空白
+        let limit = ((shown - viewport.size()) / 2.0).max(egui::Vec2::ZERO);
+        view.pan = view.pan.clamp(-limit, limit);
+        // 缩略图不够清晰时请求加载原图
+        view.needs_full = view.full_texture.is_none() && shown.x > thumbnail.size()[0] as f32 + 1.0;
+
+        let rect = egui::Rect::from_center_size(viewport.center() + view.pan, shown);
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        ui.painter_at(viewport).image(texture.id(), rect, uv, egui::Color32::WHITE);
+        if view.zoom.is_some() {
+            response.on_hover_cursor(egui::CursorIcon::Grab);
+        }
+        if view.loading_full {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("正在加载原图...");
+            });
+        }
+    }
+
+    // 取出双击图片预览时要在全窗口查看器中打开的图片
+    pub fn take_open_viewer(&mut self) -> Option<PathBuf> {
+        self.handlers.take_open_viewer()
+    }
+
+    // 取出文本预览中要在内置查看器中打开的文件
+    pub fn take_open_text_viewer(&mut self) -> Option<PathBuf> {
+        self.handlers.take_open_text_viewer()
+    }
+
+    // 预览页：图片、专辑、视频联系表、文本或文件夹内容
+    // 文件夹预览上方的深度扫描：按需开始，可随时取消
+    fn show_folder_scan(&mut self, ui: &mut egui::Ui, dir: &Path) {
+        match &mut self.folder_scan {
+            Some(scan) if scan.root() == dir => {
+                if !scan.show(ui) {
+                    self.folder_scan = None;
+                }
+            }
+            _ => {
+                if ui.button("📊 深度扫描").on_hover_text("统计总大小、最大的文件和各类文件所占空间").clicked() {
+                    self.folder_scan = Some(FolderScan::start(dir.to_path_buf(), ui.ctx()));
+                }
+            }
+        }
+        ui.separator();
+    }
+
+    fn show_preview_tab(&mut self, ui: &mut egui::Ui) {
+        // 文本、图片等由预览处理器绘制
+        if self.handlers.is_active() {
+            let mut handlers = std::mem::take(&mut self.handlers);
+            handlers.render(self, ui);
+            self.handlers = handlers;
+            return;
+        }
+        if let Some(dir) = self.current_file.clone().filter(|path| path.is_dir()) {
+            self.show_folder_scan(ui, &dir);
+        }
+        if let Some(album) = &self.album {
+            album_preview::show_album(ui, album, self.album_cover.as_ref());
+        } else if !self.preview_content.is_empty() {
+            if !self.video_stream_paths.is_empty() {
+                self.show_video_contact_sheet(ui);
+                ui.separator();
+            }
+            ui.monospace(&self.preview_content);
+            
+            // 显示图片流预览（如果有图片）
+            if !self.image_stream_paths.is_empty() {
+                ui.separator();
+                ui.heading("图片预览");
+                
+                // 显示加载状态和进度
+                let cached_count = self.image_stream_paths.iter()
+                    .filter(|path| self.preloader.is_cached(path))
+                    .count();
+                let total_count = self.image_stream_paths.len();
+                
+                if cached_count < total_count {
+                    ui.label(format!("正在加载图片: {}/{} 已缓存", cached_count, total_count));
+                    // 强制请求重绘，确保加载状态及时更新
+                    ui.ctx().request_repaint();
+                }
+                
+                // 竖向图片流 - 限制显示数量避免卡顿
+                let max_images_to_show = 20; // 最多显示20张图片
+                for (index, image_path) in self.image_stream_paths.iter().enumerate().take(max_images_to_show) {
+                    // 已缓存时显示；本帧预算用完的图片下一帧再创建纹理
+                    if let Some((texture, size)) = self.preloader.get_cached_thumbnail_budgeted(image_path, ui.ctx()) {
+                        let mut image_size = egui::vec2(size.0 as f32, size.1 as f32);
+                        // 限制图片宽度为200px，保持比例
+                        let max_width = 200.0;
+                        if image_size.x > max_width {
+                            let scale = max_width / image_size.x;
+                            image_size *= scale;
                         }
                         
-                        // 竖向图片流 - 限制显示数量避免卡顿
-                        let max_images_to_show = 20; // 最多显示20张图片
-                        for (index, image_path) in self.image_stream_paths.iter().enumerate().take(max_images_to_show) {
-                            // 检查图片是否已缓存
-                            if self.preloader.is_cached(image_path) {
-                                if let Some((texture, size)) = self.preloader.get_cached_thumbnail(image_path, ui.ctx()) {
-                                    let mut image_size = egui::vec2(size.0 as f32, size.1 as f32);
-                                    // 限制图片宽度为200px，保持比例
-                                    let max_width = 200.0;
-                                    if image_size.x > max_width {
-                                        let scale = max_width / image_size.x;
-                                        image_size *= scale;
-                                    }
-                                    
-                                    if image_size.x > 0.0 && image_size.y > 0.0 {
-                                        let response = ui.add(
-                                            egui::Image::from_texture(egui::load::SizedTexture::new(
-                                                texture.id(),
-                                                image_size,
-                                            ))
-                                        );
-                                        
-                                        // 点击图片预览
-                                        if response.clicked() {
-                                            self.selected_image_index = Some(index);
-                                            self.current_file = Some(image_path.clone());
-                                            self.pending_image_load = Some(image_path.clone());
-                                        }
-                                        
-                                        // 鼠标悬停显示文件名
-                                        if response.hovered() {
-                                            if let Some(file_name) = image_path.file_name() {
-                                                response.on_hover_text(file_name.to_string_lossy());
-                                            }
-                                        }
-                                    }
-                                }
-                            } else {
-                                // 显示占位符和加载状态
-                                ui.horizontal(|ui| {
-                                    ui.spinner();
-                                    ui.label("加载中...");
-                                    if let Some(file_name) = image_path.file_name() {
-                                        ui.label(file_name.to_string_lossy());
-                                    }
-                                });
-                                
-                                // 触发异步加载（确保只发送一次）
-                                let cache_key = image_path.to_string_lossy().to_string();
-                                if let Ok(cache_guard) = self.preloader.cache.lock() {
-                                    if !cache_guard.contains_key(&cache_key) {
-                                        let _ = self.preloader.sender.send(image_path.clone());
-                                    }
+                        if image_size.x > 0.0 && image_size.y > 0.0 {
+                            let response = ui.add(
+                                egui::Image::from_texture(egui::load::SizedTexture::new(
+                                    texture.id(),
+                                    image_size,
+                                ))
+                            );
+                            
+                            // 点击图片预览
+                            if response.clicked() {
+                                self.selected_image_index = Some(index);
+                                self.current_file = Some(image_path.clone());
+                                self.pending_image_load = Some(image_path.clone());
+                            }
+                            
+                            // 鼠标悬停显示文件名
+                            if response.hovered() {
+                                if let Some(file_name) = image_path.file_name() {
+                                    response.on_hover_text(file_name.to_string_lossy());
                                 }
                             }
                         }
+                    } else {
+                        // 显示占位符和加载状态
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("加载中...");
+                            if let Some(file_name) = image_path.file_name() {
+                                ui.label(file_name.to_string_lossy());
+                            }
+                        });
                         
-                        // 如果图片数量超过限制，显示提示信息
-                        if self.image_stream_paths.len() > max_images_to_show {
-                            ui.label(format!("还有 {} 张图片...", self.image_stream_paths.len() - max_images_to_show));
+                        // 触发异步加载（确保只发送一次）
+                        let cache_key = image_path.to_string_lossy().to_string();
+                        if let Ok(cache_guard) = self.preloader.cache.lock() {
+                            if !cache_guard.contains_key(&cache_key) {
+                                let _ = self.preloader.sender.send(image_path.clone());
+                            }
                         }
                     }
-                } else {
-                    ui.label("无预览内容");
                 }
-            });
+                
+                // 如果图片数量超过限制，显示提示信息
+                if self.image_stream_paths.len() > max_images_to_show {
+                    ui.label(format!("还有 {} 张图片...", self.image_stream_paths.len() - max_images_to_show));
+                }
+            }
         } else {
-            ui.label("选择一个文件查看预览");
+            ui.label("无预览内容");
+        }
+    }
+
+    // 视频文件夹的联系表：前几个视频的第一帧画面
+    fn show_video_contact_sheet(&self, ui: &mut egui::Ui) {
+        ui.heading("视频预览");
+        if !video_thumbnail::ffmpeg_available() {
+            ui.label("需要安装 ffmpeg 才能生成视频画面");
+            return;
+        }
+
+        let columns = 3;
+        let spacing = 4.0;
+        let cell_w = ((ui.available_width() - spacing * (columns - 1) as f32) / columns as f32).max(40.0);
+        let cell_size = egui::vec2(cell_w, cell_w * 9.0 / 16.0);
+        let mut pending = false;
+        egui::Grid::new("video_contact_sheet").spacing([spacing, spacing]).show(ui, |ui| {
+            for (index, path) in self.video_stream_paths.iter().take(CONTACT_SHEET_COUNT).enumerate() {
+                let (rect, response) = ui.allocate_exact_size(cell_size, egui::Sense::hover());
+                let painter = ui.painter();
+                painter.rect_filled(rect, 2.0, egui::Color32::BLACK);
+                if let Some((texture, (width, height))) = self.preloader.get_cached_thumbnail_budgeted(path, ui.ctx()) {
+                    // 保持宽高比居中显示
+                    let scale = (rect.width() / width as f32).min(rect.height() / height as f32);
+                    let image_rect = egui::Rect::from_center_size(rect.center(), egui::vec2(width as f32, height as f32) * scale);
+                    painter.image(texture.id(), image_rect, egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), egui::Color32::WHITE);
+                } else {
+                    let failed = video_thumbnail::has_failed(path);
+                    pending |= !failed;
+                    let text = if failed { "🎬" } else { "..." };
+                    painter.text(rect.center(), egui::Align2::CENTER_CENTER, text, egui::FontId::proportional(16.0), egui::Color32::GRAY);
+                }
+                if let Some(name) = path.file_name() {
+                    response.on_hover_text(name.to_string_lossy());
+                }
+                if (index + 1) % columns == 0 {
+                    ui.end_row();
+                }
+            }
+        });
+
+        if pending {
+            // 工作线程不会主动通知界面，定时重绘以显示生成好的画面
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        if self.video_stream_paths.len() > CONTACT_SHEET_COUNT {
+            ui.label(format!("共 {} 个视频", self.video_stream_paths.len()));
         }
     }
 
@@ -1001,7 +1385,6 @@ impl Preview {
                     last_modified: modified,
                 };
                 self.texture_cache.insert(cache_key, cached);
-                self.cleanup_cache();
             }
         }
     }
@@ -1043,12 +1426,7 @@ impl Preview {
         }
 
         // 检查是否为图片格式
-        let is_image = path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| matches!(ext.to_lowercase().as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp"))
-            .unwrap_or(false);
-
-        if !is_image {
+        if !image_formats::is_image_file(path) {
             return LoadingResult {
                 img_rgba: None,
                 size: None,
@@ -1059,7 +1437,7 @@ impl Preview {
         }
 
         // 直接加载并生成缩略图 (最大800px)
-        match image::open(path) {
+        match image_formats::open_image(path) {
             Ok(img) => {
                 let (width, height) = img.dimensions();
 
@@ -1091,10 +1469,24 @@ impl Preview {
                 }
             }
             Err(e) => {
+                // RAW 文件没有可用的内嵌预览时，改为显示相机记录的元数据
+                let error = if raw_image::is_raw_file(path) {
+                    let rows = file_metadata::registry().read(path);
+                    let mut text = format!("无法解码 RAW 预览: {}", e);
+                    if !rows.is_empty() {
+                        text.push_str("\n\n");
+                        for (key, value) in rows {
+                            text.push_str(&format!("{}: {}\n", key, value));
+                        }
+                    }
+                    text
+                } else {
+                    format!("无法加载图片: {}", e)
+                };
                 LoadingResult {
                     img_rgba: None,
                     size: None,
-                    error: Some(format!("无法加载图片: {}", e)),
+                    error: Some(error),
                     file_path: path.to_path_buf(),
                     folder_content: None,
                 }
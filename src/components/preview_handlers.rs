@@ -0,0 +1,1144 @@
+use eframe::egui;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use crossbeam_channel::{self, Receiver, Sender};
+use super::preview::{ImageView, Preview};
+use super::file_metadata::{self, MetadataRows};
+use super::album_preview::{self, TrackInfo};
+use super::audio_player::{self, AudioPlayer};
+use super::archive_preview::{self, ArchiveListing};
+use super::office_preview::{self, OfficeContent};
+use super::desktop_entry::{self, DesktopEntry};
+use super::elf_info::{self, ElfInfo};
+use super::{icon_recolor, image_formats, qr_code, text_encoding, text_viewer, video_thumbnail};
+use super::text_viewer::TextViewOptions;
+use super::video_thumbnail::VideoStream;
+use crate::utils;
+
+// 文件预览处理器：新的预览类型实现此 trait 并在 PreviewHandlers::new 中注册，
+// 不需要修改 Preview 中的代码。处理器可以自己保存状态，也可以使用 Preview 的缓存
+pub trait PreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool;
+    // 开始生成预览，耗时工作应放到后台线程
+    fn load_async(&mut self, preview: &mut Preview, path: &Path, ctx: &egui::Context);
+    // 在预览页中绘制预览
+    fn render(&mut self, preview: &Preview, ui: &mut egui::Ui);
+    // 切换到其它文件时调用，用于停止播放等
+    fn unload(&mut self) {}
+    // 取出要在全窗口查看器中打开的文件（双击图片预览）
+    fn take_open_viewer(&mut self) -> Option<PathBuf> {
+        None
+    }
+    // 取出要在内置文本查看器中打开的文件
+    fn take_open_text_viewer(&mut self) -> Option<PathBuf> {
+        None
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// 后台读取的文本预览和显示方式
+type LoadedPreview = (PathBuf, TextViewOptions, Result<TextPreview, String>);
+
+// 文本文件：后台读取前 100 行，可显示行号、切换自动换行，或在内置查看器中打开整个文件
+#[derive(Default)]
+struct TextPreviewHandler {
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<LoadedPreview>>,
+    content: Option<Result<TextPreview, String>>,
+    options: TextViewOptions,
+    open_viewer: bool,
+}
+
+const TEXT_PREVIEW_LINES: usize = 100;
+// 只读取文件开头的这么多字节，大文件不整个读入内存
+const TEXT_PREVIEW_BYTES: u64 = 256 * 1024;
+
+struct TextPreview {
+    lines: Vec<String>, // 前 TEXT_PREVIEW_LINES 行
+    total: usize,       // 读取部分的行数
+    truncated: bool,    // 文件超过 TEXT_PREVIEW_BYTES，总行数至少为 total
+}
+
+// 按检测到的编码（GBK、Big5、Latin-1 等）转换为 UTF-8 后显示
+fn read_text_preview(path: &Path) -> Result<TextPreview, String> {
+    let mut bytes = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(TEXT_PREVIEW_BYTES + 1).read_to_end(&mut bytes))
+        .map_err(|_| "无法读取文件内容".to_string())?;
+    let truncated = bytes.len() as u64 > TEXT_PREVIEW_BYTES;
+    if truncated {
+        // 去掉最后不完整的一行，避免截断多字节字符
+        let end = bytes.iter().rposition(|b| *b == b'\n').map_or(TEXT_PREVIEW_BYTES as usize, |i| i + 1);
+        bytes.truncate(end);
+    }
+    let (content, _) = text_encoding::decode(&bytes);
+    Ok(TextPreview {
+        lines: content.lines().take(TEXT_PREVIEW_LINES).map(str::to_string).collect(),
+        total: content.lines().count(),
+        truncated,
+    })
+}
+
+impl PreviewHandler for TextPreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        has_extension(path, &["txt", "rs", "js", "py", "html", "css", "json", "xml", "md"])
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path, ctx) = (path.to_path_buf(), ctx.clone());
+        self.path = Some(path.clone());
+        self.content = None;
+        self.receiver = Some(receiver);
+        std::thread::spawn(move || {
+            // 查看器中可能修改过显示方式，与文件一起在后台读取
+            let options = TextViewOptions::load();
+            let content = read_text_preview(&path);
+            let _ = sender.send((path, options, content));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        // 只接受当前文件的结果
+        if let Some((path, options, content)) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.options = options;
+                self.content = Some(content);
+                self.receiver = None;
+            }
+        }
+        match &self.content {
+            Some(Ok(preview)) => {
+                ui.horizontal(|ui| {
+                    if preview.truncated {
+                        ui.label(format!("文本预览 (前{}行，至少{}行)", preview.lines.len(), preview.total));
+                    } else if preview.total > TEXT_PREVIEW_LINES {
+                        ui.label(format!("文本预览 (前{}行，共{}行)", TEXT_PREVIEW_LINES, preview.total));
+                    } else {
+                        ui.label(format!("文本预览 ({}行)", preview.total));
+                    }
+                    if ui.small_button("在查看器中打开").on_hover_text("在内置文本查看器中显示整个文件").clicked() {
+                        self.open_viewer = true;
+                    }
+                });
+                ui.horizontal(|ui| self.options.show_toggles(ui));
+                ui.separator();
+                text_viewer::show_lines(ui, "text_preview_lines", &preview.lines, self.options);
+            }
+            Some(Err(e)) => {
+                ui.label(e);
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取文件...");
+                });
+            }
+        }
+    }
+
+    fn take_open_text_viewer(&mut self) -> Option<PathBuf> {
+        if std::mem::take(&mut self.open_viewer) {
+            self.path.clone()
+        } else {
+            None
+        }
+    }
+}
+
+// 后台读取的原图尺寸和 EXIF 字段
+type PhotoInfo = (PathBuf, Option<(u32, u32)>, MetadataRows);
+
+// 图片文件：使用 Preview 的预加载缓存、纹理缓存和异步加载；照片的 EXIF 字段和图中的二维码在后台读取
+#[derive(Default)]
+struct ImagePreviewHandler {
+    path: Option<PathBuf>,
+    exif_receiver: Option<Receiver<PhotoInfo>>,
+    exif: MetadataRows,
+    qr_receiver: Option<Receiver<(PathBuf, Vec<String>)>>,
+    qr_codes: Vec<String>,
+    view: ImageView,
+    full_receiver: Option<Receiver<(PathBuf, Result<egui::ColorImage, String>)>>,
+    full_error: Option<String>, // 原图解码失败的原因；失败后不再重试，继续放大缩略图
+}
+
+impl ImagePreviewHandler {
+    // 放大到缩略图不够清晰时在后台解码原图；超过显卡纹理尺寸上限时缩小到上限
+    fn load_full_image(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let max_side = ctx.input(|i| i.max_texture_side) as u32;
+        let dark_mode = ctx.style().visuals.dark_mode;
+        let ctx = ctx.clone();
+        self.view.loading_full = true;
+        self.full_receiver = Some(receiver);
+        std::thread::spawn(move || {
+            let image = image_formats::open_image(&path).map(|image| {
+                let image = if image.width() > max_side || image.height() > max_side {
+                    image.resize(max_side, max_side, image::imageops::FilterType::Triangle)
+                } else {
+                    image
+                };
+                let rgba = image.to_rgba8();
+                let size = [rgba.width() as usize, rgba.height() as usize];
+                match icon_recolor::prepare(&path, &rgba, dark_mode) {
+                    Some(recolored) => egui::ColorImage::from_rgba_premultiplied(size, recolored.as_raw()),
+                    None => egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()),
+                }
+            });
+            let _ = sender.send((path, image));
+            ctx.request_repaint();
+        });
+    }
+}
+
+impl PreviewHandler for ImagePreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        image_formats::is_image_file(path)
+    }
+
+    fn load_async(&mut self, preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        preview.load_image_preview(path, ctx);
+        self.view = ImageView::default();
+        self.full_receiver = None;
+        self.full_error = None;
+
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path, ctx) = (path.to_path_buf(), ctx.clone());
+        self.path = Some(path.clone());
+        self.exif.clear();
+        self.exif_receiver = Some(receiver);
+        let (exif_path, exif_ctx) = (path.clone(), ctx.clone());
+        std::thread::spawn(move || {
+            // 读取原图尺寸需要打开文件，与 EXIF 一起在后台读取
+            let size = image_formats::image_dimensions(&exif_path);
+            let rows = file_metadata::photo_exif(&exif_path);
+            let _ = sender.send((exif_path, size, rows));
+            exif_ctx.request_repaint();
+        });
+
+        // 识别二维码需要解码整张图片，与 EXIF 分开，不拖慢 EXIF 的显示
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        self.qr_codes.clear();
+        self.qr_receiver = Some(receiver);
+        std::thread::spawn(move || {
+            let codes = image_formats::open_image(&path)
+                .map(|image| qr_code::decode_qr_codes(&image))
+                .unwrap_or_default();
+            let _ = sender.send((path, codes));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render(&mut self, preview: &Preview, ui: &mut egui::Ui) {
+        if let Some((path, size, rows)) = self.exif_receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                // 解码出的原图可能已缩小到纹理尺寸上限，以文件中记录的尺寸为准
+                if size.is_some() {
+                    self.view.full_size = size;
+                }
+                self.exif = rows;
+                self.exif_receiver = None;
+            }
+        }
+        if let Some((path, codes)) = self.qr_receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.qr_codes = codes;
+                self.qr_receiver = None;
+            }
+        }
+        if let Some((path, image)) = self.full_receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.full_receiver = None;
+                self.view.loading_full = false;
+                match image {
+                    Ok(image) => {
+                        // RAW 等读不到尺寸的图片以解码出的预览图为准
+                        self.view.full_size.get_or_insert((image.size[0] as u32, image.size[1] as u32));
+                        self.view.full_texture = Some(ui.ctx().load_texture("full_image", image, egui::TextureOptions::LINEAR));
+                    }
+                    Err(e) => self.full_error = Some(e),
+                }
+            }
+        }
+        preview.show_image_preview(ui, &mut self.view);
+        if let Some(error) = &self.full_error {
+            ui.colored_label(ui.visuals().error_fg_color, format!("加载原图失败: {}", error));
+        }
+        if self.view.needs_full && !self.view.loading_full && self.full_error.is_none() {
+            self.load_full_image(ui.ctx());
+        }
+
+        // 深色主题下可以为单个文件指定是否反色，自动模式只处理单色图标
+        if let (true, Some(path)) = (ui.visuals().dark_mode, &self.path) {
+            let current = icon_recolor::mode(path);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("深色主题下:").weak());
+                egui::ComboBox::from_id_salt("icon_recolor_mode")
+                    .selected_text(current.label())
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for mode in icon_recolor::RecolorMode::ALL {
+                            if ui.selectable_label(mode == current, mode.label()).clicked() && mode != current {
+                                icon_recolor::set_mode(path, mode);
+                            }
+                        }
+                    });
+            });
+        }
+
+        for content in &self.qr_codes {
+            ui.separator();
+            ui.strong("二维码内容");
+            ui.add(egui::Label::new(content).selectable(true));
+            ui.horizontal(|ui| {
+                if ui.button("📋 复制").clicked() {
+                    ui.ctx().copy_text(content.clone());
+                }
+                if qr_code::is_url(content) && ui.button("🔗 打开链接").clicked() {
+                    ui.ctx().open_url(egui::OpenUrl::new_tab(content.trim()));
+                }
+            });
+        }
+
+        // 没有 EXIF 的图片（截图、网页图片等）不显示此部分
+        if !self.exif.is_empty() {
+            ui.separator();
+            egui::CollapsingHeader::new("EXIF 信息").id_salt("image_preview_exif").default_open(true).show(ui, |ui| {
+                egui::Grid::new("image_preview_exif_grid").num_columns(2).show(ui, |ui| {
+                    for (key, value) in &self.exif {
+                        ui.label(egui::RichText::new(key).weak());
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+
+    fn take_open_viewer(&mut self) -> Option<PathBuf> {
+        if std::mem::take(&mut self.view.open_viewer) {
+            self.path.clone()
+        } else {
+            None
+        }
+    }
+}
+
+// 视频文件：封面画面、时长/分辨率/编码信息，选中后静音自动播放（ffmpeg 连续解码到纹理），可拖动进度条。
+// 无法播放时保留封面画面，拖动进度条时逐帧截取
+#[derive(Default)]
+struct VideoPreviewHandler {
+    path: Option<PathBuf>,
+    info_receiver: Option<Receiver<(MetadataRows, Option<f64>)>>,
+    rows: MetadataRows,
+    duration: Option<f64>,
+    frame_requests: Option<Sender<f64>>, // 发给取帧线程的时间点
+    frame_receiver: Option<Receiver<Result<egui::ColorImage, String>>>,
+    texture: Option<egui::TextureHandle>,
+    error: Option<String>,
+    position: f64,
+    playing: bool,
+    last_tick: Option<Instant>,
+    stream: Option<VideoStream>,        // 播放中的画面流
+    stream_frames: u64,                 // 当前画面流已收到的帧数
+    playback_failed: bool,              // 无法连续播放，只显示封面和拖动时截取的画面
+    autoplayed: bool,
+}
+
+// 播放时画面的宽度，越小越流畅
+const PLAYBACK_WIDTH: u32 = 480;
+
+fn to_color_image(image: image::DynamicImage) -> egui::ColorImage {
+    let rgba = image.to_rgba8();
+    egui::ColorImage::from_rgba_unmultiplied([rgba.width() as usize, rgba.height() as usize], rgba.as_raw())
+}
+
+impl VideoPreviewHandler {
+    fn request_frame(&self, seconds: f64) {
+        if let Some(sender) = &self.frame_requests {
+            let _ = sender.send(seconds);
+        }
+    }
+
+    fn poll(&mut self, ctx: &egui::Context) {
+        if let Some((rows, duration)) = self.info_receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.rows = rows;
+            self.duration = duration;
+            self.info_receiver = None;
+        }
+        let frames: Vec<_> = self.frame_receiver.as_ref().map(|r| r.try_iter().collect()).unwrap_or_default();
+        for frame in frames {
+            match frame {
+                Ok(image) => match &mut self.texture {
+                    Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+                    None => {
+                        self.texture = Some(ctx.load_texture("video_preview", image, egui::TextureOptions::LINEAR));
+                        // 封面出来后静音自动播放
+                        if !self.autoplayed {
+                            self.autoplayed = true;
+                            self.playing = true;
+                            self.last_tick = None;
+                        }
+                    }
+                },
+                Err(msg) => {
+                    // 播放到结尾时取不到帧是正常的
+                    if self.texture.is_none() {
+                        self.error = Some(msg);
+                    }
+                    self.playing = false;
+                }
+            }
+        }
+    }
+
+    // 播放时按真实时间推进位置，并显示画面流中的最新一帧
+    fn advance(&mut self, ctx: &egui::Context) {
+        if !self.playing {
+            self.stream = None;
+            return;
+        }
+        let now = Instant::now();
+        let elapsed = self.last_tick.map(|t| now.duration_since(t).as_secs_f64()).unwrap_or(0.0);
+        self.last_tick = Some(now);
+        self.position += elapsed;
+        if let Some(duration) = self.duration {
+            if self.position >= duration {
+                self.position = duration;
+                self.playing = false;
+                self.stream = None;
+                return;
+            }
+        }
+
+        if self.stream.is_none() {
+            // 画面流的尺寸按封面的宽高比计算
+            let (Some(path), Some(texture)) = (&self.path, &self.texture) else {
+                return;
+            };
+            let [width, height] = texture.size();
+            let height = ((PLAYBACK_WIDTH as f64 * height as f64 / width.max(1) as f64).round() as u32).max(2) & !1;
+            let repaint = ctx.clone();
+            match VideoStream::start(path, self.position, (PLAYBACK_WIDTH, height), move || repaint.request_repaint()) {
+                Ok(stream) => {
+                    self.stream = Some(stream);
+                    self.stream_frames = 0;
+                }
+                Err(_) => {
+                    self.playback_failed = true;
+                    self.playing = false;
+                    return;
+                }
+            }
+        }
+        match self.stream.as_ref().map(|s| s.latest_frame()) {
+            Some(Ok(Some(frame))) => {
+                self.stream_frames += 1;
+                let image = egui::ColorImage::from_rgba_unmultiplied([frame.width() as usize, frame.height() as usize], frame.as_raw());
+                if let Some(texture) = &mut self.texture {
+                    texture.set(image, egui::TextureOptions::LINEAR);
+                }
+            }
+            Some(Ok(None)) | None => {}
+            // 一帧都没有解出来说明无法播放；否则是播放到了结尾
+            Some(Err(())) => {
+                self.playback_failed = self.stream_frames == 0;
+                self.playing = false;
+                self.stream = None;
+                return;
+            }
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(15));
+    }
+}
+
+impl PreviewHandler for VideoPreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        video_thumbnail::is_video_file(path)
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        // 替换通道后旧的取帧线程在当前任务结束后退出
+        *self = Self::default();
+        self.path = Some(path.to_path_buf());
+        if !video_thumbnail::ffmpeg_available() {
+            self.error = Some("未找到 ffmpeg，无法预览视频".to_string());
+            return;
+        }
+
+        let (info_sender, info_receiver) = crossbeam_channel::bounded(1);
+        let (path_buf, repaint) = (path.to_path_buf(), ctx.clone());
+        std::thread::spawn(move || {
+            let rows = file_metadata::registry().read(&path_buf);
+            let _ = info_sender.send((rows, video_thumbnail::probe_duration(&path_buf)));
+            repaint.request_repaint();
+        });
+        self.info_receiver = Some(info_receiver);
+
+        // 取帧线程：先读取封面（与缩略图共用磁盘缓存），之后只处理最新的时间点
+        let (request_sender, request_receiver) = crossbeam_channel::unbounded::<f64>();
+        let (frame_sender, frame_receiver) = crossbeam_channel::unbounded();
+        let (path_buf, repaint) = (path.to_path_buf(), ctx.clone());
+        std::thread::spawn(move || {
+            let poster = video_thumbnail::video_frame(&path_buf).map(to_color_image);
+            if frame_sender.send(poster).is_err() {
+                return;
+            }
+            repaint.request_repaint();
+            while let Ok(first) = request_receiver.recv() {
+                let seconds = request_receiver.try_iter().last().unwrap_or(first);
+                let frame = video_thumbnail::frame_at(&path_buf, seconds, PLAYBACK_WIDTH).map(to_color_image);
+                if frame_sender.send(frame).is_err() {
+                    return;
+                }
+                repaint.request_repaint();
+            }
+        });
+        self.frame_requests = Some(request_sender);
+        self.frame_receiver = Some(frame_receiver);
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        self.poll(ui.ctx());
+        self.advance(ui.ctx());
+
+        if let Some(error) = &self.error {
+            ui.label(error);
+        }
+        match &self.texture {
+            Some(texture) => {
+                let size = texture.size_vec2();
+                let scale = ((ui.available_width() - 20.0) / size.x).clamp(0.1, 1.0);
+                ui.add(egui::Image::from_texture(egui::load::SizedTexture::new(texture.id(), size * scale)));
+            }
+            None if self.error.is_none() => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取视频画面...");
+                });
+            }
+            None => {}
+        }
+
+        // 播放/暂停和进度条
+        if let Some(duration) = self.duration {
+            ui.horizontal(|ui| {
+                let label = if self.playing { "⏸ 暂停" } else { "▶ 播放" };
+                let can_play = self.texture.is_some() && !self.playback_failed;
+                if ui.add_enabled(can_play, egui::Button::new(label)).clicked() {
+                    self.playing = !self.playing;
+                    self.last_tick = None;
+                    if self.playing && self.position >= duration {
+                        self.position = 0.0;
+                    }
+                }
+                let slider = egui::Slider::new(&mut self.position, 0.0..=duration).show_value(false);
+                if ui.add(slider).changed() {
+                    // 播放中拖动时从新位置重新开始解码，暂停时只截取一帧
+                    self.stream = None;
+                    self.last_tick = None;
+                    if !self.playing {
+                        self.request_frame(self.position);
+                    }
+                }
+                ui.label(format!("{} / {}", album_preview::format_duration(self.position), album_preview::format_duration(duration)));
+            });
+            if self.playback_failed {
+                ui.label(egui::RichText::new("无法连续播放此视频，拖动进度条查看画面").weak());
+            }
+        }
+
+        if !self.rows.is_empty() {
+            ui.separator();
+            egui::Grid::new("video_preview_info").num_columns(2).show(ui, |ui| {
+                for (key, value) in &self.rows {
+                    ui.label(key);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+        }
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// 音频文件：标签、封面和时长，以及播放/暂停和进度条试听
+#[derive(Default)]
+struct AudioPreviewHandler {
+    receiver: Option<Receiver<TrackInfo>>,
+    info: Option<TrackInfo>,
+    cover: Option<egui::TextureHandle>,
+    player: Option<AudioPlayer>,
+    seek_position: f64,
+    error: Option<String>,
+}
+
+impl PreviewHandler for AudioPreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        album_preview::is_audio_file(path)
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path_buf, ctx) = (path.to_path_buf(), ctx.clone());
+        std::thread::spawn(move || {
+            let _ = sender.send(album_preview::read_track_info(&path_buf));
+            ctx.request_repaint();
+        });
+        // 替换播放器时停止上一个文件的播放
+        *self = Self {
+            receiver: Some(receiver),
+            player: Some(AudioPlayer::new(path)),
+            ..Self::default()
+        };
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        if let Some(info) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.cover = info.cover.as_ref().map(|cover| {
+                let image = egui::ColorImage::from_rgba_unmultiplied([cover.width() as usize, cover.height() as usize], cover.as_raw());
+                ui.ctx().load_texture("audio_preview_cover", image, egui::TextureOptions::LINEAR)
+            });
+            self.info = Some(info);
+            self.receiver = None;
+        }
+        let Some(info) = &self.info else {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("正在读取标签...");
+            });
+            return;
+        };
+
+        if let Some(texture) = &self.cover {
+            let size = texture.size_vec2();
+            let scale = ((ui.available_width() - 20.0) / size.x.max(1.0)).min(1.0);
+            ui.add(egui::Image::from_texture(egui::load::SizedTexture::new(texture.id(), size * scale)));
+        }
+        ui.heading(&info.track.title);
+        egui::Grid::new("audio_preview_tags").num_columns(2).show(ui, |ui| {
+            for (label, value) in [("艺术家", &info.track.artist), ("专辑", &info.album)] {
+                if let Some(value) = value {
+                    ui.label(label);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            }
+            if let Some(duration) = info.track.duration {
+                ui.label("时长");
+                ui.label(album_preview::format_duration(duration));
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        let duration = info.track.duration;
+        let Some(player) = &mut self.player else {
+            return;
+        };
+        if !audio_player::player_available() {
+            ui.label("未找到 ffplay 或 mpv，无法试听");
+            return;
+        }
+        let playing = player.is_playing();
+        if playing {
+            self.seek_position = player.position();
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        ui.horizontal(|ui| {
+            let label = if playing { "⏸ 暂停" } else { "▶ 播放" };
+            if ui.button(label).clicked() {
+                self.error = None;
+                if playing {
+                    player.pause();
+                } else {
+                    self.error = player.play().err();
+                }
+            }
+            if let Some(duration) = duration {
+                self.seek_position = self.seek_position.min(duration);
+                let response = ui.add(egui::Slider::new(&mut self.seek_position, 0.0..=duration).show_value(false));
+                // 拖动结束后再跳转，避免拖动过程中反复重启播放器
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    self.error = player.seek(self.seek_position).err();
+                }
+                ui.label(format!("{} / {}", album_preview::format_duration(self.seek_position), album_preview::format_duration(duration)));
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// 压缩包：后台列出条目的名称、大小和压缩后大小
+#[derive(Default)]
+struct ArchivePreviewHandler {
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<(PathBuf, Result<ArchiveListing, String>)>>,
+    listing: Option<Result<ArchiveListing, String>>,
+}
+
+impl PreviewHandler for ArchivePreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        archive_preview::is_archive_file(path)
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path, ctx) = (path.to_path_buf(), ctx.clone());
+        self.path = Some(path.clone());
+        self.listing = None;
+        self.receiver = Some(receiver);
+        std::thread::spawn(move || {
+            let listing = archive_preview::list_archive(&path);
+            let _ = sender.send((path, listing));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        if let Some((path, listing)) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.listing = Some(listing);
+                self.receiver = None;
+            }
+        }
+        let listing = match &self.listing {
+            Some(Ok(listing)) => listing,
+            Some(Err(e)) => {
+                ui.label(format!("无法读取压缩包: {}", e));
+                return;
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取压缩包...");
+                });
+                return;
+            }
+        };
+
+        let files = listing.entries.iter().filter(|e| !e.is_dir).count();
+        let total: u64 = listing.entries.iter().map(|e| e.size).sum();
+        ui.label(format!(
+            "{} 压缩包：{} 个文件，{} 个文件夹，解压后共 {}",
+            listing.format,
+            files,
+            listing.entries.len() - files,
+            utils::get_file_size_str(total)
+        ));
+        if listing.truncated {
+            ui.label(egui::RichText::new(format!("条目过多，只显示前 {} 项", listing.entries.len())).weak());
+        }
+        ui.separator();
+
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical().id_salt("archive_entries").max_height(ui.clip_rect().height().max(200.0)).show_rows(ui, row_height, listing.entries.len(), |ui, range| {
+            egui::Grid::new("archive_entries_grid").num_columns(3).striped(true).min_row_height(row_height).show(ui, |ui| {
+                for entry in &listing.entries[range] {
+                    let icon = if entry.is_dir { "📁" } else { "📄" };
+                    ui.label(format!("{} {}", icon, entry.name));
+                    if entry.is_dir {
+                        ui.label("");
+                        ui.label("");
+                    } else {
+                        ui.label(utils::get_file_size_str(entry.size));
+                        ui.label(entry.compressed_size.map(utils::get_file_size_str).unwrap_or_else(|| "-".to_string()));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// Office 文档：docx/odt 显示正文文字，xlsx 显示第一个工作表的前几行
+#[derive(Default)]
+struct OfficePreviewHandler {
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<(PathBuf, Result<OfficeContent, String>)>>,
+    content: Option<Result<OfficeContent, String>>,
+}
+
+impl PreviewHandler for OfficePreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        office_preview::is_office_file(path)
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path, ctx) = (path.to_path_buf(), ctx.clone());
+        self.path = Some(path.clone());
+        self.content = None;
+        self.receiver = Some(receiver);
+        std::thread::spawn(move || {
+            let content = office_preview::load_office(&path);
+            let _ = sender.send((path, content));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        if let Some((path, content)) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.content = Some(content);
+                self.receiver = None;
+            }
+        }
+        match &self.content {
+            Some(Ok(OfficeContent::Text { text, truncated })) => {
+                if text.is_empty() {
+                    ui.label("文档中没有文字");
+                    return;
+                }
+                if *truncated {
+                    ui.label(egui::RichText::new("文档较长，只显示前面的部分").weak());
+                }
+                egui::ScrollArea::vertical().id_salt("office_text").show(ui, |ui| {
+                    ui.add(egui::Label::new(text.as_str()).wrap());
+                });
+            }
+            Some(Ok(OfficeContent::Sheet { name, rows, truncated })) => {
+                ui.label(format!("工作表：{}", name));
+                if *truncated {
+                    ui.label(egui::RichText::new(format!("只显示前 {} 行、前 {} 列", office_preview::MAX_ROWS, office_preview::MAX_COLUMNS)).weak());
+                }
+                if rows.is_empty() {
+                    ui.label("工作表为空");
+                    return;
+                }
+                ui.separator();
+                let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+                egui::ScrollArea::both().id_salt("office_sheet").show(ui, |ui| {
+                    egui::Grid::new("office_sheet_grid").num_columns(columns + 1).striped(true).show(ui, |ui| {
+                        ui.label("");
+                        for column in 0..columns {
+                            ui.strong(office_preview::column_name(column));
+                        }
+                        ui.end_row();
+                        for (index, row) in rows.iter().enumerate() {
+                            ui.strong((index + 1).to_string());
+                            for column in 0..columns {
+                                ui.label(row.get(column).map(String::as_str).unwrap_or(""));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            }
+            Some(Err(e)) => {
+                ui.label(format!("无法读取文档: {}", e));
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取文档...");
+                });
+            }
+        }
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// 读取的启动器及其图标
+type DesktopLoad = (Result<DesktopEntry, String>, Option<image::RgbaImage>);
+
+// .desktop 启动器：名称、说明、命令和图标，可直接启动应用
+#[derive(Default)]
+struct DesktopPreviewHandler {
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<(PathBuf, DesktopLoad)>>,
+    entry: Option<Result<DesktopEntry, String>>,
+    icon: Option<egui::TextureHandle>,
+    launch_error: Option<String>,
+}
+
+impl PreviewHandler for DesktopPreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        desktop_entry::is_desktop_file(path)
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path, ctx) = (path.to_path_buf(), ctx.clone());
+        *self = Self { path: Some(path.clone()), receiver: Some(receiver), ..Self::default() };
+        std::thread::spawn(move || {
+            let entry = desktop_entry::read(&path);
+            let icon = entry.as_ref().ok()
+                .and_then(|entry| entry.icon.as_deref())
+                .and_then(desktop_entry::find_icon)
+                .and_then(|icon| image::open(icon).ok())
+                .map(|image| image.thumbnail(96, 96).to_rgba8());
+            let _ = sender.send((path, (entry, icon)));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        if let Some((path, (entry, icon))) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.icon = icon.map(|icon| {
+                    let image = egui::ColorImage::from_rgba_unmultiplied([icon.width() as usize, icon.height() as usize], icon.as_raw());
+                    ui.ctx().load_texture("desktop_preview_icon", image, egui::TextureOptions::LINEAR)
+                });
+                self.entry = Some(entry);
+                self.receiver = None;
+            }
+        }
+        let entry = match &self.entry {
+            Some(Ok(entry)) => entry,
+            Some(Err(e)) => {
+                ui.label(format!("无法读取启动器: {}", e));
+                return;
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取启动器...");
+                });
+                return;
+            }
+        };
+
+        ui.horizontal(|ui| {
+            match &self.icon {
+                Some(texture) => {
+                    ui.add(egui::Image::from_texture(egui::load::SizedTexture::new(texture.id(), egui::vec2(64.0, 64.0))));
+                }
+                None => {
+                    ui.label(egui::RichText::new("🚀").size(48.0));
+                }
+            }
+            ui.vertical(|ui| {
+                ui.heading(&entry.name);
+                if let Some(comment) = &entry.comment {
+                    ui.add(egui::Label::new(comment.as_str()).wrap());
+                }
+            });
+        });
+        ui.separator();
+        egui::Grid::new("desktop_preview_fields").num_columns(2).show(ui, |ui| {
+            for (label, value) in [("命令", &entry.exec), ("链接", &entry.url), ("图标", &entry.icon), ("工作目录", &entry.working_dir)] {
+                if let Some(value) = value {
+                    ui.label(label);
+                    ui.add(egui::Label::new(egui::RichText::new(value).monospace()).wrap());
+                    ui.end_row();
+                }
+            }
+            if entry.terminal {
+                ui.label("终端");
+                ui.label("在终端中运行");
+                ui.end_row();
+            }
+        });
+        ui.add_space(8.0);
+
+        let label = if entry.url.is_some() { "🔗 打开链接" } else { "▶ 启动" };
+        let launchable = entry.url.is_some() || entry.exec.is_some();
+        if ui.add_enabled(launchable, egui::Button::new(label)).clicked() {
+            if let Some(path) = &self.path {
+                self.launch_error = desktop_entry::launch(entry, path).err();
+            }
+        }
+        if let Some(error) = &self.launch_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// 可执行文件和共享库：架构、依赖的库（类似 ldd）以及是否去除了符号
+#[derive(Default)]
+struct ElfPreviewHandler {
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<(PathBuf, Result<ElfInfo, String>)>>,
+    info: Option<Result<ElfInfo, String>>,
+}
+
+impl PreviewHandler for ElfPreviewHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        elf_info::is_elf_file(path)
+    }
+
+    fn load_async(&mut self, _preview: &mut Preview, path: &Path, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let (path, ctx) = (path.to_path_buf(), ctx.clone());
+        self.path = Some(path.clone());
+        self.info = None;
+        self.receiver = Some(receiver);
+        std::thread::spawn(move || {
+            let info = elf_info::read_elf_info(&path);
+            let _ = sender.send((path, info));
+            ctx.request_repaint();
+        });
+    }
+
+    fn render(&mut self, _preview: &Preview, ui: &mut egui::Ui) {
+        if let Some((path, info)) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.info = Some(info);
+                self.receiver = None;
+            }
+        }
+        let info = match &self.info {
+            Some(Ok(info)) => info,
+            Some(Err(e)) => {
+                ui.label(e);
+                return;
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("正在读取程序信息...");
+                });
+                return;
+            }
+        };
+
+        egui::Grid::new("elf_preview_info").num_columns(2).show(ui, |ui| {
+            ui.label("类型");
+            ui.label(info.kind);
+            ui.end_row();
+            ui.label("架构");
+            ui.label(format!("{}（{} 位，{}）", info.architecture, info.bits, if info.little_endian { "小端" } else { "大端" }));
+            ui.end_row();
+            for (label, value) in [("动态链接器", &info.interpreter), ("SONAME", &info.soname)] {
+                if let Some(value) = value {
+                    ui.label(label);
+                    ui.monospace(value);
+                    ui.end_row();
+                }
+            }
+            ui.label("符号");
+            ui.label(match (info.stripped, info.debug_info) {
+                (_, true) => "含调试信息",
+                (false, false) => "含符号表，无调试信息",
+                (true, false) => "已去除符号（stripped）",
+            });
+            ui.end_row();
+        });
+        ui.separator();
+
+        if info.libraries.is_empty() {
+            ui.label(if info.interpreter.is_some() || info.kind == "共享库" { "没有依赖的共享库" } else { "静态链接，没有依赖的共享库" });
+            return;
+        }
+        let missing = info.libraries.iter().filter(|(_, path)| path.is_none()).count();
+        ui.label(format!("依赖的库：{} 个", info.libraries.len()));
+        if missing > 0 {
+            ui.colored_label(ui.visuals().error_fg_color, format!("{} 个库未找到", missing));
+        }
+        egui::ScrollArea::vertical().id_salt("elf_libraries").show(ui, |ui| {
+            egui::Grid::new("elf_libraries_grid").num_columns(2).striped(true).show(ui, |ui| {
+                for (name, path) in &info.libraries {
+                    ui.monospace(name);
+                    match path {
+                        Some(path) => ui.label(egui::RichText::new(format!("=> {}", path.display())).weak()),
+                        None => ui.colored_label(ui.visuals().error_fg_color, "未找到"),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn unload(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// 已注册的预览处理器，按注册顺序选择第一个能处理该文件的
+#[derive(Default)]
+pub struct PreviewHandlers {
+    handlers: Vec<Box<dyn PreviewHandler>>,
+    active: Option<usize>, // 当前文件使用的处理器
+}
+
+impl PreviewHandlers {
+    pub fn new() -> Self {
+        Self {
+            handlers: vec![
+                Box::new(TextPreviewHandler::default()),
+                Box::new(ImagePreviewHandler::default()),
+                Box::new(VideoPreviewHandler::default()),
+                Box::new(AudioPreviewHandler::default()),
+                Box::new(ArchivePreviewHandler::default()),
+                Box::new(OfficePreviewHandler::default()),
+                Box::new(DesktopPreviewHandler::default()),
+                Box::new(ElfPreviewHandler::default()),
+            ],
+            active: None,
+        }
+    }
+
+    // 为文件选择处理器并开始加载；没有处理器时返回 false
+    pub fn load(&mut self, preview: &mut Preview, path: &Path, ctx: &egui::Context) -> bool {
+        self.clear();
+        self.active = self.handlers.iter().position(|h| h.can_handle(path));
+        match self.active {
+            Some(index) => {
+                self.handlers[index].load_async(preview, path, ctx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn render(&mut self, preview: &Preview, ui: &mut egui::Ui) {
+        if let Some(index) = self.active {
+            self.handlers[index].render(preview, ui);
+        }
+    }
+
+    pub fn take_open_viewer(&mut self) -> Option<PathBuf> {
+        self.handlers.get_mut(self.active?)?.take_open_viewer()
+    }
+
+    pub fn take_open_text_viewer(&mut self) -> Option<PathBuf> {
+        self.handlers.get_mut(self.active?)?.take_open_text_viewer()
+    }
+
+    pub fn clear(&mut self) {
+        if let Some(index) = self.active.take() {
+            self.handlers[index].unload();
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use crossbeam_channel::Receiver;
+
+// 通过标志文件识别的文件夹类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectType {
+    Cargo,
+    Npm,
+    Git,
+    PythonVenv,
+}
+
+// Python 虚拟环境可以是文件夹本身，也可以是项目中的 .venv / venv
+const VENV_DIRS: &[&str] = &["", ".venv", "venv"];
+
+impl ProjectType {
+    pub const ALL: [ProjectType; 4] = [ProjectType::Cargo, ProjectType::Npm, ProjectType::Git, ProjectType::PythonVenv];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProjectType::Cargo => "Cargo 项目",
+            ProjectType::Npm => "npm 项目",
+            ProjectType::Git => "Git 仓库",
+            ProjectType::PythonVenv => "Python 虚拟环境",
+        }
+    }
+
+    fn badge(self) -> &'static str {
+        match self {
+            ProjectType::Cargo => "Rust",
+            ProjectType::Npm => "npm",
+            ProjectType::Git => "git",
+            ProjectType::PythonVenv => "venv",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            ProjectType::Cargo => egui::Color32::from_rgb(183, 65, 14),
+            ProjectType::Npm => egui::Color32::from_rgb(203, 56, 55),
+            ProjectType::Git => egui::Color32::from_rgb(40, 140, 70),
+            ProjectType::PythonVenv => egui::Color32::from_rgb(55, 118, 171),
+        }
+    }
+
+    fn matches(self, dir: &Path) -> bool {
+        match self {
+            ProjectType::Cargo => dir.join("Cargo.toml").is_file(),
+            ProjectType::Npm => dir.join("package.json").is_file(),
+            ProjectType::Git => dir.join(".git").exists(),
+            ProjectType::PythonVenv => venv_dir(dir).is_some(),
+        }
+    }
+
+    // 该类型文件夹右键菜单中的命令（菜单名称, 在终端中执行的命令）
+    pub fn commands(self, dir: &Path) -> Vec<(&'static str, String)> {
+        match self {
+            ProjectType::Cargo => vec![("cargo build", "cargo build".to_string()), ("cargo run", "cargo run".to_string()), ("cargo test", "cargo test".to_string())],
+            ProjectType::Npm => vec![("npm install", "npm install".to_string()), ("npm test", "npm test".to_string())],
+            ProjectType::Git => vec![("git status", "git status".to_string()), ("git pull", "git pull".to_string())],
+            ProjectType::PythonVenv => match venv_dir(dir) {
+                Some(venv) => {
+                    let activate = venv.join("bin").join("activate");
+                    vec![("在终端中激活虚拟环境", format!(". {} && exec \"${{SHELL:-sh}}\"", shell_quote(&activate.to_string_lossy())))]
+                }
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
+fn venv_dir(dir: &Path) -> Option<PathBuf> {
+    VENV_DIRS.iter().map(|name| dir.join(name)).find(|venv| venv.join("pyvenv.cfg").is_file())
+}
+
+// 识别文件夹的类型，可能同时属于多种（如同时是 Cargo 项目和 Git 仓库）
+pub fn detect(dir: &Path) -> Vec<ProjectType> {
+    ProjectType::ALL.into_iter().filter(|kind| kind.matches(dir)).collect()
+}
+
+// 后台识别文件夹类型，避免在界面线程中为每个文件夹检查标志文件；离开目录时取消
+pub struct ProjectTypeWorker {
+    receiver: Receiver<(PathBuf, Vec<ProjectType>)>,
+    cancelled: Arc<AtomicBool>,
+    running: bool,
+}
+
+impl ProjectTypeWorker {
+    // 为给定的文件夹启动识别线程，只返回识别出类型的文件夹
+    pub fn start(folders: Vec<PathBuf>) -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let cancel_flag = cancelled.clone();
+        std::thread::spawn(move || {
+            for folder in folders {
+                if cancel_flag.load(Ordering::Relaxed) {
+                    return;
+                }
+                let types = detect(&folder);
+                if !types.is_empty() && sender.send((folder, types)).is_err() {
+                    return; // 接收端已释放
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            cancelled,
+            running: true,
+        }
+    }
+
+    // 取出已识别的结果（不阻塞）
+    pub fn poll(&mut self) -> Vec<(PathBuf, Vec<ProjectType>)> {
+        let mut results = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(result) => results.push(result),
+                Err(crossbeam_channel::TryRecvError::Empty) => break,
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    self.running = false;
+                    break;
+                }
+            }
+        }
+        results
+    }
+
+    // 识别线程是否仍在运行
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+}
+
+impl Drop for ProjectTypeWorker {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// 在 pos 处（左侧垂直居中）绘制类型徽标，返回占用的宽度
+pub fn draw_badges(painter: &egui::Painter, pos: egui::Pos2, types: &[ProjectType]) -> f32 {
+    let mut x = pos.x;
+    for kind in types {
+        let galley = painter.layout_no_wrap(kind.badge().to_string(), egui::FontId::proportional(10.0), egui::Color32::WHITE);
+        let rect = egui::Rect::from_min_size(egui::pos2(x, pos.y - 7.0), egui::vec2(galley.size().x + 8.0, 14.0));
+        painter.rect_filled(rect, 3.0, kind.color());
+        painter.galley(rect.center() - galley.size() / 2.0, galley, egui::Color32::WHITE);
+        x = rect.right() + 3.0;
+    }
+    x - pos.x
+}
+
+pub fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn find_in_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+// 查找可用的终端模拟器（只检查一次），返回程序名及其执行命令的参数
+fn terminal() -> Option<(&'static str, &'static [&'static str])> {
+    static TERMINAL: OnceLock<Option<(&'static str, &'static [&'static str])>> = OnceLock::new();
+    const TERMINALS: &[(&str, &[&str])] = &[
+        ("x-terminal-emulator", &["-e"]),
+        ("gnome-terminal", &["--"]),
+        ("konsole", &["-e"]),
+        ("xfce4-terminal", &["-x"]),
+        ("kitty", &[]),
+        ("alacritty", &["-e"]),
+        ("xterm", &["-e"]),
+    ];
+    *TERMINAL.get_or_init(|| TERMINALS.iter().copied().find(|(program, _)| find_in_path(program)))
+}
+
+// 在 dir 中打开终端执行命令，结束后等待按回车再关闭窗口
+pub fn run_in_terminal(dir: &Path, command: &str) -> Result<(), String> {
+    let (program, args) = terminal().ok_or("未找到可用的终端程序")?;
+    let script = format!("{}; echo; echo '按回车关闭...'; read _", command);
+    Command::new(program)
+        .args(args)
+        .args(["sh", "-c", &script])
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("启动终端失败: {}", e))
+}
+
+// 用编辑器打开文件夹：优先使用能打开文件夹的图形编辑器，否则在终端中运行 $VISUAL / $EDITOR
+pub fn open_in_editor(dir: &Path) -> Result<(), String> {
+    const EDITORS: &[&str] = &["code", "codium", "zed", "subl"];
+    if let Some(editor) = EDITORS.iter().find(|editor| find_in_path(editor)) {
+        return Command::new(editor)
+            .arg(dir)
+            .stdin(Stdio::null())
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("启动编辑器失败: {}", e));
+    }
+    let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")).map_err(|_| "未找到可用的编辑器".to_string())?;
+    run_in_terminal(dir, &format!("{} .", editor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+    use std::fs;
+
+    #[test]
+    fn test_detect() {
+        let root = TestDir::new("project");
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join(".venv")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(root.join(".venv").join("pyvenv.cfg"), "home = /usr/bin").unwrap();
+
+        assert_eq!(detect(&root), vec![ProjectType::Cargo, ProjectType::Git, ProjectType::PythonVenv]);
+        assert_eq!(detect(&root.join(".venv")), vec![ProjectType::PythonVenv]);
+        assert!(ProjectType::PythonVenv.commands(&root)[0].1.contains(".venv/bin/activate"));
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
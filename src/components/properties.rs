@@ -0,0 +1,638 @@
+use eframe::egui;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crossbeam_channel::Receiver;
+use crate::utils;
+use super::elevated;
+use super::folder_size::FolderSizeWorker;
+use super::file_metadata::{self, MetadataRows};
+use super::file_operations::{self, OperationError, OperationHandle};
+use super::locale_format;
+
+fn format_time(time: std::io::Result<SystemTime>) -> String {
+    time.ok()
+        .map(|t| locale_format::current().datetime_long(t))
+        .unwrap_or_else(|| "未知".to_string())
+}
+
+// 权限显示为 rwxr-xr-x (755)
+#[cfg(unix)]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode();
+    let bits: String = (0..9)
+        .map(|i| {
+            let set = mode & (0o400 >> i) != 0;
+            match (set, i % 3) {
+                (false, _) => '-',
+                (true, 0) => 'r',
+                (true, 1) => 'w',
+                (true, _) => 'x',
+            }
+        })
+        .collect();
+    format!("{} ({:o})", bits, mode & 0o777)
+}
+
+#[cfg(not(unix))]
+fn format_permissions(metadata: &fs::Metadata) -> String {
+    if metadata.permissions().readonly() { "只读".to_string() } else { "可读写".to_string() }
+}
+
+#[cfg(unix)]
+fn format_owner(metadata: &fs::Metadata) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    Some(format!("uid {} / gid {}", metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn format_owner(_metadata: &fs::Metadata) -> Option<String> {
+    None
+}
+
+// setuid / setgid / 粘滞位
+#[cfg(unix)]
+fn format_special_bits(metadata: &fs::Metadata) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode();
+    let bits: Vec<&str> = [(0o4000, "setuid"), (0o2000, "setgid"), (0o1000, "粘滞位")]
+        .into_iter()
+        .filter(|(bit, _)| mode & bit != 0)
+        .map(|(_, name)| name)
+        .collect();
+    (!bits.is_empty()).then(|| bits.join("、"))
+}
+
+#[cfg(not(unix))]
+fn format_special_bits(_metadata: &fs::Metadata) -> Option<String> {
+    None
+}
+
+// 权限矩阵的行（及其权限位的位移）和列
+const PERMISSION_CLASSES: [(&str, u32); 3] = [("所有者", 6), ("用户组", 3), ("其他", 0)];
+const PERMISSION_BITS: [(&str, u32); 3] = [("读", 4), ("写", 2), ("执行", 1)];
+// 属性窗口中最多列出的失败项目
+const MAX_LISTED_ERRORS: usize = 5;
+
+fn describe_io_error(error: &io::Error) -> String {
+    if error.kind() == io::ErrorKind::PermissionDenied {
+        "没有权限（只有所有者或管理员可以修改）".to_string()
+    } else {
+        error.to_string()
+    }
+}
+
+#[cfg(unix)]
+fn read_mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).ok().map(|m| m.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn read_mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+// 逐项报告修改结果：成功或失败原因
+type ReportFn<'a> = dyn FnMut(&Path, Result<(), String>) + 'a;
+
+// 按 new_bits 修改 rwx 位，保留 setuid 等特殊位
+#[cfg(unix)]
+fn set_mode(path: &Path, new_bits: impl Fn(u32) -> u32, report: &mut ReportFn) {
+    use std::os::unix::fs::PermissionsExt;
+    let result = fs::metadata(path).and_then(|metadata| {
+        let old = metadata.permissions().mode();
+        fs::set_permissions(path, fs::Permissions::from_mode((old & !0o777) | (new_bits(old) & 0o777)))
+    });
+    report(path, result.map_err(|e| describe_io_error(&e)));
+}
+
+// 子文件夹使用 mode，文件只改读写位、保留原有的执行位；不进入符号链接。
+// 文件夹在处理完其中的项目后再修改，去掉读或执行权限时仍能进入
+#[cfg(unix)]
+fn apply_to_children(dir: &Path, mode: u32, report: &mut ReportFn, cancelled: &dyn Fn() -> bool) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            report(dir, Err(describe_io_error(&e)));
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        if cancelled() {
+            return;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            apply_to_children(&path, mode, report, cancelled);
+            set_mode(&path, |_| mode, report);
+        } else if !file_type.is_symlink() {
+            set_mode(&path, |old| (mode & 0o666) | (old & 0o111), report);
+        }
+    }
+}
+
+// 修改权限，recursive 时同时修改文件夹中的全部项目
+#[cfg(unix)]
+fn apply_permissions(path: &Path, mode: u32, recursive: bool, report: &mut ReportFn, cancelled: &dyn Fn() -> bool) {
+    if recursive && path.is_dir() {
+        apply_to_children(path, mode, report, cancelled);
+        if cancelled() {
+            return;
+        }
+    }
+    set_mode(path, |_| mode, report);
+}
+
+#[cfg(not(unix))]
+fn apply_permissions(path: &Path, _mode: u32, _recursive: bool, report: &mut ReportFn, _cancelled: &dyn Fn() -> bool) {
+    report(path, Err("当前系统不支持修改权限".to_string()));
+}
+
+// 属性窗口中的权限编辑：所有者/用户组/其他 × 读/写/执行
+struct PermissionEditor {
+    mode: u32,
+    original: u32,
+    recursive: bool,
+    result: Option<Result<String, String>>, // 上次应用的结果
+    running: Option<OperationHandle>,       // 在后台修改文件夹中的全部项目
+}
+
+impl PermissionEditor {
+    fn new(mode: u32) -> Self {
+        Self { mode, original: mode, recursive: false, result: None, running: None }
+    }
+
+    // 后台修改结束时记录结果，有项目被修改时返回 true
+    fn poll(&mut self, ui: &egui::Ui, path: &Path) -> bool {
+        let Some(handle) = self.running.as_mut() else {
+            return false;
+        };
+        let Some(result) = handle.try_result() else {
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+            return false;
+        };
+        let changed = handle.progress().files_done;
+        let count = locale_format::current().count(changed as u64);
+        self.result = Some(match result {
+            Ok(()) => Ok(format!("已修改 {} 个项目", count)),
+            Err(OperationError::Cancelled) => Err(format!("已取消，已修改 {} 个项目", count)),
+            Err(OperationError::Failed(errors)) => {
+                let mut message = format!("{} 个项目修改失败:", errors.len());
+                for error in errors.iter().take(MAX_LISTED_ERRORS) {
+                    message.push_str(&format!("\n{}", error));
+                }
+                if errors.len() > MAX_LISTED_ERRORS {
+                    message.push_str("\n...");
+                }
+                Err(message)
+            }
+        });
+        self.running = None;
+        self.original = read_mode(path).unwrap_or(self.mode);
+        changed > 0
+    }
+
+    // 绘制权限矩阵，应用成功时返回 true
+    fn show(&mut self, ui: &mut egui::Ui, path: &Path) -> bool {
+        ui.label(egui::RichText::new("修改权限").strong());
+        egui::Grid::new("permission_matrix").num_columns(4).spacing([16.0, 4.0]).show(ui, |ui| {
+            ui.label("");
+            for (label, _) in PERMISSION_BITS {
+                ui.label(label);
+            }
+            ui.end_row();
+            for (class, shift) in PERMISSION_CLASSES {
+                ui.label(class);
+                for (_, bit) in PERMISSION_BITS {
+                    let mask = bit << shift;
+                    let mut set = self.mode & mask != 0;
+                    if ui.checkbox(&mut set, "").changed() {
+                        self.mode ^= mask;
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        ui.monospace(format!("{:03o}", self.mode));
+        let is_dir = path.is_dir();
+        if is_dir {
+            ui.checkbox(&mut self.recursive, "同时应用到其中的全部项目（文件保留原有的执行权限）");
+        }
+
+        let applied = self.poll(ui, path);
+        ui.horizontal(|ui| {
+            if let Some(handle) = self.running.as_mut() {
+                ui.spinner();
+                ui.label(format!("正在修改... {} 个项目", locale_format::current().count(handle.progress().files_done as u64)));
+                if ui.button("取消").clicked() {
+                    handle.cancel_token().cancel();
+                }
+                return;
+            }
+            if ui.add_enabled(self.mode != self.original || self.recursive, egui::Button::new("应用")).clicked() {
+                let (path, mode, recursive) = (path.to_path_buf(), self.mode, self.recursive && is_dir);
+                self.result = None;
+                self.running = Some(file_operations::spawn_chmod(path.clone(), move |report, cancelled| {
+                    apply_permissions(&path, mode, recursive, report, cancelled);
+                }));
+            }
+            if ui.add_enabled(self.mode != self.original, egui::Button::new("还原")).clicked() {
+                self.mode = self.original;
+            }
+        });
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, message);
+            }
+            None => {}
+        }
+        applied
+    }
+}
+
+// /etc/passwd 或 /etc/group 中的名称和 ID，按名称排序
+fn parse_accounts(text: &str) -> Vec<(String, u32)> {
+    let mut accounts: Vec<(String, u32)> = text
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((name.to_string(), id))
+        })
+        .collect();
+    accounts.sort();
+    accounts.dedup();
+    accounts
+}
+
+fn read_accounts(file: &str) -> Vec<(String, u32)> {
+    fs::read_to_string(file).map(|text| parse_accounts(&text)).unwrap_or_default()
+}
+
+// 显示为 "name (1000)"，找不到名称时只显示 ID
+fn account_label(accounts: &[(String, u32)], id: u32) -> String {
+    accounts.iter()
+        .find(|(_, account)| *account == id)
+        .map(|(name, _)| format!("{} ({})", name, id))
+        .unwrap_or_else(|| id.to_string())
+}
+
+#[cfg(unix)]
+fn read_owner(path: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).ok().map(|m| (m.uid(), m.gid()))
+}
+
+#[cfg(not(unix))]
+fn read_owner(_path: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(unix)]
+fn change_owner(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    use nix::unistd::{chown, Gid, Uid};
+    chown(path, Some(Uid::from_raw(uid)), Some(Gid::from_raw(gid))).map_err(io::Error::from)
+}
+
+#[cfg(not(unix))]
+fn change_owner(_path: &Path, _uid: u32, _gid: u32) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "当前系统不支持修改所有者"))
+}
+
+// 普通用户只能在自己所属的组之间切换；需要管理员时在后台通过 pkexec 调用 chown
+fn spawn_elevated_chown(path: &Path, uid: u32, gid: u32, ctx: egui::Context) -> Receiver<Result<(), String>> {
+    let (sender, receiver) = crossbeam_channel::bounded(1);
+    let args = [format!("{}:{}", uid, gid).into(), "--".into(), path.as_os_str().to_os_string()];
+    std::thread::spawn(move || {
+        let _ = sender.send(elevated::run_as_root::<std::ffi::OsString>(elevated::CHOWN, &args));
+        ctx.request_repaint();
+    });
+    receiver
+}
+
+fn account_combo(ui: &mut egui::Ui, id_salt: &str, accounts: &[(String, u32)], selected: &mut u32) {
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(account_label(accounts, *selected))
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            for (name, id) in accounts {
+                ui.selectable_value(selected, *id, format!("{} ({})", name, id));
+            }
+        });
+}
+
+// 属性窗口中的所有者和用户组编辑
+struct OwnerEditor {
+    users: Vec<(String, u32)>,
+    groups: Vec<(String, u32)>,
+    uid: u32,
+    gid: u32,
+    original: (u32, u32),
+    result: Option<Result<String, String>>,
+    elevated: Option<Receiver<Result<(), String>>>, // 正在等待管理员认证
+}
+
+impl OwnerEditor {
+    fn new((uid, gid): (u32, u32)) -> Self {
+        Self {
+            users: read_accounts("/etc/passwd"),
+            groups: read_accounts("/etc/group"),
+            uid,
+            gid,
+            original: (uid, gid),
+            result: None,
+            elevated: None,
+        }
+    }
+
+    fn finish(&mut self, path: &Path, result: Result<String, String>) {
+        self.result = Some(result);
+        self.original = read_owner(path).unwrap_or(self.original);
+    }
+
+    // 绘制所有者和用户组的下拉框，所有者改变时返回 true
+    fn show(&mut self, ui: &mut egui::Ui, path: &Path) -> bool {
+        let mut changed = false;
+        if let Some(result) = self.elevated.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.elevated = None;
+            changed = result.is_ok();
+            self.finish(path, result.map(|()| "已通过管理员权限修改所有者".to_string()));
+        }
+
+        ui.label(egui::RichText::new("修改所有者").strong());
+        egui::Grid::new("owner_editor").num_columns(2).spacing([16.0, 4.0]).show(ui, |ui| {
+            ui.label("所有者");
+            account_combo(ui, "owner_user", &self.users, &mut self.uid);
+            ui.end_row();
+            ui.label("用户组");
+            account_combo(ui, "owner_group", &self.groups, &mut self.gid);
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            if self.elevated.is_some() {
+                ui.spinner();
+                ui.label("正在等待管理员认证...");
+                return;
+            }
+            if ui.add_enabled((self.uid, self.gid) != self.original, egui::Button::new("应用")).clicked() {
+                match change_owner(path, self.uid, self.gid) {
+                    Ok(()) => {
+                        changed = true;
+                        self.finish(path, Ok("已修改所有者".to_string()));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                        self.result = None;
+                        self.elevated = Some(spawn_elevated_chown(path, self.uid, self.gid, ui.ctx().clone()));
+                    }
+                    Err(e) => self.result = Some(Err(format!("修改失败: {}", e))),
+                }
+            }
+            if ui.add_enabled((self.uid, self.gid) != self.original, egui::Button::new("还原")).clicked() {
+                (self.uid, self.gid) = self.original;
+            }
+        });
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(message)) => {
+                ui.colored_label(ui.visuals().error_fg_color, message);
+            }
+            None => {}
+        }
+        changed
+    }
+}
+
+// 预览栏权限页的内容：权限、所有者、只读和特殊权限位
+pub fn permission_rows(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let metadata = fs::metadata(path)
+        .or_else(|_| fs::symlink_metadata(path))
+        .map_err(|e| format!("无法读取权限: {}", e))?;
+    let mut rows = vec![("权限".to_string(), format_permissions(&metadata))];
+    if let Some(owner) = format_owner(&metadata) {
+        rows.push(("所有者".to_string(), owner));
+    }
+    let readonly = if metadata.permissions().readonly() { "是" } else { "否" };
+    rows.push(("只读".to_string(), readonly.to_string()));
+    if let Some(special) = format_special_bits(&metadata) {
+        rows.push(("特殊权限".to_string(), special));
+    }
+    Ok(rows)
+}
+
+// 属性窗口中显示的一行
+struct PropertyRow {
+    label: &'static str,
+    value: String,
+}
+
+fn read_properties(path: &Path) -> Result<Vec<PropertyRow>, String> {
+    let link_metadata = fs::symlink_metadata(path).map_err(|e| format!("无法读取属性: {}", e))?;
+    let is_symlink = link_metadata.file_type().is_symlink();
+    // 符号链接显示目标的属性，目标不存在时显示链接本身
+    let metadata = if is_symlink { fs::metadata(path).unwrap_or_else(|_| link_metadata.clone()) } else { link_metadata };
+
+    let mut rows = vec![PropertyRow {
+        label: "名称",
+        value: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string()),
+    }];
+    let kind = if metadata.is_dir() {
+        "文件夹".to_string()
+    } else {
+        path.extension()
+            .map(|ext| format!("{} 文件", ext.to_string_lossy().to_uppercase()))
+            .unwrap_or_else(|| "文件".to_string())
+    };
+    rows.push(PropertyRow { label: "类型", value: kind });
+    if let Some(parent) = path.parent() {
+        rows.push(PropertyRow { label: "位置", value: parent.display().to_string() });
+    }
+    if is_symlink {
+        let target = fs::read_link(path).map(|t| t.display().to_string()).unwrap_or_else(|_| "未知".to_string());
+        rows.push(PropertyRow { label: "链接到", value: target });
+    }
+    if !metadata.is_dir() {
+        rows.push(PropertyRow {
+            label: "大小",
+            value: format!("{}（{} 字节）", utils::get_file_size_str(metadata.len()), locale_format::current().count(metadata.len())),
+        });
+    }
+    rows.push(PropertyRow { label: "修改时间", value: format_time(metadata.modified()) });
+    rows.push(PropertyRow { label: "访问时间", value: format_time(metadata.accessed()) });
+    rows.push(PropertyRow { label: "创建时间", value: format_time(metadata.created()) });
+    rows.push(PropertyRow { label: "权限", value: format_permissions(&metadata) });
+    if let Some(owner) = format_owner(&metadata) {
+        rows.push(PropertyRow { label: "所有者", value: owner });
+    }
+    Ok(rows)
+}
+
+// 文件/文件夹属性窗口，文件夹大小在后台计算
+pub struct PropertiesDialog {
+    path: Option<PathBuf>,
+    rows: Result<Vec<PropertyRow>, String>,
+    metadata: MetadataRows,
+    folder_size: Option<u64>,
+    size_worker: Option<FolderSizeWorker>,
+    permission_editor: Option<PermissionEditor>, // 不支持权限的系统上为 None
+    owner_editor: Option<OwnerEditor>,
+}
+
+impl PropertiesDialog {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            rows: Ok(Vec::new()),
+            metadata: Vec::new(),
+            folder_size: None,
+            size_worker: None,
+            permission_editor: None,
+            owner_editor: None,
+        }
+    }
+
+    pub fn open(&mut self, path: PathBuf) {
+        self.rows = read_properties(&path);
+        self.metadata = file_metadata::registry().read(&path);
+        self.folder_size = None;
+        self.size_worker = path.is_dir().then(|| FolderSizeWorker::start(vec![path.clone()]));
+        self.permission_editor = read_mode(&path).map(PermissionEditor::new);
+        self.owner_editor = read_owner(&path).map(OwnerEditor::new);
+        self.path = Some(path);
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn show_properties_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(worker) = self.size_worker.as_mut() {
+            if let Some((_, size)) = worker.poll().pop() {
+                self.folder_size = Some(size);
+                self.size_worker = None;
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+
+        let mut open = true;
+        let mut close = false;
+        egui::Window::new("属性")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                match &self.rows {
+                    Ok(rows) => {
+                        egui::Grid::new("properties_grid").num_columns(2).spacing([16.0, 6.0]).show(ui, |ui| {
+                            for row in rows {
+                                ui.label(row.label);
+                                ui.label(&row.value);
+                                ui.end_row();
+                            }
+                            if self.path.as_ref().is_some_and(|p| p.is_dir()) {
+                                ui.label("大小");
+                                match self.folder_size {
+                                    Some(size) => ui.label(format!("{}（{} 字节）", utils::get_file_size_str(size), locale_format::current().count(size))),
+                                    None => ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label("正在计算...");
+                                    }).response,
+                                };
+                                ui.end_row();
+                            }
+                        });
+                        // 图片、音视频、PDF 等格式的详细信息
+                        if !self.metadata.is_empty() {
+                            ui.separator();
+                            ui.label(egui::RichText::new("详细信息").strong());
+                            egui::Grid::new("properties_metadata_grid").num_columns(2).spacing([16.0, 6.0]).show(ui, |ui| {
+                                for (key, value) in &self.metadata {
+                                    ui.label(key);
+                                    ui.label(value);
+                                    ui.end_row();
+                                }
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                }
+                if let (Some(editor), Some(path)) = (self.permission_editor.as_mut(), self.path.as_ref()) {
+                    ui.separator();
+                    // 修改后刷新上方的权限一行
+                    if editor.show(ui, path) {
+                        self.rows = read_properties(path);
+                    }
+                }
+                if let (Some(editor), Some(path)) = (self.owner_editor.as_mut(), self.path.as_ref()) {
+                    ui.separator();
+                    if editor.show(ui, path) {
+                        self.rows = read_properties(path);
+                    }
+                }
+                ui.separator();
+                if ui.button("关闭").clicked() {
+                    close = true;
+                }
+            });
+
+        if !open || close {
+            // 丢弃计算线程即取消
+            self.size_worker = None;
+            self.path = None;
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_apply_permissions_recursive() {
+        let root = TestDir::new("properties_chmod");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("sub/script.sh"), "").unwrap();
+        fs::write(root.join("data.txt"), "").unwrap();
+        fs::set_permissions(root.join("sub/script.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(root.join("data.txt"), fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mode = |path: &str| fs::metadata(root.join(path)).unwrap().permissions().mode() & 0o777;
+        let (mut changed, mut errors) = (0, Vec::new());
+        apply_permissions(&root, 0o750, true, &mut |path, result| match result {
+            Ok(()) => changed += 1,
+            Err(e) => errors.push((path.to_path_buf(), e)),
+        }, &|| false);
+        assert_eq!((changed, errors), (4, Vec::new()));
+        // 文件夹使用所选权限，文件保留原有的执行位
+        assert_eq!((mode(""), mode("sub")), (0o750, 0o750));
+        assert_eq!((mode("sub/script.sh"), mode("data.txt")), (0o751, 0o640));
+    }
+
+    #[test]
+    fn test_parse_accounts() {
+        let accounts = parse_accounts("root:x:0:0:root:/root:/bin/bash\n# 注释\nuser:x:1000:1000::/home/user:/bin/sh\nbroken\nwheel:x:10:root,user\n");
+        assert_eq!(accounts, vec![("root".to_string(), 0), ("user".to_string(), 1000), ("wheel".to_string(), 10)]);
+        assert_eq!(account_label(&accounts, 1000), "user (1000)");
+        assert_eq!(account_label(&accounts, 4242), "4242");
+    }
+}
@@ -0,0 +1,42 @@
+// 识别图片中的二维码；大图先缩小再识别，二维码通常占画面较大比例，缩小后仍能识别
+const MAX_SCAN_SIZE: u32 = 1600;
+
+// 返回图片中全部可解码二维码的内容
+pub fn decode_qr_codes(image: &image::DynamicImage) -> Vec<String> {
+    let image = if image.width() > MAX_SCAN_SIZE || image.height() > MAX_SCAN_SIZE {
+        image.thumbnail(MAX_SCAN_SIZE, MAX_SCAN_SIZE)
+    } else {
+        image.clone()
+    };
+    let luma = image.to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        luma.width() as usize,
+        luma.height() as usize,
+        |x, y| luma.get_pixel(x as u32, y as u32)[0],
+    );
+    let mut contents: Vec<String> = prepared.detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok().map(|(_, content)| content))
+        .collect();
+    contents.dedup();
+    contents
+}
+
+// 可以在浏览器中打开的内容
+pub fn is_url(content: &str) -> bool {
+    let content = content.trim();
+    content.starts_with("http://") || content.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_code_helpers() {
+        assert!(is_url(" https://example.com/a "));
+        assert!(!is_url("WIFI:S:home;T:WPA;P:secret;;"));
+        let blank = image::DynamicImage::new_luma8(64, 64);
+        assert!(decode_qr_codes(&blank).is_empty());
+    }
+}
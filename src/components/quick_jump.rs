@@ -0,0 +1,295 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+// 记住的最近访问文件夹数量
+const MAX_RECENT: usize = 50;
+// 弹出框中最多显示的候选项
+const MAX_MATCHES: usize = 8;
+
+// 路径别名，如 proj -> ~/work/projects，可在地址栏和快速跳转中作为路径开头使用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathAlias {
+    pub name: String,
+    pub path: String,
+}
+
+// 展开 $VAR 和 ${VAR} 形式的环境变量，未定义的变量保持原样
+fn expand_env_vars(input: &str) -> String {
+    let mut result = String::new();
+    let mut rest = input;
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        let after = &rest[index + 1..];
+        let (name, consumed) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => result.push_str(&value),
+            _ => result.push_str(&rest[index..index + 1 + consumed]),
+        }
+        rest = &after[consumed..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// 展开输入的路径：先展开环境变量，再把开头的 "~" 展开为主目录、别名展开为对应路径；
+// 结果为绝对路径时返回，否则返回 None
+pub fn expand_path(input: &str, aliases: &[PathAlias]) -> Option<PathBuf> {
+    let input = expand_env_vars(input.trim());
+    let (first, rest) = match input.split_once('/') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (input.as_str(), None),
+    };
+    let base = if first == "~" {
+        dirs::home_dir()?
+    } else if let Some(alias) = aliases.iter().find(|a| !first.is_empty() && a.name.trim() == first) {
+        // 别名的路径中也可以使用 ~ 和环境变量，但不能再引用别名
+        expand_path(&alias.path, &[])?
+    } else {
+        return Path::new(&input).is_absolute().then(|| PathBuf::from(&input));
+    };
+    Some(match rest {
+        Some(rest) if !rest.is_empty() => base.join(rest),
+        _ => base,
+    })
+}
+
+// 模糊匹配：查询中的字符按顺序出现在文本中即匹配（不区分大小写），
+// 连续命中和从开头命中得分更高，文本越短越靠前
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut start = 0;
+    let mut last: Option<usize> = None;
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = start + text[start..].iter().position(|&c| c == query_char)?;
+        score += match last {
+            Some(previous) if found == previous + 1 => 3,
+            _ => 1,
+        };
+        if found == 0 {
+            score += 2;
+        }
+        last = Some(found);
+        start = found + 1;
+    }
+    Some(score * 10 - text.len() as i32)
+}
+
+// 常用文件夹，作为内置书签
+fn bookmark_folders() -> Vec<(String, PathBuf)> {
+    [
+        ("主目录", dirs::home_dir()),
+        ("桌面", dirs::desktop_dir()),
+        ("文档", dirs::document_dir()),
+        ("下载", dirs::download_dir()),
+        ("图片", dirs::picture_dir()),
+        ("音乐", dirs::audio_dir()),
+        ("视频", dirs::video_dir()),
+    ]
+    .into_iter()
+    .filter_map(|(label, path)| path.map(|p| (label.to_string(), p)))
+    .collect()
+}
+
+// 跳转候选项
+struct JumpCandidate {
+    label: String, // 书签名称或文件夹名
+    path: PathBuf,
+}
+
+// Ctrl+L 快速跳转弹出框：输入路径或按名称模糊匹配书签和最近访问的文件夹
+pub struct QuickJump {
+    show_dialog: bool,
+    input: String,
+    selected: usize,
+    request_focus: bool,
+    recent: Vec<PathBuf>, // 最近访问的文件夹，最新的在前
+}
+
+impl QuickJump {
+    pub fn new() -> Self {
+        Self {
+            show_dialog: false,
+            input: String::new(),
+            selected: 0,
+            request_focus: false,
+            recent: Vec::new(),
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.show_dialog = true;
+        self.input.clear();
+        self.selected = 0;
+        self.request_focus = true;
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show_dialog
+    }
+
+    // 记录访问过的文件夹
+    pub fn remember(&mut self, path: &Path) {
+        self.recent.retain(|p| p != path);
+        self.recent.insert(0, path.to_path_buf());
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    // 按得分排序的候选项；查询为空时列出书签、别名和最近访问的文件夹
+    fn matches(&self, aliases: &[PathAlias]) -> Vec<JumpCandidate> {
+        let mut candidates: Vec<JumpCandidate> = bookmark_folders()
+            .into_iter()
+            .map(|(label, path)| JumpCandidate { label, path })
+            .collect();
+        for alias in aliases {
+            if let Some(path) = expand_path(&alias.name, aliases) {
+                candidates.push(JumpCandidate { label: alias.name.trim().to_string(), path });
+            }
+        }
+        for path in &self.recent {
+            if !candidates.iter().any(|c| &c.path == path) {
+                let label = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string());
+                candidates.push(JumpCandidate { label, path: path.clone() });
+            }
+        }
+
+        let query = self.input.trim();
+        if query.is_empty() {
+            candidates.truncate(MAX_MATCHES);
+            return candidates;
+        }
+        // 名称匹配优先于完整路径匹配
+        let mut scored: Vec<(i32, JumpCandidate)> = candidates
+            .into_iter()
+            .filter_map(|c| {
+                let score = fuzzy_score(query, &c.label)
+                    .map(|s| s + 1000)
+                    .or_else(|| fuzzy_score(query, &c.path.to_string_lossy()))?;
+                Some((score, c))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().take(MAX_MATCHES).map(|(_, c)| c).collect()
+    }
+
+    // 显示弹出框，按 Enter 时返回要跳转的路径（可能是文件）
+    pub fn show_quick_jump_dialog(&mut self, ctx: &egui::Context, aliases: &[PathAlias]) -> Option<PathBuf> {
+        let typed_path = expand_path(&self.input, aliases);
+        let matches = if typed_path.is_some() { Vec::new() } else { self.matches(aliases) };
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut open = true;
+        let mut result = None;
+        egui::Window::new("跳转到")
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                // 上下键在候选项间移动，需在输入框处理按键之前取走
+                let (up, down, escape) = ui.input_mut(|i| {
+                    (
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+                    )
+                });
+                if up {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+                if down && self.selected + 1 < matches.len() {
+                    self.selected += 1;
+                }
+                if escape {
+                    open = false;
+                }
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.input)
+                        .hint_text("输入路径（/、~、$变量、别名）或文件夹名称")
+                        .desired_width(f32::INFINITY),
+                );
+                if self.request_focus {
+                    response.request_focus();
+                    self.request_focus = false;
+                }
+                if response.changed() {
+                    self.selected = 0;
+                }
+                let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+                if let Some(path) = &typed_path {
+                    if path.exists() {
+                        ui.label(egui::RichText::new(format!("按 Enter 跳转到 {}", path.display())).weak());
+                        if enter {
+                            result = Some(path.clone());
+                        }
+                    } else {
+                        ui.colored_label(ui.visuals().warn_fg_color, "路径不存在");
+                        if enter {
+                            response.request_focus();
+                        }
+                    }
+                    return;
+                }
+
+                if matches.is_empty() && !self.input.trim().is_empty() {
+                    ui.label(egui::RichText::new("没有匹配的书签或最近访问的文件夹").weak());
+                }
+                for (index, candidate) in matches.iter().enumerate() {
+                    let text = format!("{}    {}", candidate.label, candidate.path.display());
+                    if ui.selectable_label(index == self.selected, text).clicked() {
+                        result = Some(candidate.path.clone());
+                    }
+                }
+                if enter {
+                    match matches.get(self.selected) {
+                        Some(candidate) => result = Some(candidate.path.clone()),
+                        None => response.request_focus(),
+                    }
+                }
+            });
+
+        if result.is_some() || !open {
+            self.show_dialog = false;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_and_expand_path() {
+        assert!(fuzzy_score("dl", "Downloads").is_some());
+        assert!(fuzzy_score("xyz", "Downloads").is_none());
+        // 连续命中比分散命中得分高
+        assert!(fuzzy_score("doc", "Documents").unwrap() > fuzzy_score("doc", "Dropbox_code").unwrap());
+
+        assert_eq!(expand_path("/etc", &[]), Some(PathBuf::from("/etc")));
+        assert_eq!(expand_path("~/a", &[]), dirs::home_dir().map(|home| home.join("a")));
+        assert_eq!(expand_path("文档", &[]), None);
+
+        let aliases = [PathAlias { name: "proj".to_string(), path: "/work/projects".to_string() }];
+        assert_eq!(expand_path("proj", &aliases), Some(PathBuf::from("/work/projects")));
+        assert_eq!(expand_path("proj/app/src", &aliases), Some(PathBuf::from("/work/projects/app/src")));
+        assert_eq!(expand_path("project", &aliases), None);
+        std::env::set_var("QUICK_JUMP_TEST_DIR", "/data");
+        assert_eq!(expand_path("$QUICK_JUMP_TEST_DIR/a", &[]), Some(PathBuf::from("/data/a")));
+        assert_eq!(expand_path("${QUICK_JUMP_TEST_DIR}x", &[]), Some(PathBuf::from("/datax")));
+        assert_eq!(expand_env_vars("/a/$QUICK_JUMP_UNDEFINED_VAR/b"), "/a/$QUICK_JUMP_UNDEFINED_VAR/b");
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+// 相机 RAW 格式。这些格式都基于 TIFF 结构，文件中带有相机生成的 JPEG 预览，
+// 浏览时直接取出最大的内嵌预览，不解码传感器数据
+const RAW_EXTENSIONS: [&str; 4] = ["cr2", "nef", "arw", "dng"];
+
+// TIFF 标签
+const TAG_COMPRESSION: u16 = 0x0103;
+const TAG_STRIP_OFFSETS: u16 = 0x0111;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_STRIP_BYTE_COUNTS: u16 = 0x0117;
+const TAG_SUB_IFDS: u16 = 0x014A;
+const TAG_JPEG_OFFSET: u16 = 0x0201;
+const TAG_JPEG_LENGTH: u16 = 0x0202;
+
+// 表示 JPEG 数据的压缩方式：旧式 JPEG、JPEG 和 DNG 的有损 JPEG
+const JPEG_COMPRESSIONS: [u32; 3] = [6, 7, 34892];
+
+// 防止损坏的文件中 IFD 互相引用导致无限遍历
+const MAX_IFDS: usize = 64;
+
+pub fn is_raw_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| RAW_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+struct Entry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    value_pos: usize,
+}
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..4)? {
+            [b'I', b'I', 42, 0] => true,
+            [b'M', b'M', 0, 42] => false,
+            _ => return None,
+        };
+        Some(Self { data, little_endian })
+    }
+
+    fn u16_at(&self, pos: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32_at(&self, pos: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    // 读取一个 IFD 的全部条目和下一个 IFD 的位置
+    fn read_ifd(&self, offset: usize) -> Option<(Vec<Entry>, usize)> {
+        let count = self.u16_at(offset)? as usize;
+        let entries = (0..count)
+            .map(|i| {
+                let pos = offset + 2 + i * 12;
+                Some(Entry {
+                    tag: self.u16_at(pos)?,
+                    kind: self.u16_at(pos + 2)?,
+                    count: self.u32_at(pos + 4)?,
+                    value_pos: pos + 8,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let next = self.u32_at(offset + 2 + count * 12)? as usize;
+        Some((entries, next))
+    }
+
+    // 条目的第 index 个整数值；只处理 SHORT、LONG 和 IFD 类型
+    fn value(&self, entry: &Entry, index: u32) -> Option<u32> {
+        let size = match entry.kind {
+            3 => 2,
+            4 | 13 => 4,
+            _ => return None,
+        };
+        if index >= entry.count {
+            return None;
+        }
+        let base = if entry.count as usize * size <= 4 {
+            entry.value_pos
+        } else {
+            self.u32_at(entry.value_pos)? as usize
+        };
+        let pos = base + index as usize * size;
+        if size == 2 {
+            self.u16_at(pos).map(u32::from)
+        } else {
+            self.u32_at(pos)
+        }
+    }
+}
+
+// 内嵌预览的位置以及第一个 IFD 中记录的方向
+struct EmbeddedPreviews {
+    ranges: Vec<(usize, usize)>,
+    orientation: u32,
+}
+
+// 遍历全部 IFD（包括 SubIFD），收集 JPEG 数据的位置
+fn find_previews(tiff: &Tiff) -> EmbeddedPreviews {
+    let mut previews = EmbeddedPreviews { ranges: Vec::new(), orientation: 1 };
+    let mut pending = match tiff.u32_at(4) {
+        Some(first) => vec![first as usize],
+        None => return previews,
+    };
+    let mut visited = HashSet::new();
+
+    while let Some(offset) = pending.pop() {
+        if offset == 0 || visited.len() >= MAX_IFDS || !visited.insert(offset) {
+            continue;
+        }
+        let Some((entries, next)) = tiff.read_ifd(offset) else {
+            continue;
+        };
+        pending.push(next);
+
+        let find = |tag: u16| entries.iter().find(|e| e.tag == tag);
+        let first_value = |tag: u16| find(tag).and_then(|e| tiff.value(e, 0));
+
+        if offset == tiff.u32_at(4).unwrap_or(0) as usize {
+            previews.orientation = first_value(TAG_ORIENTATION).unwrap_or(1);
+        }
+        if let Some(sub_ifds) = find(TAG_SUB_IFDS) {
+            pending.extend((0..sub_ifds.count).filter_map(|i| tiff.value(sub_ifds, i)).map(|o| o as usize));
+        }
+        if let (Some(start), Some(len)) = (first_value(TAG_JPEG_OFFSET), first_value(TAG_JPEG_LENGTH)) {
+            previews.ranges.push((start as usize, len as usize));
+        }
+        // 整幅图像是单个 JPEG 条带的 IFD
+        let single_strip = find(TAG_STRIP_OFFSETS).is_some_and(|e| e.count == 1);
+        if single_strip && first_value(TAG_COMPRESSION).is_some_and(|c| JPEG_COMPRESSIONS.contains(&c)) {
+            if let (Some(start), Some(len)) = (first_value(TAG_STRIP_OFFSETS), first_value(TAG_STRIP_BYTE_COUNTS)) {
+                previews.ranges.push((start as usize, len as usize));
+            }
+        }
+    }
+    previews
+}
+
+// 只接受普通的基线或渐进式 JPEG；RAW 数据本身常以无损 JPEG 存储，不能当作预览显示
+fn is_displayable_jpeg(data: &[u8]) -> bool {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return false;
+    }
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return false;
+        }
+        let marker = data[pos + 1];
+        match marker {
+            0xC0..=0xC2 => return true,
+            0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF | 0xDA => return false,
+            0xFF => pos += 1,
+            _ => pos += 2 + u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize,
+        }
+    }
+    false
+}
+
+fn apply_orientation(image: image::DynamicImage, orientation: u32) -> image::DynamicImage {
+    match orientation {
+        3 => image.rotate180(),
+        6 => image.rotate90(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+// 从 RAW 文件数据中解码最大的可显示内嵌预览，并按相机记录的方向旋转
+pub fn decode_embedded_preview(data: &[u8]) -> Result<image::DynamicImage, String> {
+    let tiff = Tiff::parse(data).ok_or_else(|| "不是 TIFF 结构的 RAW 文件".to_string())?;
+    let mut previews = find_previews(&tiff);
+    previews.ranges.sort_by_key(|&(start, len)| (std::cmp::Reverse(len), start));
+    previews.ranges.dedup();
+
+    previews.ranges.iter()
+        .filter_map(|&(start, len)| data.get(start..start.checked_add(len)?))
+        .filter(|jpeg| is_displayable_jpeg(jpeg))
+        .find_map(|jpeg| image::load_from_memory_with_format(jpeg, image::ImageFormat::Jpeg).ok())
+        .map(|image| apply_orientation(image, previews.orientation))
+        .ok_or_else(|| "文件中没有可显示的内嵌预览".to_string())
+}
+
+// 打开 RAW 文件的内嵌预览；应在后台线程调用
+pub fn open_raw(path: &Path) -> Result<image::DynamicImage, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    decode_embedded_preview(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 构造一个小端 TIFF：IFD0 记录方向 6，并通过 JPEG 偏移/长度标签指向内嵌预览
+    fn build_raw(jpeg: &[u8]) -> Vec<u8> {
+        let entries: [(u16, u16, u32, u32); 3] = [
+            (TAG_ORIENTATION, 3, 1, 6),
+            (TAG_JPEG_OFFSET, 4, 1, 8 + 2 + 3 * 12 + 4),
+            (TAG_JPEG_LENGTH, 4, 1, jpeg.len() as u32),
+        ];
+        let mut data = vec![b'I', b'I', 42, 0];
+        data.extend(8u32.to_le_bytes());
+        data.extend((entries.len() as u16).to_le_bytes());
+        for (tag, kind, count, value) in entries {
+            data.extend(tag.to_le_bytes());
+            data.extend(kind.to_le_bytes());
+            data.extend(count.to_le_bytes());
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(0u32.to_le_bytes());
+        data.extend(jpeg);
+        data
+    }
+
+    #[test]
+    fn test_decode_embedded_preview() {
+        let mut jpeg = Vec::new();
+        image::DynamicImage::new_rgb8(4, 2)
+            .write_to(&mut std::io::Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+            .unwrap();
+        assert!(is_displayable_jpeg(&jpeg));
+
+        let image = decode_embedded_preview(&build_raw(&jpeg)).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 4));
+
+        assert!(decode_embedded_preview(b"not a raw file").is_err());
+        assert!(decode_embedded_preview(&build_raw(b"\xFF\xD8\xFF\xC3")).is_err());
+        assert!(is_raw_file(Path::new("IMG_0001.CR2")));
+        assert!(!is_raw_file(Path::new("IMG_0001.jpg")));
+    }
+}
@@ -0,0 +1,290 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crossbeam_channel::{self, Receiver, Sender};
+use super::app_event::{AppEvent, EventQueue};
+use super::file_list::FileDropRequest;
+use super::image_formats;
+use super::system_clipboard;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// 提示在没有操作时自动消失的时间
+const TOAST_LIFETIME: Duration = Duration::from_secs(20);
+const MAX_TOASTS: usize = 3;
+
+// 截图文件夹：设置中指定的文件夹，未指定时使用 图片/Screenshots，不存在时使用图片文件夹
+pub fn screenshot_folder(configured: &str) -> Option<PathBuf> {
+    let configured = configured.trim();
+    if !configured.is_empty() {
+        return Some(PathBuf::from(configured));
+    }
+    let pictures = dirs::picture_dir()?;
+    let screenshots = pictures.join("Screenshots");
+    Some(if screenshots.is_dir() { screenshots } else { pictures })
+}
+
+fn list_images(folder: &Path) -> HashMap<PathBuf, u64> {
+    fs::read_dir(folder)
+        .map(|entries| {
+            entries.flatten()
+                .map(|entry| entry.path())
+                .filter(|path| image_formats::is_image_file(path))
+                .filter_map(|path| fs::metadata(&path).ok().map(|m| (path, m.len())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 后台定期扫描文件夹；新出现的图片在大小不再变化（截图工具写完）后发送。
+// seen 接收在提示中改名后的路径，不再当作新截图
+fn spawn_watcher(folder: PathBuf, ctx: egui::Context, stop: Arc<AtomicBool>, seen: Receiver<PathBuf>) -> Receiver<PathBuf> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    std::thread::spawn(move || {
+        let mut known: HashSet<PathBuf> = list_images(&folder).into_keys().collect();
+        let mut pending: HashMap<PathBuf, u64> = HashMap::new();
+        // 改名可能稍后才完成，出现在文件夹中时再记为已知
+        let mut renamed: HashSet<PathBuf> = HashSet::new();
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(POLL_INTERVAL);
+            renamed.extend(seen.try_iter());
+            let current = list_images(&folder);
+            for (path, size) in &current {
+                if renamed.remove(path) {
+                    known.insert(path.clone());
+                }
+                if known.contains(path) {
+                    continue;
+                }
+                if size > &0 && pending.get(path) == Some(size) {
+                    known.insert(path.clone());
+                    pending.remove(path);
+                    if sender.send(path.clone()).is_err() {
+                        return;
+                    }
+                    ctx.request_repaint();
+                } else {
+                    pending.insert(path.clone(), *size);
+                }
+            }
+            // 删除或改名的文件不再记录，同名文件重新出现时再次提示
+            known.retain(|path| current.contains_key(path));
+            pending.retain(|path, _| current.contains_key(path));
+        }
+    });
+    receiver
+}
+
+// 把图片复制到系统剪贴板
+fn copy_image_to_clipboard(path: &Path) -> Result<(), String> {
+    let data = fs::read(path).map_err(|e| format!("读取截图失败: {}", e))?;
+    let mime = match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "image/png",
+    };
+    system_clipboard::write(mime, &data).map_err(|e| format!("复制图片{}", e))
+}
+
+struct Toast {
+    path: PathBuf,
+    created: Instant,
+    rename: Option<String>, // 正在编辑的新名称
+    message: Option<String>,
+    copying: Option<Receiver<Result<(), String>>>, // 正在后台复制到剪贴板
+}
+
+impl Toast {
+    fn new(path: PathBuf) -> Self {
+        Self { path, created: Instant::now(), rename: None, message: None, copying: None }
+    }
+}
+
+struct Watcher {
+    folder: PathBuf,
+    receiver: Receiver<PathBuf>,
+    seen: Sender<PathBuf>, // 改名后的路径
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// 截图收件箱：监视截图文件夹，出现新截图时在右下角提示，可以直接重命名、移到项目文件夹或复制到剪贴板
+pub struct ScreenshotInbox {
+    watcher: Option<Watcher>,
+    applied: Option<(bool, String)>, // 上次应用的设置，每帧调用 sync 时只在变化后重新开始监视
+    toasts: Vec<Toast>,
+}
+
+impl ScreenshotInbox {
+    pub fn new() -> Self {
+        Self {
+            watcher: None,
+            applied: None,
+            toasts: Vec::new(),
+        }
+    }
+
+    // 按设置开始、停止或切换监视的文件夹
+    pub fn sync(&mut self, ctx: &egui::Context, enabled: bool, configured_folder: &str) {
+        let config = (enabled, configured_folder.to_string());
+        if self.applied.as_ref() == Some(&config) {
+            return;
+        }
+        self.applied = Some(config);
+        let folder = if enabled { screenshot_folder(configured_folder) } else { None };
+        if self.watcher.as_ref().map(|w| &w.folder) == folder.as_ref() {
+            return;
+        }
+        self.watcher = folder.map(|folder| {
+            let stop = Arc::new(AtomicBool::new(false));
+            let (seen, seen_receiver) = crossbeam_channel::unbounded();
+            let receiver = spawn_watcher(folder.clone(), ctx.clone(), stop.clone(), seen_receiver);
+            Watcher { folder, receiver, seen, stop }
+        });
+        if self.watcher.is_none() {
+            self.toasts.clear();
+        }
+    }
+
+    // 在窗口右下角显示新截图的提示；project_folder 为“移到项目文件夹”的目标
+    pub fn show(&mut self, ctx: &egui::Context, project_folder: &str, events: &mut EventQueue) {
+        if let Some(watcher) = &self.watcher {
+            for path in watcher.receiver.try_iter() {
+                self.toasts.push(Toast::new(path));
+            }
+        }
+        let overflow = self.toasts.len().saturating_sub(MAX_TOASTS);
+        self.toasts.drain(..overflow);
+        // 正在重命名的提示不会自动消失
+        self.toasts.retain(|t| t.rename.is_some() || t.created.elapsed() < TOAST_LIFETIME);
+        if self.toasts.is_empty() {
+            return;
+        }
+        ctx.request_repaint_after(Duration::from_secs(1));
+
+        let project_folder = project_folder.trim();
+        let project_folder = (!project_folder.is_empty()).then(|| PathBuf::from(project_folder));
+        let seen = self.watcher.as_ref().map(|w| &w.seen);
+        let mut closed = Vec::new();
+        egui::Area::new(egui::Id::new("screenshot_inbox"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -36.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (index, toast) in self.toasts.iter_mut().enumerate() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(300.0);
+                        ui.push_id(index, |ui| {
+                            if show_toast(ui, toast, project_folder.as_deref(), seen, events) {
+                                closed.push(index);
+                            }
+                        });
+                    });
+                    ui.add_space(6.0);
+                }
+            });
+        for index in closed.into_iter().rev() {
+            self.toasts.remove(index);
+        }
+    }
+}
+
+// 绘制一条提示，返回是否关闭
+fn show_toast(ui: &mut egui::Ui, toast: &mut Toast, project_folder: Option<&Path>, seen: Option<&Sender<PathBuf>>, events: &mut EventQueue) -> bool {
+    if let Some(result) = toast.copying.as_ref().and_then(|r| r.try_recv().ok()) {
+        toast.copying = None;
+        toast.message = Some(match result {
+            Ok(()) => "已复制到剪贴板".to_string(),
+            Err(e) => e,
+        });
+    }
+    let name = toast.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut close = false;
+    ui.horizontal(|ui| {
+        ui.strong("📷 新截图");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.small_button("✕").clicked() {
+                close = true;
+            }
+        });
+    });
+    ui.label(&name).on_hover_text(toast.path.display().to_string());
+
+    if let Some(new_name) = &mut toast.rename {
+        let (mut commit, mut cancel) = (false, false);
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(new_name);
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                commit = true;
+            }
+            if ui.button("确定").clicked() {
+                commit = true;
+            }
+            if ui.button("取消").clicked() {
+                cancel = true;
+            }
+        });
+        if cancel {
+            toast.rename = None;
+        } else if commit {
+            if let Some(new_name) = toast.rename.take().filter(|n| !n.trim().is_empty() && *n != name) {
+                let new_name = new_name.trim().to_string();
+                events.emit(AppEvent::RenameCommitted { path: toast.path.clone(), new_name: new_name.clone() });
+                toast.path = toast.path.with_file_name(new_name);
+                if let Some(seen) = seen {
+                    let _ = seen.send(toast.path.clone());
+                }
+            }
+            toast.created = Instant::now();
+        }
+        return close;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        if ui.button("重命名").clicked() {
+            toast.rename = Some(name.clone());
+        }
+        let move_button = ui.add_enabled(project_folder.is_some(), egui::Button::new("移到项目文件夹"));
+        let move_button = match project_folder {
+            Some(folder) => move_button.on_hover_text(folder.display().to_string()),
+            None => move_button.on_disabled_hover_text("在选项中设置项目文件夹"),
+        };
+        if move_button.clicked() {
+            if let Some(folder) = project_folder {
+                events.emit(AppEvent::Drop(FileDropRequest {
+                    paths: vec![toast.path.clone()],
+                    target_dir: folder.to_path_buf(),
+                    copy: false,
+                }));
+                close = true;
+            }
+        }
+        if ui.add_enabled(toast.copying.is_none(), egui::Button::new("复制到剪贴板")).clicked() {
+            // 读取图片和等待剪贴板工具都可能较慢，在后台进行
+            let (sender, receiver) = crossbeam_channel::bounded(1);
+            let (path, ctx) = (toast.path.clone(), ui.ctx().clone());
+            std::thread::spawn(move || {
+                let _ = sender.send(copy_image_to_clipboard(&path));
+                ctx.request_repaint();
+            });
+            toast.copying = Some(receiver);
+            toast.message = Some("正在复制...".to_string());
+            toast.created = Instant::now();
+        }
+        if ui.button("显示").clicked() {
+            events.emit(AppEvent::Reveal(toast.path.clone()));
+            close = true;
+        }
+    });
+    if let Some(message) = &toast.message {
+        ui.label(egui::RichText::new(message).weak());
+    }
+    close
+}
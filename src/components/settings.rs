@@ -0,0 +1,620 @@
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::components::confirmation::{ConfirmationSettings, ProtectedPath, ProtectionMode};
+use crate::components::mouse_strategy::ClickOpenMode;
+use crate::components::file_list::FolderPlacement;
+use crate::components::screenshot_inbox;
+use crate::components::quick_jump::{self, PathAlias};
+use crate::components::start_page::StartupLocation;
+use crate::components::mouse_gesture::{GestureRecorder, GestureSettings};
+
+// 颜色规则的匹配条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RuleMatcher {
+    Extensions(String),      // 扩展名列表，逗号分隔，如 "zip,7z,tar"
+    Executable,              // 可执行文件
+    Directory,               // 文件夹
+    ModifiedWithinDays(u32), // 最近N天内修改（0 表示今天）
+}
+
+impl RuleMatcher {
+    fn label(&self) -> &'static str {
+        match self {
+            RuleMatcher::Extensions(_) => "扩展名",
+            RuleMatcher::Executable => "可执行文件",
+            RuleMatcher::Directory => "文件夹",
+            RuleMatcher::ModifiedWithinDays(_) => "修改时间",
+        }
+    }
+}
+
+// 详细信息视图的行着色规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorRule {
+    pub enabled: bool,
+    pub name: String,
+    pub matcher: RuleMatcher,
+    pub color_enabled: bool, // 是否修改文字颜色
+    pub color: [u8; 3],
+    pub bold: bool,
+}
+
+impl ColorRule {
+    pub fn matches(&self, path: &Path, is_dir: bool, is_executable: bool, modified: Option<SystemTime>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match &self.matcher {
+            RuleMatcher::Extensions(list) => {
+                let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+                    return false;
+                };
+                !is_dir && list.split(',').any(|item| item.trim().trim_start_matches('.').eq_ignore_ascii_case(ext))
+            }
+            RuleMatcher::Executable => !is_dir && is_executable,
+            RuleMatcher::Directory => is_dir,
+            RuleMatcher::ModifiedWithinDays(days) => {
+                let Some(modified) = modified else {
+                    return false;
+                };
+                let modified_date = chrono::DateTime::<chrono::Local>::from(modified).date_naive();
+                let today = chrono::Local::now().date_naive();
+                (today - modified_date).num_days() <= i64::from(*days)
+            }
+        }
+    }
+}
+
+// 默认规则：可执行文件绿色、压缩包紫色、今天修改的文件加粗
+pub fn default_color_rules() -> Vec<ColorRule> {
+    vec![
+        ColorRule {
+            enabled: true,
+            name: "可执行文件".to_string(),
+            matcher: RuleMatcher::Executable,
+            color_enabled: true,
+            color: [46, 160, 67],
+            bold: false,
+        },
+        ColorRule {
+            enabled: true,
+            name: "压缩包".to_string(),
+            matcher: RuleMatcher::Extensions("zip,rar,7z,tar,gz,tgz,xz,bz2,zst".to_string()),
+            color_enabled: true,
+            color: [150, 80, 200],
+            bold: false,
+        },
+        ColorRule {
+            enabled: true,
+            name: "今天修改".to_string(),
+            matcher: RuleMatcher::ModifiedWithinDays(0),
+            color_enabled: false,
+            color: [0, 0, 0],
+            bold: true,
+        },
+    ]
+}
+
+// 设置文件的结构版本；修改已有字段的含义或格式时加一，并在 MIGRATIONS 中补充转换
+const SCHEMA_VERSION: u32 = 2;
+// MIGRATIONS[i] 把版本 i+1 的设置转换为版本 i+2
+const MIGRATIONS: [fn(&mut Map<String, Value>); (SCHEMA_VERSION - 1) as usize] = [migrate_v1];
+// 设置修复提示显示的时间
+const NOTICE_LIFETIME: Duration = Duration::from_secs(12);
+
+// 版本 1 是没有记录版本号的旧文件，其字段都可以由默认值补齐，只需记录版本号
+fn migrate_v1(_map: &mut Map<String, Value>) {}
+
+// 应用程序设置，保存在配置目录的 settings.json 中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub schema_version: u32,                 // 设置文件的结构版本
+    pub color_rules_enabled: bool,
+    pub color_rules: Vec<ColorRule>,
+    pub confirmations: ConfirmationSettings, // 哪些操作需要确认
+    pub click_open_mode: ClickOpenMode,      // 单击还是双击打开
+    pub directory_pane_ratio: f32,           // 目录栏展开时占窗口宽度的比例
+    pub directory_pane_auto_hide: bool,      // 目录栏自动隐藏（F9 切换）
+    pub preview_pane_hidden: bool,           // 隐藏预览栏（F11 切换）
+    pub custom_folder_icons: bool,           // 使用文件夹中的 .directory 图标或 folder.jpg/cover.jpg
+    pub two_line_names: bool,                // 图标视图中文件名最多显示两行
+    pub mouse_gestures: GestureSettings,     // 右键拖动的鼠标手势
+    pub folder_placement: FolderPlacement,   // 排序时文件夹在前、在后或混合
+    pub screenshot_inbox: bool,              // 监视截图文件夹，出现新截图时提示
+    pub screenshot_folder: String,           // 为空时使用 图片/Screenshots 或图片文件夹
+    pub screenshot_project_folder: String,   // 截图提示中“移到项目文件夹”的目标
+    pub path_aliases: Vec<PathAlias>,        // 地址栏和快速跳转中可用的路径别名
+    pub startup_location: StartupLocation,   // 启动时打开的位置
+    pub startup_custom_path: String,         // 启动位置为“指定文件夹”时的路径
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            color_rules_enabled: false,
+            color_rules: default_color_rules(),
+            confirmations: ConfirmationSettings::default(),
+            click_open_mode: ClickOpenMode::DoubleClick,
+            directory_pane_ratio: 0.25,
+            directory_pane_auto_hide: false,
+            preview_pane_hidden: false,
+            custom_folder_icons: true,
+            two_line_names: false,
+            mouse_gestures: GestureSettings::default(),
+            folder_placement: FolderPlacement::First,
+            screenshot_inbox: false,
+            screenshot_folder: String::new(),
+            screenshot_project_folder: String::new(),
+            path_aliases: Vec::new(),
+            startup_location: StartupLocation::Home,
+            startup_custom_path: String::new(),
+        }
+    }
+}
+
+impl AppSettings {
+    fn file_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("file-explorer").join("settings.json"))
+    }
+
+    // 加载设置：按版本迁移，并把无法识别或不合理的项恢复为默认值；返回需要提示用户的说明
+    pub fn load() -> (Self, Vec<String>) {
+        let Some(path) = Self::file_path() else {
+            return (Self::default(), Vec::new());
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return (Self::default(), Vec::new());
+        };
+        let (settings, notices, changed) = Self::from_json(&text).unwrap_or_else(|| {
+            // 损坏的文件先改名备份，再写入默认设置，以便用户找回其中的内容
+            let backup = path.with_file_name("settings.json.bak");
+            let notice = match fs::rename(&path, &backup) {
+                Ok(()) => format!("设置文件已损坏，已备份为 {} 并恢复全部默认设置", backup.display()),
+                Err(e) => format!("设置文件已损坏，已恢复全部默认设置（备份失败: {}）", e),
+            };
+            (Self::default(), vec![notice], true)
+        });
+        // 迁移或修复后立即写回，避免每次启动都重复提示
+        if changed {
+            if let Err(error) = settings.save() {
+                return (settings, notices.into_iter().chain(std::iter::once(error)).collect());
+            }
+        }
+        (settings, notices)
+    }
+
+    // 解析设置文件内容，返回设置、提示说明和是否需要写回；文件已损坏时返回 None
+    fn from_json(text: &str) -> Option<(Self, Vec<String>, bool)> {
+        let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(text) else {
+            return None;
+        };
+        let mut notices = Vec::new();
+        let version = map.get("schema_version").and_then(Value::as_u64).unwrap_or(1).max(1) as u32;
+        if version > SCHEMA_VERSION {
+            // 较新版本写入的文件：尽量读取已知的项，但不写回，以免丢失新版本的设置
+            notices.push(format!("设置文件来自较新的版本（{}），部分设置可能不会生效", version));
+        } else {
+            for migrate in &MIGRATIONS[(version - 1) as usize..] {
+                migrate(&mut map);
+            }
+        }
+        map.insert("schema_version".to_string(), Value::from(SCHEMA_VERSION));
+
+        let (mut settings, invalid) = Self::from_map_repaired(map);
+        let repaired: Vec<&str> = invalid.iter().map(|key| field_label(key)).chain(settings.validate()).collect();
+        if !repaired.is_empty() {
+            notices.push(format!("以下设置无效，已恢复默认值: {}", repaired.join("、")));
+        }
+        let changed = version < SCHEMA_VERSION || (version == SCHEMA_VERSION && !repaired.is_empty());
+        Some((settings, notices, changed))
+    }
+
+    // 逐项反序列化，类型不对的项使用默认值；返回设置和被重置的项
+    fn from_map_repaired(map: Map<String, Value>) -> (Self, Vec<String>) {
+        let Ok(Value::Object(defaults)) = serde_json::to_value(Self::default()) else {
+            return (Self::default(), Vec::new());
+        };
+        let mut merged = defaults.clone();
+        let mut invalid = Vec::new();
+        for (key, value) in map {
+            // 未知的项（旧版本遗留或较新版本新增）直接忽略
+            if !defaults.contains_key(&key) {
+                continue;
+            }
+            let mut candidate = defaults.clone();
+            candidate.insert(key.clone(), value.clone());
+            if serde_json::from_value::<Self>(Value::Object(candidate)).is_ok() {
+                merged.insert(key, value);
+            } else {
+                invalid.push(key);
+            }
+        }
+        (serde_json::from_value(Value::Object(merged)).unwrap_or_default(), invalid)
+    }
+
+    // 检查取值范围，把不合理的值改回默认值，返回被修正的项
+    fn validate(&mut self) -> Vec<&'static str> {
+        let defaults = Self::default();
+        let mut repaired = Vec::new();
+        if !(0.1..=0.5).contains(&self.directory_pane_ratio) {
+            self.directory_pane_ratio = defaults.directory_pane_ratio;
+            repaired.push(field_label("directory_pane_ratio"));
+        }
+        if self.confirmations.large_move_threshold == 0 {
+            self.confirmations.large_move_threshold = defaults.confirmations.large_move_threshold;
+            repaired.push(field_label("confirmations"));
+        }
+        let bindings = self.mouse_gestures.bindings.len();
+        self.mouse_gestures.bindings.retain(|binding| !binding.stroke.is_empty());
+        if self.mouse_gestures.bindings.len() != bindings {
+            repaired.push(field_label("mouse_gestures"));
+        }
+        repaired
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::file_path().ok_or_else(|| "无法确定配置目录".to_string())?;
+        crate::utils::save_json(&path, self).map_err(|e| format!("保存设置失败: {}", e))
+    }
+
+    // 当前生效的颜色规则（总开关关闭时为空）
+    pub fn active_color_rules(&self) -> Vec<ColorRule> {
+        if self.color_rules_enabled {
+            self.color_rules.clone()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// 设置项在提示中显示的名称
+fn field_label(key: &str) -> &'static str {
+    match key {
+        "schema_version" => "版本号",
+        "color_rules_enabled" | "color_rules" => "行颜色规则",
+        "confirmations" => "操作确认",
+        "click_open_mode" => "单击/双击打开",
+        "directory_pane_ratio" | "directory_pane_auto_hide" => "目录栏",
+        "preview_pane_hidden" => "预览栏",
+        "custom_folder_icons" | "two_line_names" => "图标",
+        "mouse_gestures" => "鼠标手势",
+        "folder_placement" => "排序",
+        "screenshot_inbox" | "screenshot_folder" | "screenshot_project_folder" => "截图收件箱",
+        "path_aliases" => "路径别名",
+        "startup_location" | "startup_custom_path" => "启动位置",
+        _ => "其他",
+    }
+}
+
+// 加载设置时的迁移和修复说明，在窗口右上角短暂显示
+pub struct SettingsNotice {
+    messages: Vec<String>,
+    created: Instant,
+}
+
+impl SettingsNotice {
+    pub fn new(messages: Vec<String>) -> Self {
+        Self { messages, created: Instant::now() }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if self.messages.is_empty() {
+            return;
+        }
+        if self.created.elapsed() >= NOTICE_LIFETIME {
+            self.messages.clear();
+            return;
+        }
+        ctx.request_repaint_after(Duration::from_secs(1));
+        egui::Area::new(egui::Id::new("settings_notice"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 36.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(320.0);
+                    ui.horizontal(|ui| {
+                        ui.colored_label(ui.visuals().warn_fg_color, "⚠ 设置");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("✕").clicked() {
+                                self.messages.clear();
+                            }
+                        });
+                    });
+                    for message in &self.messages {
+                        ui.label(message);
+                    }
+                });
+            });
+    }
+}
+
+// 设置对话框
+pub struct SettingsDialog {
+    show_dialog: bool,
+    draft: AppSettings, // 编辑中的设置，点击确定后才生效
+    gesture_recorder: GestureRecorder,
+}
+
+impl SettingsDialog {
+    pub fn new() -> Self {
+        Self {
+            show_dialog: false,
+            draft: AppSettings::default(),
+            gesture_recorder: GestureRecorder::default(),
+        }
+    }
+
+    // 打开设置对话框，以当前设置为初始值
+    pub fn open(&mut self, current: &AppSettings) {
+        self.draft = current.clone();
+        self.show_dialog = true;
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show_dialog
+    }
+
+    // 显示设置对话框，点击确定时写回 settings 并返回 true
+    pub fn show_settings_dialog(&mut self, ctx: &egui::Context, settings: &mut AppSettings) -> bool {
+        let mut open = true;
+        let mut applied = false;
+
+        egui::Window::new("选项")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_size(egui::Vec2::new(560.0, 560.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.heading("行颜色规则");
+                ui.checkbox(&mut self.draft.color_rules_enabled, "在详细信息视图中按规则为行着色");
+                ui.label("同时匹配多条规则时，文字颜色取第一条匹配的规则。");
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    let mut remove_index = None;
+                    for (index, rule) in self.draft.color_rules.iter_mut().enumerate() {
+                        ui.push_id(index, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut rule.enabled, "");
+                                ui.add(egui::TextEdit::singleline(&mut rule.name).desired_width(90.0));
+                                show_matcher_editor(ui, &mut rule.matcher);
+                                ui.checkbox(&mut rule.color_enabled, "颜色");
+                                ui.color_edit_button_srgb(&mut rule.color);
+                                ui.checkbox(&mut rule.bold, "加粗");
+                                if ui.small_button("删除").clicked() {
+                                    remove_index = Some(index);
+                                }
+                            });
+                        });
+                    }
+                    if let Some(index) = remove_index {
+                        self.draft.color_rules.remove(index);
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("添加规则").clicked() {
+                        self.draft.color_rules.push(ColorRule {
+                            enabled: true,
+                            name: "新规则".to_string(),
+                            matcher: RuleMatcher::Extensions(String::new()),
+                            color_enabled: true,
+                            color: [30, 120, 220],
+                            bold: false,
+                        });
+                    }
+                    if ui.button("恢复默认规则").clicked() {
+                        self.draft.color_rules = default_color_rules();
+                    }
+                });
+
+                ui.separator();
+                ui.heading("启动");
+                ui.horizontal(|ui| {
+                    ui.label("启动时打开:");
+                    for location in StartupLocation::ALL {
+                        ui.radio_value(&mut self.draft.startup_location, location, location.label());
+                    }
+                });
+                ui.add_enabled_ui(self.draft.startup_location == StartupLocation::Custom, |ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.draft.startup_custom_path).hint_text("文件夹路径，可以使用 ~、$变量和路径别名").desired_width(300.0));
+                });
+
+                ui.separator();
+                ui.heading("鼠标");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.draft.click_open_mode, ClickOpenMode::DoubleClick, "单击选择，双击打开");
+                    ui.radio_value(&mut self.draft.click_open_mode, ClickOpenMode::SingleClick, "单击打开（Ctrl+单击选择）");
+                });
+                self.gesture_recorder.show(ui, &mut self.draft.mouse_gestures);
+
+                ui.separator();
+                ui.heading("排序");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.draft.folder_placement, FolderPlacement::First, "文件夹在前");
+                    ui.radio_value(&mut self.draft.folder_placement, FolderPlacement::Last, "文件夹在后");
+                    ui.radio_value(&mut self.draft.folder_placement, FolderPlacement::Mixed, "与文件混合");
+                });
+
+                ui.separator();
+                ui.heading("图标");
+                ui.checkbox(&mut self.draft.custom_folder_icons, "显示文件夹自定义图标（.directory 中的 Icon= 或 folder.jpg、cover.jpg）");
+                ui.checkbox(&mut self.draft.two_line_names, "图标视图中文件名最多显示两行（否则在中间省略）");
+
+                ui.separator();
+                ui.heading("截图收件箱");
+                ui.checkbox(&mut self.draft.screenshot_inbox, "出现新截图时在右下角提示，可直接重命名、移动或复制到剪贴板");
+                ui.add_enabled_ui(self.draft.screenshot_inbox, |ui| {
+                    egui::Grid::new("screenshot_inbox_grid").num_columns(2).show(ui, |ui| {
+                        ui.label("截图文件夹");
+                        let auto = screenshot_inbox::screenshot_folder("").map(|p| p.display().to_string()).unwrap_or_default();
+                        ui.add(egui::TextEdit::singleline(&mut self.draft.screenshot_folder).hint_text(auto).desired_width(300.0));
+                        ui.end_row();
+                        ui.label("项目文件夹");
+                        ui.add(egui::TextEdit::singleline(&mut self.draft.screenshot_project_folder).hint_text("移到项目文件夹的目标").desired_width(300.0));
+                        ui.end_row();
+                    });
+                });
+
+                ui.separator();
+                ui.heading("路径别名");
+                ui.label("在地址栏或 Ctrl+L 中输入别名（可接 /子路径）即可跳转，路径中可以使用 ~ 和 $变量。");
+                let aliases = &mut self.draft.path_aliases;
+                let mut remove_index = None;
+                egui::Grid::new("path_aliases_grid").num_columns(4).striped(true).show(ui, |ui| {
+                    ui.strong("别名");
+                    ui.strong("路径");
+                    ui.label("");
+                    ui.label("");
+                    ui.end_row();
+                    for (index, alias) in aliases.iter_mut().enumerate() {
+                        ui.push_id(("alias", index), |ui| {
+                            ui.add(egui::TextEdit::singleline(&mut alias.name).hint_text("proj").desired_width(90.0));
+                        });
+                        ui.push_id(("alias_path", index), |ui| {
+                            ui.add(egui::TextEdit::singleline(&mut alias.path).hint_text("~/work/projects").desired_width(240.0));
+                        });
+                        match quick_jump::expand_path(&alias.path, &[]) {
+                            Some(path) if path.is_dir() => ui.label("✔").on_hover_text(path.display().to_string()),
+                            _ => ui.colored_label(ui.visuals().warn_fg_color, "⚠").on_hover_text("文件夹不存在"),
+                        };
+                        if ui.small_button("删除").clicked() {
+                            remove_index = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(index) = remove_index {
+                    aliases.remove(index);
+                }
+                if ui.button("添加别名").clicked() {
+                    aliases.push(PathAlias { name: String::new(), path: String::new() });
+                }
+
+                ui.separator();
+                ui.heading("操作确认");
+                let confirmations = &mut self.draft.confirmations;
+                ui.checkbox(&mut confirmations.trash_delete, "删除到回收站前确认");
+                ui.checkbox(&mut confirmations.permanent_delete, "永久删除前确认");
+                ui.checkbox(&mut confirmations.overwrite, "覆盖同名项目前确认");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut confirmations.large_move, "一次移动超过");
+                    ui.add(egui::DragValue::new(&mut confirmations.large_move_threshold).range(1..=10000));
+                    ui.label("个项目前确认");
+                });
+                ui.checkbox(&mut confirmations.system_paths, "操作系统目录中的文件前确认");
+                ui.checkbox(&mut confirmations.companion_files, "带有字幕等配套文件时询问是否一并处理");
+
+                ui.separator();
+                ui.heading("受保护的路径");
+                ui.label("删除、移走或覆盖这些路径及其子项时阻止操作，或要求输入文件夹名称确认。相对路径从主目录算起。");
+                let mut remove_index = None;
+                for (index, protected) in self.draft.confirmations.protected_paths.iter_mut().enumerate() {
+                    ui.push_id(("protected", index), |ui| {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut protected.path).hint_text("/etc").desired_width(240.0));
+                            egui::ComboBox::from_id_salt("protection_mode")
+                                .selected_text(protected.mode.label())
+                                .show_ui(ui, |ui| {
+                                    for mode in [ProtectionMode::Block, ProtectionMode::TypeToConfirm] {
+                                        ui.selectable_value(&mut protected.mode, mode, mode.label());
+                                    }
+                                });
+                            if ui.small_button("删除").clicked() {
+                                remove_index = Some(index);
+                            }
+                        });
+                    });
+                }
+                if let Some(index) = remove_index {
+                    self.draft.confirmations.protected_paths.remove(index);
+                }
+                if ui.button("添加路径").clicked() {
+                    self.draft.confirmations.protected_paths.push(ProtectedPath { path: String::new(), mode: ProtectionMode::TypeToConfirm });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("取消").clicked() {
+                            self.show_dialog = false;
+                        }
+                        if ui.button("确定").clicked() {
+                            *settings = self.draft.clone();
+                            applied = true;
+                            self.show_dialog = false;
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            self.show_dialog = false;
+        }
+
+        applied
+    }
+}
+
+// 规则条件的编辑控件：类型下拉框 + 参数
+fn show_matcher_editor(ui: &mut egui::Ui, matcher: &mut RuleMatcher) {
+    egui::ComboBox::from_id_salt("matcher_kind")
+        .selected_text(matcher.label())
+        .width(90.0)
+        .show_ui(ui, |ui| {
+            for option in [
+                RuleMatcher::Extensions(String::new()),
+                RuleMatcher::Executable,
+                RuleMatcher::Directory,
+                RuleMatcher::ModifiedWithinDays(0),
+            ] {
+                let selected = std::mem::discriminant(matcher) == std::mem::discriminant(&option);
+                if ui.selectable_label(selected, option.label()).clicked() && !selected {
+                    *matcher = option;
+                }
+            }
+        });
+
+    match matcher {
+        RuleMatcher::Extensions(list) => {
+            ui.add(egui::TextEdit::singleline(list).hint_text("zip,7z").desired_width(100.0));
+        }
+        RuleMatcher::ModifiedWithinDays(days) => {
+            ui.add(egui::DragValue::new(days).range(0..=365).suffix(" 天内"));
+        }
+        RuleMatcher::Executable | RuleMatcher::Directory => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_migrates_and_repairs() {
+        // 没有版本号的旧文件：迁移到当前版本，保留有效的项
+        let (settings, notices, changed) = AppSettings::from_json(r#"{"two_line_names": true, "unknown": 1}"#).unwrap();
+        assert!(settings.two_line_names && changed && notices.is_empty());
+        assert_eq!(settings.schema_version, SCHEMA_VERSION);
+
+        // 类型错误和超出范围的项恢复默认值，其余项不受影响
+        let (settings, notices, changed) = AppSettings::from_json(r#"{"schema_version": 2, "click_open_mode": "Triple", "directory_pane_ratio": 3.0, "custom_folder_icons": false}"#).unwrap();
+        let defaults = AppSettings::default();
+        assert_eq!(settings.click_open_mode, defaults.click_open_mode);
+        assert_eq!(settings.directory_pane_ratio, defaults.directory_pane_ratio);
+        assert!(!settings.custom_folder_icons && changed);
+        assert_eq!(notices, vec!["以下设置无效，已恢复默认值: 单击/双击打开、目录栏".to_string()]);
+
+        // 当前版本的有效文件不需要写回
+        let text = serde_json::to_string(&defaults).unwrap();
+        assert_eq!(AppSettings::from_json(&text), Some((defaults, Vec::new(), false)));
+        assert_eq!(AppSettings::from_json("not json"), None);
+    }
+}
@@ -0,0 +1,221 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use super::app_event::{AppEvent, EventQueue};
+use super::drive_bar::Drive;
+use super::quick_jump::{self, PathAlias};
+
+const MAX_RECENT: usize = 12;
+
+// 启动时打开的位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StartupLocation {
+    Home,         // 主目录
+    LastLocation, // 上次退出时的文件夹
+    Custom,       // 指定的文件夹
+    StartPage,    // 起始页
+}
+
+impl StartupLocation {
+    pub const ALL: [StartupLocation; 4] = [StartupLocation::Home, StartupLocation::LastLocation, StartupLocation::Custom, StartupLocation::StartPage];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StartupLocation::Home => "主目录",
+            StartupLocation::LastLocation => "上次的位置",
+            StartupLocation::Custom => "指定文件夹",
+            StartupLocation::StartPage => "起始页",
+        }
+    }
+}
+
+// 固定的文件夹和最近访问的位置，保存在配置目录的 start_page.json 中
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StartPageData {
+    pinned: Vec<PathBuf>,
+    recent: Vec<PathBuf>, // 最近的在前
+}
+
+// 起始页：固定的文件夹、最近访问的位置和驱动器，显示在内容框中
+pub struct StartPage {
+    file_path: Option<PathBuf>,
+    data: StartPageData,
+    show: bool,
+    last_visited: Option<PathBuf>,
+}
+
+impl StartPage {
+    pub fn load() -> Self {
+        let file_path = dirs::config_dir().map(|dir| dir.join("file-explorer").join("start_page.json"));
+        let data = file_path
+            .as_ref()
+            .and_then(|path| crate::utils::load_json(path))
+            .unwrap_or_default();
+        Self { file_path, data, show: false, last_visited: None }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if let Err(e) = crate::utils::save_json(path, &self.data) {
+            eprintln!("保存起始页失败: {}", e);
+        }
+    }
+
+    // 按设置决定启动时的文件夹；文件夹不存在时使用主目录。选择起始页时同时显示起始页
+    pub fn startup_path(&mut self, location: StartupLocation, custom: &str, aliases: &[PathAlias]) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+        let path = match location {
+            StartupLocation::Home => None,
+            StartupLocation::LastLocation => self.data.recent.first().cloned(),
+            StartupLocation::Custom => quick_jump::expand_path(custom, aliases),
+            StartupLocation::StartPage => {
+                self.show = true;
+                None
+            }
+        };
+        let path = path.filter(|p| p.is_dir()).unwrap_or(home);
+        self.last_visited = Some(path.clone());
+        path
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show
+    }
+
+    pub fn open(&mut self) {
+        self.show = true;
+    }
+
+    // 记录内容框进入的文件夹：加入最近访问并关闭起始页；与上次相同时不做任何事
+    pub fn visit(&mut self, path: &Path) {
+        if self.last_visited.as_deref() == Some(path) {
+            return;
+        }
+        self.last_visited = Some(path.to_path_buf());
+        self.show = false;
+        self.data.recent.retain(|p| p != path);
+        self.data.recent.insert(0, path.to_path_buf());
+        self.data.recent.truncate(MAX_RECENT);
+        self.save();
+    }
+
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        self.data.pinned.iter().any(|p| p == path)
+    }
+
+    pub fn toggle_pin(&mut self, path: &Path) {
+        if self.is_pinned(path) {
+            self.data.pinned.retain(|p| p != path);
+        } else {
+            self.data.pinned.push(path.to_path_buf());
+        }
+        self.save();
+    }
+
+    fn folder_name(path: &Path) -> String {
+        path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string())
+    }
+
+    // 一组文件夹按钮，右键可固定或取消固定；返回点击的文件夹
+    fn show_folders(&self, ui: &mut egui::Ui, id: &str, folders: &[PathBuf], icon: &str, toggled: &mut Option<PathBuf>) -> Option<PathBuf> {
+        let mut clicked = None;
+        ui.horizontal_wrapped(|ui| {
+            for path in folders {
+                let exists = path.is_dir();
+                let text = egui::RichText::new(format!("{} {}", icon, Self::folder_name(path)));
+                let button = egui::Button::new(if exists { text } else { text.weak() }).min_size(egui::vec2(140.0, 32.0));
+                let response = ui.add(button).on_hover_text(path.display().to_string());
+                if response.clicked() && exists {
+                    clicked = Some(path.clone());
+                }
+                response.context_menu(|ui| {
+                    let label = if self.is_pinned(path) { "取消固定" } else { "固定到起始页" };
+                    if ui.button(label).clicked() {
+                        *toggled = Some(path.clone());
+                        ui.close_menu();
+                    }
+                });
+            }
+            if folders.is_empty() {
+                ui.label(egui::RichText::new(match id {
+                    "pinned" => "右键最近访问的位置可以固定到这里",
+                    _ => "还没有访问记录",
+                }).weak());
+            }
+        });
+        clicked
+    }
+
+    // 点击文件夹或驱动器后关闭起始页，即使与当前文件夹相同
+    pub fn show(&mut self, ui: &mut egui::Ui, current_path: &Path, drives: &[Drive], events: &mut EventQueue) {
+        let mut toggled = None;
+        let mut clicked = None;
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("起始页");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("返回文件列表").clicked() {
+                        self.show = false;
+                    }
+                    let label = if self.is_pinned(current_path) { "取消固定当前文件夹" } else { "固定当前文件夹" };
+                    if ui.button(label).clicked() {
+                        toggled = Some(current_path.to_path_buf());
+                    }
+                });
+            });
+            ui.separator();
+
+            ui.label(egui::RichText::new("固定的文件夹").strong());
+            clicked = clicked.take().or(self.show_folders(ui, "pinned", &self.data.pinned, "📌", &mut toggled));
+            ui.add_space(12.0);
+
+            ui.label(egui::RichText::new("最近访问").strong());
+            clicked = clicked.take().or(self.show_folders(ui, "recent", &self.data.recent, "🕒", &mut toggled));
+            ui.add_space(12.0);
+
+            ui.label(egui::RichText::new("驱动器").strong());
+            ui.horizontal_wrapped(|ui| {
+                for drive in drives {
+                    let button = egui::Button::new(format!("💽 {}", drive.name)).min_size(egui::vec2(140.0, 32.0));
+                    if ui.add(button).on_hover_text(drive.path.display().to_string()).clicked() {
+                        events.emit(AppEvent::SwitchWorkspace(drive.path.clone()));
+                        self.show = false;
+                    }
+                }
+            });
+        });
+        if let Some(path) = toggled {
+            self.toggle_pin(&path);
+        }
+        if let Some(path) = clicked {
+            events.emit(AppEvent::Navigate(path));
+            self.show = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_locations() {
+        let mut page = StartPage { file_path: None, data: StartPageData::default(), show: true, last_visited: None };
+        for index in 0..MAX_RECENT + 3 {
+            page.visit(Path::new(&format!("/tmp/{}", index)));
+        }
+        page.visit(Path::new("/tmp/3"));
+        assert!(!page.is_showing());
+        assert_eq!(page.data.recent.len(), MAX_RECENT);
+        assert_eq!(page.data.recent[0], PathBuf::from("/tmp/3"));
+        assert_eq!(page.data.recent.iter().filter(|p| p.as_path() == Path::new("/tmp/3")).count(), 1);
+
+        page.toggle_pin(Path::new("/tmp/a"));
+        assert!(page.is_pinned(Path::new("/tmp/a")));
+        page.toggle_pin(Path::new("/tmp/a"));
+        assert!(!page.is_pinned(Path::new("/tmp/a")));
+    }
+}
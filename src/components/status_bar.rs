@@ -0,0 +1,72 @@
+use eframe::egui;
+use crate::utils;
+use super::file_operations::OperationHandle;
+use super::locale_format;
+
+// 状态栏显示的列表信息，由 FileList 提供
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListSummary {
+    pub total: usize,                // 目录中的全部条目
+    pub shown: usize,                // 筛选后显示的条目
+    pub selected: usize,
+    pub selected_size: u64,          // 选中条目的总大小（文件夹按已算出的大小计入）
+    pub selected_size_partial: bool, // 有选中的文件夹尚未算出大小
+    pub filter: Option<String>,      // 当前筛选条件的描述
+}
+
+// 底部状态栏：条目数、选中项数量和大小、筛选状态、最近的操作错误、当前磁盘可用空间和后台文件操作的进度
+// 点击错误旁的关闭按钮时清除 error；点击进度旁的详情按钮时返回 true
+pub fn show_status_bar(ui: &mut egui::Ui, summary: &ListSummary, free_space: Option<u64>, operations: &mut [OperationHandle], error: &mut Option<String>) -> bool {
+    let format = locale_format::current();
+    let mut show_details = false;
+    ui.horizontal(|ui| {
+        ui.label(format!("{} 个项目", format.count(summary.total as u64)));
+
+        if summary.selected > 0 {
+            ui.separator();
+            let size = utils::get_file_size_str(summary.selected_size);
+            let suffix = if summary.selected_size_partial { "（不含未计算的文件夹）" } else { "" };
+            ui.label(format!("已选择 {} 个项目，{}{}", format.count(summary.selected as u64), size, suffix));
+        }
+
+        if let Some(filter) = &summary.filter {
+            ui.separator();
+            ui.label(format!("{}，显示 {} 项", filter, format.count(summary.shown as u64)));
+        }
+
+        if let Some(message) = error.as_deref() {
+            ui.separator();
+            ui.colored_label(ui.visuals().error_fg_color, message);
+            if ui.small_button("✖").on_hover_text("关闭").clicked() {
+                *error = None;
+            }
+        }
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if let Some(free) = free_space {
+                ui.label(format!("可用空间 {}", utils::get_file_size_str(free)));
+            }
+            // 后台进行中的文件操作：进度和取消按钮
+            if !operations.is_empty() {
+                ui.separator();
+                if ui.small_button("详情").on_hover_text("显示速度和剩余时间").clicked() {
+                    show_details = true;
+                }
+            }
+            for handle in operations.iter_mut().rev() {
+                ui.separator();
+                if ui.small_button("✖").on_hover_text("取消").clicked() {
+                    handle.cancel_token().cancel();
+                }
+                let label = handle.kind().label();
+                let progress = handle.progress();
+                let text = format!("{} {}/{}", label, progress.files_done, progress.files_total);
+                let bar = ui.add(egui::ProgressBar::new(progress.fraction()).desired_width(120.0).text(text));
+                if let Some(current) = &progress.current {
+                    bar.on_hover_text(current.display().to_string());
+                }
+            }
+        });
+    });
+    show_details
+}
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+// 存储设备类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeviceClass {
+    Rotational, // 机械硬盘（HDD）
+    SolidState, // 固态硬盘（SSD/NVMe）
+    Unknown,    // 无法识别（网络文件系统、tmpfs 等）
+}
+
+// 按设备类型调整的复制参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CopyTuning {
+    pub buffer_size: usize, // 单次读写的缓冲区大小
+    pub parallelism: usize, // 同一目录内同时复制的文件数
+}
+
+impl DeviceClass {
+    // HDD 使用大缓冲区、顺序复制以减少磁头寻道；SSD 使用中等缓冲区并行复制
+    pub fn copy_tuning(self) -> CopyTuning {
+        match self {
+            DeviceClass::Rotational => CopyTuning { buffer_size: 1024 * 1024, parallelism: 1 },
+            DeviceClass::SolidState => CopyTuning { buffer_size: 256 * 1024, parallelism: 4 },
+            DeviceClass::Unknown => CopyTuning { buffer_size: 128 * 1024, parallelism: 1 },
+        }
+    }
+}
+
+// 检测路径所在的块设备是否为机械硬盘（读取 /sys/block/*/queue/rotational）
+#[cfg(target_os = "linux")]
+pub fn detect_device_class(path: &Path) -> DeviceClass {
+    use std::os::unix::fs::MetadataExt;
+
+    // 目标路径可能尚不存在，向上查找第一个存在的祖先目录
+    let Some(metadata) = path.ancestors().find_map(|p| std::fs::metadata(p).ok()) else {
+        return DeviceClass::Unknown;
+    };
+    let dev = metadata.dev();
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+
+    // /sys/dev/block/主:次 指向设备目录；分区本身没有 queue，需要查看其所属磁盘
+    let Ok(device_dir) = std::fs::canonicalize(format!("/sys/dev/block/{}:{}", major, minor)) else {
+        return DeviceClass::Unknown;
+    };
+    let rotational = [device_dir.as_path(), device_dir.parent().unwrap_or(&device_dir)]
+        .iter()
+        .find_map(|dir| std::fs::read_to_string(dir.join("queue/rotational")).ok());
+
+    match rotational.as_deref().map(str::trim) {
+        Some("1") => DeviceClass::Rotational,
+        Some("0") => DeviceClass::SolidState,
+        _ => DeviceClass::Unknown,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detect_device_class(_path: &Path) -> DeviceClass {
+    DeviceClass::Unknown
+}
+
+// 一次复制/移动操作的参数：源或目标任一在机械硬盘上时按机械硬盘处理
+pub fn tuning_for_operation(sources: &[std::path::PathBuf], target_dir: &Path) -> CopyTuning {
+    let mut classes = sources.iter().map(|p| detect_device_class(p)).collect::<Vec<_>>();
+    classes.push(detect_device_class(target_dir));
+
+    let class = if classes.contains(&DeviceClass::Rotational) {
+        DeviceClass::Rotational
+    } else if classes.iter().all(|c| *c == DeviceClass::SolidState) {
+        DeviceClass::SolidState
+    } else {
+        DeviceClass::Unknown
+    };
+    class.copy_tuning()
+}
@@ -0,0 +1,202 @@
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use crossbeam_channel::{self, Receiver};
+use super::trash;
+
+// 与其它文件管理器交换文件的剪贴板格式。wl-copy 和 xclip 一次只能提供一种格式：
+// 复制使用 Nautilus 和 Dolphin 都支持的 text/uri-list，剪切使用 GNOME 系（Nautilus、Nemo、Caja）识别的格式
+const GNOME_FILES: &str = "x-special/gnome-copied-files";
+const URI_LIST: &str = "text/uri-list";
+// 读取时依次尝试的格式，纯文本中每行一个路径或 URI
+const READ_TYPES: [&str; 5] = [GNOME_FILES, URI_LIST, "text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
+// 剪贴板所有者无响应时放弃读取
+const READ_TIMEOUT: Duration = Duration::from_millis(800);
+
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+// 系统中是否装有读取剪贴板的工具（wl-paste 或 xclip），只检查一次
+pub fn is_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        let tool = if is_wayland() { "wl-paste" } else { "xclip" };
+        std::env::var_os("PATH").is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(tool).is_file()))
+    })
+}
+
+// 把数据以指定格式写入系统剪贴板：Wayland 使用 wl-copy，X11 使用 xclip
+pub fn write(mime: &str, data: &[u8]) -> Result<(), String> {
+    let mut commands = Vec::new();
+    if is_wayland() {
+        let mut wl_copy = Command::new("wl-copy");
+        wl_copy.args(["--type", mime]);
+        commands.push(wl_copy);
+    }
+    let mut xclip = Command::new("xclip");
+    xclip.args(["-selection", "clipboard", "-t", mime, "-i"]);
+    commands.push(xclip);
+
+    for mut command in commands {
+        let Ok(mut child) = command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn() else {
+            continue;
+        };
+        // 两个工具都会在后台保留剪贴板内容，写完后关闭输入即可
+        let written = child.stdin.take().is_some_and(|mut stdin| stdin.write_all(data).is_ok());
+        if written && child.wait().is_ok_and(|s| s.success()) {
+            return Ok(());
+        }
+    }
+    Err("需要安装 wl-copy 或 xclip".to_string())
+}
+
+// 运行读取命令，超时后结束进程
+fn output_with_timeout(mut command: Command) -> Option<Vec<u8>> {
+    let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn().ok()?;
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut data = Vec::new();
+        stdout.read_to_end(&mut data).map(|_| data)
+    });
+    let deadline = Instant::now() + READ_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return reader.join().ok()?.ok().filter(|_| status.success()),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+// 读取剪贴板中指定格式的内容；mime 为 None 时列出可用的格式
+fn read(mime: Option<&str>) -> Option<Vec<u8>> {
+    let command = if is_wayland() {
+        let mut wl_paste = Command::new("wl-paste");
+        match mime {
+            Some(mime) => wl_paste.args(["--no-newline", "--type", mime]),
+            None => wl_paste.arg("--list-types"),
+        };
+        wl_paste
+    } else {
+        let mut xclip = Command::new("xclip");
+        xclip.args(["-selection", "clipboard", "-o", "-t", mime.unwrap_or("TARGETS")]);
+        xclip
+    };
+    output_with_timeout(command)
+}
+
+fn file_uri(path: &Path) -> String {
+    format!("file://{}", trash::percent_encode(path))
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn percent_decode(text: &str) -> Option<PathBuf> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    Some(path_from_bytes(decoded))
+}
+
+// 解析剪贴板中的文件列表，返回路径和是否为剪切：
+// 支持 gnome-copied-files（首行为 copy 或 cut）、uri-list、Nautilus 的纯文本格式和每行一个绝对路径
+fn parse_file_list(text: &str) -> Option<(Vec<PathBuf>, bool)> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')).peekable();
+    if lines.peek() == Some(&"x-special/nautilus-clipboard") {
+        lines.next();
+    }
+    let cut = match lines.peek() {
+        Some(&"cut") => true,
+        Some(&"copy") => false,
+        _ => return parse_paths(lines).map(|paths| (paths, false)),
+    };
+    lines.next();
+    parse_paths(lines).map(|paths| (paths, cut))
+}
+
+// 每一行都必须是本地文件的 URI 或绝对路径
+fn parse_paths<'a>(lines: impl Iterator<Item = &'a str>) -> Option<Vec<PathBuf>> {
+    let paths = lines
+        .map(|line| match line.strip_prefix("file://") {
+            // 跳过主机名部分，如 file://localhost/home
+            Some(rest) => percent_decode(&rest[rest.find('/')?..]),
+            None => line.starts_with('/').then(|| PathBuf::from(line)),
+        })
+        .collect::<Option<Vec<PathBuf>>>()?;
+    (!paths.is_empty()).then_some(paths)
+}
+
+// 把复制或剪切的文件放到系统剪贴板，供其它文件管理器粘贴
+pub fn write_files(paths: &[PathBuf], cut: bool) -> Result<(), String> {
+    let uris: Vec<String> = paths.iter().map(|path| file_uri(path)).collect();
+    if cut {
+        write(GNOME_FILES, format!("cut\n{}", uris.join("\n")).as_bytes())
+    } else {
+        write(URI_LIST, format!("{}\r\n", uris.join("\r\n")).as_bytes())
+    }
+}
+
+// 剪贴板中的文件和是否为剪切
+pub type ClipboardFiles = Option<(Vec<PathBuf>, bool)>;
+
+// 读取其它程序复制的文件，剪贴板中没有文件时返回 None
+pub fn read_files() -> ClipboardFiles {
+    let types = String::from_utf8(read(None)?).ok()?;
+    let available: Vec<&str> = types.lines().map(str::trim).collect();
+    READ_TYPES.iter()
+        .filter(|mime| available.contains(mime))
+        .find_map(|mime| read(Some(mime)).and_then(|data| parse_file_list(&String::from_utf8_lossy(&data))))
+}
+
+// 在后台线程读取剪贴板中的文件，剪贴板所有者响应慢时不阻塞界面
+pub fn read_files_async() -> Receiver<ClipboardFiles> {
+    let (sender, receiver) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let _ = sender.send(read_files());
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_list() {
+        let path = PathBuf::from("/home/user/a b/文件.txt");
+        assert_eq!(parse_file_list(&format!("cut\n{}", file_uri(&path))), Some((vec![path.clone()], true)));
+        assert_eq!(parse_file_list(&format!("# 注释\r\n{}\r\nfile://localhost/tmp\r\n", file_uri(&path))), Some((vec![path.clone(), PathBuf::from("/tmp")], false)));
+        assert_eq!(parse_file_list("x-special/nautilus-clipboard\ncopy\nfile:///tmp/x\n"), Some((vec![PathBuf::from("/tmp/x")], false)));
+        assert_eq!(parse_file_list("/tmp/a\n/tmp/b"), Some((vec![PathBuf::from("/tmp/a"), PathBuf::from("/tmp/b")], false)));
+        // 普通文本不是文件列表
+        assert_eq!(parse_file_list("hello world"), None);
+        assert_eq!(parse_file_list("/tmp/a\nhello"), None);
+        assert_eq!(parse_file_list(""), None);
+    }
+}
@@ -0,0 +1,144 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+// 文件标签颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TagColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+}
+
+impl TagColor {
+    pub const ALL: [TagColor; 7] = [
+        TagColor::Red,
+        TagColor::Orange,
+        TagColor::Yellow,
+        TagColor::Green,
+        TagColor::Blue,
+        TagColor::Purple,
+        TagColor::Gray,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TagColor::Red => "红色",
+            TagColor::Orange => "橙色",
+            TagColor::Yellow => "黄色",
+            TagColor::Green => "绿色",
+            TagColor::Blue => "蓝色",
+            TagColor::Purple => "紫色",
+            TagColor::Gray => "灰色",
+        }
+    }
+
+    pub fn color32(self) -> egui::Color32 {
+        match self {
+            TagColor::Red => egui::Color32::from_rgb(230, 70, 70),
+            TagColor::Orange => egui::Color32::from_rgb(240, 150, 50),
+            TagColor::Yellow => egui::Color32::from_rgb(235, 200, 40),
+            TagColor::Green => egui::Color32::from_rgb(70, 180, 80),
+            TagColor::Blue => egui::Color32::from_rgb(60, 130, 230),
+            TagColor::Purple => egui::Color32::from_rgb(160, 90, 210),
+            TagColor::Gray => egui::Color32::from_rgb(140, 140, 140),
+        }
+    }
+}
+
+// 文件标签数据库，存放在 ~/.local/share/file-explorer/tags.json
+pub struct TagStore {
+    file_path: Option<PathBuf>,
+    entries: HashMap<String, Vec<TagColor>>,
+    revision: u64, // 每次修改递增，供列表判断是否需要重新读取标签
+}
+
+impl TagStore {
+    // 从数据文件加载，文件不存在或损坏时从空记录开始
+    pub fn load() -> Self {
+        let file_path = dirs::data_dir().map(|dir| dir.join("file-explorer").join("tags.json"));
+        let entries = file_path
+            .as_ref()
+            .and_then(|path| crate::utils::load_json(path))
+            .unwrap_or_default();
+
+        Self {
+            file_path,
+            entries,
+            revision: 0,
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn tags(&self, path: &Path) -> &[TagColor] {
+        self.entries.get(path.to_string_lossy().as_ref()).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // 添加或移除一个标签
+    pub fn toggle(&mut self, path: &Path, tag: TagColor) {
+        let key = path.to_string_lossy().to_string();
+        let tags = self.entries.entry(key.clone()).or_default();
+        if let Some(pos) = tags.iter().position(|t| *t == tag) {
+            tags.remove(pos);
+        } else {
+            tags.push(tag);
+            tags.sort_by_key(|t| TagColor::ALL.iter().position(|c| c == t));
+        }
+        if tags.is_empty() {
+            self.entries.remove(&key);
+        }
+        self.changed();
+    }
+
+    pub fn clear(&mut self, path: &Path) {
+        if self.entries.remove(path.to_string_lossy().as_ref()).is_some() {
+            self.changed();
+        }
+    }
+
+    // 文件重命名后迁移其标签
+    pub fn rename_path(&mut self, old: &Path, new: &Path) {
+        if let Some(tags) = self.entries.remove(old.to_string_lossy().as_ref()) {
+            self.entries.insert(new.to_string_lossy().to_string(), tags);
+            self.changed();
+        }
+    }
+
+    fn changed(&mut self) {
+        self.revision += 1;
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if let Err(e) = crate::utils::save_json(path, &self.entries) {
+            eprintln!("保存文件标签失败: {}", e);
+        }
+    }
+}
+
+// 在 pos 处（左侧垂直居中）绘制一组互相叠压的标签圆点，返回占用的宽度
+pub fn draw_tag_dots(painter: &egui::Painter, pos: egui::Pos2, tags: &[TagColor]) -> f32 {
+    const RADIUS: f32 = 4.0;
+    const STEP: f32 = 5.0;
+    let outline = egui::Stroke::new(1.0, painter.ctx().style().visuals.extreme_bg_color);
+    for (index, tag) in tags.iter().enumerate() {
+        let center = egui::pos2(pos.x + RADIUS + index as f32 * STEP, pos.y);
+        painter.circle(center, RADIUS, tag.color32(), outline);
+    }
+    if tags.is_empty() {
+        0.0
+    } else {
+        RADIUS * 2.0 + (tags.len() - 1) as f32 * STEP
+    }
+}
@@ -0,0 +1,110 @@
+use eframe::egui;
+
+const ELLIPSIS: char = '…';
+
+// 换行时优先在这些字符之后断开
+fn is_break_char(c: char) -> bool {
+    matches!(c, ' ' | '-' | '_' | '.' | ',' | '+')
+}
+
+// 按字符宽度在中间省略，保留开头和结尾（通常是扩展名）
+fn elide_middle_by(text: &str, max_width: f32, width: impl Fn(char) -> f32) -> String {
+    let chars: Vec<(char, f32)> = text.chars().map(|c| (c, width(c))).collect();
+    let total: f32 = chars.iter().map(|(_, w)| w).sum();
+    if total <= max_width {
+        return text.to_string();
+    }
+    let available = max_width - width(ELLIPSIS);
+    if available <= 0.0 {
+        return ELLIPSIS.to_string();
+    }
+    // 先各分一半给开头和结尾，结尾用不完的宽度再补给开头
+    let (mut front, mut front_width) = (0, 0.0);
+    while front < chars.len() && front_width + chars[front].1 <= available / 2.0 {
+        front_width += chars[front].1;
+        front += 1;
+    }
+    let (mut back, mut back_width) = (chars.len(), 0.0);
+    while back > front && front_width + back_width + chars[back - 1].1 <= available {
+        back_width += chars[back - 1].1;
+        back -= 1;
+    }
+    while front < back && front_width + back_width + chars[front].1 <= available {
+        front_width += chars[front].1;
+        front += 1;
+    }
+    chars[..front].iter().map(|(c, _)| c)
+        .chain(std::iter::once(&ELLIPSIS))
+        .chain(chars[back..].iter().map(|(c, _)| c))
+        .collect()
+}
+
+// 按宽度折成最多 max_lines 行，最后一行放不下时在中间省略；汉字之间可以直接断开
+fn elide_lines_by(text: &str, max_width: f32, max_lines: usize, width: impl Fn(char) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut rest: &str = text;
+    while lines.len() + 1 < max_lines {
+        let mut line_width = 0.0;
+        let mut end = rest.len();
+        let mut last_break = None;
+        for (i, c) in rest.char_indices() {
+            line_width += width(c);
+            if line_width > max_width {
+                end = i;
+                break;
+            }
+            if is_break_char(c) {
+                last_break = Some(i + c.len_utf8());
+            }
+        }
+        if end == rest.len() {
+            break;
+        }
+        // 断点不要太靠前，否则第一行过短
+        if let Some(break_at) = last_break.filter(|&b| b * 2 >= end) {
+            end = break_at;
+        }
+        // 至少放一个字符，避免宽度过小时死循环
+        let end = if end == 0 { rest.chars().next().map_or(0, char::len_utf8) } else { end };
+        lines.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() || lines.is_empty() {
+        lines.push(elide_middle_by(rest, max_width, &width));
+    }
+    lines
+}
+
+// 单行显示：超出 max_width 时在中间省略，如“很长的文件…名称.txt”
+pub fn elide_middle(ctx: &egui::Context, text: &str, font_id: &egui::FontId, max_width: f32) -> String {
+    ctx.fonts(|fonts| elide_middle_by(text, max_width, |c| fonts.glyph_width(font_id, c)))
+}
+
+// 多行显示：前几行按宽度折行，最后一行放不下时在中间省略
+pub fn elide_lines(ctx: &egui::Context, text: &str, font_id: &egui::FontId, max_width: f32, max_lines: usize) -> Vec<String> {
+    ctx.fonts(|fonts| elide_lines_by(text, max_width, max_lines, |c| fonts.glyph_width(font_id, c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 模拟字体：汉字占两个单位，其他字符和省略号占一个
+    fn width(c: char) -> f32 {
+        if c.is_ascii() || c == ELLIPSIS { 1.0 } else { 2.0 }
+    }
+
+    #[test]
+    fn test_elide_middle_and_lines() {
+        assert_eq!(elide_middle_by("short.txt", 20.0, width), "short.txt");
+        assert_eq!(elide_middle_by("a-very-long-name.txt", 11.0, width), "a-ver…e.txt");
+        // 宽字符按实际宽度计算，不会比 ASCII 名称显示得更长
+        assert_eq!(elide_middle_by("非常长的中文文件名.txt", 12.0, width), "非常…名.txt");
+        assert_eq!(elide_middle_by("abc", 0.5, width), "…");
+
+        assert_eq!(elide_lines_by("short", 10.0, 2, width), vec!["short"]);
+        assert_eq!(elide_lines_by("holiday-photo-2024.jpg", 10.0, 2, width), vec!["holiday-", "phot…4.jpg"]);
+        assert_eq!(elide_lines_by("中文文件名称很长.png", 8.0, 2, width), vec!["中文文件", "名….png"]);
+        assert_eq!(elide_lines_by("", 8.0, 2, width), vec![""]);
+    }
+}
@@ -0,0 +1,132 @@
+use encoding_rs::{DecoderResult, Encoding, BIG5, GBK, SHIFT_JIS, UTF_8, WINDOWS_1252};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// 检测编码时读取的字节数
+const SAMPLE_SIZE: usize = 64 * 1024;
+
+// 按 encoding 解码时没有错误；末尾被截断的多字节字符不算错误
+fn decodes_cleanly(encoding: &'static Encoding, bytes: &[u8]) -> bool {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let Some(capacity) = decoder.max_utf8_buffer_length_without_replacement(bytes.len()) else {
+        return false;
+    };
+    let mut text = String::with_capacity(capacity);
+    let (result, _) = decoder.decode_to_string_without_replacement(bytes, &mut text, false);
+    result == DecoderResult::InputEmpty
+}
+
+// 高位字节后面紧跟高位字节的比例。中日文编码的汉字两个字节通常都是高位字节，
+// 而 Latin-1 文本中的重音字母前后多是 ASCII 字母
+fn high_pair_ratio(bytes: &[u8]) -> f32 {
+    let (mut pairs, mut singles) = (0usize, 0usize);
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] < 0x80 {
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1) {
+            Some(next) if *next >= 0x80 => pairs += 1,
+            _ => singles += 1,
+        }
+        i += 2;
+    }
+    if pairs + singles == 0 {
+        0.0
+    } else {
+        pairs as f32 / (pairs + singles) as f32
+    }
+}
+
+// 双字节字符中落在 GB2312 汉字区（首字节 B0-F7，次字节 A1-FE）的比例。
+// 简体中文的 GBK 文本几乎全部在此区内，繁体的 Big5 文本约一半的次字节在 40-7E
+fn gb2312_ratio(bytes: &[u8]) -> f32 {
+    let (mut hanzi, mut total) = (0usize, 0usize);
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        let (lead, trail) = (bytes[i], bytes[i + 1]);
+        if lead < 0x80 {
+            i += 1;
+            continue;
+        }
+        total += 1;
+        if (0xB0..=0xF7).contains(&lead) && (0xA1..=0xFE).contains(&trail) {
+            hanzi += 1;
+        }
+        i += 2;
+    }
+    if total == 0 {
+        0.0
+    } else {
+        hanzi as f32 / total as f32
+    }
+}
+
+// 检测文本的编码：BOM、UTF-8，然后依次尝试 GBK、Big5、Shift_JIS，都不合适时按 Windows-1252（Latin-1）处理
+pub fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => return UTF_8,
+        Err(e) if e.error_len().is_none() => return UTF_8, // 只是末尾的字符被截断
+        Err(_) => {}
+    }
+    if high_pair_ratio(bytes) >= 0.3 {
+        let gbk = decodes_cleanly(GBK, bytes);
+        if gbk && gb2312_ratio(bytes) >= 0.8 {
+            return GBK;
+        }
+        if decodes_cleanly(BIG5, bytes) {
+            return BIG5;
+        }
+        if gbk {
+            return GBK;
+        }
+        if decodes_cleanly(SHIFT_JIS, bytes) {
+            return SHIFT_JIS;
+        }
+    }
+    WINDOWS_1252
+}
+
+// 把文件内容按检测到的编码转换为 UTF-8，返回文本和编码名称
+pub fn decode(bytes: &[u8]) -> (String, &'static str) {
+    let encoding = detect(&bytes[..bytes.len().min(SAMPLE_SIZE)]);
+    let (text, encoding, _) = encoding.decode(bytes);
+    (text.into_owned(), encoding.name())
+}
+
+// 读取文件开头检测编码；包含 NUL 字节（UTF-16 除外）的视为二进制文件，返回 None
+pub fn detect_file(path: &Path) -> Option<&'static str> {
+    let mut sample = Vec::with_capacity(SAMPLE_SIZE);
+    File::open(path).ok()?.take(SAMPLE_SIZE as u64).read_to_end(&mut sample).ok()?;
+    if sample.is_empty() {
+        return None;
+    }
+    let encoding = detect(&sample);
+    if encoding != encoding_rs::UTF_16LE && encoding != encoding_rs::UTF_16BE && sample.contains(&0) {
+        return None;
+    }
+    Some(encoding.name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        let (gbk, _, _) = GBK.encode("这是一段简体中文的测试文本，用来检测编码。");
+        assert_eq!(decode(&gbk), ("这是一段简体中文的测试文本，用来检测编码。".to_string(), "GBK"));
+        let (big5, _, _) = BIG5.encode("這是一段繁體中文的測試文本，用來檢測編碼。");
+        assert_eq!(decode(&big5).1, "Big5");
+        let (latin1, _, _) = WINDOWS_1252.encode("Café déjà vu, über naïve.");
+        assert_eq!(decode(&latin1), ("Café déjà vu, über naïve.".to_string(), "windows-1252"));
+        assert_eq!(decode("纯 UTF-8 文本".as_bytes()).1, "UTF-8");
+        // 截断在多字节字符中间的 UTF-8 样本
+        assert_eq!(detect(&"中文".as_bytes()[..4]), UTF_8);
+    }
+}
@@ -0,0 +1,239 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crossbeam_channel::{self, Receiver};
+use super::text_encoding;
+
+// 查看器读取的文件大小上限
+const MAX_VIEWER_SIZE: u64 = 32 * 1024 * 1024;
+
+// 文本预览和查看器的显示方式，保存在配置目录的 text_view.json 中
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextViewOptions {
+    pub line_numbers: bool, // 显示行号
+    pub wrap: bool,         // 自动换行
+}
+
+impl Default for TextViewOptions {
+    fn default() -> Self {
+        Self { line_numbers: false, wrap: true }
+    }
+}
+
+impl TextViewOptions {
+    fn file_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("file-explorer").join("text_view.json"))
+    }
+
+    pub fn load() -> Self {
+        Self::file_path()
+            .and_then(|path| crate::utils::load_json(&path))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let Some(path) = Self::file_path() else {
+            return;
+        };
+        if let Err(e) = crate::utils::save_json(&path, self) {
+            eprintln!("保存文本显示方式失败: {}", e);
+        }
+    }
+
+    // 行号和自动换行开关；修改后立即保存
+    pub fn show_toggles(&mut self, ui: &mut egui::Ui) {
+        let before = *self;
+        ui.checkbox(&mut self.line_numbers, "行号");
+        ui.checkbox(&mut self.wrap, "自动换行");
+        if *self != before {
+            self.save();
+        }
+    }
+}
+
+// 一行文字占的等宽字符列数，中日韩等全角字符按两列计算
+fn display_columns(line: &str) -> usize {
+    line.chars().map(|c| if c as u32 >= 0x1100 { 2 } else { 1 }).sum()
+}
+
+// 换行显示时各行的估计起始位置（最后一项为总高度），按内容和宽度缓存在界面状态中
+#[derive(Clone)]
+struct WrapLayout {
+    key: (usize, usize, u32), // 行数组的地址、行数、每行可容纳的列数
+    offsets: Arc<Vec<f32>>,
+}
+
+fn wrap_offsets(lines: &[String], columns_per_row: usize, row_height: f32) -> Vec<f32> {
+    let mut offsets = Vec::with_capacity(lines.len() + 1);
+    let mut y = 0.0;
+    offsets.push(y);
+    for line in lines {
+        let rows = display_columns(line).div_ceil(columns_per_row.max(1)).max(1);
+        y += rows as f32 * row_height;
+        offsets.push(y);
+    }
+    offsets
+}
+
+// 逐行显示文本，可带行号；只绘制可见的行，换行时按估计的行高定位
+pub fn show_lines(ui: &mut egui::Ui, id: &str, lines: &[String], options: TextViewOptions) {
+    let number_width = lines.len().max(1).to_string().len();
+    let font = egui::TextStyle::Monospace.resolve(ui.style());
+    let row_height = ui.fonts(|fonts| fonts.row_height(&font));
+    let show_line = |ui: &mut egui::Ui, index: usize, line: &str| {
+        ui.horizontal_top(|ui| {
+            if options.line_numbers {
+                ui.label(egui::RichText::new(format!("{:>width$}", index + 1, width = number_width)).monospace().weak());
+            }
+            let label = egui::Label::new(egui::RichText::new(line).monospace());
+            ui.add(if options.wrap { label.wrap() } else { label.extend() });
+        });
+    };
+    if options.wrap {
+        let glyph_width = ui.fonts(|fonts| fonts.glyph_width(&font, '0')).max(1.0);
+        let number_columns = if options.line_numbers { number_width + 1 } else { 0 };
+        let text_width = ui.available_width() - number_columns as f32 * glyph_width - ui.spacing().item_spacing.x;
+        let columns_per_row = (text_width / glyph_width).floor().max(1.0) as usize;
+        let key = (lines.as_ptr() as usize, lines.len(), columns_per_row as u32);
+        let layout_id = ui.id().with(id).with("wrap_layout");
+        let offsets = match ui.data(|data| data.get_temp::<WrapLayout>(layout_id)) {
+            Some(layout) if layout.key == key => layout.offsets,
+            _ => {
+                let offsets = Arc::new(wrap_offsets(lines, columns_per_row, row_height));
+                ui.data_mut(|data| data.insert_temp(layout_id, WrapLayout { key, offsets: offsets.clone() }));
+                offsets
+            }
+        };
+        egui::ScrollArea::vertical().id_salt(id).auto_shrink([false, false]).show_viewport(ui, |ui, viewport| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            ui.set_min_height(offsets.last().copied().unwrap_or(0.0));
+            let first = offsets.partition_point(|&y| y <= viewport.min.y).saturating_sub(1).min(lines.len());
+            let top = ui.min_rect().top();
+            ui.add_space(offsets[first]);
+            for (index, line) in lines.iter().enumerate().skip(first) {
+                if ui.cursor().top() - top > viewport.max.y {
+                    break;
+                }
+                show_line(ui, index, line);
+            }
+        });
+    } else {
+        egui::ScrollArea::both().id_salt(id).auto_shrink([false, false]).show_rows(ui, row_height, lines.len(), |ui, range| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            for index in range {
+                show_line(ui, index, &lines[index]);
+            }
+        });
+    }
+}
+
+// 读取整个文件并按检测到的编码转换为 UTF-8，返回各行和编码名称
+fn read_lines(path: &Path) -> Result<(Vec<String>, &'static str), String> {
+    let size = path.metadata().map_err(|e| e.to_string())?.len();
+    if size > MAX_VIEWER_SIZE {
+        return Err(format!("文件超过 {}，无法在查看器中打开", crate::utils::get_file_size_str(MAX_VIEWER_SIZE)));
+    }
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let (text, encoding) = text_encoding::decode(&bytes);
+    Ok((text.lines().map(str::to_string).collect(), encoding))
+}
+
+type LoadedText = (PathBuf, Result<(Vec<String>, &'static str), String>);
+
+// 内置文本查看器：在窗口中显示整个文件，行号和换行方式与文本预览共用
+pub struct TextViewer {
+    show: bool,
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<LoadedText>>,
+    content: Option<Result<(Vec<String>, &'static str), String>>,
+    options: TextViewOptions,
+}
+
+impl TextViewer {
+    pub fn new() -> Self {
+        Self { show: false, path: None, receiver: None, content: None, options: TextViewOptions::default() }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.show
+    }
+
+    // 打开文件并在后台读取
+    pub fn open(&mut self, path: PathBuf, ctx: &egui::Context) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        let thread_ctx = ctx.clone();
+        let thread_path = path.clone();
+        std::thread::spawn(move || {
+            let content = read_lines(&thread_path);
+            let _ = sender.send((thread_path, content));
+            thread_ctx.request_repaint();
+        });
+        self.path = Some(path);
+        self.receiver = Some(receiver);
+        self.content = None;
+        self.options = TextViewOptions::load();
+        self.show = true;
+    }
+
+    pub fn show_text_viewer(&mut self, ctx: &egui::Context) {
+        if let Some((path, content)) = self.receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            if self.path.as_ref() == Some(&path) {
+                self.content = Some(content);
+                self.receiver = None;
+            }
+        }
+        let title = self.path.as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut open = self.show;
+        egui::Window::new(format!("查看: {}", title))
+            .id(egui::Id::new("text_viewer"))
+            .open(&mut open)
+            .default_size([800.0, 600.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    self.options.show_toggles(ui);
+                    if let Some(Ok((lines, encoding))) = &self.content {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(egui::RichText::new(format!("{} 行，{}", lines.len(), encoding)).weak());
+                        });
+                    }
+                });
+                ui.separator();
+                match &self.content {
+                    Some(Ok((lines, _))) => show_lines(ui, "text_viewer_lines", lines, self.options),
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("正在读取文件...");
+                        });
+                    }
+                }
+            });
+        self.show = open;
+        if !self.show {
+            self.content = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_offsets() {
+        let lines = vec![String::new(), "a".repeat(25), "中文".repeat(5)];
+        assert_eq!(display_columns("中文ab"), 6);
+        // 每行 10 列：空行占一行，25 个字符占三行，10 个全角字符（20 列）占两行
+        assert_eq!(wrap_offsets(&lines, 10, 2.0), vec![0.0, 2.0, 8.0, 12.0]);
+    }
+}
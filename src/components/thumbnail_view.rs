@@ -1,12 +1,16 @@
 use eframe::egui;
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use crate::components::preview::{Preview, CachedImage};
+use crate::components::image_formats;
 
 /// 缩略图视图模块 - 作为大图标模式的图片显示增强
 /// 复用预览组件的纹理缓存，为图片文件提供缩略图显示
 pub struct ThumbnailView {
     /// 对预览组件的引用，用于访问已缓存的纹理
     preview_ref: Option<*const Preview>,
+    /// 已发送过预加载请求的路径，避免每帧重复发送
+    requested_paths: HashSet<PathBuf>,
 }
 
 impl ThumbnailView {
@@ -14,6 +18,7 @@ impl ThumbnailView {
     pub fn new() -> Self {
         Self {
             preview_ref: None,
+            requested_paths: HashSet::new(),
         }
     }
 
@@ -26,13 +31,7 @@ impl ThumbnailView {
 
     /// 检查文件是否为支持的图片格式
     pub fn is_image_file(&self, path: &Path) -> bool {
-        if let Some(ext) = path.extension() {
-            if let Some(ext_str) = ext.to_str() {
-                let ext_lower = ext_str.to_lowercase();
-                return matches!(ext_lower.as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp");
-            }
-        }
-        false
+        image_formats::is_image_file(path)
     }
 
     /// 绘制缩略图（如果可用）
@@ -62,7 +61,7 @@ impl ThumbnailView {
             let preview = unsafe { &*preview_ptr };
 
             // 尝试从预加载缓存中获取缩略图
-            if let Some((texture, texture_size)) = preview.preloader.get_cached_thumbnail(file_path, ui.ctx()) {
+            if let Some((texture, texture_size)) = preview.preloader.get_cached_thumbnail_budgeted(file_path, ui.ctx()) {
                 // 计算缩略图显示尺寸，保持宽高比
                 let (width, height) = texture_size;
                 let scale = (size / width as f32).min(size / height as f32);
@@ -134,12 +133,17 @@ impl ThumbnailView {
     }
 
     /// 请求预加载缩略图（如果还没有缓存）
-    pub fn request_thumbnail_preload(&self, file_path: &Path) {
+    /// 同一路径只发送一次，目录刷新时通过 reset_requests 重置
+    pub fn request_thumbnail_preload(&mut self, file_path: &Path) {
         // 检查是否为图片文件
         if !self.is_image_file(file_path) {
             return;
         }
 
+        if self.requested_paths.contains(file_path) {
+            return;
+        }
+
         // 安全地访问预览组件
         if let Some(preview_ptr) = self.preview_ref {
             if preview_ptr.is_null() {
@@ -152,8 +156,14 @@ impl ThumbnailView {
             if !preview.preloader.is_cached(file_path) {
                 let _ = preview.preloader.sender.send(file_path.to_path_buf());
             }
+            self.requested_paths.insert(file_path.to_path_buf());
         }
     }
+
+    /// 清空已请求记录（切换目录时调用）
+    pub fn reset_requests(&mut self) {
+        self.requested_paths.clear();
+    }
 }
 
 impl Default for ThumbnailView {
@@ -1,55 +1,59 @@
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::Path;
 use dirs;
 use super::file_list::ViewMode;
+use super::tags::TagColor;
+use super::app_event::{AppEvent, EventQueue};
+use super::quick_jump::{self, PathAlias};
 
-pub fn show_toolbar(ui: &mut egui::Ui, current_path: &mut PathBuf, view_mode: &mut ViewMode) -> (bool, bool) {
-    let mut needs_refresh = false;
-    let mut should_create_folder = false;
-
+pub fn show_toolbar(ui: &mut egui::Ui, current_path: &Path, aliases: &[PathAlias], view_mode: &mut ViewMode, tag_filter: &mut Option<TagColor>, events: &mut EventQueue) {
     ui.horizontal(|ui| {
         // 导航按钮
         if ui.add(egui::Button::new("⬅️ 返回").small()).clicked() {
-            if let Some(parent) = current_path.parent() {
-                *current_path = parent.to_path_buf();
-                needs_refresh = true;
-            }
+            events.emit(AppEvent::NavigateUp);
         }
 
         if ui.add(egui::Button::new("🏠 主页").small()).clicked() {
             if let Some(home_dir) = dirs::home_dir() {
-                *current_path = home_dir;
-                needs_refresh = true;
+                events.emit(AppEvent::Navigate(home_dir));
             }
         }
 
         ui.add_space(10.0);
 
-        // 路径输入框
+        // 路径输入框：编辑中的文字保存在 egui 内存中，失去焦点后恢复为当前路径。
+        // 可以输入 ~、$变量 和路径别名
         ui.label("路径:");
-        let mut path_text = current_path.to_string_lossy().to_string();
+        let text_id = egui::Id::new("toolbar_path_text");
+        let mut path_text = ui.data_mut(|d| d.get_temp::<String>(text_id))
+            .unwrap_or_else(|| current_path.to_string_lossy().to_string());
         let response = ui.add_sized(
             egui::vec2(400.0, 24.0),
             egui::TextEdit::singleline(&mut path_text)
         );
 
         if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-            let new_path = PathBuf::from(&path_text);
-            if new_path.exists() && new_path.is_dir() {
-                *current_path = new_path;
-                needs_refresh = true;
+            if let Some(new_path) = quick_jump::expand_path(&path_text, aliases) {
+                if new_path.is_dir() {
+                    events.emit(AppEvent::Navigate(new_path));
+                }
             }
         }
+        if response.has_focus() {
+            ui.data_mut(|d| d.insert_temp(text_id, path_text));
+        } else {
+            ui.data_mut(|d| d.remove::<String>(text_id));
+        }
 
         ui.add_space(10.0);
 
         // 快捷访问按钮
         if ui.add(egui::Button::new("📁 新建文件夹").small()).clicked() {
-            should_create_folder = true;
+            events.emit(AppEvent::CreateFolder);
         }
 
         if ui.add(egui::Button::new("🔄 刷新").small()).clicked() {
-            needs_refresh = true;
+            events.emit(AppEvent::RefreshContent);
         }
 
         ui.add_space(10.0);
@@ -65,10 +69,27 @@ pub fn show_toolbar(ui: &mut egui::Ui, current_path: &mut PathBuf, view_mode: &m
         if ui.add(egui::Button::new("缩略图").small()).clicked() {
             *view_mode = ViewMode::ThumbnailIcons;
         }
+        if ui.add(egui::Button::new("平铺").small()).clicked() {
+            *view_mode = ViewMode::Tiles;
+        }
         if ui.add(egui::Button::new("详情").small()).clicked() {
             *view_mode = ViewMode::Details;
         }
 
+        ui.add_space(10.0);
+
+        // 标签筛选
+        ui.label("标签:");
+        egui::ComboBox::from_id_salt("tag_filter")
+            .selected_text(tag_filter.map(|t| t.label()).unwrap_or("全部"))
+            .width(60.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(tag_filter, None, "全部");
+                for tag in TagColor::ALL {
+                    ui.selectable_value(tag_filter, Some(tag), egui::RichText::new(format!("● {}", tag.label())).color(tag.color32()));
+                }
+            });
+
         // 右侧对齐剩余空间
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             // 搜索框
@@ -81,6 +102,4 @@ pub fn show_toolbar(ui: &mut egui::Ui, current_path: &mut PathBuf, view_mode: &m
             );
         });
     });
-
-    (needs_refresh, should_create_folder)
 }
\ No newline at end of file
@@ -0,0 +1,166 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// 按 freedesktop.org 回收站规范把文件移入回收站：
+// 与主目录在同一分区时使用 ~/.local/share/Trash，否则使用该分区顶层的 .Trash/$uid 或 .Trash-$uid
+
+// info 文件和文件 URI 中的路径按 URL 规则转义，保留 '/'
+pub fn percent_encode(path: &Path) -> String {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes().to_vec()
+    };
+    #[cfg(not(unix))]
+    let bytes = path.to_string_lossy().replace('\\', "/").into_bytes();
+    let mut encoded = String::new();
+    for byte in bytes {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+// 回收站中的候选名称：name、name.2.ext、name.3.ext……
+#[cfg(unix)]
+fn candidate_name(file_name: &str, attempt: usize) -> String {
+    if attempt == 1 {
+        return file_name.to_string();
+    }
+    match file_name.rfind('.').filter(|&dot| dot > 0) {
+        Some(dot) => format!("{}.{}{}", &file_name[..dot], attempt, &file_name[dot..]),
+        None => format!("{}.{}", file_name, attempt),
+    }
+}
+
+// 把 path 移入 trash_dir；info_path 为写入 .trashinfo 的原路径（相对分区顶层或绝对路径）
+#[cfg(unix)]
+fn trash_into(path: &Path, trash_dir: &Path, info_path: &Path) -> io::Result<()> {
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+    let file_name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的路径"))?.to_string_lossy().to_string();
+
+    let deletion_date = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S");
+    let info = format!("[Trash Info]\nPath={}\nDeletionDate={}\n", percent_encode(info_path), deletion_date);
+    for attempt in 1..10000 {
+        let name = candidate_name(&file_name, attempt);
+        // 先独占创建 info 文件占住名称，再移动文件
+        let info_file = info_dir.join(format!("{}.trashinfo", name));
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&info_file) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        };
+        let target = files_dir.join(&name);
+        if fs::symlink_metadata(&target).is_ok() {
+            // 没有 info 的残留文件，换一个名称
+            drop(file);
+            let _ = fs::remove_file(&info_file);
+            continue;
+        }
+        let result = file.write_all(info.as_bytes()).and_then(|()| fs::rename(path, &target));
+        if result.is_err() {
+            let _ = fs::remove_file(&info_file);
+        }
+        return result;
+    }
+    Err(io::Error::other("回收站中同名文件过多"))
+}
+
+#[cfg(unix)]
+fn current_uid() -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    // /proc/self 的所有者就是当前进程的用户
+    fs::metadata("/proc/self").ok().map(|m| m.uid())
+}
+
+// 所在分区的顶层目录：向上查找直到设备号改变
+#[cfg(unix)]
+fn mount_top(path: &Path, dev: u64) -> PathBuf {
+    use std::os::unix::fs::MetadataExt;
+    let mut top = path.to_path_buf();
+    while let Some(parent) = top.parent() {
+        if fs::metadata(parent).ok().map(|m| m.dev()) != Some(dev) {
+            break;
+        }
+        top = parent.to_path_buf();
+    }
+    top
+}
+
+// 分区顶层的回收站：管理员创建的 .Trash（需设置粘滞位且不是符号链接）下的 $uid，否则为 .Trash-$uid
+#[cfg(unix)]
+fn topdir_trash(top: &Path, uid: u32) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    let shared = top.join(".Trash");
+    let usable = fs::symlink_metadata(&shared).is_ok_and(|m| m.is_dir() && m.permissions().mode() & 0o1000 != 0);
+    if usable {
+        shared.join(uid.to_string())
+    } else {
+        top.join(format!(".Trash-{}", uid))
+    }
+}
+
+// 把文件或文件夹移入回收站
+#[cfg(unix)]
+pub fn move_to_trash(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    // 只规范化上级目录，符号链接本身移入回收站而不是其目标
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的路径"))?;
+    let path = fs::canonicalize(parent)?.join(name);
+    let dev = fs::symlink_metadata(&path)?.dev();
+
+    let home_trash = dirs::data_dir().map(|dir| dir.join("Trash")).ok_or_else(|| io::Error::other("无法确定回收站位置"))?;
+    let home_dev = home_trash.ancestors().find_map(|dir| fs::metadata(dir).ok()).map(|m| m.dev());
+    if home_dev == Some(dev) {
+        return trash_into(&path, &home_trash, &path);
+    }
+
+    let uid = current_uid().ok_or_else(|| io::Error::other("无法确定当前用户"))?;
+    let top = mount_top(&path, dev);
+    let trash_dir = topdir_trash(&top, uid);
+    if !trash_dir.exists() {
+        fs::create_dir_all(&trash_dir)?;
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&trash_dir, fs::Permissions::from_mode(0o700))?;
+    }
+    let relative = path.strip_prefix(&top).unwrap_or(&path);
+    trash_into(&path, &trash_dir, relative)
+}
+
+#[cfg(not(unix))]
+pub fn move_to_trash(_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "当前系统不支持回收站"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_trash_into() {
+        assert_eq!(percent_encode(Path::new("/home/a b/文.txt")), "/home/a%20b/%E6%96%87.txt");
+        assert_eq!(candidate_name("photo.jpg", 2), "photo.2.jpg");
+        assert_eq!(candidate_name(".bashrc", 3), ".bashrc.3");
+
+        let root = TestDir::new("trash");
+        let trash = root.join("Trash");
+        for _ in 0..2 {
+            fs::write(root.join("a.txt"), "a").unwrap();
+            trash_into(&root.join("a.txt"), &trash, &root.join("a.txt")).unwrap();
+        }
+        // 同名文件依次编号，info 记录原路径
+        assert!(!root.join("a.txt").exists());
+        assert!(trash.join("files/a.txt").exists() && trash.join("files/a.2.txt").exists());
+        let info = fs::read_to_string(trash.join("info/a.2.txt.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\n") && info.contains(&format!("Path={}\n", percent_encode(&root.join("a.txt")))));
+    }
+}
@@ -0,0 +1,228 @@
+//! io_uring 复制引擎（需要启用 `io-uring` 特性）
+//!
+//! 批量提交多个文件的读写请求，减少大量小文件复制时的系统调用开销。
+//! 内核不支持 io_uring 或复制出错时删除出错的一批中新建的目标文件并返回错误，由调用方对未完成的文件回退到标准复制引擎。
+
+use std::fs::{self, File};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use io_uring::{opcode, types, IoUring};
+
+// 提交队列深度，也是同时复制的文件数上限（每个文件同一时间只有一个请求在途）
+const QUEUE_DEPTH: u32 = 64;
+
+// 单个文件的复制状态
+enum CopyState {
+    NeedRead,
+    Reading,
+    Writing,
+    Done,
+}
+
+struct FileCopy {
+    src: File,
+    dst: File,
+    buf: Vec<u8>,
+    offset: u64,        // 下一次读取的位置
+    pending_len: usize, // 本轮读到、待写出的字节数
+    written: usize,     // 本轮已写出的字节数
+    state: CopyState,
+}
+
+// 复制一组文件（源路径, 目标路径），目标文件已存在时覆盖。每批复制完成后对其中的源文件调用 done，
+// 批与批之间检查是否已取消（取消时返回 Interrupted）。
+// 失败时删除出错的一批中新建的目标文件，回退到标准引擎时不会与半成品重名；已存在的目标文件不删除
+pub fn copy_files(pairs: &[(PathBuf, PathBuf)], buffer_size: usize, cancelled: &dyn Fn() -> bool, done: &mut dyn FnMut(&Path)) -> io::Result<()> {
+    let mut ring = IoUring::new(QUEUE_DEPTH)?;
+    for chunk in pairs.chunks(QUEUE_DEPTH as usize) {
+        if cancelled() {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let mut created = Vec::new();
+        if let Err(e) = copy_chunk(&mut ring, chunk, buffer_size, &mut created) {
+            for target in created {
+                let _ = fs::remove_file(target);
+            }
+            return Err(e);
+        }
+        for (source, _) in chunk {
+            done(source);
+        }
+    }
+    Ok(())
+}
+
+// 等待在途的请求全部完成；出错后不再提交新请求，但在缓冲区释放前必须等内核用完
+fn drain(ring: &mut IoUring, mut in_flight: usize) -> io::Result<()> {
+    while in_flight > 0 {
+        match ring.submit_and_wait(1) {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+        in_flight = in_flight.saturating_sub(ring.completion().count());
+    }
+    Ok(())
+}
+
+// created 收集本批新建（之前不存在）的目标文件
+fn copy_chunk(ring: &mut IoUring, pairs: &[(PathBuf, PathBuf)], buffer_size: usize, created: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut files = Vec::with_capacity(pairs.len());
+    for (source, target) in pairs {
+        let src = File::open(source)?;
+        let existed = target.symlink_metadata().is_ok();
+        let dst = File::create(target)?;
+        if !existed {
+            created.push(target.clone());
+        }
+        files.push(FileCopy {
+            src,
+            dst,
+            buf: vec![0; buffer_size],
+            offset: 0,
+            pending_len: 0,
+            written: 0,
+            state: CopyState::NeedRead,
+        });
+    }
+
+    let mut in_flight = 0usize;
+    let result = run_chunk(ring, &mut files, &mut in_flight);
+    if in_flight > 0 {
+        if let Err(e) = drain(ring, in_flight) {
+            // 无法确认内核已用完缓冲区时宁可泄漏，也不能释放
+            std::mem::forget(files);
+            return Err(e);
+        }
+    }
+    result
+}
+
+// 提交并处理请求直到全部文件复制完成；出错时立即返回，in_flight 为仍在途的请求数，由调用方等待
+fn run_chunk(ring: &mut IoUring, files: &mut [FileCopy], in_flight: &mut usize) -> io::Result<()> {
+    // user_data 低位区分读(0)/写(1)，其余位为文件下标
+    loop {
+        for (index, file) in files.iter_mut().enumerate() {
+            if matches!(file.state, CopyState::NeedRead) {
+                let entry = opcode::Read::new(types::Fd(file.src.as_raw_fd()), file.buf.as_mut_ptr(), file.buf.len() as u32)
+                    .offset(file.offset)
+                    .build()
+                    .user_data((index as u64) << 1);
+                // 安全性：缓冲区在请求完成前不会被移动或释放：files 在复制过程中不增删，
+                // 出错返回后 copy_chunk 先等待所有在途请求完成再释放 files
+                unsafe { push_entry(ring, &entry)? };
+                file.state = CopyState::Reading;
+                *in_flight += 1;
+            }
+        }
+
+        if *in_flight == 0 {
+            return Ok(());
+        }
+        ring.submit_and_wait(1)?;
+
+        let completions: Vec<(u64, i32)> = ring.completion().map(|cqe| (cqe.user_data(), cqe.result())).collect();
+        for (user_data, result) in completions {
+            *in_flight -= 1;
+            if result < 0 {
+                return Err(io::Error::from_raw_os_error(-result));
+            }
+            let file = &mut files[(user_data >> 1) as usize];
+            let is_write = user_data & 1 == 1;
+
+            if !is_write {
+                if result == 0 {
+                    file.state = CopyState::Done; // 读到文件末尾
+                    continue;
+                }
+                file.pending_len = result as usize;
+                file.written = 0;
+            } else {
+                file.written += result as usize;
+                if file.written >= file.pending_len {
+                    file.offset += file.pending_len as u64;
+                    file.state = CopyState::NeedRead;
+                    continue;
+                }
+            }
+
+            // 写出本轮剩余的数据（短写时继续写）
+            let remaining = &file.buf[file.written..file.pending_len];
+            let entry = opcode::Write::new(types::Fd(file.dst.as_raw_fd()), remaining.as_ptr(), remaining.len() as u32)
+                .offset(file.offset + file.written as u64)
+                .build()
+                .user_data(user_data | 1);
+            unsafe { push_entry(ring, &entry)? };
+            file.state = CopyState::Writing;
+            *in_flight += 1;
+        }
+    }
+}
+
+// 提交队列已满时先提交已有请求再重试
+unsafe fn push_entry(ring: &mut IoUring, entry: &io_uring::squeue::Entry) -> io::Result<()> {
+    if ring.submission().push(entry).is_err() {
+        ring.submit()?;
+        ring.submission()
+            .push(entry)
+            .map_err(|_| io::Error::other("io_uring 提交队列已满"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+    use std::fs;
+
+    #[test]
+    fn test_uring_copy_matches_std_copy() {
+        // 沙箱或旧内核不支持 io_uring 时由标准引擎兜底，这里跳过
+        if let Err(e) = IoUring::new(QUEUE_DEPTH) {
+            eprintln!("跳过：io_uring 不可用（{}）", e);
+            return;
+        }
+        let root = TestDir::new("uring");
+        let (src_dir, uring_dir, std_dir) = (root.join("src"), root.join("uring"), root.join("std"));
+        for dir in [&src_dir, &uring_dir, &std_dir] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        // 空文件、小于/等于/大于缓冲区的文件
+        let buffer_size = 4096;
+        let sizes = [0usize, 1, 100, buffer_size, buffer_size + 1, buffer_size * 5 + 17];
+        let mut pairs = Vec::new();
+        for (i, size) in sizes.iter().enumerate() {
+            let name = format!("f{}.bin", i);
+            let data: Vec<u8> = (0..*size).map(|b| (b * 31 % 251) as u8).collect();
+            fs::write(src_dir.join(&name), &data).unwrap();
+            fs::copy(src_dir.join(&name), std_dir.join(&name)).unwrap();
+            pairs.push((src_dir.join(&name), uring_dir.join(&name)));
+        }
+
+        let mut done = Vec::new();
+        copy_files(&pairs, buffer_size, &|| false, &mut |source| done.push(source.to_path_buf())).unwrap();
+        assert_eq!(done.len(), sizes.len());
+        for (i, _) in sizes.iter().enumerate() {
+            let name = format!("f{}.bin", i);
+            assert_eq!(fs::read(uring_dir.join(&name)).unwrap(), fs::read(std_dir.join(&name)).unwrap());
+        }
+
+        // 失败时不留下新建的目标文件，出错之后未打开的已有文件保持不变
+        let failed_dir = root.join("failed");
+        fs::create_dir_all(&failed_dir).unwrap();
+        fs::write(failed_dir.join("f5.bin"), "keep").unwrap();
+        let mut broken: Vec<(PathBuf, PathBuf)> = pairs.iter().map(|(source, _)| (source.clone(), failed_dir.join(source.file_name().unwrap()))).collect();
+        broken.insert(3, (src_dir.join("missing.bin"), failed_dir.join("missing.bin")));
+        assert!(copy_files(&broken, buffer_size, &|| false, &mut |_| {}).is_err());
+        assert_eq!(fs::read_dir(&failed_dir).unwrap().count(), 1);
+        assert_eq!(fs::read_to_string(failed_dir.join("f5.bin")).unwrap(), "keep");
+
+        // 已取消时不再开始新的一批
+        let result = copy_files(&broken, buffer_size, &|| true, &mut |_| {});
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert_eq!(fs::read_dir(&failed_dir).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,187 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use crossbeam_channel::{Receiver, TryRecvError};
+
+// 截取画面时的缩放宽度
+const FRAME_WIDTH: u32 = 320;
+
+// 生成失败的视频，避免界面一直显示加载中
+static FAILED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+
+pub fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_lowercase().as_str(), "mp4" | "m4v" | "mkv" | "webm" | "avi" | "mov" | "wmv" | "flv" | "mpg" | "mpeg"))
+        .unwrap_or(false)
+}
+
+// 是否能调用 ffmpeg（只检查一次）
+pub fn ffmpeg_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        Command::new("ffmpeg")
+            .arg("-version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    })
+}
+
+pub fn has_failed(path: &Path) -> bool {
+    FAILED.lock().ok().and_then(|set| set.as_ref().map(|s| s.contains(path))).unwrap_or(false)
+}
+
+fn mark_failed(path: &Path) {
+    if let Ok(mut set) = FAILED.lock() {
+        set.get_or_insert_with(HashSet::new).insert(path.to_path_buf());
+    }
+}
+
+// 磁盘缓存位置：按路径、大小和修改时间生成文件名，视频改动后自动失效
+fn cache_path(path: &Path) -> Option<PathBuf> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    let mut hasher = Sha256::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(modified.to_le_bytes());
+    let name: String = hasher.finalize().iter().take(16).map(|b| format!("{:02x}", b)).collect();
+    Some(dirs::cache_dir()?.join("file-explorer").join("video-thumbnails").join(format!("{}.jpg", name)))
+}
+
+// 用 ffmpeg 截取一帧，先尝试第 1 秒（跳过片头黑屏），太短的视频再取第一帧
+fn extract_frame(path: &Path, output: &Path) -> bool {
+    ["1", "0"].iter().any(|seek| {
+        Command::new("ffmpeg")
+            .args(["-v", "error", "-y", "-ss", seek, "-i"])
+            .arg(path)
+            .args(["-frames:v", "1", "-vf", &format!("scale={}:-2", FRAME_WIDTH)])
+            .arg(output)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+            && output.is_file()
+    })
+}
+
+// 视频的第一帧画面，优先从磁盘缓存读取；由缩略图工作线程调用
+pub fn video_frame(path: &Path) -> Result<image::DynamicImage, String> {
+    let cached = cache_path(path).ok_or_else(|| "无法确定缓存位置".to_string())?;
+    if let Ok(image) = image::open(&cached) {
+        return Ok(image);
+    }
+    if !ffmpeg_available() {
+        mark_failed(path);
+        return Err("未找到 ffmpeg".to_string());
+    }
+    if let Some(dir) = cached.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+    }
+    if !extract_frame(path, &cached) {
+        mark_failed(path);
+        return Err(format!("无法截取视频画面: {}", path.display()));
+    }
+    image::open(&cached).map_err(|e| {
+        mark_failed(path);
+        e.to_string()
+    })
+}
+
+// 截取指定时间点的一帧，通过管道直接读取 PNG 数据，不写缓存；用于预览栏的播放和拖动
+pub fn frame_at(path: &Path, seconds: f64, width: u32) -> Result<image::DynamicImage, String> {
+    if !ffmpeg_available() {
+        return Err("未找到 ffmpeg".to_string());
+    }
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-ss", &format!("{:.3}", seconds.max(0.0)), "-i"])
+        .arg(path)
+        .args(["-frames:v", "1", "-vf", &format!("scale={}:-2", width), "-f", "image2pipe", "-vcodec", "png", "-"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("无法运行 ffmpeg: {}", e))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!("无法截取 {:.1} 秒处的画面", seconds));
+    }
+    image::load_from_memory(&output.stdout).map_err(|e| e.to_string())
+}
+
+// 视频时长（秒），依赖 ffprobe
+pub fn probe_duration(path: &Path) -> Option<f64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse::<f64>().ok().filter(|d| *d > 0.0)
+}
+
+// 连续播放用的画面流：ffmpeg 按原速（-re）解码为 RGBA 原始帧，不输出声音，
+// 有可用的 VAAPI/VDPAU/NVDEC 时使用硬件解码。丢弃时结束 ffmpeg
+pub struct VideoStream {
+    child: Child,
+    receiver: Receiver<image::RgbaImage>,
+}
+
+impl VideoStream {
+    // 从 seconds 处开始解码，画面缩放为 size；每收到一帧调用 on_frame（用于请求重绘）
+    pub fn start(path: &Path, seconds: f64, size: (u32, u32), on_frame: impl Fn() + Send + 'static) -> Result<Self, String> {
+        if !ffmpeg_available() {
+            return Err("未找到 ffmpeg".to_string());
+        }
+        let (width, height) = size;
+        let mut child = Command::new("ffmpeg")
+            .args(["-v", "error", "-hwaccel", "auto", "-ss", &format!("{:.3}", seconds.max(0.0)), "-re", "-i"])
+            .arg(path)
+            .args(["-an", "-sn", "-vf", &format!("scale={}:{}", width, height), "-pix_fmt", "rgba", "-f", "rawvideo", "-"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("无法运行 ffmpeg: {}", e))?;
+        let mut stdout = child.stdout.take().ok_or_else(|| "无法读取 ffmpeg 输出".to_string())?;
+
+        // 界面来不及显示时阻塞读取，ffmpeg 随之暂停解码
+        let (sender, receiver) = crossbeam_channel::bounded(2);
+        std::thread::spawn(move || {
+            let mut buffer = vec![0u8; width as usize * height as usize * 4];
+            while stdout.read_exact(&mut buffer).is_ok() {
+                let Some(frame) = image::RgbaImage::from_raw(width, height, buffer.clone()) else {
+                    break;
+                };
+                if sender.send(frame).is_err() {
+                    break;
+                }
+                on_frame();
+            }
+        });
+        Ok(Self { child, receiver })
+    }
+
+    // 取出最新的一帧，跳过积压的旧帧；解码结束（到结尾或出错）后返回 Err
+    pub fn latest_frame(&self) -> Result<Option<image::RgbaImage>, ()> {
+        let mut latest = None;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(frame) => latest = Some(frame),
+                Err(TryRecvError::Empty) => return Ok(latest),
+                Err(TryRecvError::Disconnected) => return if latest.is_some() { Ok(latest) } else { Err(()) },
+            }
+        }
+    }
+}
+
+impl Drop for VideoStream {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -5,10 +5,14 @@ use std::fs;
 mod components;
 use components::*;
 use components::app_icon::*;
+use components::mouse_gesture::{GestureAction, GestureTracker};
 
 mod utils;
 use utils::*;
 
+// 目录栏自动隐藏时保留的细边宽度
+const DIRECTORY_STRIP_WIDTH: f32 = 8.0;
+
 fn main() -> Result<(), eframe::Error> {
     // 加载应用程序图标
     let icon_data = load_app_icon();
@@ -37,6 +41,15 @@ fn main() -> Result<(), eframe::Error> {
     )
 }
 
+// 把复制或剪切的文件同时放到系统剪贴板，供其它文件管理器粘贴；外部工具可能较慢，在后台线程中执行
+fn export_to_system_clipboard(paths: Vec<PathBuf>, cut: bool) {
+    std::thread::spawn(move || {
+        if let Err(e) = system_clipboard::write_files(&paths, cut) {
+            eprintln!("写入系统剪贴板失败: {}", e);
+        }
+    });
+}
+
 fn setup_custom_fonts(ctx: &egui::Context) {
     // 设置字体以支持中文显示
     let mut fonts = egui::FontDefinitions::default();
@@ -94,42 +107,108 @@ struct FileExplorerApp {
     directory_list: FileList,  // 使用FileList代替DirectoryTree
     preview: Preview,
     file_operations: FileOperations,
+    running_operations: Vec<OperationHandle>, // 后台执行中的复制、移动和删除
+    clipboard_import: Option<(PathBuf, crossbeam_channel::Receiver<system_clipboard::ClipboardFiles>)>, // 正在读取系统剪贴板的粘贴及其目标目录
+    operation_progress: OperationProgressDialog, // 较慢的操作显示速度和剩余时间
     create_operations: CreateOperations,
     help_system: HelpSystem,
     drive_bar: DriveBar,  // 新增盘符栏
-    show_hidden: bool,
+    show_hidden: bool, // 查看菜单的“显示隐藏文件”，内容框和目录框都按此设置
     nav_history: Vec<PathBuf>,
     history_pos: usize,
     left_ratio: f32,
     mid_ratio: f32,
+    directory_pane_auto_hide: bool, // 目录栏自动隐藏，F9 切换
+    directory_pane_revealed: bool,  // 自动隐藏时目录栏是否正浮出显示
+    preview_pane_hidden: bool,      // 隐藏预览栏，F11 切换
     // 对话框状态
     show_rename_dialog: bool,
     rename_input: String,
-    show_delete_confirmation: bool,
-    delete_confirmation_message: String,
+    confirm_dialog: ConfirmDialog,  // 删除、覆盖、批量移动等操作的统一确认
+    companion_dialog: CompanionDialog, // 询问是否一并处理字幕等配套文件
+    show_paste_duplicate_dialog: bool,
+    paste_duplicate_message: String,
     show_new_folder_dialog: bool,
     new_folder_name: String,
+    new_folder_suggestions: Vec<String>, // 按最近的命名和已有编号序列生成的名称建议
     view_mode: components::file_list::ViewMode,
     // 查看菜单选项状态
     show_drive_capacity: bool,
     show_capacity_size: bool,
+    show_jump_bar: bool,
+    show_recent_strip: bool, // 内容框顶部的最近修改文件
+    gesture_tracker: GestureTracker, // 右键拖动的鼠标手势
+    group_by: components::file_list::GroupBy,
+    downloads_group_by: components::file_list::GroupBy, // 下载文件夹单独的分组方式，默认按来源
+    sticky_group_header: bool,
+    group_companions: bool, // 字幕、照片附属文件等归到主文件下显示
+    compute_folder_sizes: bool,
+    // 按目录记住的查看方式
+    folder_views: FolderViewStore,
+    view_settings_path: PathBuf,
+    view_settings_snapshot: Option<FolderViewSettings>,
+    // 应用程序设置（行颜色规则等）
+    settings: AppSettings,
+    settings_dialog: SettingsDialog,
+    settings_notice: SettingsNotice, // 加载设置时迁移或修复的提示
+    checksum_dialog: ChecksumDialog,
+    // 文件标签库和工具栏的标签筛选
+    tag_store: TagStore,
+    tag_filter: Option<TagColor>,
+    photo_organizer: PhotoOrganizer,
+    duplicate_finder: DuplicateFinder,
+    broken_link_finder: BrokenLinkFinder,
+    disk_usage: DiskUsageAnalyzer,
+    file_report: FileReport,
+    status_summary: ListSummary, // 状态栏显示的内容框信息
+    status_error: Option<String>, // 状态栏显示的最近一次操作错误，点击关闭后清除
+    quick_jump: QuickJump,       // Ctrl+L 快速跳转
+    image_viewer: ImageViewer,   // 全窗口图片查看器
+    text_viewer: TextViewer,     // 内置文本查看器
+    open_with_dialog: OpenWithDialog,
+    properties_dialog: PropertiesDialog,
+    multi_copy_dialog: MultiCopyDialog,
+    create_link_dialog: CreateLinkDialog,
+    compress_dialog: CompressDialog,
+    extract_dialog: ExtractDialog,
+    hash_verify_dialog: HashVerifyDialog,
+    elevated_retry: ElevatedRetryDialog,
+    events: EventQueue, // 本帧组件发出的事件，由 handle_event 统一处理
+    screenshot_inbox: ScreenshotInbox,
+    group_move_dialog: GroupMoveDialog,
+    basket: FileBasket, // 暂存篮，只在本次运行中保留
+    bookmarks: BookmarkSlots, // Ctrl+1..9 文件夹书签
+    start_page: StartPage,    // 固定的文件夹、最近访问的位置和驱动器
 }
 
 impl FileExplorerApp {
     fn new() -> Self {
-        let current_path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+        // 按设置选择启动时的文件夹：主目录、上次的位置、指定文件夹或起始页
+        let (settings, settings_notices) = AppSettings::load();
+        let mut start_page = StartPage::load();
+        let current_path = start_page.startup_path(settings.startup_location, &settings.startup_custom_path, &settings.path_aliases);
         let directory_current_path = current_path.parent().unwrap_or(&current_path).to_path_buf();
         let mut file_list = FileList::new();
         let mut directory_list = FileList::new();
 
         // 初始化文件列表
-        file_list.refresh(current_path.clone(), false);
-        directory_list.refresh(directory_current_path.clone(), false);
+        let options = components::file_list::ListOptions { folder_placement: settings.folder_placement, ..Default::default() };
+        file_list.refresh(current_path.clone(), &options);
+        directory_list.refresh(directory_current_path.clone(), &options);
 
         // 加载图标
         let _ = file_list.load_icons();
         let _ = directory_list.load_icons();
 
+        // 应用颜色规则（只作用于内容框）
+        file_list.set_color_rules(settings.active_color_rules());
+        file_list.set_click_open_mode(settings.click_open_mode);
+        file_list.set_custom_folder_icons(settings.custom_folder_icons);
+        file_list.set_two_line_names(settings.two_line_names);
+        directory_list.set_custom_folder_icons(settings.custom_folder_icons);
+        // 目录栏宽度按上次保存的比例，预览栏保持 30%
+        let left_ratio = settings.directory_pane_ratio.clamp(0.1, 0.5);
+
         let mut preview = Preview::new();
         preview.init_preloader(); // 初始化预加载器
 
@@ -144,33 +223,83 @@ impl FileExplorerApp {
             directory_list,
             preview,
             file_operations: FileOperations::new(),
+            running_operations: Vec::new(),
+            clipboard_import: None,
+            operation_progress: OperationProgressDialog::new(),
             create_operations: CreateOperations::new(),
             help_system: HelpSystem::new(),
-            drive_bar: DriveBar::new(&current_path),
+            drive_bar: DriveBar::new(),
             show_hidden: false,
             nav_history: vec![current_path.clone()],
             history_pos: 0,
-            left_ratio: 0.25,
-            mid_ratio: 0.45,
+            left_ratio,
+            mid_ratio: 0.7 - left_ratio,
+            directory_pane_auto_hide: settings.directory_pane_auto_hide,
+            preview_pane_hidden: settings.preview_pane_hidden,
+            directory_pane_revealed: false,
             show_rename_dialog: false,
             rename_input: String::new(),
-            show_delete_confirmation: false,
-            delete_confirmation_message: String::new(),
+            confirm_dialog: ConfirmDialog::new(),
+            companion_dialog: CompanionDialog::new(),
+            show_paste_duplicate_dialog: false,
+            paste_duplicate_message: String::new(),
             show_new_folder_dialog: false,
             new_folder_name: String::new(),
+            new_folder_suggestions: Vec::new(),
             view_mode: components::file_list::ViewMode::Details,
             // 查看菜单选项状态初始化
             show_drive_capacity: false,
             show_capacity_size: false,
+            show_jump_bar: false,
+            show_recent_strip: false,
+            gesture_tracker: GestureTracker::default(),
+            group_by: components::file_list::GroupBy::None,
+            downloads_group_by: components::file_list::GroupBy::Source,
+            sticky_group_header: true,
+            group_companions: false,
+            compute_folder_sizes: false,
+            folder_views: FolderViewStore::load(),
+            view_settings_path: PathBuf::new(),
+            view_settings_snapshot: None,
+            settings,
+            settings_dialog: SettingsDialog::new(),
+            settings_notice: SettingsNotice::new(settings_notices),
+            checksum_dialog: ChecksumDialog::new(),
+            tag_store: TagStore::load(),
+            tag_filter: None,
+            photo_organizer: PhotoOrganizer::new(),
+            duplicate_finder: DuplicateFinder::new(),
+            broken_link_finder: BrokenLinkFinder::new(),
+            disk_usage: DiskUsageAnalyzer::new(),
+            file_report: FileReport::new(),
+            status_summary: ListSummary::default(),
+            status_error: None,
+            quick_jump: QuickJump::new(),
+            image_viewer: ImageViewer::new(),
+            text_viewer: TextViewer::new(),
+            open_with_dialog: OpenWithDialog::new(),
+            properties_dialog: PropertiesDialog::new(),
+            multi_copy_dialog: MultiCopyDialog::new(),
+            create_link_dialog: CreateLinkDialog::new(),
+            compress_dialog: CompressDialog::new(),
+            extract_dialog: ExtractDialog::new(),
+            hash_verify_dialog: HashVerifyDialog::new(),
+            elevated_retry: ElevatedRetryDialog::new(),
+            events: EventQueue::default(),
+            screenshot_inbox: ScreenshotInbox::new(),
+            group_move_dialog: GroupMoveDialog::new(),
+            basket: FileBasket::new(),
+            bookmarks: BookmarkSlots::load(),
+            start_page,
         }
     }
 
     fn navigate_to(&mut self, path: PathBuf) {
         if path.is_dir() {
             self.current_path = path.clone();
-            self.file_list.refresh(path.clone(), self.show_hidden);
             self.selected_file = None;
             self.preview.clear();
+            self.refresh_file_list();
 
             // 请求延迟预加载，避免阻塞UI
             self.preview.request_delayed_preload(&path);
@@ -179,12 +308,43 @@ impl FileExplorerApp {
 
     fn refresh_file_list(&mut self) {
         // 只刷新内容框
-        self.file_list.refresh(self.current_path.clone(), self.show_hidden);
+        let options = self.content_list_options();
+        self.file_list.refresh(self.current_path.clone(), &options);
+        // 返回之前的目录时恢复其选中项
+        if let Some(path) = self.file_list.take_restored_selection() {
+            self.selected_file = Some(path);
+        }
+    }
+
+    // 进入新目录时恢复其上次的查看方式；在当前目录修改查看方式时记录下来
+    fn sync_folder_view_settings(&mut self, ctx: &egui::Context) {
+        if self.view_settings_path != self.current_path {
+            self.view_settings_path = self.current_path.clone();
+            if let Some(settings) = self.folder_views.get(&self.current_path).cloned() {
+                self.view_mode = settings.view_mode;
+                self.file_list.restore_view_settings(&settings);
+                ctx.request_repaint();
+            }
+            self.view_settings_snapshot = Some(self.file_list.capture_view_settings(self.view_mode));
+            return;
+        }
+
+        let current = self.file_list.capture_view_settings(self.view_mode);
+        if self.view_settings_snapshot.as_ref() != Some(&current) {
+            self.folder_views.set(&self.current_path, current.clone());
+            self.view_settings_snapshot = Some(current);
+        }
+
+        // 拖动列宽时不频繁写盘，松开鼠标后再保存
+        if !ctx.input(|i| i.pointer.any_down()) {
+            self.folder_views.save_if_dirty();
+        }
     }
 
     fn refresh_directory_list(&mut self) {
         // 只刷新目录框
-        self.directory_list.refresh(self.directory_current_path.clone(), self.show_hidden);
+        let options = self.directory_list_options();
+        self.directory_list.refresh(self.directory_current_path.clone(), &options);
     }
 
     // 异步预加载当前文件夹中的图片（不阻塞UI）
@@ -199,6 +359,36 @@ impl FileExplorerApp {
         }
     }
 
+    // 鼠标手势作用于开始画手势的窗格：内容框使用导航历史，目录框没有历史，后退即上级目录
+    fn handle_mouse_gesture(&mut self, ctx: &egui::Context, directory_rect: egui::Rect, content_rect: egui::Rect) {
+        if !self.settings.mouse_gestures.enabled {
+            return;
+        }
+        let Some((stroke, start)) = self.gesture_tracker.update(ctx, directory_rect.union(content_rect)) else {
+            return;
+        };
+        let Some(action) = self.settings.mouse_gestures.action_for(&stroke) else {
+            return;
+        };
+        let event = if directory_rect.contains(start) {
+            match action {
+                GestureAction::Back | GestureAction::Up => self.directory_current_path.parent().map(|p| AppEvent::NavigateDirectory(p.to_path_buf())),
+                GestureAction::Forward => None,
+                GestureAction::Refresh => Some(AppEvent::RefreshAll),
+            }
+        } else {
+            Some(match action {
+                GestureAction::Back => AppEvent::GoBack,
+                GestureAction::Forward => AppEvent::GoForward,
+                GestureAction::Up => AppEvent::NavigateUp,
+                GestureAction::Refresh => AppEvent::RefreshContent,
+            })
+        };
+        if let Some(event) = event {
+            self.events.emit(event);
+        }
+    }
+
     fn navigate_directory_to(&mut self, path: PathBuf) {
         // 目录框导航，不刷新内容框
         if path.is_dir() {
@@ -207,13 +397,6 @@ impl FileExplorerApp {
         }
     }
 
-    fn go_up_directory(&mut self) {
-        // 返回上级目录
-        if let Some(parent) = self.directory_current_path.parent() {
-            self.navigate_directory_to(parent.to_path_buf());
-        }
-    }
-
     fn select_file(&mut self, file: PathBuf, ctx: &egui::Context) {
         self.selected_file = Some(file.clone());
         self.preview.load_preview(file, ctx);
@@ -225,6 +408,540 @@ impl FileExplorerApp {
         }
         self.nav_history.push(path.clone());
         self.history_pos = self.nav_history.len() - 1;
+        self.quick_jump.remember(&path);
+    }
+
+    // 打开文件所在的文件夹并在内容框中选中它
+    fn reveal_path(&mut self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            if parent != self.current_path {
+                self.navigate_to(parent.to_path_buf());
+                self.push_history(self.current_path.clone());
+            }
+            self.file_list.reveal(path, &mut self.selected_file);
+        }
+    }
+
+    // 开始重命名：详细信息视图中就地重命名，其它视图使用重命名对话框
+    fn begin_rename(&mut self, path: PathBuf) {
+        if self.view_mode == components::file_list::ViewMode::Details {
+            self.file_list.begin_inline_rename(&path);
+        } else {
+            self.rename_input = path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string();
+            self.show_rename_dialog = true;
+        }
+    }
+
+    // 下载文件夹使用单独的分组方式
+    fn effective_group_by(&self) -> components::file_list::GroupBy {
+        if components::downloads::is_downloads_folder(&self.current_path) {
+            self.downloads_group_by
+        } else {
+            self.group_by
+        }
+    }
+
+    // 内容框的显示选项：排序和筛选框沿用列表自己的，其余取自菜单、工具栏和设置
+    fn content_list_options(&self) -> components::file_list::ListOptions {
+        components::file_list::ListOptions {
+            show_hidden: self.show_hidden,
+            tag_filter: self.tag_filter,
+            folder_placement: self.settings.folder_placement,
+            group_by: self.effective_group_by(),
+            group_companions: self.group_companions,
+            ..self.file_list.options().clone()
+        }
+    }
+
+    // 目录框的显示选项：不分组、不按标签筛选
+    fn directory_list_options(&self) -> components::file_list::ListOptions {
+        components::file_list::ListOptions {
+            show_hidden: self.show_hidden,
+            folder_placement: self.settings.folder_placement,
+            ..self.directory_list.options().clone()
+        }
+    }
+
+    // 粘贴前在后台读取系统剪贴板，读取结束后再粘贴到目标目录
+    fn begin_paste(&mut self, dir: PathBuf) {
+        self.clipboard_import = Some((dir, system_clipboard::read_files_async()));
+    }
+
+    // 系统剪贴板读取结束后粘贴：其它文件管理器复制或剪切的文件与本程序的剪贴板不同时，改为粘贴这些文件
+    fn poll_clipboard_import(&mut self, ctx: &egui::Context) {
+        let Some((_, receiver)) = &self.clipboard_import else {
+            return;
+        };
+        let files = match receiver.try_recv() {
+            Ok(files) => files,
+            Err(crossbeam_channel::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(50));
+                return;
+            }
+            Err(crossbeam_channel::TryRecvError::Disconnected) => None,
+        };
+        let Some((dir, _)) = self.clipboard_import.take() else {
+            return;
+        };
+        if let Some((paths, cut)) = files {
+            self.import_system_clipboard(paths, cut);
+        }
+        self.request_operation(PendingOperation::Paste { target_dir: dir, companions: Vec::new() });
+    }
+
+    fn import_system_clipboard(&mut self, paths: Vec<PathBuf>, cut: bool) {
+        // 已被移走的文件（如剪切后已粘贴）不再导入
+        if !paths.iter().all(|path| path.exists()) {
+            return;
+        }
+        if self.file_operations.clipboard().is_some_and(|clipboard| clipboard.source_paths == paths) {
+            return;
+        }
+        if cut {
+            self.file_operations.cut_to_clipboard(paths);
+        } else {
+            self.file_operations.copy_to_clipboard(paths);
+        }
+    }
+
+    // 执行内容框右键菜单选择的操作
+    fn handle_context_action(&mut self, action: ContextAction, ctx: &egui::Context) {
+        match action {
+            // 打开由 FileList 直接处理
+            ContextAction::Open(_) => {}
+            ContextAction::OpenWith(path) => self.open_with_dialog.open(path),
+            ContextAction::Cut(paths) => {
+                export_to_system_clipboard(paths.clone(), true);
+                self.file_operations.cut_to_clipboard(paths);
+            }
+            ContextAction::Copy(paths) => {
+                export_to_system_clipboard(paths.clone(), false);
+                self.file_operations.copy_to_clipboard(paths);
+            }
+            ContextAction::CopyToMultiple(paths) => self.multi_copy_dialog.open(paths),
+            ContextAction::Paste(dir) => self.begin_paste(dir),
+            ContextAction::Rename(path) => self.begin_rename(path),
+            ContextAction::CreateLink(path) => self.create_link_dialog.open(path, &self.current_path),
+            ContextAction::Delete(paths) => self.request_operation(PendingOperation::Delete { paths, permanent: false }),
+            ContextAction::DeletePermanently(paths) => self.request_operation(PendingOperation::Delete { paths, permanent: true }),
+            ContextAction::Properties(path) => self.properties_dialog.open(path),
+            ContextAction::VerifyChecksum(path) => self.hash_verify_dialog.open(path, ctx),
+            ContextAction::CopyPath(paths) => {
+                let text = paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join("\n");
+                ctx.copy_text(text);
+            }
+            ContextAction::Compare(left, right) => self.preview.start_compare(left, right, ctx),
+            ContextAction::Compress(paths) => self.compress_dialog.open(paths),
+            ContextAction::Extract { archive, dest } => self.running_operations.push(self.file_operations.extract_archive(archive, dest)),
+            ContextAction::ExtractTo(archive) => self.extract_dialog.open(archive),
+            ContextAction::AddToBasket(paths) => self.basket.add(paths, &self.current_path),
+            ContextAction::RunInTerminal { dir, command } => {
+                if let Err(e) = project_types::run_in_terminal(&dir, &command) {
+                    self.report_error(e);
+                }
+            }
+            ContextAction::OpenInEditor(dir) => {
+                if let Err(e) = project_types::open_in_editor(&dir) {
+                    self.report_error(e);
+                }
+            }
+        }
+    }
+
+    // 统一处理组件发出的事件，应用状态只在这里修改
+    fn handle_event(&mut self, event: AppEvent, ctx: &egui::Context) {
+        match event {
+            AppEvent::Navigate(path) => {
+                if path.is_dir() {
+                    self.navigate_to(path);
+                    self.push_history(self.current_path.clone());
+                }
+            }
+            AppEvent::NavigateUp => {
+                if let Some(parent) = self.current_path.parent() {
+                    let parent = parent.to_path_buf();
+                    self.navigate_to(parent.clone());
+                    self.push_history(parent);
+                }
+            }
+            AppEvent::GoBack => self.go_back(),
+            AppEvent::GoForward => self.go_forward(),
+            AppEvent::Reveal(path) => self.reveal_path(&path),
+            AppEvent::NavigateDirectory(path) => self.navigate_directory_to(path),
+            AppEvent::RefreshContent => self.refresh_file_list(),
+            AppEvent::RefreshAll => {
+                self.refresh_file_list();
+                self.refresh_directory_list();
+            }
+            AppEvent::SwitchWorkspace(drive_root) => {
+                // 先保存离开的工作区，再恢复目标盘符的路径和导航历史
+                self.save_current_workspace_state();
+                let state = self.drive_bar.workspace_state(&drive_root);
+                self.current_path = state.current_path;
+                self.directory_current_path = state.directory_current_path;
+                self.nav_history = state.nav_history;
+                self.history_pos = state.history_pos;
+                self.selected_file = None;
+                self.refresh_file_list();
+                self.refresh_directory_list();
+            }
+            AppEvent::CreateFolder => {
+                self.new_folder_name = generate_default_folder_name(&self.current_path);
+                self.new_folder_suggestions = self.create_operations.suggest_folder_names(&self.current_path);
+                self.show_new_folder_dialog = true;
+            }
+            AppEvent::OpenBasket => self.basket.open(&self.current_path),
+            AppEvent::OpenStartPage => self.start_page.open(),
+            AppEvent::FileAction(action) => self.handle_context_action(action, ctx),
+            AppEvent::RenameCommitted { path, new_name } => {
+                if self.rename_with_companions(&path, &new_name) {
+                    self.selected_file = Some(path.with_file_name(&new_name));
+                }
+            }
+            AppEvent::Drop(drop) => self.request_operation(PendingOperation::Drop(drop)),
+            AppEvent::Compare { left, right } => self.preview.start_compare(left, right, ctx),
+            AppEvent::Selection(command) => self.file_list.apply_selection_command(command, &mut self.selected_file),
+            AppEvent::Tool(command) => self.run_tool(command),
+            AppEvent::ToggleTag(path, tag) => self.tag_store.toggle(&path, tag),
+            AppEvent::ClearTags(path) => self.tag_store.clear(&path),
+        }
+    }
+
+    // 菜单栏启动的工具
+    fn run_tool(&mut self, command: ToolCommand) {
+        match command {
+            ToolCommand::GenerateChecksums(dir) => self.checksum_dialog.start_generate(dir),
+            ToolCommand::VerifyChecksums(manifest) => self.checksum_dialog.start_verify(manifest),
+            ToolCommand::FindDuplicates(dir) => self.duplicate_finder.start(dir),
+            ToolCommand::FindBrokenLinks(dir) => self.broken_link_finder.start(dir),
+            ToolCommand::AnalyzeDiskUsage(dir) => self.disk_usage.start(dir),
+            ToolCommand::FileReport(dir) => self.file_report.start(dir),
+            ToolCommand::OrganizePhotos(dir) => self.photo_organizer.open(&dir),
+            ToolCommand::UndoPhotoOrganize => {
+                if self.photo_organizer.undo_last() {
+                    self.events.emit(AppEvent::RefreshAll);
+                }
+            }
+            ToolCommand::OpenSettings => self.settings_dialog.open(&self.settings),
+            ToolCommand::ShowAbout => self.help_system.show_about(),
+        }
+    }
+
+    // 处理队列中的全部事件；处理过程中新发出的事件也在本轮处理
+    fn dispatch_events(&mut self, ctx: &egui::Context) {
+        if self.events.is_empty() {
+            return;
+        }
+        while !self.events.is_empty() {
+            for event in self.events.drain() {
+                self.handle_event(event, ctx);
+            }
+        }
+        ctx.request_repaint();
+    }
+
+    // 内容框中的文件或外部文件拖到预览栏上时，与正在预览的文件比较
+    fn handle_compare_drop(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        let Some(left) = self.preview.current_file().filter(|p| p.is_file()).cloned() else {
+            return;
+        };
+        if response.dnd_hover_payload::<DraggedFiles>().is_some() {
+            ui.painter().rect_stroke(response.rect, 4.0, egui::Stroke::new(2.0, ui.visuals().selection.stroke.color));
+        }
+        let mut right = response.dnd_release_payload::<DraggedFiles>().and_then(|payload| payload.0.first().cloned());
+        if right.is_none() && response.contains_pointer() {
+            right = ui.ctx().input(|i| i.raw.dropped_files.iter().find_map(|f| f.path.clone()));
+        }
+        if let Some(right) = right.filter(|r| r.is_file() && *r != left) {
+            self.events.emit(AppEvent::Compare { left, right });
+        }
+    }
+
+    // 重命名文件；有配套文件时询问是否按新名称一起重命名
+    fn rename_with_companions(&mut self, path: &Path, new_name: &str) -> bool {
+        let companions = if self.settings.confirmations.companion_files { companions::find_companions(path) } else { Vec::new() };
+        match self.file_operations.rename_file(path, new_name) {
+            Ok(()) => {
+                self.tag_store.rename_path(path, &path.with_file_name(new_name));
+                self.refresh_file_list();
+                let renames = companions::plan_renames(path, new_name, &companions);
+                if !renames.is_empty() {
+                    self.companion_dialog.open_rename(renames);
+                }
+                true
+            }
+            Err(e) => {
+                self.report_error(format!("重命名错误: {}", e));
+                if e.permission_denied {
+                    let task = ElevatedTask::Rename { from: path.to_path_buf(), to: path.with_file_name(new_name) };
+                    self.elevated_retry.offer(task, e.message);
+                }
+                false
+            }
+        }
+    }
+
+    // 项目带有配套文件时先询问是否一并处理，再按设置检查是否需要确认，无需确认时立即执行
+    fn request_operation(&mut self, operation: PendingOperation) {
+        if self.settings.confirmations.companion_files {
+            let sources = match &operation {
+                PendingOperation::Delete { paths, .. } => paths.clone(),
+                PendingOperation::Paste { .. } => self.file_operations.clipboard().map(|c| c.source_paths.clone()).unwrap_or_default(),
+                PendingOperation::Drop(drop) => drop.paths.clone(),
+            };
+            let companions = companions::find_all_companions(&sources);
+            if !companions.is_empty() {
+                self.companion_dialog.open_operation(operation, companions);
+                return;
+            }
+        }
+        self.confirm_operation(operation);
+    }
+
+    // 把选择一并处理的配套文件加入操作，再按确认设置执行
+    fn include_companions(&mut self, mut operation: PendingOperation, companions: Vec<PathBuf>) {
+        match &mut operation {
+            PendingOperation::Delete { paths, .. } => paths.extend(companions),
+            PendingOperation::Paste { companions: pasted, .. } => pasted.extend(companions),
+            PendingOperation::Drop(drop) => drop.paths.extend(companions),
+        }
+        self.confirm_operation(operation);
+    }
+
+    // 按设置检查是否需要确认，无需确认时立即执行
+    fn confirm_operation(&mut self, operation: PendingOperation) {
+        if let Some(operation) = self.confirm_dialog.request(operation, &self.settings.confirmations, self.file_operations.clipboard()) {
+            self.run_operation(operation);
+        }
+    }
+
+    // 执行已确认的删除、粘贴或拖放操作；操作在后台进行，结束后由 poll_operations 刷新列表
+    fn run_operation(&mut self, operation: PendingOperation) {
+        match operation {
+            PendingOperation::Delete { paths, permanent } => {
+                let handle = if permanent { self.file_operations.delete_files(&paths) } else { self.file_operations.trash_files(&paths) };
+                self.running_operations.push(handle);
+            }
+            PendingOperation::Paste { target_dir, companions } => {
+                // 确认后才把一并粘贴的配套文件加入剪贴板
+                self.file_operations.extend_clipboard(companions);
+                match self.file_operations.paste_from_clipboard(&target_dir) {
+                    Ok(PasteStart::Started(handle)) => self.running_operations.push(handle),
+                    Ok(PasteStart::NeedsConfirmation(message)) => {
+                        // 重复的复制任务，询问合并或跳过
+                        self.paste_duplicate_message = message;
+                        self.show_paste_duplicate_dialog = true;
+                    }
+                    Err(msg) => self.report_error(format!("粘贴错误: {}", msg)),
+                }
+            }
+            PendingOperation::Drop(drop) => match self.file_operations.drop_files(&drop.paths, &drop.target_dir, drop.copy) {
+                Ok(handle) => self.running_operations.push(handle),
+                Err(msg) => {
+                    self.report_error(format!("拖放错误: {}", msg));
+                    self.refresh_file_list();
+                }
+            },
+        }
+    }
+
+    // 检查后台操作是否结束：报告失败的文件，有操作结束时刷新两个列表
+    fn poll_operations(&mut self, ctx: &egui::Context) {
+        let mut finished = false;
+        let mut denied = None;
+        let mut error = None;
+        self.running_operations.retain_mut(|handle| match handle.try_result() {
+            Some(result) => {
+                if let Err(e) = result {
+                    error = Some(format!("{}错误: {}", handle.kind().label(), e));
+                    // 有文件因权限不足失败时询问是否以管理员权限重试
+                    if let Some(task) = handle.elevated_retry() {
+                        denied = Some((task.clone(), e.to_string()));
+                    }
+                }
+                finished = true;
+                false
+            }
+            None => true,
+        });
+        if let Some(message) = error {
+            self.report_error(message);
+        }
+        if let Some((task, message)) = denied {
+            self.elevated_retry.offer(task, message);
+        }
+        if finished {
+            if self.selected_file.as_ref().is_some_and(|path| !path.exists()) {
+                self.selected_file = None;
+            }
+            self.refresh_file_list();
+            self.refresh_directory_list();
+        }
+        if !self.running_operations.is_empty() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        // 删除请求已确认并执行完，或在确认时被取消
+        let settled = self.events.is_empty() && self.running_operations.is_empty()
+            && !self.confirm_dialog.is_showing() && !self.companion_dialog.is_showing();
+        if settled {
+            self.disk_usage.settle_deletions();
+        }
+    }
+
+    // 三栏宽度；目录栏自动隐藏时只保留一条细边，预览栏隐藏时宽度为 0，让出的宽度都给内容框
+    fn pane_widths(&self, total_w: f32) -> (f32, f32, f32) {
+        let left_w = total_w * self.left_ratio;
+        let (mid_w, right_w) = if self.preview_pane_hidden {
+            (total_w - left_w, 0.0)
+        } else {
+            let mid_w = total_w * self.mid_ratio;
+            (mid_w, total_w - left_w - mid_w)
+        };
+        if self.directory_pane_auto_hide {
+            (DIRECTORY_STRIP_WIDTH, mid_w + left_w - DIRECTORY_STRIP_WIDTH, right_w)
+        } else {
+            (left_w, mid_w, right_w)
+        }
+    }
+
+    // 目录栏：返回上级按钮和目录列表
+    fn show_directory_pane(&mut self, ui: &mut egui::Ui) {
+        // 返回上级目录按钮
+        if ui.add_sized(
+            [ui.available_width(), ui.spacing().interact_size.y * 1.5],
+            egui::Button::new("⬆ 返回上级目录")
+        ).clicked() {
+            if let Some(parent) = self.directory_current_path.parent() {
+                self.events.emit(AppEvent::NavigateDirectory(parent.to_path_buf()));
+            }
+        }
+
+        self.bookmarks.show(ui, &self.current_path, &mut self.events);
+        ui.separator();
+
+        // 独立的滚动区域
+        let mut temp_current_path = self.directory_current_path.clone();
+        egui::ScrollArea::vertical().id_salt("directory_scroll").show(ui, |ui| {
+            // 确保目录框的纹理已加载，文件夹自定义图标复用预览组件的缓存
+            self.directory_list.ensure_textures(ui.ctx());
+            self.directory_list.set_preview_ref(&self.preview);
+
+            let (should_refresh_content, should_navigate_directory, should_open_file) =
+                self.directory_list.show_for_directory(ui, &mut temp_current_path, &mut self.selected_file);
+
+            if should_refresh_content {
+                // 单击目录：内容框进入该目录
+                if let Some(selected_path) = self.selected_file.clone() {
+                    self.events.emit(AppEvent::Navigate(selected_path));
+                }
+            }
+
+            if should_navigate_directory {
+                // 双击目录：目录框进入该目录
+                self.events.emit(AppEvent::NavigateDirectory(temp_current_path.clone()));
+            }
+
+            if should_open_file {
+                // 双击文件：文件已通过mouse_strategy打开
+                // 这里可以添加成功打开的提示，如果需要的话
+            }
+        });
+
+        // 从内容框拖到目录框文件夹上的项目，与内容框内的拖放一样经过确认后执行
+        if let Some(drop) = self.directory_list.take_drop_request() {
+            self.events.emit(AppEvent::Drop(drop));
+        }
+    }
+
+    // 自动隐藏时的细边，悬停在细边或浮出的目录栏上时显示目录栏
+    fn show_directory_strip(&mut self, ui: &mut egui::Ui, expanded_w: f32, height: f32) {
+        let (strip_rect, strip_response) = ui.allocate_exact_size(egui::vec2(DIRECTORY_STRIP_WIDTH, height), egui::Sense::hover());
+        ui.painter().rect_filled(strip_rect, 2.0, ui.visuals().widgets.inactive.bg_fill);
+        // 拖放文件时也可以悬停细边浮出目录栏
+        if strip_response.contains_pointer() {
+            self.directory_pane_revealed = true;
+        }
+        if !self.directory_pane_revealed {
+            return;
+        }
+
+        let area = egui::Area::new(egui::Id::new("directory_pane_overlay"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(strip_rect.left_top())
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(expanded_w);
+                    ui.set_height(height);
+                    self.show_directory_pane(ui);
+                });
+            });
+        // 指针离开细边和目录栏后收起
+        let pointer = ui.ctx().pointer_hover_pos();
+        let inside = pointer.is_some_and(|pos| area.response.rect.contains(pos) || strip_rect.contains(pos));
+        if !inside {
+            self.directory_pane_revealed = false;
+        }
+    }
+
+    // 目录栏和内容框之间的分隔条，拖动调整目录栏宽度，松开后保存
+    fn handle_pane_splitter(&mut self, ui: &mut egui::Ui, left_rect: egui::Rect, total_w: f32) {
+        let spacing = ui.spacing().item_spacing.x;
+        let splitter_rect = egui::Rect::from_min_max(
+            egui::pos2(left_rect.right(), left_rect.top()),
+            egui::pos2(left_rect.right() + spacing, left_rect.bottom()),
+        );
+        let response = ui.interact(splitter_rect, ui.id().with("directory_pane_splitter"), egui::Sense::drag())
+            .on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+        if response.dragged() && total_w > 0.0 {
+            let delta = response.drag_delta().x / total_w;
+            let left_ratio = (self.left_ratio + delta).clamp(0.1, 0.5);
+            // 内容框让出或获得相应宽度，预览栏宽度不变
+            let mid_ratio = self.mid_ratio - (left_ratio - self.left_ratio);
+            if mid_ratio >= 0.2 {
+                self.left_ratio = left_ratio;
+                self.mid_ratio = mid_ratio;
+            }
+        }
+        if response.drag_stopped() {
+            self.save_pane_settings();
+        }
+    }
+
+    // F9：切换目录栏的自动隐藏
+    fn toggle_directory_pane(&mut self) {
+        self.directory_pane_auto_hide = !self.directory_pane_auto_hide;
+        self.directory_pane_revealed = false;
+        self.save_pane_settings();
+    }
+
+    // F11：显示或隐藏预览栏；隐藏时清空预览，停止播放中的音视频
+    fn toggle_preview_pane(&mut self) {
+        self.preview_pane_hidden = !self.preview_pane_hidden;
+        self.preview_pane_changed();
+    }
+
+    fn preview_pane_changed(&mut self) {
+        if self.preview_pane_hidden {
+            self.preview.clear();
+        }
+        self.save_pane_settings();
+    }
+
+    fn save_pane_settings(&mut self) {
+        self.settings.directory_pane_ratio = self.left_ratio;
+        self.settings.directory_pane_auto_hide = self.directory_pane_auto_hide;
+        self.settings.preview_pane_hidden = self.preview_pane_hidden;
+        if let Err(e) = self.settings.save() {
+            self.report_error(format!("保存设置失败: {}", e));
+        }
+    }
+
+    // 在状态栏中显示操作失败的原因，替换之前的错误
+    fn report_error(&mut self, message: String) {
+        self.status_error = Some(message);
     }
 
     fn can_go_back(&self) -> bool { self.history_pos > 0 }
@@ -235,6 +952,7 @@ impl FileExplorerApp {
             self.history_pos -= 1;
             let path = self.nav_history[self.history_pos].clone();
             self.current_path = path;
+            self.file_list.restore_view_state_on_refresh();
             self.refresh_file_list();
         }
     }
@@ -244,6 +962,7 @@ impl FileExplorerApp {
             self.history_pos += 1;
             let path = self.nav_history[self.history_pos].clone();
             self.current_path = path;
+            self.file_list.restore_view_state_on_refresh();
             self.refresh_file_list();
         }
     }
@@ -273,99 +992,91 @@ impl eframe::App for FileExplorerApp {
             style.spacing.button_padding = egui::vec2(16.0, 8.0);
         });
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // 顶部菜单栏和工具栏
-            ui.vertical(|ui| {
-                // 菜单栏
-                let (menu_needs_refresh, menu_should_paste, menu_should_rename, menu_should_delete, menu_should_create_folder) =
-                    menu_bar::show_menu_bar(ui, &mut self.current_path, &mut self.show_hidden, &mut self.file_operations, &self.selected_file, &mut self.help_system, &mut self.view_mode, &mut self.show_drive_capacity, &mut self.show_capacity_size);
+        // 全窗口图片查看器打开时先取走方向键和 Esc
+        self.image_viewer.handle_input(ctx);
 
-                // 处理菜单栏的刷新请求（来自查看和转到功能）
-                if menu_needs_refresh {
-                    self.refresh_file_list();
-                    self.refresh_directory_list();
-                }
+        // F9：切换目录栏自动隐藏
+        if ctx.input(|i| i.key_pressed(egui::Key::F9)) {
+            self.toggle_directory_pane();
+        }
 
-                // 处理菜单栏的粘贴请求
-                if menu_should_paste {
-                    match self.file_operations.paste_from_clipboard(&self.current_path) {
-                        FileOperationResult::Success => {
-                            self.refresh_file_list();
-                            self.refresh_directory_list();
-                        }
-                        FileOperationResult::Error(msg) => {
-                            eprintln!("粘贴错误: {}", msg);
-                        }
-                        FileOperationResult::NeedsConfirmation(_) => {}
-                    }
-                }
+        // F11：显示或隐藏预览栏
+        if ctx.input(|i| i.key_pressed(egui::Key::F11)) {
+            self.toggle_preview_pane();
+        }
 
-                // 处理菜单栏的重命名请求
-                if menu_should_rename {
-                    if let Some(ref path) = self.selected_file {
-                        self.rename_input = path.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("")
-                            .to_string();
-                        self.show_rename_dialog = true;
-                    }
-                }
+        // Ctrl+L：打开快速跳转
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::L)) {
+            self.quick_jump.open();
+        }
 
-                // 处理菜单栏的删除请求
-                if menu_should_delete {
-                    if let Some(ref path) = self.selected_file {
-                        match self.file_operations.delete_files(&[path.clone()]) {
-                            FileOperationResult::NeedsConfirmation(message) => {
-                                self.delete_confirmation_message = message;
-                                self.show_delete_confirmation = true;
-                            }
-                            FileOperationResult::Error(msg) => {
-                                eprintln!("删除错误: {}", msg);
-                            }
-                            FileOperationResult::Success => {}
-                        }
-                    }
-                }
+        // Ctrl+1..9：跳转到书签；Ctrl+Shift+1..9：把当前文件夹设为书签
+        self.bookmarks.handle_shortcuts(ctx, &self.current_path, &mut self.events);
 
-                // 处理菜单栏的新建文件夹请求
-                if menu_should_create_folder {
-                    self.new_folder_name = generate_default_folder_name(&self.current_path);
-                    self.show_new_folder_dialog = true;
+        // F2：详细信息视图中就地重命名，其它视图仍使用重命名对话框
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::F2)) {
+            if let Some(path) = self.selected_file.clone() {
+                self.events.emit(AppEvent::FileAction(ContextAction::Rename(path)));
+            }
+        }
+
+        // 底部状态栏（需在中央面板之前添加以保留空间）
+        let free_space = self.drive_bar.free_space(&self.current_path);
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            if status_bar::show_status_bar(ui, &self.status_summary, free_space, &mut self.running_operations, &mut self.status_error) {
+                self.operation_progress.open();
+            }
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // 顶部菜单栏和工具栏
+            ui.vertical(|ui| {
+                // 菜单栏
+                let (directory_pane_auto_hide, preview_pane_hidden) = (self.directory_pane_auto_hide, self.preview_pane_hidden);
+                // 在下载文件夹中修改的是下载文件夹的分组方式
+                let group_by = if components::downloads::is_downloads_folder(&self.current_path) {
+                    &mut self.downloads_group_by
+                } else {
+                    &mut self.group_by
+                };
+                let menu_state = menu_bar::MenuBarState {
+                    current_path: &self.current_path,
+                    selected_file: &self.selected_file,
+                    selected_tags: self.selected_file.as_ref().map(|path| self.tag_store.tags(path)).unwrap_or_default(),
+                    can_undo_photo_organize: self.photo_organizer.can_undo(),
+                    show_hidden: &mut self.show_hidden,
+                    view_mode: &mut self.view_mode,
+                    group_by,
+                    sticky_group_header: &mut self.sticky_group_header,
+                    group_companions: &mut self.group_companions,
+                    show_drive_capacity: &mut self.show_drive_capacity,
+                    show_capacity_size: &mut self.show_capacity_size,
+                    show_jump_bar: &mut self.show_jump_bar,
+                    show_recent_strip: &mut self.show_recent_strip,
+                    directory_pane_auto_hide: &mut self.directory_pane_auto_hide,
+                    preview_pane_hidden: &mut self.preview_pane_hidden,
+                    compute_folder_sizes: &mut self.compute_folder_sizes,
+                };
+                menu_bar::show_menu_bar(ui, menu_state, &mut self.events);
+                if self.directory_pane_auto_hide != directory_pane_auto_hide {
+                    self.directory_pane_revealed = false;
+                    self.save_pane_settings();
+                }
+                if self.preview_pane_hidden != preview_pane_hidden {
+                    self.preview_pane_changed();
                 }
 
                 ui.separator();
 
                 // 盘符栏 - 切换工作区
-                let workspace_switched = self.drive_bar.show(ui, &mut self.current_path);
-                if workspace_switched {
-                    println!("主程序: 工作区切换成功，当前路径: {}", self.current_path.display());
-
-                    // 重置导航历史和位置
-                    self.nav_history = vec![self.current_path.clone()];
-                    self.history_pos = 0;
-                    self.directory_current_path = self.current_path.clone();
-
-                    // 刷新两个列表
-                    self.refresh_file_list();
-                    self.refresh_directory_list();
-
-                    println!("主程序: 文件列表已刷新");
+                if let Some(drive_root) = self.drive_bar.show(ui, &self.current_path) {
+                    self.events.emit(AppEvent::SwitchWorkspace(drive_root));
                 }
 
                 ui.separator();
 
                 // 工具栏
-                let (toolbar_needs_refresh, toolbar_should_create_folder) = toolbar::show_toolbar(ui, &mut self.current_path, &mut self.view_mode);
-                if toolbar_needs_refresh {
-                    // 工具栏只影响内容框，不影响目录框
-                    self.refresh_file_list();
-                }
-
-                // 处理新建文件夹请求
-                if toolbar_should_create_folder {
-                    self.new_folder_name = generate_default_folder_name(&self.current_path);
-                    self.show_new_folder_dialog = true;
-                }
+                toolbar::show_toolbar(ui, &self.current_path, &self.settings.path_aliases, &mut self.view_mode, &mut self.tag_filter, &mut self.events);
 
                 ui.separator();
 
@@ -374,9 +1085,7 @@ impl eframe::App for FileExplorerApp {
                     let total_w = ui.available_width();
                     let row_h = ui.spacing().interact_size.y * 1.1;
                     let (rect, _resp) = ui.allocate_exact_size([total_w, row_h].into(), egui::Sense::hover());
-                    let left_w = total_w * self.left_ratio;
-                    let mid_w = total_w * self.mid_ratio;
-                    let right_w = total_w - left_w - mid_w;
+                    let (left_w, mid_w, _) = self.pane_widths(total_w);
 
                     let spacing = ui.spacing().item_spacing.x;
                     let button_w = (mid_w - 3.0 * spacing) / 4.0;
@@ -396,93 +1105,61 @@ impl eframe::App for FileExplorerApp {
                     let make_rect = |x0: f32| egui::Rect::from_min_max(egui::pos2(x0, mid_rect.top()), egui::pos2(x0 + button_w, mid_rect.bottom()));
                     let r_back = make_rect(x);
                     let resp_back = ui.put(r_back, egui::Button::new("返回").min_size(egui::vec2(button_w, button_h)));
-                    if resp_back.clicked() { self.go_back(); }
+                    if resp_back.clicked() { self.events.emit(AppEvent::GoBack); }
                     x += button_w + spacing;
                     let r_fwd = make_rect(x);
                     let resp_fwd = ui.put(r_fwd, egui::Button::new("前进").min_size(egui::vec2(button_w, button_h)));
-                    if resp_fwd.clicked() { self.go_forward(); }
+                    if resp_fwd.clicked() { self.events.emit(AppEvent::GoForward); }
                     x += button_w + spacing;
                     let r_refresh = make_rect(x);
                     let resp_refresh = ui.put(r_refresh, egui::Button::new("刷新").min_size(egui::vec2(button_w, button_h)));
-                    if resp_refresh.clicked() { self.refresh_file_list(); }
+                    if resp_refresh.clicked() { self.events.emit(AppEvent::RefreshContent); }
                     x += button_w + spacing;
                     let r_home = make_rect(x);
                     let resp_home = ui.put(r_home, egui::Button::new("主页").min_size(egui::vec2(button_w, button_h)));
                     if resp_home.clicked() {
                         if let Some(home_dir) = dirs::home_dir() {
-                            self.current_path = home_dir.clone();
-                            self.refresh_file_list();
-                            self.push_history(home_dir);
+                            self.events.emit(AppEvent::Navigate(home_dir));
                         }
                     }
 
-                    // 右侧：预览（考虑与中栏的间距对齐）
+                    // 右侧：预览（考虑与中栏的间距对齐），隐藏时不显示
                     let right_left = mid_rect.right() + spacing;
                     let right_rect = egui::Rect::from_min_max(egui::pos2(right_left, rect.top()), egui::pos2(rect.right(), rect.bottom()));
-                    ui.painter().with_clip_rect(right_rect).text(egui::pos2(right_rect.left() + 6.0, right_rect.center().y), egui::Align2::LEFT_CENTER, "预览", font_id, color);
+                    if !self.preview_pane_hidden {
+                        ui.painter().with_clip_rect(right_rect).text(egui::pos2(right_rect.left() + 6.0, right_rect.center().y), egui::Align2::LEFT_CENTER, "预览", font_id, color);
+                    }
                 }
 
                 // 统一分割线
                 ui.separator();
 
+                // 先处理菜单栏、工具栏和导航按钮的事件，下方三栏使用更新后的状态
+                self.dispatch_events(ctx);
+
                 // 主内容区域 - 使用剩余的全部高度
                 let available_height = ui.available_height() - 40.0; // 留一些边距
                 ui.horizontal(|ui| {
                     let total_w = ui.available_width();
-                    let left_w = total_w * self.left_ratio;
-                    let mid_w = total_w * self.mid_ratio;
-                    let right_w = total_w - left_w - mid_w;
-                    // 左侧目录列表 (25%宽度) - 使用FileList
-                    ui.allocate_ui_with_layout(
+                    let (left_w, mid_w, right_w) = self.pane_widths(total_w);
+                    // 左侧目录列表 - 使用FileList；自动隐藏时只显示一条细边，悬停时浮出
+                    let left_response = ui.allocate_ui_with_layout(
                         [left_w, available_height].into(),
                         egui::Layout::top_down(egui::Align::LEFT),
                         |ui| {
-                            // 左侧标题由贯穿式标题栏提供
-
-                            // 返回上级目录按钮
-                            if ui.add_sized(
-                                [ui.available_width(), ui.spacing().interact_size.y * 1.5],
-                                egui::Button::new("⬆ 返回上级目录")
-                            ).clicked() {
-                                self.go_up_directory();
+                            if self.directory_pane_auto_hide {
+                                self.show_directory_strip(ui, total_w * self.left_ratio, available_height);
+                            } else {
+                                self.show_directory_pane(ui);
                             }
-
-                            ui.separator();
-
-                            // 独立的滚动区域
-                            let mut temp_current_path = self.directory_current_path.clone();
-                            egui::ScrollArea::vertical().id_salt("directory_scroll").show(ui, |ui| {
-                                // 确保目录框的纹理已加载
-                                self.directory_list.ensure_textures(ui.ctx());
-
-                                let (should_refresh_content, should_navigate_directory, should_open_file) =
-                                    self.directory_list.show_for_directory(ui, &mut temp_current_path, &mut self.selected_file);
-
-                                if should_refresh_content {
-                                    // 单击目录：内容框刷新到该目录
-                                    if let Some(selected_path) = self.selected_file.clone() {
-                                        self.current_path = selected_path.clone();
-                                        self.refresh_file_list();
-                                        self.push_history(selected_path);
-                                    }
-                                }
-
-                                if should_navigate_directory {
-                                    // 双击目录：目录框进入该目录
-                                    self.directory_current_path = temp_current_path.clone();
-                                    self.refresh_directory_list();
-                                }
-
-                                if should_open_file {
-                                    // 双击文件：文件已通过mouse_strategy打开
-                                    // 这里可以添加成功打开的提示，如果需要的话
-                                }
-                            });
                         }
-                    );
+                    ).response;
+                    if !self.directory_pane_auto_hide {
+                        self.handle_pane_splitter(ui, left_response.rect, total_w);
+                    }
 
                     // 中间文件列表 (45%宽度)
-                    ui.allocate_ui_with_layout(
+                    let mid_response = ui.allocate_ui_with_layout(
                         [mid_w, available_height].into(),
                         egui::Layout::top_down(egui::Align::LEFT),
                         |ui| {
@@ -493,95 +1170,186 @@ impl eframe::App for FileExplorerApp {
                             let spacing = ui.spacing().item_spacing.x;
                             let button_w = (total_w - 3.0 * spacing) / 4.0;
                             ui.horizontal(|ui| {
-                                // 复制按钮
-                                if ui.add(egui::Button::new("复制").min_size(egui::vec2(button_w, button_h))).clicked() {
-                                    if let Some(ref path) = self.selected_file {
-                                        self.file_operations.copy_to_clipboard(vec![path.clone()]);
-                                    }
-                                }
-
-                                // 粘贴按钮
-                                if ui.add(egui::Button::new("粘贴").min_size(egui::vec2(button_w, button_h))).clicked() {
-                                    // 总是粘贴到当前路径（内容框的当前目录）
-                                    match self.file_operations.paste_from_clipboard(&self.current_path) {
-                                        FileOperationResult::Success => {
-                                            self.refresh_file_list();
-                                        }
-                                        FileOperationResult::Error(msg) => {
-                                            // TODO: 显示错误消息
-                                            eprintln!("粘贴错误: {}", msg);
-                                        }
-                                        FileOperationResult::NeedsConfirmation(_) => {}
-                                    }
-                                }
-
-                                // 重命名按钮
-                                if ui.add(egui::Button::new("重命名").min_size(egui::vec2(button_w, button_h))).clicked() {
-                                    if let Some(ref path) = self.selected_file {
-                                        self.rename_input = path.file_name()
-                                            .and_then(|n| n.to_str())
-                                            .unwrap_or("")
-                                            .to_string();
-                                        self.show_rename_dialog = true;
-                                    }
-                                }
-
-                                // 删除按钮
-                                if ui.add(egui::Button::new("删除").min_size(egui::vec2(button_w, button_h))).clicked() {
-                                    if let Some(ref path) = self.selected_file {
-                                        match self.file_operations.delete_files(&[path.clone()]) {
-                                            FileOperationResult::NeedsConfirmation(message) => {
-                                                self.delete_confirmation_message = message;
-                                                self.show_delete_confirmation = true;
-                                            }
-                                            FileOperationResult::Error(msg) => {
-                                                eprintln!("删除错误: {}", msg);
-                                            }
-                                            FileOperationResult::Success => {
-                                                // 这个情况不应该发生，删除总是需要确认
-                                            }
+                                // 按钮作用于选中项，粘贴总是粘贴到内容框的当前目录
+                                let selected = self.selected_file.clone();
+                                let buttons = [
+                                    ("复制", selected.clone().map(|p| ContextAction::Copy(vec![p]))),
+                                    ("粘贴", Some(ContextAction::Paste(self.current_path.clone()))),
+                                    ("重命名", selected.clone().map(ContextAction::Rename)),
+                                    ("删除", selected.map(|p| ContextAction::Delete(vec![p]))),
+                                ];
+                                for (label, action) in buttons {
+                                    if ui.add(egui::Button::new(label).min_size(egui::vec2(button_w, button_h))).clicked() {
+                                        if let Some(action) = action {
+                                            self.events.emit(AppEvent::FileAction(action));
                                         }
                                     }
                                 }
                             });
 
-                            // 独立的滚动区域
-                            egui::ScrollArea::vertical().id_salt("file_scroll").show(ui, |ui| {
-                                let should_navigate = self.file_list.show(ui, &mut self.current_path, &mut self.selected_file, self.view_mode, Some(&self.preview));
-                                if should_navigate {
-                                    // 内容框点击文件夹时：只更新内容框，不刷新目录框
-                                    self.current_path = self.selected_file.as_ref().unwrap_or(&self.current_path).clone();
-                                    self.refresh_file_list();
-                                    self.push_history(self.current_path.clone());
+                            // 显示起始页时代替文件列表
+                            if self.start_page.is_showing() {
+                                self.start_page.show(ui, &self.current_path, self.drive_bar.drives(), &mut self.events);
+                                return;
+                            }
 
-                                    // 目录框保持不变，不自动更新
-                                }
-                            });
+                            self.file_list.set_jump_bar_enabled(self.show_jump_bar);
+                            self.file_list.set_recent_strip_enabled(self.show_recent_strip);
+                            let options = self.content_list_options();
+                            self.file_list.set_options(&options);
+                            self.file_list.set_sticky_header_enabled(self.sticky_group_header);
+                            self.file_list.set_folder_size_enabled(self.compute_folder_sizes);
+                            // 系统剪贴板中可能有其它文件管理器复制的文件，粘贴时才在后台读取
+                            self.file_list.set_clipboard_available(self.file_operations.has_clipboard_content() || system_clipboard::is_available());
+
+                            // 各视图自带滚动区域，列头固定在滚动区域上方，不再外套一层滚动
+                            self.file_list.sync_tags(&self.tag_store);
+                            // 内容框进入的目录作为事件交给主程序，当前路径由 handle_event 修改
+                            let mut target_path = self.current_path.clone();
+                            if self.file_list.show(ui, &mut target_path, &mut self.selected_file, self.view_mode, Some(&self.preview)) {
+                                self.events.emit(AppEvent::Navigate(target_path));
+                            }
+                            if let Some((path, new_name)) = self.file_list.take_rename_commit() {
+                                self.events.emit(AppEvent::RenameCommitted { path, new_name });
+                            }
+                            if let Some(drop) = self.file_list.take_drop_request() {
+                                self.events.emit(AppEvent::Drop(drop));
+                            }
+                            if let Some(path) = self.file_list.take_view_image_request() {
+                                self.image_viewer.open(path, self.file_list.image_paths());
+                            }
+                            if let Some((group, paths)) = self.file_list.take_group_move_request() {
+                                self.group_move_dialog.open(group, paths, self.current_path.clone());
+                            }
+                            if let Some(action) = self.file_list.take_context_action() {
+                                self.events.emit(AppEvent::FileAction(action));
+                            }
                         }
-                    );
-
-                    // 右侧预览面板 (30%宽度)
-                    ui.allocate_ui_with_layout(
-                        [right_w, available_height].into(),
-                        egui::Layout::top_down(egui::Align::LEFT),
-                        |ui| {
-                            // 右侧标题由贯穿式标题栏提供
-                            egui::ScrollArea::vertical().show(ui, |ui| {
-                                if let Some(selected_file) = &self.selected_file {
-                                    // 只有当选中的文件发生变化时才加载预览
-                                    if self.preview.current_file() != Some(selected_file) {
-                                        self.preview.load_preview(selected_file.clone(), ctx);
+                    ).response;
+                    self.handle_mouse_gesture(ctx, left_response.rect, mid_response.rect);
+
+                    if !self.preview_pane_hidden {
+                        // 右侧预览面板 (30%宽度)，隐藏时宽度让给内容框
+                        // 拖动文件时保持当前预览，松开在预览栏上时与拖动的文件比较
+                        let dragging_files = egui::DragAndDrop::has_payload_of_type::<DraggedFiles>(ctx);
+                        let preview_response = ui.allocate_ui_with_layout(
+                            [right_w, available_height].into(),
+                            egui::Layout::top_down(egui::Align::LEFT),
+                            |ui| {
+                                // 右侧标题由贯穿式标题栏提供
+                                egui::ScrollArea::vertical().show(ui, |ui| {
+                                    if !dragging_files {
+                                        let pair = self.file_list.selected_file_pair(&self.selected_file);
+                                        self.preview.sync_selected_pair(pair, ctx);
                                     }
-                                }
-                                self.preview.update(ctx);
-                                self.preview.show(ui);
-                            });
-                        }
-                    );
+                                    if let Some(selected_file) = &self.selected_file {
+                                        // 只有当选中的文件发生变化时才加载预览；比较模式下选中参与比较的文件时保持比较
+                                        if !dragging_files && self.preview.current_file() != Some(selected_file) && !self.preview.compare_involves(selected_file) {
+                                            self.preview.load_preview(selected_file.clone(), ctx);
+                                        }
+                                    }
+                                    self.preview.update(ctx);
+                                    self.preview.show(ui);
+                                    if let Some(path) = self.preview.take_open_viewer() {
+                                        self.image_viewer.open(path, self.file_list.image_paths());
+                                    }
+                                    if let Some(path) = self.preview.take_open_text_viewer() {
+                                        self.text_viewer.open(path, ctx);
+                                    }
+                                });
+                            }
+                        ).response;
+                        self.handle_compare_drop(ui, &preview_response);
+                    }
                 });
             });
         });
 
+        // 全窗口图片查看器盖在其它界面之上；关闭后选中最后查看的图片
+        if self.text_viewer.is_showing() {
+            self.text_viewer.show_text_viewer(ctx);
+        }
+        if self.image_viewer.is_showing() {
+            self.image_viewer.show(ctx, &self.preview);
+        }
+        if let Some(path) = self.image_viewer.take_closed() {
+            self.selected_file = Some(path);
+        }
+
+        // 显示快速跳转弹出框：文件夹直接进入，文件则定位到所在文件夹
+        if self.quick_jump.is_showing() {
+            if let Some(path) = self.quick_jump.show_quick_jump_dialog(ctx, &self.settings.path_aliases) {
+                if !path.is_dir() {
+                    self.events.emit(AppEvent::Reveal(path));
+                } else if path != self.current_path {
+                    self.events.emit(AppEvent::Navigate(path));
+                }
+            }
+        }
+
+        // 显示打开方式和属性对话框
+        if self.open_with_dialog.is_showing() {
+            self.open_with_dialog.show_open_with_dialog(ctx);
+        }
+        if self.properties_dialog.is_showing() {
+            self.properties_dialog.show_properties_dialog(ctx);
+        }
+        if self.multi_copy_dialog.is_showing() && self.multi_copy_dialog.show_multi_copy_dialog(ctx) {
+            self.events.emit(AppEvent::RefreshAll);
+        }
+        if self.create_link_dialog.is_showing() && self.create_link_dialog.show_create_link_dialog(ctx) {
+            self.events.emit(AppEvent::RefreshAll);
+        }
+        if self.compress_dialog.is_showing() {
+            if let Some(request) = self.compress_dialog.show_compress_dialog(ctx) {
+                self.running_operations.push(self.file_operations.compress_files(request));
+            }
+        }
+        if self.hash_verify_dialog.is_showing() {
+            self.hash_verify_dialog.show_hash_verify_dialog(ctx);
+        }
+        if self.elevated_retry.is_showing() {
+            if let Some(task) = self.elevated_retry.show_elevated_retry_dialog(ctx) {
+                if let ElevatedTask::Rename { from, to } = &task {
+                    self.tag_store.rename_path(from, to);
+                }
+                if self.selected_file.as_ref().is_some_and(|path| !path.exists()) {
+                    self.selected_file = None;
+                }
+                self.events.emit(AppEvent::RefreshAll);
+            }
+        }
+        if self.extract_dialog.is_showing() {
+            if let Some((archive, dest)) = self.extract_dialog.show_extract_dialog(ctx) {
+                self.running_operations.push(self.file_operations.extract_archive(archive, dest));
+            }
+        }
+        if self.group_move_dialog.is_showing() {
+            if let Some(drop) = self.group_move_dialog.show_group_move_dialog(ctx) {
+                self.events.emit(AppEvent::Drop(drop));
+            }
+        }
+        if self.basket.is_showing() {
+            self.basket.show_basket_panel(ctx, &self.current_path, &mut self.events);
+        }
+        // 新截图的提示
+        self.screenshot_inbox.sync(ctx, self.settings.screenshot_inbox, &self.settings.screenshot_folder);
+        self.screenshot_inbox.show(ctx, &self.settings.screenshot_project_folder, &mut self.events);
+        self.settings_notice.show(ctx);
+        self.operation_progress.show_progress_dialog(ctx, &mut self.running_operations);
+        self.dispatch_events(ctx);
+        // 导航后记录当前盘符的工作区，重启后切换盘符时恢复
+        self.save_current_workspace_state();
+        // 记录最近访问的位置，进入其它文件夹时关闭起始页
+        self.start_page.visit(&self.current_path);
+
+        // 内容框的选择或筛选在本帧发生变化时，重绘一次以更新状态栏
+        let summary = self.file_list.summary(&self.selected_file);
+        if summary != self.status_summary {
+            self.status_summary = summary;
+            ctx.request_repaint();
+        }
+
         // 显示重命名对话框
         if self.show_rename_dialog {
             let mut open = true;
@@ -600,17 +1368,11 @@ impl eframe::App for FileExplorerApp {
 
                     ui.horizontal(|ui| {
                         if ui.button("确定").clicked() {
-                            if let Some(ref path) = self.selected_file {
-                                match self.file_operations.rename_file(path, &self.rename_input) {
-                                    FileOperationResult::Success => {
-                                        self.refresh_file_list();
-                                        self.show_rename_dialog = false;
-                                    }
-                                    FileOperationResult::Error(msg) => {
-                                        eprintln!("重命名错误: {}", msg);
-                                        // TODO: 显示错误消息给用户
-                                    }
-                                    FileOperationResult::NeedsConfirmation(_) => {}
+                            if let Some(path) = self.selected_file.clone() {
+                                let new_name = self.rename_input.clone();
+                                // 失败时保留对话框，错误显示在状态栏
+                                if self.rename_with_companions(&path, &new_name) {
+                                    self.show_rename_dialog = false;
                                 }
                             }
                         }
@@ -625,43 +1387,66 @@ impl eframe::App for FileExplorerApp {
             }
         }
 
-        // 显示删除确认对话框
-        if self.show_delete_confirmation {
+        // 询问是否一并处理配套文件
+        if self.companion_dialog.is_showing() {
+            match self.companion_dialog.show_companion_dialog(ctx) {
+                Some(CompanionChoice::Operation(operation, companions)) => self.include_companions(operation, companions),
+                Some(CompanionChoice::Rename(renames)) => {
+                    for (path, new_name) in renames {
+                        match self.file_operations.rename_file(&path, &new_name) {
+                            Ok(()) => self.tag_store.rename_path(&path, &path.with_file_name(&new_name)),
+                            Err(msg) => self.report_error(format!("重命名配套文件错误: {}", msg)),
+                        }
+                    }
+                    self.refresh_file_list();
+                }
+                None => {}
+            }
+        }
+
+        // 显示操作确认对话框
+        if self.confirm_dialog.is_showing() {
+            if let Some(operation) = self.confirm_dialog.show_confirm_dialog(ctx) {
+                self.run_operation(operation);
+            }
+        }
+
+        // 显示重复粘贴对话框
+        if self.show_paste_duplicate_dialog {
             let mut open = true;
-            egui::Window::new("确认删除")
+            let mut action = None;
+            egui::Window::new("重复的复制任务")
                 .collapsible(false)
                 .resizable(false)
                 .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
                 .open(&mut open)
                 .show(ctx, |ui| {
-                    ui.label(&self.delete_confirmation_message);
+                    ui.label(&self.paste_duplicate_message);
                     ui.separator();
 
                     ui.horizontal(|ui| {
-                        if ui.button("确定").clicked() {
-                            if let Some(ref path) = self.selected_file {
-                                match self.file_operations.confirm_delete(&[path.clone()]) {
-                                    FileOperationResult::Success => {
-                                        self.selected_file = None;
-                                        self.refresh_file_list();
-                                        self.show_delete_confirmation = false;
-                                    }
-                                    FileOperationResult::Error(msg) => {
-                                        eprintln!("删除错误: {}", msg);
-                                        self.show_delete_confirmation = false;
-                                    }
-                                    FileOperationResult::NeedsConfirmation(_) => {}
-                                }
-                            }
+                        if ui.button("合并").clicked() {
+                            action = Some(DuplicatePasteAction::Merge);
                         }
-                        if ui.button("取消").clicked() {
-                            self.show_delete_confirmation = false;
+                        if ui.button("跳过").clicked() {
+                            action = Some(DuplicatePasteAction::Skip);
+                        }
+                        if ui.button("仍然复制").clicked() {
+                            action = Some(DuplicatePasteAction::CopyAgain);
                         }
                     });
                 });
 
             if !open {
-                self.show_delete_confirmation = false;
+                action = Some(DuplicatePasteAction::Skip);
+            }
+            if let Some(action) = action {
+                self.show_paste_duplicate_dialog = false;
+                match self.file_operations.resolve_duplicate_paste(action) {
+                    Ok(Some(handle)) => self.running_operations.push(handle),
+                    Ok(None) => {}
+                    Err(msg) => self.report_error(format!("粘贴错误: {}", msg)),
+                }
             }
         }
 
@@ -679,6 +1464,18 @@ impl eframe::App for FileExplorerApp {
                         ui.text_edit_singleline(&mut self.new_folder_name);
                     });
 
+                    // 点击建议填入名称
+                    if !self.new_folder_suggestions.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("建议:");
+                            for name in &self.new_folder_suggestions {
+                                if ui.small_button(name).clicked() {
+                                    self.new_folder_name = name.clone();
+                                }
+                            }
+                        });
+                    }
+
                     ui.separator();
 
                     ui.horizontal(|ui| {
@@ -688,10 +1485,7 @@ impl eframe::App for FileExplorerApp {
                                     self.refresh_file_list();
                                     self.show_new_folder_dialog = false;
                                 }
-                                CreateOperationResult::Error(msg) => {
-                                    eprintln!("新建文件夹错误: {}", msg);
-                                    // TODO: 显示错误消息给用户
-                                }
+                                CreateOperationResult::Error(msg) => self.report_error(format!("新建文件夹错误: {}", msg)),
                                 CreateOperationResult::NeedsConfirmation(_) => {}
                                 CreateOperationResult::NeedsInput(_) => {}
                             }
@@ -711,5 +1505,58 @@ impl eframe::App for FileExplorerApp {
         if self.help_system.is_about_dialog_showing() {
             self.help_system.show_about_dialog(ctx);
         }
+
+        // 显示设置对话框，确定后保存并应用
+        if self.settings_dialog.is_showing() && self.settings_dialog.show_settings_dialog(ctx, &mut self.settings) {
+            self.file_list.set_color_rules(self.settings.active_color_rules());
+            self.file_list.set_click_open_mode(self.settings.click_open_mode);
+            self.file_list.set_custom_folder_icons(self.settings.custom_folder_icons);
+            self.file_list.set_two_line_names(self.settings.two_line_names);
+            self.directory_list.set_custom_folder_icons(self.settings.custom_folder_icons);
+            let options = self.directory_list_options();
+            self.directory_list.set_options(&options);
+            // 目录栏和预览栏状态不在对话框中编辑，以当前状态为准
+            self.save_pane_settings();
+        }
+
+        // 显示校验清单的进度/结果窗口，生成新清单后刷新列表
+        if self.checksum_dialog.is_showing() && self.checksum_dialog.show_checksum_dialog(ctx) {
+            self.refresh_file_list();
+        }
+
+        // 显示照片整理预览，执行后刷新列表
+        if self.photo_organizer.is_showing() && self.photo_organizer.show_organizer_dialog(ctx) {
+            self.refresh_file_list();
+        }
+
+        // 显示重复文件查找结果，勾选的文件与其它删除一样移到回收站，并检查受保护路径、配套文件和确认设置
+        if self.duplicate_finder.is_showing() {
+            if let Some(paths) = self.duplicate_finder.show_duplicate_dialog(ctx) {
+                self.request_operation(PendingOperation::Delete { paths, permanent: false });
+            }
+        }
+
+        // 磁盘使用分析，删除请求经过确认后执行
+        if self.disk_usage.is_showing() {
+            self.disk_usage.show_disk_usage_dialog(ctx, &mut self.events);
+        }
+
+        // 显示断开的符号链接，删除或重新指向后刷新列表
+        if self.broken_link_finder.is_showing() && self.broken_link_finder.show_broken_links_dialog(ctx) {
+            self.refresh_file_list();
+        }
+
+        // 大文件和旧文件报告：定位或删除文件
+        if self.file_report.is_showing() {
+            match self.file_report.show_report_dialog(ctx) {
+                Some(ReportAction::Reveal(path)) => self.reveal_path(&path),
+                Some(ReportAction::Delete(path)) => self.request_operation(PendingOperation::Delete { paths: vec![path], permanent: false }),
+                None => {}
+            }
+        }
+
+        self.poll_clipboard_import(ctx);
+        self.poll_operations(ctx);
+        self.sync_folder_view_settings(ctx);
     }
 }
\ No newline at end of file
@@ -1,22 +1,13 @@
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::time::SystemTime;
+use std::io::Write;
 
-pub fn get_file_size_str(size: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    let mut size_f = size as f64;
-    let mut unit_index = 0;
-
-    while size_f >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size_f /= 1024.0;
-        unit_index += 1;
-    }
+use crate::components::locale_format;
 
-    if unit_index == 0 {
-        format!("{} {}", size, UNITS[unit_index])
-    } else {
-        format!("{:.1} {}", size_f, UNITS[unit_index])
-    }
+// 文件大小，按系统区域显示小数点
+pub fn get_file_size_str(size: u64) -> String {
+    locale_format::current().size(size)
 }
 
 pub fn get_file_modified_time(path: &Path) -> Option<String> {
@@ -24,10 +15,7 @@ pub fn get_file_modified_time(path: &Path) -> Option<String> {
         .ok()?
         .modified()
         .ok()
-        .map(|time| {
-            let datetime = chrono::DateTime::<chrono::Local>::from(time);
-            datetime.format("%Y-%m-%d %H:%M").to_string()
-        })
+        .map(|time| locale_format::current().datetime(time))
 }
 
 pub fn get_file_icon(path: &Path) -> &'static str {
@@ -37,7 +25,7 @@ pub fn get_file_icon(path: &Path) -> &'static str {
         match path.extension().and_then(|ext| ext.to_str()) {
             Some("txt") => "📄",
             Some("rs") | Some("js") | Some("py") | Some("html") | Some("css") => "📝",
-            Some("jpg") | Some("jpeg") | Some("png") | Some("gif") | Some("bmp") => "🖼️",
+            _ if crate::components::image_formats::is_image_file(path) => "🖼️",
             Some("mp4") | Some("avi") | Some("mkv") => "🎬",
             Some("mp3") | Some("wav") | Some("flac") => "🎵",
             Some("pdf") => "📕",
@@ -53,4 +41,111 @@ pub fn is_hidden_file(path: &Path) -> bool {
         .and_then(|name| name.to_str())
         .map(|name| name.starts_with('.'))
         .unwrap_or(false)
+}
+
+// 通配符匹配：* 匹配任意个字符，? 匹配单个字符
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None; // 最近一个 * 的位置及其匹配到的文本位置
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // 回溯：让上一个 * 多匹配一个字符
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+
+// 读取 JSON 配置文件，不存在或无法解析时返回 None
+pub fn load_json<T: serde::de::DeserializeOwned>(path: &Path) -> Option<T> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+// 保存 JSON 配置文件：先写入同一文件夹中的临时文件再改名替换，写到一半时崩溃不会损坏原文件
+pub fn save_json<T: serde::Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let text = serde_json::to_string_pretty(value).map_err(|e| format!("序列化失败: {}", e))?;
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp-{}", file_name, std::process::id()));
+    let result = fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(text.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+// 测试用的临时文件夹，离开作用域时删除，测试失败时也不会留下文件
+#[cfg(test)]
+pub struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("file_explorer_{}_test_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &PathBuf {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_json() {
+        let dir = TestDir::new("save_json");
+        let path = dir.join("config").join("slots.json");
+        save_json(&path, &vec![1, 2, 3]).unwrap();
+        save_json(&path, &vec![4]).unwrap();
+        assert_eq!(load_json::<Vec<i32>>(&path), Some(vec![4]));
+        // 临时文件已改名替换，不留在文件夹中
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+        assert_eq!(load_json::<Vec<i32>>(&dir.join("missing.json")), None);
+    }
 }
\ No newline at end of file